@@ -0,0 +1,195 @@
+//! # Модуль сохраненных значений полей
+//!
+//! `SavedAnswers` запоминает значения динамических полей и опций, введенные при
+//! последнем создании проекта по каждому пресету, и хранит их в JSON файле
+//! `saved_answers.json` в директории конфигурации (рядом с `settings.toml`).
+//! При следующем выборе пресета форма заполняется этими значениями. Значения полей
+//! и опций, которых больше нет в конфигурации пресета, отбрасываются при чтении.
+
+use crate::persist::{read_with_backup, write_with_backup};
+use crate::presets::PresetConfig;
+use crate::settings::config_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Имя файла сохраненных значений в директории конфигурации
+pub const SAVED_ANSWERS_FILE_NAME: &str = "saved_answers.json";
+
+/// Значения полей и опций одного пресета
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetAnswers {
+    /// field_id -> значение
+    pub fields: BTreeMap<String, String>,
+    /// option_id -> включена
+    pub options: BTreeMap<String, bool>,
+}
+
+impl PresetAnswers {
+    /// Значения, которые есть в конфигурации пресета `config`
+    ///
+    /// Поля и опции, удаленные из пресета (а также встроенные опции вроде
+    /// `force`, если пресет их не объявляет), отбрасываются.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::answers::PresetAnswers;
+    /// use ai_project_template::presets::PresetConfig;
+    ///
+    /// let config: PresetConfig = serde_json::from_str(r#"{
+    ///     "preset_id": "book", "preset_name": "Book", "description": "",
+    ///     "directories": [], "templates": [], "empty_files": [],
+    ///     "fields": [{"id": "author", "label": "Author", "required": false, "type": "text"}],
+    ///     "options": [{"id": "with_tests", "label": "Tests", "default": false}]
+    /// }"#).unwrap();
+    ///
+    /// let mut answers = PresetAnswers::default();
+    /// answers.fields.insert("author".to_string(), "Ann".to_string());
+    /// answers.fields.insert("publisher".to_string(), "Old field".to_string());
+    /// answers.options.insert("with_tests".to_string(), true);
+    /// answers.options.insert("force".to_string(), true);
+    ///
+    /// let known = answers.for_config(&config);
+    /// assert_eq!(known.fields.keys().collect::<Vec<_>>(), ["author"]);
+    /// assert_eq!(known.options.keys().collect::<Vec<_>>(), ["with_tests"]);
+    /// ```
+    pub fn for_config(&self, config: &PresetConfig) -> PresetAnswers {
+        PresetAnswers {
+            fields: self.fields.iter()
+                .filter(|(id, _)| config.fields.iter().any(|field| &field.id == *id))
+                .map(|(id, value)| (id.clone(), value.clone()))
+                .collect(),
+            options: self.options.iter()
+                .filter(|(id, _)| config.options.iter().any(|option| &option.id == *id))
+                .map(|(id, enabled)| (id.clone(), *enabled))
+                .collect(),
+        }
+    }
+
+    /// Нет ни одного значения
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.options.is_empty()
+    }
+}
+
+/// Сохраненные значения полей по пресетам
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::answers::SavedAnswers;
+///
+/// let mut answers = SavedAnswers::default();
+/// answers.remember(
+///     "book",
+///     &HashMap::from([("author".to_string(), "Ann".to_string())]),
+///     &HashMap::from([("with_tests".to_string(), true)]),
+/// );
+///
+/// let json = answers.to_json().unwrap();
+/// let restored = SavedAnswers::from_json(&json).unwrap();
+/// assert_eq!(restored, answers);
+/// assert_eq!(restored.get("book").unwrap().fields["author"], "Ann");
+///
+/// // Файл прежней версии без раздела presets читается как пустой
+/// assert!(SavedAnswers::from_json("{}").unwrap().get("book").is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedAnswers {
+    /// preset_id -> значения
+    presets: BTreeMap<String, PresetAnswers>,
+}
+
+impl SavedAnswers {
+    /// Загрузить значения из директории конфигурации
+    ///
+    /// Отсутствующий или поврежденный файл не является ошибкой: поврежденный файл
+    /// восстанавливается из резервной копии, иначе возвращается пустой набор.
+    pub fn load() -> Self {
+        config_dir()
+            .map(|dir| Self::load_from(&dir.join(SAVED_ANSWERS_FILE_NAME)))
+            .unwrap_or_default()
+    }
+
+    /// Загрузить значения из файла `path` (см. `load`)
+    pub fn load_from(path: &Path) -> Self {
+        read_with_backup(path, Self::from_json).unwrap_or_default()
+    }
+
+    /// Сохранить значения в директорию конфигурации
+    ///
+    /// Файл записывается атомарно, рядом сохраняется копия `saved_answers.json.bak`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` если значения сохранены, иначе `Err` с описанием ошибки
+    pub fn save(&self) -> Result<(), String> {
+        let dir = config_dir()
+            .ok_or_else(|| "Could not determine config directory".to_string())?;
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create config dir: {}", e))?;
+        self.save_to(&dir.join(SAVED_ANSWERS_FILE_NAME))
+    }
+
+    /// Сохранить значения в файл `path` (см. `save`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use ai_project_template::answers::SavedAnswers;
+    ///
+    /// let dir = std::env::temp_dir().join(format!("answers_doc_{}", std::process::id()));
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// let path = dir.join("saved_answers.json");
+    ///
+    /// let mut answers = SavedAnswers::default();
+    /// answers.remember("book", &HashMap::from([("license".to_string(), "MIT".to_string())]), &HashMap::new());
+    /// answers.save_to(&path).unwrap();
+    /// assert_eq!(SavedAnswers::load_from(&path), answers);
+    /// std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        let content = self.to_json()?;
+        write_with_backup(path, content.as_bytes())
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    /// Разобрать значения из JSON
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        serde_json::from_str(content).map_err(|e| format!("Failed to parse saved answers: {}", e))
+    }
+
+    /// Записать значения в JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize saved answers: {}", e))
+    }
+
+    /// Сохраненные значения пресета `preset_id`
+    pub fn get(&self, preset_id: &str) -> Option<&PresetAnswers> {
+        self.presets.get(preset_id)
+    }
+
+    /// Запомнить значения полей и опций пресета `preset_id` (заменяют прежние)
+    pub fn remember(&mut self, preset_id: &str, fields: &HashMap<String, String>, options: &HashMap<String, bool>) {
+        let answers = PresetAnswers {
+            fields: fields.iter().map(|(id, value)| (id.clone(), value.clone())).collect(),
+            options: options.iter().map(|(id, enabled)| (id.clone(), *enabled)).collect(),
+        };
+        self.presets.insert(preset_id.to_string(), answers);
+    }
+
+    /// Удалить сохраненные значения пресета `preset_id`
+    ///
+    /// # Returns
+    ///
+    /// `true`, если значения были сохранены
+    pub fn forget(&mut self, preset_id: &str) -> bool {
+        self.presets.remove(preset_id).is_some()
+    }
+}
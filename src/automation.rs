@@ -0,0 +1,518 @@
+//! # Модуль сервера автоматизации
+//!
+//! Локальный HTTP-сервер для тестовых стендов: позволяет управлять GUI без
+//! автоматизации по скриншотам. Собирается только с cargo-фичей `automation`
+//! и запускается флагом `--automation-port PORT`.
+//!
+//! ## API
+//!
+//! Все запросы требуют заголовок `Authorization: Bearer <token>`; токен
+//! генерируется при запуске и выводится в стандартный вывод.
+//!
+//! - `GET /state` - снимок состояния: пресеты, выбор, имя проекта, занятость
+//! - `POST /create` - создать проект (тело - `CreateRequest` в JSON)
+//! - `POST /refresh-presets` - обновить пресеты
+//!
+//! Сервер не меняет состояние сам: каждый запрос передается приложению как
+//! `AutomationEvent::Request` и обрабатывается в `update`, как действие
+//! пользователя. Ответ возвращается через `AutomationRequest::respond`.
+//!
+//! ## Безопасность
+//!
+//! Сервер слушает только `127.0.0.1`. Запрос без верного токена получает `401`.
+//! Токен - 128 бит из генератора случайных чисел ОС. Клиент, не приславший запрос
+//! за `READ_TIMEOUT`, получает `408`, чтобы зависшие соединения не копились.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// Флаг командной строки с портом сервера
+pub const AUTOMATION_PORT_FLAG: &str = "--automation-port";
+
+/// Максимальный размер запроса (заголовки и тело) в байтах
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// Время на получение запроса (заголовков и тела) после подключения
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Параметры запуска сервера
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AutomationServer {
+    /// Порт на `127.0.0.1` (`0` - выбрать свободный)
+    pub port: u16,
+    /// Токен для заголовка `Authorization`
+    pub token: String,
+}
+
+impl AutomationServer {
+    /// Параметры сервера со случайным токеном
+    ///
+    /// # Returns
+    ///
+    /// `Err`, если генератор случайных чисел ОС недоступен
+    pub fn new(port: u16) -> Result<Self, String> {
+        Ok(Self { port, token: random_token()? })
+    }
+
+    /// Подписка, запускающая сервер и передающая его события приложению
+    ///
+    /// Подписка живет, пока ее возвращает `subscription`; повторный вызов с теми же
+    /// параметрами не перезапускает сервер.
+    pub fn subscription(&self) -> iced::Subscription<AutomationEvent> {
+        let server = self.clone();
+        iced::subscription::channel(server.clone(), 16, move |output| serve(server, output))
+    }
+}
+
+/// Событие сервера автоматизации
+#[derive(Debug, Clone)]
+pub enum AutomationEvent {
+    /// Сервер слушает указанный адрес
+    Started(SocketAddr),
+    /// Сервер не удалось запустить
+    Failed(String),
+    /// Получен запрос (уже прошедший проверку токена)
+    Request(AutomationRequest),
+}
+
+/// Запрос к приложению
+#[derive(Debug, Clone)]
+pub struct AutomationRequest {
+    /// Что нужно сделать
+    pub action: AutomationAction,
+    reply: Arc<Mutex<Option<oneshot::Sender<AutomationReply>>>>,
+}
+
+impl AutomationRequest {
+    /// Запрос без соединения: ответ приходит в возвращаемый приемник
+    #[cfg(test)]
+    pub fn detached(action: AutomationAction) -> (Self, oneshot::Receiver<AutomationReply>) {
+        let (sender, receiver) = oneshot::channel();
+        (Self { action, reply: Arc::new(Mutex::new(Some(sender))) }, receiver)
+    }
+
+    /// Отправить ответ клиенту (повторные вызовы игнорируются)
+    pub fn respond(&self, reply: AutomationReply) {
+        let sender = self.reply.lock().ok().and_then(|mut sender| sender.take());
+        if let Some(sender) = sender {
+            let _ = sender.send(reply);
+        }
+    }
+}
+
+/// Действие, запрошенное через API
+#[derive(Debug, Clone)]
+pub enum AutomationAction {
+    /// `GET /state`
+    State,
+    /// `POST /create`
+    Create(CreateRequest),
+    /// `POST /refresh-presets`
+    RefreshPresets,
+}
+
+/// Тело запроса `POST /create`
+///
+/// Те же параметры, что и у запуска GUI с `--preset`, `--name` и `--field`,
+/// плюс опции и необязательная директория назначения.
+///
+/// # Example
+///
+/// ```json
+/// {"preset": "software", "name": "demo", "fields": {"author": "Alice"}, "options": {"git_init": true}}
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct CreateRequest {
+    /// Идентификатор пресета
+    pub preset: String,
+    /// Имя проекта
+    pub name: String,
+    /// Значения полей пресета (field_id -> value)
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    /// Опции пресета и встроенные опции (`force`, `dry_run`, `git_init`, ...): option_id -> включена
+    #[serde(default)]
+    pub options: BTreeMap<String, bool>,
+    /// Директория назначения (по умолчанию - текущая директория назначения в GUI)
+    #[serde(default)]
+    pub destination: Option<PathBuf>,
+}
+
+/// Снимок состояния приложения для `GET /state`
+#[derive(Debug, Clone, Serialize)]
+pub struct StateSnapshot {
+    /// Директория пресетов
+    pub presets_dir: Option<PathBuf>,
+    /// Доступные пресеты в порядке списка
+    pub presets: Vec<PresetEntry>,
+    /// Выбранный пресет
+    pub selected_preset: Option<String>,
+    /// Имя проекта в форме
+    pub project_name: String,
+    /// Директория назначения
+    pub destination: Option<PathBuf>,
+    /// Выполняется операция
+    pub busy: bool,
+    /// Описание выполняемой операции
+    pub busy_label: String,
+    /// Причины, по которым проект сейчас нельзя создать
+    pub problems: Vec<String>,
+    /// Путь последнего созданного проекта
+    pub last_project: Option<PathBuf>,
+    /// Последняя строка лога
+    pub last_log: Option<String>,
+}
+
+/// Пресет в снимке состояния
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetEntry {
+    /// Идентификатор пресета
+    pub id: String,
+    /// Отображаемое имя
+    pub name: String,
+}
+
+/// Ответ на запрос
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomationReply {
+    /// HTTP-статус
+    pub status: u16,
+    /// Тело ответа
+    pub body: serde_json::Value,
+}
+
+impl AutomationReply {
+    /// Успешный ответ `200` с телом
+    pub fn ok(body: serde_json::Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// Запрос принят, результат будет виден в `GET /state` (`202`)
+    pub fn accepted() -> Self {
+        Self { status: 202, body: serde_json::json!({ "accepted": true }) }
+    }
+
+    /// Ответ с ошибкой
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self { status, body: serde_json::json!({ "error": message.into() }) }
+    }
+}
+
+/// Извлечь флаг `--automation-port PORT` из аргументов
+///
+/// # Returns
+///
+/// `Ok(Some(port))` если флаг указан (флаг и значение удаляются из `args`),
+/// `Ok(None)` если флага нет, `Err` если значение не является портом
+pub fn take_port_flag(args: &mut Vec<String>) -> Result<Option<u16>, String> {
+    let Some(index) = args.iter().position(|arg| arg == AUTOMATION_PORT_FLAG) else {
+        return Ok(None);
+    };
+    let value = args.get(index + 1)
+        .ok_or_else(|| format!("{} requires a port", AUTOMATION_PORT_FLAG))?;
+    let port = value.parse::<u16>()
+        .map_err(|_| format!("Invalid automation port: {}", value))?;
+    args.drain(index..=index + 1);
+    Ok(Some(port))
+}
+
+/// Случайный токен из 32 шестнадцатеричных символов (128 бит из генератора ОС)
+fn random_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| format!("Failed to generate automation token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Запустить сервер и принимать соединения
+async fn serve(
+    server: AutomationServer,
+    mut output: iced::futures::channel::mpsc::Sender<AutomationEvent>,
+) -> std::convert::Infallible {
+    use iced::futures::SinkExt;
+
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, server.port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            let _ = output.send(AutomationEvent::Failed(format!(
+                "Failed to bind 127.0.0.1:{}: {}", server.port, e
+            ))).await;
+            return std::future::pending().await;
+        }
+    };
+    if let Ok(addr) = listener.local_addr() {
+        let _ = output.send(AutomationEvent::Started(addr)).await;
+    }
+
+    loop {
+        let Ok((stream, _)) = listener.accept().await else { continue };
+        let token = server.token.clone();
+        let output = output.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &token, output).await {
+                eprintln!("Automation request failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Обработать одно соединение: прочитать запрос, передать приложению, записать ответ
+async fn handle_connection(
+    mut stream: TcpStream,
+    token: &str,
+    mut output: iced::futures::channel::mpsc::Sender<AutomationEvent>,
+) -> Result<(), String> {
+    use iced::futures::SinkExt;
+
+    let request = tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await
+        .unwrap_or_else(|_| Err(AutomationReply::error(408, "Request timed out")));
+    let reply = match request {
+        Ok(request) if !is_authorized(&request, token) => {
+            AutomationReply::error(401, "Missing or invalid token")
+        }
+        Ok(request) => match route(&request) {
+            Ok(action) => {
+                let (sender, receiver) = oneshot::channel();
+                let request = AutomationRequest { action, reply: Arc::new(Mutex::new(Some(sender))) };
+                if output.send(AutomationEvent::Request(request)).await.is_err() {
+                    AutomationReply::error(503, "Application is shutting down")
+                } else {
+                    receiver.await
+                        .unwrap_or_else(|_| AutomationReply::error(500, "Request was dropped"))
+                }
+            }
+            Err(reply) => reply,
+        },
+        Err(reply) => reply,
+    };
+    write_reply(&mut stream, &reply).await
+}
+
+/// Разобранный HTTP-запрос
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Значение заголовка (имя без учета регистра)
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Прочитать HTTP/1.1 запрос (заголовки и тело по `Content-Length`)
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, AutomationReply> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_REQUEST_BYTES {
+            return Err(AutomationReply::error(413, "Request is too large"));
+        }
+        let read = stream.read(&mut chunk).await
+            .map_err(|e| AutomationReply::error(400, e.to_string()))?;
+        if read == 0 {
+            return Err(AutomationReply::error(400, "Incomplete request"));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let mut request = HttpRequest { method, path, headers, body: buffer[head_end + 4..].to_vec() };
+    let length = match request.header("Content-Length") {
+        Some(value) => value.parse::<usize>()
+            .map_err(|_| AutomationReply::error(400, "Invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_REQUEST_BYTES {
+        return Err(AutomationReply::error(413, "Request is too large"));
+    }
+    while request.body.len() < length {
+        let read = stream.read(&mut chunk).await
+            .map_err(|e| AutomationReply::error(400, e.to_string()))?;
+        if read == 0 {
+            return Err(AutomationReply::error(400, "Incomplete request body"));
+        }
+        request.body.extend_from_slice(&chunk[..read]);
+    }
+    request.body.truncate(length);
+    Ok(request)
+}
+
+/// Проверить токен из заголовка `Authorization: Bearer <token>`
+fn is_authorized(request: &HttpRequest, token: &str) -> bool {
+    let Some(provided) = request.header("Authorization").and_then(|v| v.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    // Сравнение без раннего выхода, чтобы время ответа не выдавало совпавший префикс
+    provided.len() == token.len()
+        && provided.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Сопоставить запрос с действием
+fn route(request: &HttpRequest) -> Result<AutomationAction, AutomationReply> {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/state") => Ok(AutomationAction::State),
+        ("POST", "/create") => serde_json::from_slice::<CreateRequest>(&request.body)
+            .map(AutomationAction::Create)
+            .map_err(|e| AutomationReply::error(400, format!("Invalid create request: {}", e))),
+        ("POST", "/refresh-presets") => Ok(AutomationAction::RefreshPresets),
+        (_, "/state" | "/create" | "/refresh-presets") => {
+            Err(AutomationReply::error(405, "Method not allowed"))
+        }
+        _ => Err(AutomationReply::error(404, "Not found")),
+    }
+}
+
+/// Записать JSON-ответ и закрыть соединение
+async fn write_reply(stream: &mut TcpStream, reply: &AutomationReply) -> Result<(), String> {
+    let body = reply.body.to_string();
+    let reason = match reply.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        reply.status, reason, body.len(), body
+    );
+    stream.write_all(response.as_bytes()).await.map_err(|e| e.to_string())?;
+    stream.shutdown().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::futures::channel::mpsc;
+    use iced::futures::StreamExt;
+
+    const TOKEN: &str = "0123456789abcdef0123456789abcdef";
+
+    /// Соединение, обслуживаемое `handle_connection`: сокет клиента и события для приложения
+    async fn connect() -> (TcpStream, mpsc::Receiver<AutomationEvent>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+        let (output, events) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let _ = handle_connection(stream, TOKEN, output).await;
+        });
+        (client, events)
+    }
+
+    fn http_request(method: &str, path: &str, token: &str, body: &str) -> String {
+        format!(
+            "{} {} HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: {}\r\n\r\n{}",
+            method, path, token, body.len(), body
+        )
+    }
+
+    /// Отправить запрос и прочитать ответ целиком (сервер закрывает соединение)
+    async fn send(client: &mut TcpStream, request: &str) -> String {
+        client.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn random_token_is_32_hex_chars() {
+        let token = random_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, random_token().unwrap());
+    }
+
+    #[tokio::test]
+    async fn request_with_wrong_token_is_rejected() {
+        let (mut client, mut events) = connect().await;
+
+        let response = send(&mut client, &http_request("GET", "/state", "wrong", "")).await;
+
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"), "{}", response);
+        assert!(response.ends_with(r#"{"error":"Missing or invalid token"}"#), "{}", response);
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn malformed_create_body_is_rejected() {
+        let (mut client, mut events) = connect().await;
+
+        let response = send(&mut client, &http_request("POST", "/create", TOKEN, r#"{"preset": "demo"}"#)).await;
+
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{}", response);
+        assert!(response.contains("Invalid create request: missing field `name`"), "{}", response);
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn create_request_is_passed_to_application() {
+        let (mut client, mut events) = connect().await;
+        let application = tokio::spawn(async move {
+            let Some(AutomationEvent::Request(request)) = events.next().await else {
+                panic!("expected a request");
+            };
+            request.respond(AutomationReply::accepted());
+            request.action
+        });
+
+        let body = r#"{"preset": "demo", "name": "my_project", "fields": {"language": "Rust"}, "options": {"git_init": true}}"#;
+        let response = send(&mut client, &http_request("POST", "/create", TOKEN, body)).await;
+
+        assert!(response.starts_with("HTTP/1.1 202 Accepted\r\n"), "{}", response);
+        let AutomationAction::Create(create) = application.await.unwrap() else {
+            panic!("expected a create action");
+        };
+        assert_eq!(create, CreateRequest {
+            preset: "demo".to_string(),
+            name: "my_project".to_string(),
+            fields: BTreeMap::from([("language".to_string(), "Rust".to_string())]),
+            options: BTreeMap::from([("git_init".to_string(), true)]),
+            destination: None,
+        });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn silent_client_times_out() {
+        let (mut client, mut events) = connect().await;
+
+        // Клиент ничего не отправляет; остановленное время сразу доходит до READ_TIMEOUT
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"), "{}", response);
+        assert!(events.next().await.is_none());
+    }
+}
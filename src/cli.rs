@@ -0,0 +1,701 @@
+//! # Модуль командной строки
+//!
+//! Подкоманды для скриптов и диагностики, выполняемые без запуска GUI.
+//! Если подкоманда не указана, приложение запускает графический интерфейс
+//! (в сборке без фичи `gui` - завершается с подсказкой).
+//!
+//! ## Подкоманды
+//!
+//! - `list-presets [--presets-dir PATH] [--broken]` (или `list`) - вывести таблицу установленных пресетов
+//! - `create --preset ID --name NAME [--dest DIR] [--field id=value]... [--option id=true|false]...
+//!   [--presets-dir PATH]` - создать проект без GUI, лог выводится в stdout
+//! - `refresh [--presets-dir PATH]` - скачать и распаковать пресеты
+//! - `validate-presets [--presets-dir PATH] [--preset ID] [--deny-warnings]` - проверить пресеты (для CI)
+//! - `export-schema [--output PATH]` - вывести JSON Schema `files_config.json`
+//!
+//! ## Параметры запуска GUI
+//!
+//! `--preset ID`, `--name NAME`, `--field id=value` (повторяемый) - предвыбор пресета
+//! и предзаполнение формы (см. `LaunchOptions`).
+//!
+//! ## Общие флаги
+//!
+//! `--portable` - хранить настройки и путь к пресетам рядом с исполняемым файлом
+//! (действует и для GUI, и для подкоманд).
+//!
+//! `--automation-port PORT` - запустить локальный сервер автоматизации для тестовых
+//! стендов (только при сборке с фичей `automation`, см. модуль `automation`).
+
+use ai_project_template::command::{is_valid_project_name, ProjectBuilder};
+use ai_project_template::presets::{
+    count_changed_presets, discover_preset_summaries, discover_presets_merged, download_and_extract_presets,
+    export_preset_config_schema, load_preset_config, preset_config_schema, preset_fingerprints, resolve_presets_dir,
+    ScanOptions,
+};
+use ai_project_template::settings::AppSettings;
+use ai_project_template::validation::{validate_presets, Severity, ValidationIssue};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Подкоманда командной строки
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    /// Вывести список установленных пресетов
+    ListPresets {
+        /// Директория пресетов, заданная флагом `--presets-dir`
+        presets_dir: Option<PathBuf>,
+        /// Включать ли пресеты с ошибкой разбора конфигурации
+        broken: bool,
+    },
+    /// Проверить пресеты и завершиться с ненулевым кодом при ошибках
+    ValidatePresets {
+        /// Директория пресетов, заданная флагом `--presets-dir`
+        presets_dir: Option<PathBuf>,
+        /// Проверить только один пресет
+        preset: Option<String>,
+        /// Считать предупреждения ошибками
+        deny_warnings: bool,
+    },
+    /// Вывести JSON Schema конфигурации пресета
+    ExportSchema {
+        /// Файл для записи схемы (`None` - стандартный вывод)
+        output: Option<PathBuf>,
+    },
+    /// Создать проект без запуска GUI
+    Create {
+        /// Директория пресетов, заданная флагом `--presets-dir`
+        presets_dir: Option<PathBuf>,
+        /// Идентификатор пресета
+        preset: String,
+        /// Имя проекта
+        name: String,
+        /// Родительская директория проекта (`None` - текущая директория)
+        destination: Option<PathBuf>,
+        /// Значения динамических полей (field_id, value) в порядке указания
+        fields: Vec<(String, String)>,
+        /// Значения опций (option_id, enabled) в порядке указания
+        options: Vec<(String, bool)>,
+    },
+    /// Скачать и распаковать пресеты (основной архив и дополнительные источники)
+    Refresh {
+        /// Директория пресетов, заданная флагом `--presets-dir`
+        presets_dir: Option<PathBuf>,
+    },
+}
+
+/// Параметры запуска GUI: предвыбранный пресет и предзаполненные значения формы
+///
+/// Заполняется через `LaunchOptions::set` по парам ключ/значение, поэтому один и тот же
+/// код разбора используется для аргументов командной строки и для параметров URL.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LaunchOptions {
+    /// Идентификатор пресета, который нужно выбрать после загрузки списка
+    pub preset: Option<String>,
+    /// Имя проекта для предзаполнения
+    pub name: Option<String>,
+    /// Значения динамических полей (field_id, value) в порядке указания
+    pub fields: Vec<(String, String)>,
+    /// Сервер автоматизации из флага `--automation-port` (не задается через `set`)
+    #[cfg(feature = "automation")]
+    pub automation: Option<crate::automation::AutomationServer>,
+}
+
+#[cfg(feature = "gui")]
+impl LaunchOptions {
+    /// Применить один параметр запуска
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - `preset`, `name` или `field`
+    /// * `value` - значение; для `field` в формате `id=value`
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "preset" => self.preset = Some(value.to_string()),
+            "name" => self.name = Some(value.to_string()),
+            "field" => self.fields.push(parse_assignment(value)?),
+            other => return Err(format!("Unknown launch parameter: {}", other)),
+        }
+        Ok(())
+    }
+}
+
+/// Флаг портативного режима
+pub const PORTABLE_FLAG: &str = "--portable";
+
+/// Извлечь флаг `--portable` из аргументов
+///
+/// # Returns
+///
+/// `true`, если флаг был указан (все его вхождения удаляются из `args`)
+pub fn take_portable_flag(args: &mut Vec<String>) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != PORTABLE_FLAG);
+    args.len() != before
+}
+
+/// Разобрать присваивание вида `id=value`
+pub fn parse_assignment(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((id, v)) if !id.trim().is_empty() => Ok((id.trim().to_string(), v.to_string())),
+        _ => Err(format!("Expected id=value, got '{}'", value)),
+    }
+}
+
+/// Разобрать значение опции вида `id=true` (`true`/`false`, `yes`/`no`, `1`/`0`)
+pub fn parse_option_assignment(value: &str) -> Result<(String, bool), String> {
+    let (id, flag) = parse_assignment(value)?;
+    let enabled = match flag.trim().to_lowercase().as_str() {
+        "true" | "yes" | "1" => true,
+        "false" | "no" | "0" => false,
+        other => return Err(format!("Option '{}' expects true or false, got '{}'", id, other)),
+    };
+    Ok((id, enabled))
+}
+
+/// Разобрать параметры запуска GUI (`--preset`, `--name`, `--field`)
+///
+/// # Arguments
+///
+/// * `args` - аргументы без имени исполняемого файла
+#[cfg(feature = "gui")]
+pub fn parse_launch_args(args: &[String]) -> Result<LaunchOptions, String> {
+    let mut options = LaunchOptions::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let key = arg.strip_prefix("--")
+            .ok_or_else(|| format!("Unexpected argument: {}", arg))?;
+        let value = iter.next()
+            .ok_or_else(|| format!("{} requires a value", arg))?;
+        options.set(key, value)?;
+    }
+    Ok(options)
+}
+
+/// Разобрать аргументы командной строки
+///
+/// # Arguments
+///
+/// * `args` - аргументы без имени исполняемого файла
+///
+/// # Returns
+///
+/// `Ok(None)` если подкоманда не указана (нужно запустить GUI),
+/// `Ok(Some(CliCommand))` для распознанной подкоманды,
+/// `Err(String)` с описанием ошибки разбора
+pub fn parse_args(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some(subcommand) = args.first() else {
+        return Ok(None);
+    };
+
+    match subcommand.as_str() {
+        "list-presets" | "list" => {
+            let mut presets_dir = None;
+            let mut broken = false;
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--presets-dir" => {
+                        let value = iter.next()
+                            .ok_or_else(|| "--presets-dir requires a path".to_string())?;
+                        presets_dir = Some(PathBuf::from(value));
+                    }
+                    "--broken" => broken = true,
+                    other => return Err(format!("Unknown argument for list-presets: {}", other)),
+                }
+            }
+            Ok(Some(CliCommand::ListPresets { presets_dir, broken }))
+        }
+        "validate-presets" => {
+            let mut presets_dir = None;
+            let mut preset = None;
+            let mut deny_warnings = false;
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--presets-dir" => {
+                        let value = iter.next()
+                            .ok_or_else(|| "--presets-dir requires a path".to_string())?;
+                        presets_dir = Some(PathBuf::from(value));
+                    }
+                    "--preset" => {
+                        let value = iter.next()
+                            .ok_or_else(|| "--preset requires a preset id".to_string())?;
+                        preset = Some(value.clone());
+                    }
+                    "--deny-warnings" => deny_warnings = true,
+                    other => return Err(format!("Unknown argument for validate-presets: {}", other)),
+                }
+            }
+            Ok(Some(CliCommand::ValidatePresets { presets_dir, preset, deny_warnings }))
+        }
+        "export-schema" => {
+            let mut output = None;
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--output" => {
+                        let value = iter.next()
+                            .ok_or_else(|| "--output requires a path".to_string())?;
+                        output = Some(PathBuf::from(value));
+                    }
+                    other => return Err(format!("Unknown argument for export-schema: {}", other)),
+                }
+            }
+            Ok(Some(CliCommand::ExportSchema { output }))
+        }
+        "create" => {
+            let mut presets_dir = None;
+            let mut preset = None;
+            let mut name = None;
+            let mut destination = None;
+            let mut fields = Vec::new();
+            let mut options = Vec::new();
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                let value = iter.next()
+                    .ok_or_else(|| format!("{} requires a value", arg))?;
+                match arg.as_str() {
+                    "--presets-dir" => presets_dir = Some(PathBuf::from(value)),
+                    "--preset" => preset = Some(value.clone()),
+                    "--name" => name = Some(value.clone()),
+                    "--dest" => destination = Some(PathBuf::from(value)),
+                    "--field" => fields.push(parse_assignment(value)?),
+                    "--option" => options.push(parse_option_assignment(value)?),
+                    other => return Err(format!("Unknown argument for create: {}", other)),
+                }
+            }
+            let preset = preset.ok_or_else(|| "create requires --preset ID".to_string())?;
+            let name = name.ok_or_else(|| "create requires --name NAME".to_string())?;
+            Ok(Some(CliCommand::Create { presets_dir, preset, name, destination, fields, options }))
+        }
+        "refresh" => {
+            let mut presets_dir = None;
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--presets-dir" => {
+                        let value = iter.next()
+                            .ok_or_else(|| "--presets-dir requires a path".to_string())?;
+                        presets_dir = Some(PathBuf::from(value));
+                    }
+                    other => return Err(format!("Unknown argument for refresh: {}", other)),
+                }
+            }
+            Ok(Some(CliCommand::Refresh { presets_dir }))
+        }
+        // Аргументы, начинающиеся с "-", относятся к GUI
+        other if other.starts_with('-') => Ok(None),
+        other => Err(format!("Unknown subcommand: {}", other)),
+    }
+}
+
+/// Выполнить подкоманду
+///
+/// # Returns
+///
+/// Код завершения процесса
+pub async fn run(command: CliCommand) -> i32 {
+    match command {
+        CliCommand::ListPresets { presets_dir, broken } => list_presets(presets_dir, broken),
+        CliCommand::ValidatePresets { presets_dir, preset, deny_warnings } => {
+            validate(presets_dir, preset.as_deref(), deny_warnings)
+        }
+        CliCommand::ExportSchema { output } => export_schema(output.as_deref()),
+        CliCommand::Create { presets_dir, preset, name, destination, fields, options } => {
+            create(presets_dir, &preset, &name, destination, fields, options)
+        }
+        CliCommand::Refresh { presets_dir } => refresh(presets_dir).await,
+    }
+}
+
+/// Создать проект и вывести лог
+///
+/// Код завершения: 0 - проект создан, 1 - ошибка создания, 2 - не найдены
+/// директория пресетов или пресет, либо имя проекта некорректно.
+fn create(
+    presets_dir: Option<PathBuf>,
+    preset: &str,
+    name: &str,
+    destination: Option<PathBuf>,
+    fields: Vec<(String, String)>,
+    options: Vec<(String, bool)>,
+) -> i32 {
+    let Some(dir) = resolve_presets_dir(presets_dir.as_deref()) else {
+        eprintln!("Presets directory is not configured. Use --presets-dir PATH.");
+        return 2;
+    };
+    if !is_valid_project_name(name) {
+        eprintln!("Error: Invalid project name: '{}'", name);
+        return 2;
+    }
+    let config = locate_preset(&dir, preset, &AppSettings::load())
+        .and_then(|root| load_preset_config(&root, preset).map(|config| (root, config)));
+    let (root, config) = match config {
+        Ok(found) => found,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return 2;
+        }
+    };
+    println!("Creating '{}' from preset '{}'", name, config.name);
+
+    let mut builder = ProjectBuilder::new(&root, preset).project_name(name);
+    if let Some(destination) = destination {
+        builder = builder.destination(destination);
+    }
+    for (id, value) in fields {
+        builder = builder.field(id, value);
+    }
+    for (id, enabled) in options {
+        builder = builder.option(id, enabled);
+    }
+    match builder.build() {
+        Ok(report) => {
+            for line in &report.log_lines {
+                println!("{}", line);
+            }
+            println!("Project created at {}", report.project_path.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+/// Найти директорию, содержащую пресет, так же, как список пресетов GUI
+///
+/// Просматриваются основная директория, директория пользовательских пресетов и
+/// поддиректории дополнительных источников (`discover_presets_merged`); пресет
+/// источника, совпадающий с основным, доступен как `id@источник`.
+///
+/// # Returns
+///
+/// Директория для `load_preset_config` и `ProjectBuilder::new` или `Err`, если
+/// пресет не найден
+fn locate_preset(presets_dir: &Path, preset: &str, settings: &AppSettings) -> Result<PathBuf, String> {
+    let options = ScanOptions { max_dirs: settings.max_scanned_dirs, cancel: None };
+    let user_dir = settings.user_presets_path();
+    let scan = discover_presets_merged(presets_dir, user_dir.as_deref(), &settings.preset_sources, &options)?;
+    scan.presets.into_iter()
+        .find(|(id, _)| id == preset)
+        .map(|(_, root)| root)
+        .ok_or_else(|| format!("Preset '{}' not found in {}", preset, presets_dir.display()))
+}
+
+/// Скачать основной архив пресетов и дополнительные источники из настроек
+///
+/// Код завершения: 0 - пресеты обновлены, 1 - ошибка загрузки, 2 - директория
+/// пресетов не задана.
+async fn refresh(presets_dir: Option<PathBuf>) -> i32 {
+    let Some(dir) = resolve_presets_dir(presets_dir.as_deref()) else {
+        eprintln!("Presets directory is not configured. Use --presets-dir PATH.");
+        return 2;
+    };
+    let settings = AppSettings::load();
+    let mut targets = vec![(dir.clone(), settings.presets_zip_url.clone())];
+    targets.extend(settings.preset_sources.iter().map(|source| (dir.join(&source.subdir), source.zip_url.clone())));
+
+    let mut changed = 0;
+    for (target_dir, zip_url) in targets {
+        println!("Downloading {} into {}", zip_url, target_dir.display());
+        match download_into(&target_dir, &zip_url, &settings).await {
+            Ok(count) => changed += count,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return 1;
+            }
+        }
+    }
+    println!("Presets changed: {}", changed);
+    0
+}
+
+/// Скачать архив в директорию
+///
+/// # Returns
+///
+/// Число изменившихся пресетов (сравнение отпечатков до и после загрузки)
+async fn download_into(target_dir: &Path, zip_url: &str, settings: &AppSettings) -> Result<usize, String> {
+    std::fs::create_dir_all(target_dir)
+        .map_err(|e| format!("Failed to create {:?}: {}", target_dir, e))?;
+    let before = preset_fingerprints(target_dir);
+    download_and_extract_presets(target_dir, zip_url, &settings.network, None, |_| {}).await?;
+    Ok(count_changed_presets(&before, &preset_fingerprints(target_dir)))
+}
+
+/// Записать JSON Schema конфигурации пресета в файл или вывести ее
+fn export_schema(output: Option<&std::path::Path>) -> i32 {
+    let Some(path) = output else {
+        println!("{:#}", preset_config_schema());
+        return 0;
+    };
+    match export_preset_config_schema(path) {
+        Ok(()) => {
+            println!("Schema written to {}", path.display());
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+/// Вывести таблицу пресетов: id, имя, описание, количество полей и опций, происхождение
+///
+/// Пресеты ищутся так же, как в `create` и в GUI: в основной и пользовательской
+/// директориях и в поддиректориях источников.
+fn list_presets(presets_dir: Option<PathBuf>, broken: bool) -> i32 {
+    let Some(dir) = resolve_presets_dir(presets_dir.as_deref()) else {
+        eprintln!("Presets directory is not configured. Use --presets-dir PATH.");
+        return 2;
+    };
+
+    let settings = AppSettings::load();
+    let options = ScanOptions { max_dirs: settings.max_scanned_dirs, cancel: None };
+    let user_dir = settings.user_presets_path();
+    let summaries = match discover_preset_summaries(&dir, user_dir.as_deref(), &settings.preset_sources, &options) {
+        Ok(summaries) => summaries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return 1;
+        }
+    };
+
+    println!("Presets directory: {}", dir.display());
+    println!("{:<20} {:<24} {:<40} {:>6} {:>7}  ORIGIN", "ID", "NAME", "DESCRIPTION", "FIELDS", "OPTIONS");
+    for summary in &summaries {
+        match &summary.error {
+            None => println!(
+                "{:<20} {:<24} {:<40} {:>6} {:>7}  {}",
+                summary.id,
+                summary.name,
+                truncate(&summary.description, 40),
+                summary.field_count,
+                summary.option_count,
+                summary.origin,
+            ),
+            Some(e) if broken => println!("{:<20} BROKEN: {}", summary.id, e),
+            Some(_) => {}
+        }
+    }
+
+    let broken_count = summaries.iter().filter(|s| s.error.is_some()).count();
+    if broken_count > 0 && !broken {
+        println!("({} broken preset(s) hidden, use --broken to show them)", broken_count);
+    }
+    0
+}
+
+/// Проверить пресеты и вывести проблемы, сгруппированные по пресетам
+///
+/// Формат строки проблемы стабилен: `<severity> <preset_id> <path>: <message>`.
+/// Код завершения: 0 - ошибок нет, 1 - найдены ошибки (или предупреждения
+/// при `--deny-warnings`), 2 - директория не задана или не читается, либо пресет не найден.
+fn validate(presets_dir: Option<PathBuf>, preset: Option<&str>, deny_warnings: bool) -> i32 {
+    let Some(presets_dir) = resolve_presets_dir(presets_dir.as_deref()) else {
+        eprintln!("Presets directory is not configured. Use --presets-dir PATH.");
+        return 2;
+    };
+
+    let mut report = match validate_presets(&presets_dir) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return 2;
+        }
+    };
+
+    if let Some(id) = preset {
+        report.retain(|preset_id, _| preset_id == id);
+        if report.is_empty() {
+            eprintln!("Error: preset '{}' not found in {}", id, presets_dir.display());
+            return 2;
+        }
+    }
+
+    let (lines, errors, warnings) = validation_output(&report);
+    for line in lines {
+        println!("{}", line);
+    }
+
+    if errors > 0 || (deny_warnings && warnings > 0) { 1 } else { 0 }
+}
+
+/// Строки вывода `validate-presets` и число ошибок и предупреждений
+fn validation_output(report: &BTreeMap<String, Vec<ValidationIssue>>) -> (Vec<String>, usize, usize) {
+    let mut lines = Vec::new();
+    let mut errors = 0;
+    let mut warnings = 0;
+    for (preset_id, issues) in report {
+        if issues.is_empty() {
+            lines.push(format!("ok {}", preset_id));
+            continue;
+        }
+        lines.push(format!("== {} ({} issue(s))", preset_id, issues.len()));
+        for issue in issues {
+            match issue.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+            lines.push(format!("{} {} {}: {}", issue.severity, preset_id, issue.path, issue.message));
+        }
+    }
+
+    lines.push(format!(
+        "Checked {} preset(s): {} error(s), {} warning(s)",
+        report.len(), errors, warnings
+    ));
+    (lines, errors, warnings)
+}
+
+/// Обрезать строку до `max` символов, добавив многоточие
+fn truncate(value: &str, max: usize) -> String {
+    if value.chars().count() <= max {
+        value.to_string()
+    } else {
+        let mut result: String = value.chars().take(max.saturating_sub(1)).collect();
+        result.push('…');
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_project_template::presets::PresetOrigin;
+    use ai_project_template::settings::PresetSource;
+    use std::fs;
+
+    /// Директория пресетов для `validate-presets`: `good` без проблем, `warned`
+    /// с предупреждением и `broken` с ошибкой разбора (удаляется в конце теста)
+    fn validation_fixture(name: &str) -> PathBuf {
+        let root = std::env::temp_dir()
+            .join(format!("ai_project_template_cli_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (preset_id, config) in [
+            ("good", r#"{"preset_id": "good", "preset_name": "Good", "description": "", "directories": ["src"]}"#),
+            ("warned", r#"{"preset_id": "warned", "preset_name": "Warned", "description": "", "directories": [],
+                "readme_template": "{licence}"}"#),
+            ("broken", r#"{"preset_id": "broken""#),
+        ] {
+            fs::create_dir_all(root.join(preset_id)).unwrap();
+            fs::write(root.join(preset_id).join("files_config.json"), config).unwrap();
+        }
+        root
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_validate_presets_flags() {
+        assert_eq!(
+            parse_args(&args(&["validate-presets", "--presets-dir", "p", "--preset", "demo", "--deny-warnings"])),
+            Ok(Some(CliCommand::ValidatePresets {
+                presets_dir: Some(PathBuf::from("p")),
+                preset: Some("demo".to_string()),
+                deny_warnings: true,
+            }))
+        );
+        // Без флага директория берется из окружения или конфиг-файла, как у list-presets
+        assert_eq!(
+            parse_args(&args(&["validate-presets"])),
+            Ok(Some(CliCommand::ValidatePresets { presets_dir: None, preset: None, deny_warnings: false }))
+        );
+        assert_eq!(
+            parse_args(&args(&["validate-presets", "--presets-dir", "p", "--fix"])),
+            Err("Unknown argument for validate-presets: --fix".to_string())
+        );
+    }
+
+    #[test]
+    fn validation_output_is_grouped_by_preset() {
+        let root = validation_fixture("validate_output");
+
+        let (lines, errors, warnings) = validation_output(&validate_presets(&root).unwrap());
+
+        assert_eq!(lines, vec![
+            "== broken (1 issue(s))",
+            "error broken files_config.json: Failed to parse preset config: EOF while parsing an object at line 1 column 22",
+            "ok good",
+            "== warned (1 issue(s))",
+            "warning warned readme_template: Placeholder {licence} does not match any field",
+            "Checked 3 preset(s): 1 error(s), 1 warning(s)",
+        ]);
+        assert_eq!((errors, warnings), (1, 1));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn validate_exit_codes() {
+        let root = validation_fixture("validate_exit");
+
+        assert_eq!(validate(Some(root.clone()), None, false), 1);
+        assert_eq!(validate(Some(root.clone()), Some("good"), true), 0);
+        assert_eq!(validate(Some(root.clone()), Some("warned"), false), 0);
+        assert_eq!(validate(Some(root.clone()), Some("warned"), true), 1);
+        assert_eq!(validate(Some(root.clone()), Some("missing"), false), 2);
+        assert_eq!(validate(Some(root.join("missing")), None, false), 2);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn create_finds_presets_like_the_gui() {
+        let root = std::env::temp_dir()
+            .join(format!("ai_project_template_cli_locate_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let presets_dir = root.join("presets");
+        let user_dir = root.join("user");
+        let config = |id: &str| format!(r#"{{"preset_id": "{}", "preset_name": "{}", "description": "", "directories": []}}"#, id, id);
+        for (dir, id) in [
+            (presets_dir.join("software"), "software"),
+            (user_dir.join("mine"), "mine"),
+            (presets_dir.join("company").join("software"), "software"),
+            (presets_dir.join("company").join("handbook"), "handbook"),
+        ] {
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("files_config.json"), config(id)).unwrap();
+        }
+        let settings = AppSettings {
+            user_presets_dir: Some(user_dir.clone()),
+            preset_sources: vec![PresetSource {
+                name: "company".to_string(),
+                zip_url: "https://git.example.com/presets.zip".to_string(),
+                subdir: "company".to_string(),
+            }],
+            ..AppSettings::default()
+        };
+
+        assert_eq!(locate_preset(&presets_dir, "software", &settings), Ok(presets_dir.clone()));
+        assert_eq!(locate_preset(&presets_dir, "mine", &settings), Ok(user_dir.clone()));
+        assert_eq!(locate_preset(&presets_dir, "handbook", &settings), Ok(presets_dir.join("company")));
+        let shadowed = locate_preset(&presets_dir, "software@company", &settings).unwrap();
+        assert_eq!(
+            load_preset_config(&shadowed, "software@company").unwrap().source_dir,
+            Some(presets_dir.join("company").join("software"))
+        );
+        assert_eq!(
+            locate_preset(&presets_dir, "missing", &settings),
+            Err(format!("Preset 'missing' not found in {}", presets_dir.display()))
+        );
+
+        // `list-presets` показывает ровно те пресеты, которые принимает `create`
+        let options = ScanOptions { max_dirs: settings.max_scanned_dirs, cancel: None };
+        let summaries = discover_preset_summaries(&presets_dir, Some(&user_dir), &settings.preset_sources, &options).unwrap();
+        let ids: Vec<&str> = summaries.iter().map(|summary| summary.id.as_str()).collect();
+        assert_eq!(ids, ["handbook", "mine", "software", "software@company"]);
+        for summary in &summaries {
+            assert_eq!(summary.error, None);
+            assert!(locate_preset(&presets_dir, &summary.id, &settings).is_ok(), "{}", summary.id);
+        }
+        assert_eq!(summaries[1].origin, PresetOrigin::User);
+        assert_eq!(summaries[1].path, user_dir.join("mine"));
+        assert_eq!(summaries[3].path, presets_dir.join("company").join("software"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
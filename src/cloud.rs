@@ -0,0 +1,210 @@
+//! # Модуль обнаружения папок облачной синхронизации
+//!
+//! Клиенты синхронизации (OneDrive, Dropbox, Google Drive, iCloud) блокируют файлы
+//! во время выгрузки, из-за чего копирование шаблонов может завершиться ошибкой или
+//! проект синхронизируется частично. `detect_cloud_sync` эвристически определяет,
+//! находится ли путь внутри такой папки:
+//!
+//! - путь лежит внутри корня синхронизации из переменных окружения (`OneDrive`,
+//!   `OneDriveConsumer`, `OneDriveCommercial`) или из известных мест в домашней
+//!   директории (`Dropbox`, `OneDrive`, `Google Drive`, `Library/CloudStorage`,
+//!   `Library/Mobile Documents`);
+//! - одна из родительских директорий содержит маркер `.dropbox` или `desktop.ini`,
+//!   в котором упоминается клиент синхронизации.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Клиент облачной синхронизации
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncProvider {
+    /// Microsoft OneDrive
+    OneDrive,
+    /// Dropbox
+    Dropbox,
+    /// Google Drive
+    GoogleDrive,
+    /// iCloud Drive
+    ICloud,
+    /// Другой клиент (`~/Library/CloudStorage` на macOS)
+    Other,
+}
+
+impl fmt::Display for SyncProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncProvider::OneDrive => write!(f, "OneDrive"),
+            SyncProvider::Dropbox => write!(f, "Dropbox"),
+            SyncProvider::GoogleDrive => write!(f, "Google Drive"),
+            SyncProvider::ICloud => write!(f, "iCloud Drive"),
+            SyncProvider::Other => write!(f, "a cloud storage provider"),
+        }
+    }
+}
+
+/// Найденная папка синхронизации
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudSync {
+    /// Клиент синхронизации
+    pub provider: SyncProvider,
+    /// Корень синхронизируемой папки (или директория с маркером)
+    pub root: PathBuf,
+}
+
+impl CloudSync {
+    /// Текст предупреждения для лога и формы
+    pub fn warning(&self) -> String {
+        format!(
+            "The project is inside a folder synced by {} ({}). The sync client may lock files while they are copied; creation retries locked files, but consider creating the project outside the synced folder.",
+            self.provider,
+            self.root.display()
+        )
+    }
+}
+
+/// Корни синхронизации из переменных окружения и известных мест домашней директории
+pub fn known_sync_roots() -> Vec<(SyncProvider, PathBuf)> {
+    let mut roots = Vec::new();
+    for var in ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"] {
+        if let Ok(value) = env::var(var) {
+            if !value.is_empty() {
+                roots.push((SyncProvider::OneDrive, PathBuf::from(value)));
+            }
+        }
+    }
+
+    if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        let home = PathBuf::from(home);
+        roots.extend([
+            (SyncProvider::Dropbox, home.join("Dropbox")),
+            (SyncProvider::OneDrive, home.join("OneDrive")),
+            (SyncProvider::GoogleDrive, home.join("Google Drive")),
+            (SyncProvider::ICloud, home.join("Library").join("Mobile Documents")),
+            (SyncProvider::Other, home.join("Library").join("CloudStorage")),
+        ]);
+    }
+    roots
+}
+
+/// Определить, находится ли путь внутри папки облачной синхронизации
+///
+/// # Arguments
+///
+/// * `path` - путь проекта (может еще не существовать)
+///
+/// # Returns
+///
+/// `Some(CloudSync)` для первого совпадения или `None`
+pub fn detect_cloud_sync(path: &Path) -> Option<CloudSync> {
+    detect_cloud_sync_with(path, &known_sync_roots())
+}
+
+/// То же, что `detect_cloud_sync`, но с заданным списком корней синхронизации
+pub fn detect_cloud_sync_with(path: &Path, roots: &[(SyncProvider, PathBuf)]) -> Option<CloudSync> {
+    let matched_root = roots.iter()
+        .filter(|(_, root)| path.starts_with(root))
+        .max_by_key(|(_, root)| root.components().count());
+    if let Some((provider, root)) = matched_root {
+        return Some(CloudSync { provider: *provider, root: root.clone() });
+    }
+
+    path.ancestors()
+        .find_map(|dir| marker_provider(dir).map(|provider| CloudSync { provider, root: dir.to_path_buf() }))
+}
+
+/// Клиент синхронизации по маркерам в директории
+fn marker_provider(dir: &Path) -> Option<SyncProvider> {
+    if dir.join(".dropbox").exists() {
+        return Some(SyncProvider::Dropbox);
+    }
+    // desktop.ini есть во многих папках Windows - учитывается только упоминание клиента
+    let ini = fs::read(dir.join("desktop.ini")).ok()?;
+    let content = String::from_utf8_lossy(&ini).replace('\0', "").to_lowercase();
+    if content.contains("onedrive") {
+        Some(SyncProvider::OneDrive)
+    } else if content.contains("dropbox") {
+        Some(SyncProvider::Dropbox)
+    } else if content.contains("google drive") || content.contains("googledrive") {
+        Some(SyncProvider::GoogleDrive)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Временная директория теста (удаляется в конце теста)
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("ai_project_template_cloud_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn path_inside_known_root_is_detected() {
+        let roots = [
+            (SyncProvider::OneDrive, PathBuf::from("/home/user/OneDrive")),
+            (SyncProvider::Dropbox, PathBuf::from("/home/user/Dropbox")),
+        ];
+
+        let sync = detect_cloud_sync_with(Path::new("/home/user/Dropbox/work/my_project"), &roots).unwrap();
+
+        assert_eq!(sync, CloudSync { provider: SyncProvider::Dropbox, root: PathBuf::from("/home/user/Dropbox") });
+        assert!(sync.warning().contains("synced by Dropbox (/home/user/Dropbox)"));
+    }
+
+    #[test]
+    fn nested_root_wins_over_its_parent() {
+        let roots = [
+            (SyncProvider::Other, PathBuf::from("/home/user/Library/CloudStorage")),
+            (SyncProvider::GoogleDrive, PathBuf::from("/home/user/Library/CloudStorage/GoogleDrive")),
+        ];
+
+        let sync = detect_cloud_sync_with(Path::new("/home/user/Library/CloudStorage/GoogleDrive/p"), &roots).unwrap();
+
+        assert_eq!(sync.provider, SyncProvider::GoogleDrive);
+    }
+
+    #[test]
+    fn root_prefix_without_separator_does_not_match() {
+        let roots = [(SyncProvider::Dropbox, PathBuf::from("/home/user/Dropbox"))];
+
+        assert_eq!(detect_cloud_sync_with(Path::new("/home/user/Dropbox-old/my_project"), &roots), None);
+    }
+
+    #[test]
+    fn markers_in_parent_directories_are_detected() {
+        let dir = temp_dir("markers");
+        let dropbox = dir.join("box");
+        fs::create_dir_all(dropbox.join("work")).unwrap();
+        fs::write(dropbox.join(".dropbox"), "").unwrap();
+        // desktop.ini обычно записан в UTF-16: NUL между символами не мешает поиску
+        let onedrive = dir.join("drive");
+        fs::create_dir_all(&onedrive).unwrap();
+        let ini: Vec<u8> = "[.ShellClassInfo]\r\nIconResource=C:\\OneDrive\\icon.ico"
+            .bytes()
+            .flat_map(|b| [b, 0])
+            .collect();
+        fs::write(onedrive.join("desktop.ini"), ini).unwrap();
+
+        let sync = detect_cloud_sync_with(&dropbox.join("work").join("my_project"), &[]).unwrap();
+        assert_eq!(sync, CloudSync { provider: SyncProvider::Dropbox, root: dropbox.clone() });
+        let sync = detect_cloud_sync_with(&onedrive.join("my_project"), &[]).unwrap();
+        assert_eq!(sync, CloudSync { provider: SyncProvider::OneDrive, root: onedrive.clone() });
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn desktop_ini_without_sync_client_is_ignored() {
+        let dir = temp_dir("plain_ini");
+        fs::write(dir.join("desktop.ini"), "[.ShellClassInfo]\r\nIconResource=shell32.dll,4").unwrap();
+
+        assert_eq!(detect_cloud_sync_with(&dir.join("my_project"), &[]), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
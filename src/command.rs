@@ -3,11 +3,708 @@
 //! Этот модуль содержит логику создания структуры проекта на основе конфигурации пресета.
 //! Все операции создания проекта выполняются синхронно и возвращают детальный лог операций.
 
-use crate::presets::PresetConfig;
+use crate::cloud::{detect_cloud_sync, CloudSync};
+use crate::locale::tr;
+use crate::manifest::{copy_hashed, hash_bytes, hash_file, write_hashed, ProjectManifest};
+use crate::operation::check_cancelled;
+use crate::presets::{copy_dir_recursive, field_visible, load_preset_config, normalize_preset_config, resolved_field_values, FieldConfig, LineEnding, PresetConfig, TemplateConfig};
+use crate::retry::{retry_io, RetryPolicy};
+use crate::substitution::{placeholder_values, substitute_path_placeholders, substitute_placeholders};
+use crate::timing::{PhaseTimer, TimingReport, SLOW_OPERATION_THRESHOLD};
+use crate::validation::case_collisions;
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
-use std::io::Write;
-use std::path::Path;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::process;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Встроенные опции создания проекта, которые не объявляются в пресете
+///
+/// `create_project` понимает их для любого пресета, поэтому `ProjectBuilder`
+/// не считает их неизвестными.
+pub const BUILTIN_OPTIONS: &[&str] = &["force", "refresh", "dry_run", "git_init", "no_atomic"];
+
+/// Имя файла исключений git (см. `PresetConfig::gitignore_template`)
+pub const GITIGNORE_FILE_NAME: &str = ".gitignore";
+
+/// Сообщение первого коммита проекта (опция "git_init")
+pub const INITIAL_COMMIT_MESSAGE: &str = "Initial commit";
+
+/// Имя файла, сохраняющего пустую директорию в git (см. `PresetConfig::keep_empty_dirs`)
+pub const GITKEEP_FILE_NAME: &str = ".gitkeep";
+
+/// Порог размера файла по умолчанию, начиная с которого файл считается большим (100 MB)
+pub const DEFAULT_LARGE_FILE_THRESHOLD: u64 = 100 * 1024 * 1024;
+
+/// Мягкий лимит общего размера проекта по умолчанию, сверх которого создание
+/// требует подтверждения (1 GB)
+pub const DEFAULT_PROJECT_SIZE_LIMIT: u64 = 1024 * 1024 * 1024;
+
+/// Файл шаблона, который будет записан в проект
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedFile {
+    /// Путь к файлу-источнику в директории пресета
+    pub source: PathBuf,
+    /// Путь к файлу относительно директории проекта
+    pub destination: PathBuf,
+    /// Размер файла в байтах (0, если источник не найден)
+    pub bytes: u64,
+    /// Будет создана жесткая ссылка вместо копии
+    pub link: bool,
+    /// Файл назначения уже существует и не будет перезаписан (см. `ProjectPlan::skip_existing`)
+    pub skipped: bool,
+}
+
+/// План создания проекта: файлы шаблонов с их размерами
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectPlan {
+    /// Файлы шаблонов в порядке объявления в конфигурации
+    pub files: Vec<PlannedFile>,
+    /// Пути, различающиеся только регистром (см. `validation::case_collisions`)
+    pub case_collisions: Vec<(String, String)>,
+}
+
+impl ProjectPlan {
+    /// Общий размер файлов шаблонов в байтах
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|f| f.bytes).sum()
+    }
+
+    /// Размер файлов, которые действительно будут записаны (без пропущенных)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::command::{PlannedFile, ProjectPlan};
+    /// use std::path::PathBuf;
+    ///
+    /// let file = |name: &str, bytes: u64, skipped: bool| PlannedFile {
+    ///     source: PathBuf::from(name),
+    ///     destination: PathBuf::from(name),
+    ///     bytes,
+    ///     link: false,
+    ///     skipped,
+    /// };
+    /// let plan = ProjectPlan {
+    ///     files: vec![
+    ///         file("video.mp4", 900, false),
+    ///         file("old.bin", 5000, true),
+    ///         file("notes.md", 10, false),
+    ///         file("image.png", 300, false),
+    ///     ],
+    ///     case_collisions: Vec::new(),
+    /// };
+    /// assert_eq!(plan.total_bytes(), 6210);
+    /// // Существующий файл не перезаписывается и места не занимает
+    /// assert_eq!(plan.projected_bytes(), 1210);
+    /// let largest: Vec<_> = plan.largest_files(2).iter().map(|f| f.bytes).collect();
+    /// assert_eq!(largest, [900, 300]);
+    /// assert_eq!(plan.large_files(1000).len(), 0);
+    /// ```
+    pub fn projected_bytes(&self) -> u64 {
+        self.written_files().map(|f| f.bytes).sum()
+    }
+
+    /// Файлы, которые будут записаны, размер которых не меньше `threshold` байт
+    pub fn large_files(&self, threshold: u64) -> Vec<&PlannedFile> {
+        self.written_files().filter(|f| f.bytes >= threshold).collect()
+    }
+
+    /// До `count` самых больших записываемых файлов, от большего к меньшему
+    pub fn largest_files(&self, count: usize) -> Vec<&PlannedFile> {
+        let mut files: Vec<&PlannedFile> = self.written_files().collect();
+        files.sort_by_key(|file| std::cmp::Reverse(file.bytes));
+        files.truncate(count);
+        files
+    }
+
+    /// Отметить файлы, которые не будут записаны: назначение уже существует, а
+    /// опция "refresh" выключена (те же условия, что и в `create_project`)
+    ///
+    /// # Arguments
+    ///
+    /// * `project_path` - директория проекта
+    /// * `refresh` - включена ли опция "refresh"
+    pub fn skip_existing(&mut self, project_path: &Path, refresh: bool) {
+        for file in &mut self.files {
+            file.skipped = !refresh && project_path.join(&file.destination).exists();
+        }
+    }
+
+    /// Файлы, которые будут записаны
+    fn written_files(&self) -> impl Iterator<Item = &PlannedFile> {
+        self.files.iter().filter(|f| !f.skipped)
+    }
+}
+
+/// Составить план создания проекта по конфигурации пресета
+///
+/// Читает только метаданные файлов-источников, ничего не записывает.
+///
+/// # Arguments
+///
+/// * `presets_dir` - корневая директория со всеми пресетами
+/// * `preset_config` - конфигурация пресета
+pub fn plan_project(presets_dir: &Path, preset_config: &PresetConfig) -> ProjectPlan {
+    let preset_source_dir = preset_config.source_dir(presets_dir);
+    let files = preset_config.templates.iter()
+        .map(|template| {
+            let source = preset_source_dir.join(&template.source);
+            let bytes = fs::metadata(&source).map(|m| m.len()).unwrap_or(0);
+            PlannedFile {
+                source,
+                destination: PathBuf::from(&template.destination),
+                bytes,
+                link: template.link,
+                skipped: false,
+            }
+        })
+        .collect();
+    ProjectPlan { files, case_collisions: case_collisions(preset_config) }
+}
+
+/// Отформатировать размер в байтах для отображения ("480 MB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Результат создания проекта
+///
+/// Возвращается `ProjectBuilder::build` и содержит всё, что нужно вызывающему коду
+/// для отображения итога операции: путь, лог, предупреждения и длительность.
+#[derive(Debug, Clone, Default)]
+pub struct CreationReport {
+    /// Имя проекта
+    pub project_name: String,
+    /// Идентификатор использованного пресета
+    pub preset_id: String,
+    /// Полный путь к директории проекта
+    pub project_path: PathBuf,
+    /// Был ли это пробный запуск (без изменений на диске)
+    pub dry_run: bool,
+    /// Строки лога выполнения операции
+    pub log_lines: Vec<String>,
+    /// Предупреждения, выделенные из лога (например, отсутствующие шаблоны)
+    pub warnings: Vec<String>,
+    /// Файлы шаблонов с размерами (из плана создания)
+    pub files: Vec<PlannedFile>,
+    /// Общий размер файлов шаблонов в байтах
+    pub total_bytes: u64,
+    /// Длительность операции
+    pub duration: Duration,
+    /// Длительность этапов (и медленных операций в подробном режиме)
+    pub timing: TimingReport,
+    /// Папка облачной синхронизации, внутри которой создан проект
+    pub cloud_sync: Option<CloudSync>,
+    /// Шаблоны, пропущенные из-за отсутствующего файла-источника
+    /// (можно докопировать через `rerun_missing_templates`)
+    pub missing_templates: Vec<MissingTemplate>,
+}
+
+/// Шаблон, пропущенный при создании проекта: файл-источник не найден
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingTemplate {
+    /// Путь к источнику относительно директории пресета
+    pub source: String,
+    /// Путь назначения относительно директории проекта
+    pub destination: String,
+}
+
+/// Построитель для программного создания проектов
+///
+/// Загружает конфигурацию пресета, проверяет имя проекта, обязательные поля и
+/// идентификаторы опций, после чего делегирует работу `create_project`.
+///
+/// # Example
+///
+/// ```no_run
+/// use ai_project_template::command::ProjectBuilder;
+///
+/// let report = ProjectBuilder::new("/path/to/presets", "software")
+///     .project_name("my_project")
+///     .destination("/path/to/work")
+///     .field("language", "Rust")
+///     .option("git_init", true)
+///     .dry_run(true)
+///     .build()?;
+///
+/// for line in &report.log_lines {
+///     println!("{}", line);
+/// }
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProjectBuilder {
+    presets_dir: PathBuf,
+    preset_id: String,
+    project_name: Option<String>,
+    destination: Option<PathBuf>,
+    fields: HashMap<String, String>,
+    options: HashMap<String, bool>,
+    dry_run: bool,
+    verbose: bool,
+    cloud_sync_warning: bool,
+    progress: Option<ProgressSink>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+/// Обработчик прогресса: `(шаг, всего, описание)`
+type ProgressFn = dyn Fn(usize, usize, &str) + Send + Sync;
+
+/// Получатель прогресса создания проекта (см. `ProjectBuilder::on_progress`)
+#[derive(Clone)]
+struct ProgressSink(Arc<ProgressFn>);
+
+impl fmt::Debug for ProgressSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressSink")
+    }
+}
+
+impl ProjectBuilder {
+    /// Создать построитель для пресета `preset_id` из директории `presets_dir`
+    pub fn new(presets_dir: impl Into<PathBuf>, preset_id: impl Into<String>) -> Self {
+        Self {
+            presets_dir: presets_dir.into(),
+            preset_id: preset_id.into(),
+            project_name: None,
+            destination: None,
+            fields: HashMap::new(),
+            options: HashMap::new(),
+            dry_run: false,
+            verbose: false,
+            cloud_sync_warning: true,
+            progress: None,
+            cancel: None,
+        }
+    }
+
+    /// Задать имя проекта (обязательно)
+    pub fn project_name(mut self, name: impl Into<String>) -> Self {
+        self.project_name = Some(name.into());
+        self
+    }
+
+    /// Задать родительскую директорию проекта (по умолчанию текущая директория)
+    pub fn destination(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.destination = Some(dir.into());
+        self
+    }
+
+    /// Задать значение динамического поля пресета
+    pub fn field(mut self, id: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.insert(id.into(), value.into());
+        self
+    }
+
+    /// Задать значения нескольких динамических полей
+    pub fn fields(mut self, fields: HashMap<String, String>) -> Self {
+        self.fields.extend(fields);
+        self
+    }
+
+    /// Задать значение опции пресета
+    pub fn option(mut self, id: impl Into<String>, enabled: bool) -> Self {
+        self.options.insert(id.into(), enabled);
+        self
+    }
+
+    /// Задать значения нескольких опций
+    pub fn options(mut self, options: HashMap<String, bool>) -> Self {
+        self.options.extend(options);
+        self
+    }
+
+    /// Сообщать о прогрессе создания: `callback(шаг, всего шагов, описание)` вызывается
+    /// перед каждой директорией, шаблоном, пустым файлом и генерацией README
+    /// (см. `create_project_timed`). Вызывается из потока, выполняющего `build`.
+    pub fn on_progress(mut self, callback: impl Fn(usize, usize, &str) + Send + Sync + 'static) -> Self {
+        self.progress = Some(ProgressSink(Arc::new(callback)));
+        self
+    }
+
+    /// Задать флаг отмены: установленный флаг прерывает создание перед следующим шагом
+    /// с ошибкой `operation::CANCELLED`, новый проект при этом не остается
+    /// (см. `create_project_timed`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::AtomicBool;
+    /// use std::sync::Arc;
+    /// use ai_project_template::command::ProjectBuilder;
+    /// use ai_project_template::operation::CANCELLED;
+    ///
+    /// let root = std::env::temp_dir().join(format!("ai_project_template_cancel_{}", std::process::id()));
+    /// let presets_dir = root.join("presets");
+    /// std::fs::create_dir_all(presets_dir.join("demo")).unwrap();
+    /// std::fs::write(presets_dir.join("demo").join("files_config.json"), r#"{
+    ///     "preset_id": "demo", "preset_name": "Demo", "description": "",
+    ///     "directories": ["src"], "empty_files": ["plan.md"], "fields": [], "options": [], "templates": []
+    /// }"#).unwrap();
+    ///
+    /// // Флаг уже установлен (пользователь нажал Cancel): ничего не создается
+    /// let result = ProjectBuilder::new(&presets_dir, "demo")
+    ///     .project_name("my_project")
+    ///     .destination(&root)
+    ///     .cancel_flag(Arc::new(AtomicBool::new(true)))
+    ///     .build();
+    /// assert_eq!(result.unwrap_err(), CANCELLED);
+    /// assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1);
+    /// std::fs::remove_dir_all(&root).unwrap();
+    /// ```
+    pub fn cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Включить пробный запуск: все шаги создания выполняются без изменений на диске,
+    /// а лог отчета описывает планируемые действия (см. опцию "dry_run" в `create_project`)
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Включить подробные замеры: записывать файловые операции дольше
+    /// `SLOW_OPERATION_THRESHOLD` в `CreationReport::timing`
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Предупреждать в логе, если проект создается в папке облачной синхронизации
+    /// (по умолчанию включено). Повторы заблокированных файлов включаются в любом случае.
+    pub fn cloud_sync_warning(mut self, warn: bool) -> Self {
+        self.cloud_sync_warning = warn;
+        self
+    }
+
+    /// Проверить параметры и создать проект
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку если:
+    /// - имя проекта не задано или невалидно
+    /// - конфигурация пресета не может быть загружена
+    /// - два разных шаблона пишут в один файл (см. `normalize_preset_config`)
+    /// - не заполнено обязательное поле (или значение select-поля не входит в его опции);
+    ///   поля, скрытые выключенной опцией (`depends_on_option`), не проверяются
+    /// - передана опция, которой нет в пресете и среди `BUILTIN_OPTIONS`
+    /// - пути проекта различаются только регистром, а файловая система назначения
+    ///   не различает регистр
+    /// - `create_project` завершился с ошибкой
+    pub fn build(self) -> Result<CreationReport, String> {
+        let started = Instant::now();
+        let mut timer = if self.verbose {
+            PhaseTimer::verbose(SLOW_OPERATION_THRESHOLD)
+        } else {
+            PhaseTimer::new()
+        };
+        timer.start("pre-flight");
+
+        let project_name = self.project_name
+            .ok_or_else(|| "Project name is not set".to_string())?;
+        if !is_valid_project_name(&project_name) {
+            return Err(format!("Invalid project name: '{}'", project_name));
+        }
+
+        let normalized = normalize_preset_config(&load_preset_config(&self.presets_dir, &self.preset_id)?)?;
+        let preset_config = normalized.config;
+
+        for field in &preset_config.fields {
+            if field.required && field_visible(&preset_config, field, &self.options) && !field.is_filled(self.fields.get(&field.id)) {
+                return Err(match self.fields.get(&field.id).filter(|v| !v.trim().is_empty()) {
+                    Some(value) => format!("Value '{}' of required field '{}' is not one of its options", value, field.id),
+                    None => format!("Required field '{}' is empty", field.id),
+                });
+            }
+        }
+
+        let mut option_ids: Vec<&String> = self.options.keys().collect();
+        option_ids.sort();
+        for option_id in option_ids {
+            let known = preset_config.options.iter().any(|o| &o.id == option_id)
+                || BUILTIN_OPTIONS.contains(&option_id.as_str());
+            if !known {
+                return Err(format!(
+                    "Unknown option '{}' for preset '{}'",
+                    option_id, preset_config.id
+                ));
+            }
+        }
+
+        let destination = match self.destination {
+            Some(dir) => dir,
+            None => std::env::current_dir()
+                .map_err(|e| format!("Failed to determine current directory: {}", e))?,
+        };
+        let project_path = destination.join(&project_name);
+        let plan = plan_project(&self.presets_dir, &preset_config);
+
+        let collision_warnings: Vec<String> = plan.case_collisions.iter()
+            .map(|(first, second)| format!("Warning: Paths '{}' and '{}' differ only by case", first, second))
+            .collect();
+        // Пробный запуск задается методом `dry_run` или опцией "dry_run"
+        let dry_run = self.dry_run || self.options.get("dry_run").copied().unwrap_or(false);
+        if !dry_run && !plan.case_collisions.is_empty() && is_case_insensitive_fs(&destination)? {
+            let (first, second) = &plan.case_collisions[0];
+            return Err(format!(
+                "Paths '{}' and '{}' differ only by case, but the filesystem at {:?} is case-insensitive",
+                first, second, destination
+            ));
+        }
+
+        let mut log_lines = normalized.warnings;
+        log_lines.extend(collision_warnings);
+        log_lines.extend(preset_config.fields.iter().filter_map(FieldConfig::unknown_type_warning));
+
+        // Пути назначения с плейсхолдерами проверяются до записи первого файла.
+        // Пропущенные шаблоны определяются по тем же условиям, что и в create_project
+        let refresh = self.options.get("refresh").copied().unwrap_or(false);
+        let preset_source_dir = preset_config.source_dir(&self.presets_dir);
+        let datetime = readme_datetime();
+        let fields = resolved_field_values(&preset_config, &project_name, &self.fields, &self.options)?;
+        let values = placeholder_values(&project_name, &datetime, &fields);
+        let mut missing_templates = Vec::new();
+        for template in &preset_config.templates {
+            let destination = template_destination(template, &values)?;
+            let skipped = !refresh && project_path.join(&destination).exists();
+            if !skipped && !preset_source_dir.join(&template.source).exists() {
+                missing_templates.push(MissingTemplate { source: template.source.clone(), destination });
+            }
+        }
+
+        // Клиент синхронизации может заблокировать только что записанный файл
+        let cloud_sync = detect_cloud_sync(&project_path);
+        let retry = match cloud_sync {
+            Some(ref sync) => {
+                if self.cloud_sync_warning {
+                    log_lines.push(format!("Warning: {}", sync.warning()));
+                }
+                RetryPolicy::cloud_sync()
+            }
+            None => RetryPolicy::transient(),
+        };
+        let mut options = self.options;
+        options.insert("dry_run".to_string(), dry_run);
+        let progress = self.progress;
+        log_lines.extend(create_project_timed(
+            &project_path,
+            &self.presets_dir,
+            &preset_config,
+            &project_name,
+            &self.fields,
+            &options,
+            &mut timer,
+            &retry,
+            &|step, total, message| {
+                if let Some(ProgressSink(ref callback)) = progress {
+                    callback(step, total, message);
+                }
+            },
+            self.cancel.as_deref(),
+        )?);
+
+        let warnings = log_lines.iter()
+            .filter(|l| l.starts_with("Warning:"))
+            .cloned()
+            .collect();
+
+        Ok(CreationReport {
+            project_name,
+            preset_id: preset_config.id,
+            project_path,
+            dry_run,
+            log_lines,
+            warnings,
+            total_bytes: plan.total_bytes(),
+            files: plan.files,
+            duration: started.elapsed(),
+            timing: timer.into_report(),
+            cloud_sync,
+            missing_templates,
+        })
+    }
+}
+
+/// Проверить валидность имени проекта
+///
+/// Имя проекта должно соответствовать следующим правилам:
+/// - Начинаться с буквы или цифры
+/// - Содержать только буквы, цифры, точки, подчеркивания и дефисы
+/// - Длина от 1 до 64 символов
+/// - Не заканчиваться точкой или пробелом
+/// - Не быть зарезервированным именем Windows (CON, PRN, AUX, NUL, COM1-9, LPT1-9)
+///
+/// # Arguments
+///
+/// * `name` - строка с именем проекта для проверки
+///
+/// # Returns
+///
+/// `true` если имя валидно, иначе `false`
+///
+/// # Examples
+///
+/// ```
+/// use ai_project_template::command::is_valid_project_name;
+///
+/// assert!(is_valid_project_name("my_project"));
+/// assert!(is_valid_project_name("test-123"));
+/// assert!(!is_valid_project_name("CON")); // зарезервированное имя Windows
+/// assert!(!is_valid_project_name("")); // пустое имя
+/// ```
+pub fn is_valid_project_name(name: &str) -> bool {
+    use regex::Regex;
+    use std::sync::OnceLock;
+    static NAME_REGEX: OnceLock<Regex> = OnceLock::new();
+    let ok = NAME_REGEX
+        .get_or_init(|| Regex::new(r"^[A-Za-z0-9][A-Za-z0-9._-]{0,63}$").unwrap())
+        .is_match(name);
+    if !ok { return false; }
+    if name.ends_with('.') || name.ends_with(' ') { return false; }
+    const RESERVED: &[&str] = &[
+        "CON","PRN","AUX","NUL","COM1","COM2","COM3","COM4","COM5","COM6","COM7","COM8","COM9",
+        "LPT1","LPT2","LPT3","LPT4","LPT5","LPT6","LPT7","LPT8","LPT9"
+    ];
+    let upper = name.to_ascii_uppercase();
+    !RESERVED.iter().any(|&r| r == upper)
+}
+
+/// Состояние директории, в которой будет создан проект
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetDirStatus {
+    /// Директории нет - она будет создана
+    Missing,
+    /// Директория существует и пуста - проект будет создан в ней
+    Empty,
+    /// Директория (или файл с таким именем) существует и не пуста - нужна опция "force"
+    NotEmpty,
+}
+
+/// Определить состояние директории проекта
+///
+/// Использует ту же проверку, что и `create_project`: непустая существующая
+/// директория без опции "force" приводит к ошибке.
+///
+/// # Arguments
+///
+/// * `project_path` - путь к директории проекта (`destination/name`)
+pub fn target_dir_status(project_path: &Path) -> TargetDirStatus {
+    if !project_path.exists() {
+        return TargetDirStatus::Missing;
+    }
+    match project_path.read_dir() {
+        Ok(mut entries) => match entries.next() {
+            None => TargetDirStatus::Empty,
+            Some(_) => TargetDirStatus::NotEmpty,
+        },
+        Err(_) => TargetDirStatus::NotEmpty,
+    }
+}
+
+/// Проверить, что директория существует и в нее можно записывать
+///
+/// Создает и сразу удаляет временный файл - это надежнее проверки атрибутов,
+/// которые не учитывают ACL и сетевые диски.
+///
+/// # Arguments
+///
+/// * `dir` - проверяемая директория (например, директория назначения проекта)
+///
+/// # Returns
+///
+/// `Ok(())` если в директорию можно записывать, иначе `Err` с описанием причины
+pub fn check_writable_dir(dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {:?}", dir));
+    }
+    let probe = dir.join(".ai_project_template_write_test");
+    std::fs::write(&probe, b"")
+        .map_err(|e| format!("Directory is not writable {:?}: {}", dir, e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Текущие дата и время в формате, который подставляется в README
+pub fn readme_datetime() -> String {
+    chrono::Local::now()
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// Сформировать содержимое README.md проекта
+///
+/// Подставляет в `readme_template` имя проекта, дату и значения динамических полей
+/// (плейсхолдеры `{ID}`, `{id}`, `{{ID}}` и `{{id}}`, см. модуль `substitution`)
+/// и добавляет заголовок с датой создания на текущем языке (см. модуль `locale`).
+/// BOM шаблона удаляется, переводы строк приводятся к `\n` (`LineEnding::Lf`).
+/// Используется `create_project` и предпросмотром README в GUI, поэтому
+/// предпросмотр всегда совпадает с тем, что будет записано.
+///
+/// # Arguments
+///
+/// * `preset_config` - конфигурация пресета
+/// * `project_name` - имя проекта
+/// * `dynamic_fields` - значения динамических полей
+/// * `datetime` - дата создания (см. `readme_datetime`)
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::command::render_readme;
+/// use ai_project_template::presets::PresetConfig;
+///
+/// let config: PresetConfig = serde_json::from_str(r###"{
+///     "preset_id": "book", "preset_name": "Book", "description": "",
+///     "directories": [], "empty_files": [], "options": [], "templates": [],
+///     "fields": [{"id": "synopsis", "label": "Synopsis", "required": false, "type": "textarea", "rows": 6}],
+///     "readme_template": "## Synopsis\n\n{synopsis}\n"
+/// }"###).unwrap();
+/// // Значение многострочного поля подставляется с переводами строк
+/// let fields = HashMap::from([("synopsis".to_string(), "First line.\r\nSecond line.".to_string())]);
+///
+/// let readme = render_readme(&config, "My Book", &fields, "2024-03-09");
+/// assert!(readme.starts_with("# My Book\n"));
+/// assert!(readme.ends_with("## Synopsis\n\nFirst line.\nSecond line.\n"));
+/// ```
+pub fn render_readme(
+    preset_config: &PresetConfig,
+    project_name: &str,
+    dynamic_fields: &HashMap<String, String>,
+    datetime: &str,
+) -> String {
+    // Подстановка значений в шаблон README
+    let values = placeholder_values(project_name, datetime, dynamic_fields);
+    // BOM и CRLF могут прийти из шаблона или из значений полей
+    let readme_content = LineEnding::Lf.normalize(&substitute_placeholders(&preset_config.readme_template, &values));
+    
+    // Добавить заголовок и дату в начало README
+    format!(
+        "# {}\n\n{}: {}\n\n## {}\n{}",
+        project_name,
+        tr("readme.created"),
+        datetime,
+        tr("readme.next_steps"),
+        readme_content
+    )
+}
 
 /// Создать проект на основе конфигурации пресета
 ///
@@ -16,7 +713,25 @@ use std::path::Path;
 /// 2. Создает поддиректории согласно конфигурации
 /// 3. Копирует шаблоны файлов из пресета
 /// 4. Создает пустые файлы
-/// 5. Генерирует README.md с подстановкой значений
+/// 5. Генерирует README.md с подстановкой значений (если `generate_readme` включен;
+///    README, скопированный из шаблона пресета, не перезаписывается даже с "refresh")
+///    и `.gitignore` из `gitignore_template`
+/// 6. Записывает манифест `.ai_project_template.json` с SHA-256 записанных файлов
+///    (при повторном создании с "force" записи существующего манифеста дополняются)
+/// 7. С опцией "git_init" выполняет `git init` и первый коммит всех файлов проекта
+///    (если git не найден или коммит не удался - предупреждение в логе)
+/// 8. Выполняет команды `post_commands` пресета (ошибка команды - предупреждение в логе)
+///
+/// Новый проект (директории `project_path` еще нет) собирается во временной директории
+/// рядом с ним (`my_project.__tmp_1234`) и после шага 6 переносится на место через
+/// `fs::rename` (между файловыми системами - копированием с удалением временной
+/// директории); если шаги 1-6 завершились ошибкой, временная директория удаляется и
+/// недостроенный проект не остается. Пути в логе указывают на `project_path`. Существующая
+/// директория (с "force" или пустая) дополняется на месте, опция "no_atomic" отключает
+/// временную директорию и для нового проекта. Новый проект, записываемый на месте
+/// ("no_atomic"), при ошибке откатывается: созданные директории и файлы удаляются в
+/// обратном порядке, а к ошибке добавляется строка "Rolled back: removed ..." на каждый
+/// удаленный путь. Файлы директории, существовавшей до вызова, не удаляются.
 ///
 /// # Arguments
 ///
@@ -25,7 +740,11 @@ use std::path::Path;
 /// * `preset_config` - конфигурация выбранного пресета
 /// * `project_name` - имя проекта (используется в README и уведомлениях)
 /// * `dynamic_fields` - значения динамических полей пресета для подстановки в шаблоны
-/// * `options` - опции создания проекта (например, "refresh", "force")
+/// * `options` - опции создания проекта (например, "refresh", "force", "dry_run", "git_init", "no_atomic")
+///
+/// С опцией "dry_run" выполняются те же шаги и проверки (существующие файлы,
+/// отсутствующие источники, непустая директория проекта), но на диск ничего не
+/// записывается: лог описывает планируемые действия ("[DRY RUN] Would create ...", "[DRY RUN] Would copy ...").
 ///
 /// # Returns
 ///
@@ -45,12 +764,12 @@ use std::path::Path;
 /// ```no_run
 /// use std::path::Path;
 /// use std::collections::HashMap;
-/// # use ai_project_template::presets::PresetConfig;
-/// # use ai_project_template::command::create_project;
+/// use ai_project_template::presets::load_preset_config;
+/// use ai_project_template::command::create_project;
 ///
 /// let project_path = Path::new("./my_project");
 /// let presets_dir = Path::new("./presets");
-/// let preset_config = /* ... */;
+/// let preset_config = load_preset_config(presets_dir, "software")?;
 /// let project_name = "my_project";
 /// let dynamic_fields = HashMap::new();
 /// let options = HashMap::new();
@@ -70,6 +789,7 @@ use std::path::Path;
 ///     }
 ///     Err(e) => eprintln!("Ошибка: {}", e),
 /// }
+/// # Ok::<(), String>(())
 /// ```
 pub fn create_project(
     project_path: &Path,
@@ -79,7 +799,70 @@ pub fn create_project(
     dynamic_fields: &HashMap<String, String>,
     options: &HashMap<String, bool>,
 ) -> Result<Vec<String>, String> {
+    create_project_timed(
+        project_path,
+        presets_dir,
+        preset_config,
+        project_name,
+        dynamic_fields,
+        options,
+        &mut PhaseTimer::new(),
+        &RetryPolicy::transient(),
+        &|_, _, _| {},
+        None,
+    )
+}
+
+/// Создать проект, замеряя длительность этапов
+///
+/// То же, что `create_project`, но этапы (`pre-flight`, `directories`, `templates`,
+/// `empty files`, `README`, `manifest`, `move` (перенос из временной директории), `git`, `post commands`) и, для подробного таймера, медленные
+/// файловые операции записываются в `timer`. Если у таймера уже идет этап
+/// (например, проверки `ProjectBuilder`), проверка директории проекта входит в него.
+///
+/// Запись файлов повторяется при временных ошибках согласно `retry`
+/// (см. `retry::retry_io`); каждый повтор записывается в лог.
+///
+/// `progress(шаг, всего, описание)` вызывается перед каждой директорией, шаблоном,
+/// пустым файлом и генерацией README; всего шагов - их общее число, последний
+/// вызов (README) имеет `шаг == всего`. Пропущенные элементы тоже считаются шагами.
+///
+/// Флаг `cancel` проверяется перед каждым из этих шагов и перед переносом проекта из
+/// временной директории: установленный флаг прерывает создание с ошибкой
+/// `operation::CANCELLED`, временная директория удаляется. После переноса (git, команды
+/// пресета) проект уже создан и отмена не действует. Новый проект с "no_atomic" при
+/// отмене откатывается (к `CANCELLED` добавляются строки отката), а существующая
+/// директория остается частично записанной.
+#[allow(clippy::too_many_arguments)]
+pub fn create_project_timed(
+    project_path: &Path,
+    presets_dir: &Path,
+    preset_config: &PresetConfig,
+    project_name: &str,
+    dynamic_fields: &HashMap<String, String>,
+    options: &HashMap<String, bool>,
+    timer: &mut PhaseTimer,
+    retry: &RetryPolicy,
+    progress: &dyn Fn(usize, usize, &str),
+    cancel: Option<&AtomicBool>,
+) -> Result<Vec<String>, String> {
+    // Поля, скрытые выключенной опцией, подставляются пустой строкой, остальные -
+    // с примененным `transform`, к ним добавляются вычисляемые поля; эти же значения
+    // записываются в манифест
+    let dynamic_fields = &resolved_field_values(preset_config, project_name, dynamic_fields, options)?;
     let mut log_lines = Vec::new();
+    let total_steps = preset_config.directories.len()
+        + preset_config.templates.len()
+        + preset_config.empty_files.len()
+        + 1;
+    let mut step = 0;
+    let mut advance = |message: String| {
+        step += 1;
+        progress(step, total_steps, &message);
+    };
+    if !timer.is_running() {
+        timer.start("pre-flight");
+    }
     
     // Проверка: существует ли директория и не пуста ли она
     let force = options.get("force").copied().unwrap_or(false);
@@ -97,24 +880,165 @@ pub fn create_project(
         }
     }
     
+    // Пробный запуск проходит те же шаги и проверки, но только описывает изменения
+    let dry_run = options.get("dry_run").copied().unwrap_or(false);
+    
+    // Новый проект собирается во временной директории рядом с `project_path` и переносится
+    // на место в конце: ошибка на середине не оставляет недостроенный проект. Существующая
+    // директория ("force", "refresh") дополняется на месте; "no_atomic" отключает сборку
+    let no_atomic = options.get("no_atomic").copied().unwrap_or(false);
+    let staging_path = if dry_run || no_atomic || project_path.exists() {
+        None
+    } else {
+        Some(create_staging_dir(project_path)?)
+    };
+    let build_path = staging_path.as_deref().unwrap_or(project_path);
+    // Новый проект, создаваемый на месте ("no_atomic"), при ошибке откатывается по списку
+    // созданных путей; файлы существовавшей ранее директории никогда не удаляются
+    let roll_back = staging_path.is_none() && !dry_run && !project_path.exists();
+    let mut created: Vec<PathBuf> = Vec::new();
+    if let Err(e) = write_project_files(
+        build_path,
+        presets_dir,
+        preset_config,
+        project_name,
+        dynamic_fields,
+        options,
+        timer,
+        retry,
+        cancel,
+        &mut advance,
+        &mut created,
+        &mut log_lines,
+    ) {
+        return Err(match staging_path {
+            Some(ref staging) => discard_staging_dir(staging, e),
+            None if roll_back => roll_back_created_paths(&created, e),
+            None => e,
+        });
+    }
+    if let Some(ref staging) = staging_path {
+        timer.start("move");
+        // Отмененный до переноса проект не появляется на месте
+        if let Err(e) = check_cancelled(cancel)
+            .and_then(|()| move_staged_project(staging, project_path, &mut log_lines))
+        {
+            return Err(discard_staging_dir(staging, e));
+        }
+        relocate_log_paths(&mut log_lines, staging, project_path);
+        log_lines.push(format!("Moved project into place from temporary directory {:?}", staging));
+    }
+    
+    // 7. Репозиторий с первым коммитом сгенерированных файлов (до команд пресета,
+    // чтобы в коммит не попали, например, установленные зависимости)
+    if options.get("git_init").copied().unwrap_or(false) {
+        timer.start("git");
+        if dry_run {
+            log_lines.push(format!("[DRY RUN] Would initialize git repository: {:?}", project_path));
+        } else {
+            init_git_repository(project_path, &mut log_lines);
+        }
+    }
+    
+    // 8. Выполнить команды пресета, когда все файлы уже записаны
+    if !preset_config.post_commands.is_empty() {
+        timer.start("post commands");
+        run_post_commands(preset_config, project_path, options, dry_run, &mut log_lines);
+    }
+    
+    timer.finish();
+    if dry_run {
+        log_lines.push("[DRY RUN] Finished: no changes were made".to_string());
+    } else {
+        log_lines.push("Project created successfully!".to_string());
+    }
+    Ok(log_lines)
+}
+
+
+/// Записать директории и файлы проекта (шаги 1-6 `create_project_timed`)
+///
+/// # Arguments
+///
+/// * `project_path` - директория, в которую пишутся файлы (при атомарном создании -
+///   временная директория, см. `create_staging_dir`)
+/// * `cancel` - флаг отмены, проверяется перед каждым шагом (см. `create_project_timed`)
+/// * `advance` - сообщить о следующем шаге (см. `progress` в `create_project_timed`)
+/// * `created` - список, в который добавляются создаваемые директории и файлы
+///   (в порядке создания, см. `roll_back_created_paths`)
+/// * `log_lines` - лог, в который добавляются строки операций
+#[allow(clippy::too_many_arguments)]
+fn write_project_files(
+    project_path: &Path,
+    presets_dir: &Path,
+    preset_config: &PresetConfig,
+    project_name: &str,
+    dynamic_fields: &HashMap<String, String>,
+    options: &HashMap<String, bool>,
+    timer: &mut PhaseTimer,
+    retry: &RetryPolicy,
+    cancel: Option<&AtomicBool>,
+    advance: &mut dyn FnMut(String),
+    created: &mut Vec<PathBuf>,
+    log_lines: &mut Vec<String>,
+) -> Result<(), String> {
+    let dry_run = options.get("dry_run").copied().unwrap_or(false);
+    // Файлы, которые записал бы пробный запуск (для проверки пустых директорий)
+    let mut planned: Vec<PathBuf> = Vec::new();
+    
     // 1. Создать директорию проекта
-    log_lines.push(format!("Creating project directory: {:?}", project_path));
-    fs::create_dir_all(project_path)
-        .map_err(|e| format!("Failed to create project directory: {}", e))?;
+    timer.start("directories");
+    if dry_run {
+        if !project_path.exists() {
+            log_lines.push(format!("[DRY RUN] Would create project directory: {:?}", project_path));
+        }
+    } else {
+        log_lines.push(format!("Creating project directory: {:?}", project_path));
+        note_new_paths(project_path, created);
+        fs::create_dir_all(project_path)
+            .map_err(|e| format!("Failed to create project directory: {}", e))?;
+    }
+    
+    let mut manifest = ProjectManifest::load(project_path)
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    manifest.preset_id = preset_config.id.clone();
+    manifest.fields = dynamic_fields.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
     
     // 2. Создать поддиректории из конфига пресета
     for dir_name in &preset_config.directories {
+        check_cancelled(cancel)?;
+        advance(format!("Creating directory {}", dir_name));
         let dir_path = project_path.join(dir_name);
+        if dry_run {
+            if !dir_path.exists() {
+                log_lines.push(format!("[DRY RUN] Would create subdirectory: {:?}", dir_path));
+            }
+            continue;
+        }
         log_lines.push(format!("Creating subdirectory: {:?}", dir_path));
-        fs::create_dir_all(&dir_path)
+        note_new_paths(&dir_path, created);
+        timer.time(|| format!("mkdir {}", dir_name), || fs::create_dir_all(&dir_path))
             .map_err(|e| format!("Failed to create directory {:?}: {}", dir_path, e))?;
     }
     
     // 3. Скопировать шаблоны из папки пресета
-    let preset_source_dir = presets_dir.join(&preset_config.id);
+    timer.start("templates");
+    let preset_source_dir = preset_config.source_dir(presets_dir);
     let refresh = options.get("refresh").copied().unwrap_or(false);
+    // Одна дата для шаблонов с подстановкой и README
+    let datetime = readme_datetime();
+    let values = placeholder_values(project_name, &datetime, dynamic_fields);
     
     for template in &preset_config.templates {
+        // Дальше шаблон используется с уже подставленным путем назначения
+        let template = &TemplateConfig {
+            destination: template_destination(template, &values)?,
+            ..template.clone()
+        };
+        check_cancelled(cancel)?;
+        advance(format!("Copying template {}", template.destination));
         let source_path = preset_source_dir.join(&template.source);
         let dest_path = project_path.join(&template.destination);
         
@@ -129,27 +1053,47 @@ pub fn create_project(
             continue;
         }
         
-        log_lines.push(format!("Copying template: {:?} -> {:?}", source_path, dest_path));
-        
-        // Создать родительские директории если нужно
-        if let Some(parent) = dest_path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create parent directory for {:?}: {}", dest_path, e))?;
+        if dry_run {
+            let rewritten = template.rewrites_content() && read_text_template(&source_path, log_lines)?.is_some();
+            let action = if !rewritten {
+                if template.link { "link" } else { "copy" }
+            } else if template.substitute {
+                "render"
+            } else {
+                "write"
+            };
+            log_lines.push(format!("[DRY RUN] Would {} template: {:?} -> {:?}", action, source_path, dest_path));
+            planned.push(dest_path);
+            continue;
         }
-        
-        fs::copy(&source_path, &dest_path)
-            .map_err(|e| format!("Failed to copy template {:?} to {:?}: {}", source_path, dest_path, e))?;
+        note_new_paths(&dest_path, created);
+        if template.rewrites_content() {
+            if let Some(hash) = write_text_template(template, &source_path, &dest_path, &values, timer, retry, log_lines)? {
+                manifest.record(Path::new(&template.destination), hash);
+                continue;
+            }
+        }
+        copy_template(template, &source_path, &dest_path, &mut manifest, timer, retry, log_lines)?;
     }
     
     // 4. Создать пустые файлы из конфига
+    timer.start("empty files");
     for file_name in &preset_config.empty_files {
+        check_cancelled(cancel)?;
+        advance(format!("Creating empty file {}", file_name));
         let file_path = project_path.join(file_name);
         if file_path.exists() && !refresh {
             log_lines.push(format!("Skipping existing empty file: {:?}", file_path));
             continue;
         }
+        if dry_run {
+            log_lines.push(format!("[DRY RUN] Would create empty file: {:?}", file_path));
+            planned.push(file_path);
+            continue;
+        }
         
         log_lines.push(format!("Creating empty file: {:?}", file_path));
+        note_new_paths(&file_path, created);
         
         // Создать родительские директории если нужно
         if let Some(parent) = file_path.parent() {
@@ -157,57 +1101,1095 @@ pub fn create_project(
                 .map_err(|e| format!("Failed to create parent directory for {:?}: {}", file_path, e))?;
         }
         
-        fs::File::create(&file_path)
+        let description = format!("create {}", file_name);
+        let fresh = !file_path.exists();
+        timer.time(|| description.clone(), || {
+            retry_io(retry, &description, fresh, log_lines, || fs::File::create(&file_path))
+        })
             .map_err(|e| format!("Failed to create empty file {:?}: {}", file_path, e))?;
+        manifest.record(Path::new(file_name), hash_bytes(b""));
+    }
+    
+    // 4a. Сохранить пустые директории в git (после шаблонов и пустых файлов,
+    // чтобы директории с содержимым не получили лишний .gitkeep)
+    if preset_config.keep_empty_dirs {
+        for dir_name in &preset_config.directories {
+            let dir_path = project_path.join(dir_name);
+            let is_empty = match dir_path.read_dir() {
+                Ok(mut entries) => entries.next().is_none(),
+                // Пробный запуск директорию не создавал
+                Err(_) if dry_run => true,
+                Err(e) => return Err(format!("Failed to read directory {:?}: {}", dir_path, e)),
+            };
+            if !is_empty || planned.iter().any(|path| path.starts_with(&dir_path)) {
+                continue;
+            }
+            let keep_path = dir_path.join(GITKEEP_FILE_NAME);
+            if dry_run {
+                log_lines.push(format!("[DRY RUN] Would create {}: {:?}", GITKEEP_FILE_NAME, keep_path));
+                continue;
+            }
+            log_lines.push(format!("Creating {}: {:?}", GITKEEP_FILE_NAME, keep_path));
+            note_new_paths(&keep_path, created);
+            let relative = Path::new(dir_name).join(GITKEEP_FILE_NAME);
+            let description = format!("create {}", relative.display());
+            let hash = timer.time(|| description.clone(), || {
+                retry_io(retry, &description, true, log_lines, || write_hashed(&keep_path, b""))
+            })
+                .map_err(|e| format!("Failed to create {:?}: {}", keep_path, e))?;
+            manifest.record(&relative, hash);
+        }
     }
     
     // 5. Генерировать README на основе шаблона из пресета
+    timer.start("README");
+    check_cancelled(cancel)?;
+    advance("Generating README".to_string());
     let readme_path = project_path.join("README.md");
     let refresh_readme = refresh || !readme_path.exists();
     
-    if refresh_readme {
+    if !preset_config.generate_readme {
+        log_lines.push("Skipping README generation (disabled by preset)".to_string());
+    } else if preset_config.has_readme_template_file() {
+        log_lines.push(format!("Keeping README from preset template: {:?}", readme_path));
+    } else if preset_config.readme_template.trim().is_empty() {
+        log_lines.push("Skipping README generation (readme_template is empty)".to_string());
+    } else if refresh_readme && dry_run {
+        log_lines.push(format!("[DRY RUN] Would generate README: {:?}", readme_path));
+    } else if refresh_readme {
         log_lines.push(format!("Generating README: {:?}", readme_path));
+        note_new_paths(&readme_path, created);
         
-        let datetime = chrono::Local::now()
-            .format("%Y-%m-%d %H:%M")
-            .to_string();
+        let full_readme = render_readme(preset_config, project_name, dynamic_fields, &datetime);
         
-        // Подстановка значений в шаблон README
-        let mut readme_content = preset_config.readme_template.clone();
-        
-        // Подстановка имени проекта
-        readme_content = readme_content.replace("{PROJECT_NAME}", project_name);
-        readme_content = readme_content.replace("{project_name}", project_name);
-        
-        // Подстановка даты создания
-        readme_content = readme_content.replace("{DATE}", &datetime);
-        readme_content = readme_content.replace("{date}", &datetime);
-        
-        // Подстановка значений динамических полей
-        for (field_id, value) in dynamic_fields {
-            let placeholder = format!("{{{}}}", field_id.to_uppercase());
-            readme_content = readme_content.replace(&placeholder, value);
-            
-            let placeholder_lower = format!("{{{}}}", field_id.to_lowercase());
-            readme_content = readme_content.replace(&placeholder_lower, value);
+        let fresh = !readme_path.exists();
+        let hash = retry_io(retry, "write README.md", fresh, log_lines, || write_hashed(&readme_path, full_readme.as_bytes()))
+            .map_err(|e| format!("Failed to write README {:?}: {}", readme_path, e))?;
+        manifest.record(Path::new("README.md"), hash);
+    }
+    
+    // .gitignore из пресета (шаблон с тем же назначением имеет приоритет)
+    if !preset_config.gitignore_template.is_empty() {
+        let gitignore_path = project_path.join(GITIGNORE_FILE_NAME);
+        let from_template = preset_config.templates.iter()
+            .any(|t| t.destination.trim_start_matches("./") == GITIGNORE_FILE_NAME);
+        if from_template {
+            log_lines.push(format!("Keeping .gitignore from preset template: {:?}", gitignore_path));
+        } else if gitignore_path.exists() && !refresh {
+            log_lines.push(format!("Skipping existing file: {:?}", gitignore_path));
+        } else if dry_run {
+            log_lines.push(format!("[DRY RUN] Would write .gitignore: {:?}", gitignore_path));
+        } else {
+            log_lines.push(format!("Writing .gitignore: {:?}", gitignore_path));
+            note_new_paths(&gitignore_path, created);
+            let fresh = !gitignore_path.exists();
+            let hash = retry_io(retry, "write .gitignore", fresh, log_lines, || {
+                write_hashed(&gitignore_path, preset_config.gitignore_template.as_bytes())
+            }).map_err(|e| format!("Failed to write .gitignore {:?}: {}", gitignore_path, e))?;
+            manifest.record(Path::new(GITIGNORE_FILE_NAME), hash);
+        }
+    }
+    
+    // 6. Записать манифест с хешами записанных файлов
+    timer.start("manifest");
+    let manifest_path = project_path.join(crate::manifest::MANIFEST_FILE_NAME);
+    if dry_run {
+        log_lines.push(format!("[DRY RUN] Would write manifest: {:?}", manifest_path));
+    } else {
+        log_lines.push(format!("Writing manifest: {:?}", manifest_path));
+        note_new_paths(&manifest_path, created);
+        manifest.save(project_path)?;
+    }
+    Ok(())
+}
+
+/// Создать временную директорию для сборки проекта рядом с `project_path`
+/// (`my_project.__tmp_1234`, при совпадении имени добавляется счетчик)
+fn create_staging_dir(project_path: &Path) -> Result<PathBuf, String> {
+    let name = project_path.file_name()
+        .ok_or_else(|| format!("Invalid project path {:?}", project_path))?
+        .to_string_lossy();
+    let parent = project_path.parent().unwrap_or(Path::new(""));
+    if !parent.as_os_str().is_empty() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    for attempt in 0..100 {
+        let suffix = if attempt == 0 { String::new() } else { format!("_{}", attempt) };
+        let candidate = parent.join(format!("{}.__tmp_{}{}", name, std::process::id(), suffix));
+        match fs::create_dir(&candidate) {
+            Ok(()) => return Ok(candidate),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("Failed to create temporary directory {:?}: {}", candidate, e)),
+        }
+    }
+    Err(format!("Failed to create a temporary directory next to {:?}", project_path))
+}
+
+/// Удалить временную директорию недостроенного проекта
+///
+/// # Returns
+///
+/// Ошибку создания проекта, дополненную ошибкой удаления, если удалить не удалось
+fn discard_staging_dir(staging_path: &Path, error: String) -> String {
+    match fs::remove_dir_all(staging_path) {
+        Ok(()) => error,
+        Err(e) => format!("{} (failed to remove temporary directory {:?}: {})", error, staging_path, e),
+    }
+}
+
+/// Запомнить `path` и его еще не существующие родительские директории перед созданием
+///
+/// Пути добавляются в порядке создания (сначала родители), уже существующие
+/// пути не добавляются - откат их не трогает.
+fn note_new_paths(path: &Path, created: &mut Vec<PathBuf>) {
+    let missing: Vec<&Path> = path.ancestors()
+        .take_while(|p| !p.as_os_str().is_empty() && fs::symlink_metadata(p).is_err())
+        .collect();
+    for p in missing.into_iter().rev() {
+        if !created.iter().any(|c| c == p) {
+            created.push(p.to_path_buf());
+        }
+    }
+}
+
+/// Удалить созданные при записи проекта на месте пути в обратном порядке
+///
+/// Каждый удаленный путь (или неудачное удаление) добавляется к ошибке отдельной
+/// строкой, чтобы откат был виден в логе.
+///
+/// # Returns
+///
+/// Ошибку создания проекта со строками отката
+fn roll_back_created_paths(created: &[PathBuf], error: String) -> String {
+    let mut lines = vec![error];
+    for path in created.iter().rev() {
+        let result = match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir(path),
+            Ok(_) => fs::remove_file(path),
+            // Файл не успел появиться
+            Err(_) => continue,
+        };
+        lines.push(match result {
+            Ok(()) => format!("Rolled back: removed {:?}", path),
+            Err(e) => format!("Warning: Failed to remove {:?} during rollback: {}", path, e),
+        });
+    }
+    lines.join("\n")
+}
+
+/// Перенести собранный проект из временной директории в `project_path`
+///
+/// Переименование не работает между файловыми системами; в этом случае проект
+/// копируется и временная директория удаляется. Если копирование не удалось,
+/// частично скопированный проект удаляется.
+fn move_staged_project(staging_path: &Path, project_path: &Path, log_lines: &mut Vec<String>) -> Result<(), String> {
+    match fs::rename(staging_path, project_path) {
+        Ok(()) => Ok(()),
+        Err(e) if is_cross_device_error(&e) => {
+            log_lines.push(format!(
+                "Note: {:?} is on another filesystem, copying the project instead of moving it",
+                project_path
+            ));
+            if let Err(e) = copy_dir_recursive(staging_path, project_path) {
+                let _ = fs::remove_dir_all(project_path);
+                return Err(format!("Failed to move project to {:?}: {}", project_path, e));
+            }
+            // Проект уже на месте: оставшаяся временная директория - не повод для ошибки
+            if let Err(e) = fs::remove_dir_all(staging_path) {
+                log_lines.push(format!("Warning: Failed to remove temporary directory {:?}: {}", staging_path, e));
+            }
+            Ok(())
+        }
+        Err(e) => Err(format!("Failed to move project from {:?} to {:?}: {}", staging_path, project_path, e)),
+    }
+}
+
+/// Является ли ошибка `rename` переносом между файловыми системами
+/// (`EXDEV` на Unix, `ERROR_NOT_SAME_DEVICE` на Windows)
+fn is_cross_device_error(error: &io::Error) -> bool {
+    #[cfg(windows)]
+    const CROSS_DEVICE: i32 = 17;
+    #[cfg(not(windows))]
+    const CROSS_DEVICE: i32 = 18;
+    error.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+/// Заменить в строках лога путь временной директории на путь проекта
+///
+/// Пути записываются в лог через `{:?}`, поэтому заменяется экранированный путь
+/// без закрывающей кавычки - так заменяются и пути файлов внутри директории.
+fn relocate_log_paths(log_lines: &mut [String], staging_path: &Path, project_path: &Path) {
+    let from = format!("{:?}", staging_path);
+    let to = format!("{:?}", project_path);
+    let (from, to) = (from.trim_end_matches('"'), to.trim_end_matches('"'));
+    for line in log_lines.iter_mut().filter(|line| line.contains(from)) {
+        *line = line.replace(from, to);
+    }
+}
+
+/// Скопировать (или связать жесткой ссылкой) один шаблон и записать его хеш в манифест
+///
+/// Общий шаг `create_project_timed` и `rerun_missing_templates`.
+fn copy_template(
+    template: &TemplateConfig,
+    source_path: &Path,
+    dest_path: &Path,
+    manifest: &mut ProjectManifest,
+    timer: &mut PhaseTimer,
+    retry: &RetryPolicy,
+    log_lines: &mut Vec<String>,
+) -> Result<(), String> {
+    copy_template_with(template, source_path, dest_path, manifest, timer, retry, log_lines, |source, dest| fs::hard_link(source, dest))
+}
+
+/// `copy_template` с заданной функцией создания жесткой ссылки (в тестах - с
+/// ошибкой, как при проекте на другой файловой системе)
+#[allow(clippy::too_many_arguments)]
+fn copy_template_with(
+    template: &TemplateConfig,
+    source_path: &Path,
+    dest_path: &Path,
+    manifest: &mut ProjectManifest,
+    timer: &mut PhaseTimer,
+    retry: &RetryPolicy,
+    log_lines: &mut Vec<String>,
+    hard_link: impl FnOnce(&Path, &Path) -> io::Result<()>,
+) -> Result<(), String> {
+    // Создать родительские директории если нужно
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create parent directory for {:?}: {}", dest_path, e))?;
+    }
+    
+    if template.link {
+        // Жесткая ссылка не перезаписывает существующий файл - удалить его (refresh)
+        if dest_path.exists() {
+            fs::remove_file(dest_path)
+                .map_err(|e| format!("Failed to replace {:?}: {}", dest_path, e))?;
+        }
+        match timer.time(|| format!("link {}", template.destination), || hard_link(source_path, dest_path)) {
+            Ok(()) => {
+                log_lines.push(format!("Linking template: {:?} -> {:?}", source_path, dest_path));
+                manifest.record(Path::new(&template.destination), hash_file(dest_path)?);
+                return Ok(());
+            }
+            Err(e) => {
+                // Например, проект на другой файловой системе, чем пресеты
+                log_lines.push(format!("Note: Hard link not possible for {:?} ({}), copying instead", dest_path, e));
+            }
+        }
+    }
+    
+    log_lines.push(format!("Copying template: {:?} -> {:?}", source_path, dest_path));
+    let description = format!("copy {}", template.destination);
+    let fresh = !dest_path.exists();
+    let hash = timer.time(|| description.clone(), || {
+        retry_io(retry, &description, fresh, log_lines, || copy_hashed(source_path, dest_path))
+    })
+        .map_err(|e| format!("Failed to copy template {:?} to {:?}: {}", source_path, dest_path, e))?;
+    manifest.record(Path::new(&template.destination), hash);
+    Ok(())
+}
+
+/// Выполнить команды `post_commands` пресета
+///
+/// Команда с `option_guard` выполняется, только если опция включена в `options`
+/// (или по умолчанию в пресете). Вывод команды (stdout, затем stderr) добавляется
+/// в лог. Ошибка запуска, ненулевой код выхода и рабочая директория вне проекта
+/// записываются в лог как предупреждения, следующие команды все равно выполняются.
+fn run_post_commands(
+    preset_config: &PresetConfig,
+    project_path: &Path,
+    options: &HashMap<String, bool>,
+    dry_run: bool,
+    log_lines: &mut Vec<String>,
+) {
+    for command in &preset_config.post_commands {
+        if let Some(ref guard) = command.option_guard {
+            let enabled = options.get(guard).copied()
+                .or_else(|| preset_config.options.iter().find(|o| &o.id == guard).map(|o| o.default))
+                .unwrap_or(false);
+            if !enabled {
+                log_lines.push(format!("Skipping command `{}` (option '{}' is off)", command, guard));
+                continue;
+            }
         }
         
-        // Добавить заголовок и дату в начало README
-        let full_readme = format!(
-            "# {}\n\nСоздано: {}\n\n## Что дальше\n{}",
-            project_name,
-            datetime,
-            readme_content
-        );
-        
-        let mut readme_file = fs::File::create(&readme_path)
-            .map_err(|e| format!("Failed to create README {:?}: {}", readme_path, e))?;
+        let working_dir = match command.working_dir {
+            Some(ref dir) if Path::new(dir).components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) => {
+                log_lines.push(format!(
+                    "Warning: Working directory {:?} of command `{}` is outside the project, command skipped",
+                    dir, command
+                ));
+                continue;
+            }
+            Some(ref dir) => project_path.join(dir),
+            None => project_path.to_path_buf(),
+        };
         
-        readme_file.write_all(full_readme.as_bytes())
-            .map_err(|e| format!("Failed to write README: {}", e))?;
+        if dry_run {
+            log_lines.push(format!("[DRY RUN] Would run command `{}` in {:?}", command, working_dir));
+            continue;
+        }
+        log_lines.push(format!("Running command `{}` in {:?}", command, working_dir));
+        let mut process = process::Command::new(&command.program);
+        process.args(&command.args).current_dir(&working_dir);
+        if let Err(e) = run_logged(&mut process, &command.to_string(), log_lines) {
+            log_lines.push(format!("Warning: Failed to run command `{}`: {}", command, e));
+        }
+    }
+}
+
+/// Инициализировать git-репозиторий и закоммитить все файлы проекта
+///
+/// Ошибки не прерывают создание проекта: если git не найден или команда
+/// завершилась с ошибкой (например, не настроен `user.name`), в лог пишется
+/// предупреждение и следующие шаги git пропускаются.
+fn init_git_repository(project_path: &Path, log_lines: &mut Vec<String>) {
+    log_lines.push(format!("Initializing git repository: {:?}", project_path));
+    let steps: [&[&str]; 3] = [&["init"], &["add", "--all"], &["commit", "-m", INITIAL_COMMIT_MESSAGE]];
+    for args in steps {
+        let label = format!("git {}", args.join(" "));
+        let mut git = process::Command::new("git");
+        git.args(args).current_dir(project_path);
+        match run_logged(&mut git, &label, log_lines) {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                log_lines.push("Warning: git is not installed or not on PATH, repository was not initialized".to_string());
+                return;
+            }
+            Err(e) => {
+                log_lines.push(format!("Warning: Failed to run `{}`: {}", label, e));
+                return;
+            }
+        }
+    }
+}
+
+/// Запустить программу и добавить ее вывод (stdout, затем stderr) в лог
+///
+/// # Returns
+///
+/// `Ok(true)` при успешном завершении, `Ok(false)` при ненулевом коде выхода
+/// (предупреждение уже записано в лог), `Err` если программу не удалось запустить
+fn run_logged(command: &mut process::Command, label: &str, log_lines: &mut Vec<String>) -> io::Result<bool> {
+    let output = command.output()?;
+    for stream in [&output.stdout, &output.stderr] {
+        log_lines.extend(String::from_utf8_lossy(stream).lines().map(|line| format!("  {}", line)));
+    }
+    if !output.status.success() {
+        log_lines.push(format!("Warning: Command `{}` failed ({})", label, output.status));
     }
+    Ok(output.status.success())
+}
+
+/// Путь назначения шаблона с подставленными значениями полей (`src/{module}.rs`)
+///
+/// # Returns
+///
+/// Путь относительно проекта или `Err`, если значение не подходит для пути
+/// или путь выходит за директорию проекта (см. `substitute_path_placeholders`)
+fn template_destination(template: &TemplateConfig, values: &HashMap<String, &str>) -> Result<String, String> {
+    substitute_path_placeholders(&template.destination, values)
+        .map_err(|e| format!("Invalid destination {:?} of template {:?}: {}", template.destination, template.source, e))
+}
+
+/// Прочитать шаблон с подстановкой или приведением переводов строк как текст
+///
+/// # Returns
+///
+/// `Ok(None)`, если файл не является текстом в UTF-8 (в лог записано предупреждение,
+/// файл копируется без изменений)
+fn read_text_template(source_path: &Path, log_lines: &mut Vec<String>) -> Result<Option<String>, String> {
+    let bytes = fs::read(source_path)
+        .map_err(|e| format!("Failed to read template {:?}: {}", source_path, e))?;
+    match String::from_utf8(bytes) {
+        // NUL в тексте почти наверняка означает двоичный файл
+        Ok(text) if !text.contains('\0') => Ok(Some(text)),
+        _ => {
+            log_lines.push(format!(
+                "Warning: Template {:?} is not UTF-8 text, copied unchanged",
+                source_path
+            ));
+            Ok(None)
+        }
+    }
+}
+
+/// Записать шаблон как текст: с подстановкой значений полей (`substitute`)
+/// и приведением переводов строк без BOM (`line_endings`)
+///
+/// # Returns
+///
+/// `Ok(Some(hash))` - файл записан; `Ok(None)` - источник не является текстом в UTF-8
+/// (в лог записано предупреждение), вызывающий код копирует его без изменений
+fn write_text_template(
+    template: &TemplateConfig,
+    source_path: &Path,
+    dest_path: &Path,
+    values: &HashMap<String, &str>,
+    timer: &mut PhaseTimer,
+    retry: &RetryPolicy,
+    log_lines: &mut Vec<String>,
+) -> Result<Option<String>, String> {
+    let Some(text) = read_text_template(source_path, log_lines)? else { return Ok(None) };
     
-    log_lines.push("Project created successfully!".to_string());
-    Ok(log_lines)
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create parent directory for {:?}: {}", dest_path, e))?;
+    }
+    if template.link {
+        log_lines.push(format!("Note: {:?} is copied, not linked, because its content is rewritten", dest_path));
+        // Существующая жесткая ссылка указывает на файл пресета - не писать через нее
+        if dest_path.exists() {
+            fs::remove_file(dest_path)
+                .map_err(|e| format!("Failed to replace {:?}: {}", dest_path, e))?;
+        }
+    }
+    
+    let content = if template.substitute {
+        log_lines.push(format!("Rendering template: {:?} -> {:?}", source_path, dest_path));
+        substitute_placeholders(&text, values)
+    } else {
+        log_lines.push(format!("Writing template: {:?} -> {:?}", source_path, dest_path));
+        text
+    };
+    let content = match template.text_line_ending() {
+        Some(ending) => ending.normalize(&content),
+        None => content,
+    };
+    let description = format!("write {}", template.destination);
+    let fresh = !dest_path.exists();
+    timer.time(|| description.clone(), || {
+        retry_io(retry, &description, fresh, log_lines, || write_hashed(dest_path, content.as_bytes()))
+    })
+        .map(Some)
+        .map_err(|e| format!("Failed to write template {:?}: {}", dest_path, e))
+}
+
+/// Докопировать шаблоны, пропущенные при создании проекта
+///
+/// Используется при отладке пресета: после того как автор пресета добавил недостающие
+/// файлы (или исправил `source`), копируются только эти шаблоны, остальные файлы
+/// проекта не трогаются. Пресет берется из манифеста проекта, шаблон ищется по пути
+/// назначения. В шаблоны с `substitute` подставляются значения полей, сохраненные
+/// в манифесте при создании. Манифест дополняется хешами скопированных файлов.
+///
+/// # Arguments
+///
+/// * `presets_dir` - директория, содержащая пресет проекта
+/// * `project_path` - директория созданного проекта
+/// * `missing` - пропущенные шаблоны (см. `CreationReport::missing_templates`)
+/// * `retry` - политика повторов записи (см. `retry::retry_io`)
+///
+/// # Returns
+///
+/// `Ok((log_lines, still_missing))` - лог и шаблоны, источник которых все еще не найден;
+/// `Err` если манифест или пресет не загрузились либо копирование завершилось ошибкой
+pub fn rerun_missing_templates(
+    presets_dir: &Path,
+    project_path: &Path,
+    missing: &[MissingTemplate],
+    retry: &RetryPolicy,
+) -> Result<(Vec<String>, Vec<MissingTemplate>), String> {
+    let mut manifest = ProjectManifest::load(project_path)?
+        .ok_or_else(|| format!("Project {:?} has no manifest", project_path))?;
+    let preset_config = normalize_preset_config(&load_preset_config(presets_dir, &manifest.preset_id)?)?.config;
+    let preset_source_dir = preset_config.source_dir(presets_dir);
+    
+    let project_name = project_path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let fields: HashMap<String, String> = manifest.fields.clone().into_iter().collect();
+    let datetime = readme_datetime();
+    let values = placeholder_values(&project_name, &datetime, &fields);
+    
+    let mut log_lines = Vec::new();
+    let mut still_missing = Vec::new();
+    let mut timer = PhaseTimer::new();
+    for entry in missing {
+        let resolved = preset_config.templates.iter()
+            .find_map(|t| template_destination(t, &values).ok()
+                .filter(|destination| *destination == entry.destination)
+                .map(|destination| TemplateConfig { destination, ..t.clone() }));
+        let Some(ref template) = resolved else {
+            log_lines.push(format!(
+                "Note: Template for {:?} was removed from preset '{}', skipped",
+                entry.destination, preset_config.id
+            ));
+            continue;
+        };
+        let source_path = preset_source_dir.join(&template.source);
+        if !source_path.exists() {
+            log_lines.push(format!("Warning: Template source not found: {:?}", source_path));
+            still_missing.push(MissingTemplate {
+                source: template.source.clone(),
+                destination: template.destination.clone(),
+            });
+            continue;
+        }
+        let dest_path = project_path.join(&template.destination);
+        if template.rewrites_content() {
+            if let Some(hash) = write_text_template(template, &source_path, &dest_path, &values, &mut timer, retry, &mut log_lines)? {
+                manifest.record(Path::new(&template.destination), hash);
+                continue;
+            }
+        }
+        copy_template(template, &source_path, &dest_path, &mut manifest, &mut timer, retry, &mut log_lines)?;
+    }
+    
+    log_lines.push(format!("Writing manifest: {:?}", project_path.join(crate::manifest::MANIFEST_FILE_NAME)));
+    manifest.save(project_path)?;
+    Ok((log_lines, still_missing))
+}
+
+/// Определить, различает ли файловая система регистр имен файлов
+///
+/// Создает во временной поддиректории файл `a` и проверяет, виден ли он как `A`.
+/// Проверяется ближайшая существующая директория из `dir` и его родителей,
+/// поскольку сама директория назначения может еще не существовать.
+///
+/// # Arguments
+///
+/// * `dir` - директория на проверяемом томе (например, директория назначения проекта)
+///
+/// # Returns
+///
+/// `Ok(true)` если регистр не различается (Windows, macOS по умолчанию),
+/// `Ok(false)` если различается, `Err` если пробный файл создать не удалось
+pub fn is_case_insensitive_fs(dir: &Path) -> Result<bool, String> {
+    let existing = dir.ancestors()
+        .find(|path| path.is_dir())
+        .ok_or_else(|| format!("No existing directory found for {:?}", dir))?;
+    let probe_dir = existing.join(format!(".ai_project_template_case_probe_{}", std::process::id()));
+    fs::create_dir_all(&probe_dir)
+        .map_err(|e| format!("Failed to create case probe in {:?}: {}", existing, e))?;
+    let result = fs::write(probe_dir.join("a"), b"")
+        .map(|()| probe_dir.join("A").exists())
+        .map_err(|e| format!("Failed to create case probe in {:?}: {}", existing, e));
+    let _ = fs::remove_dir_all(&probe_dir);
+    result
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Временная директория теста с пресетом `demo` (удаляется в конце теста)
+    fn preset_fixture(name: &str) -> PathBuf {
+        let root = std::env::temp_dir()
+            .join(format!("ai_project_template_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let preset_dir = root.join("presets").join("demo");
+        fs::create_dir_all(&preset_dir).unwrap();
+        fs::write(preset_dir.join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(preset_dir.join("files_config.json"), r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src", "docs"],
+            "templates": [{"source": "main.rs", "destination": "src/main.rs"}],
+            "empty_files": ["docs/notes.md"],
+            "readme_template": "{language} project",
+            "fields": [{"id": "language", "label": "Language", "required": true, "type": "text"}],
+            "options": [{"id": "ci", "label": "CI", "default": false}]
+        }"#).unwrap();
+        root
+    }
+
+    #[test]
+    fn build_creates_project_files() {
+        let root = preset_fixture("builder_files");
+        let report = ProjectBuilder::new(root.join("presets"), "demo")
+            .project_name("my_project")
+            .destination(root.join("work"))
+            .field("language", "Rust")
+            .option("ci", true)
+            .build()
+            .unwrap();
+
+        let project = root.join("work").join("my_project");
+        assert_eq!(report.project_path, project);
+        assert!(!report.dry_run);
+        assert!(report.warnings.is_empty());
+        assert!(project.join("docs").is_dir());
+        assert_eq!(fs::read_to_string(project.join("src").join("main.rs")).unwrap(), "fn main() {}\n");
+        assert_eq!(fs::read(project.join("docs").join("notes.md")).unwrap(), b"");
+        assert!(fs::read_to_string(project.join("README.md")).unwrap().contains("Rust project"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn build_accepts_builtin_options_in_dry_run() {
+        let root = preset_fixture("builder_dry_run");
+        let report = ProjectBuilder::new(root.join("presets"), "demo")
+            .project_name("my_project")
+            .destination(root.join("work"))
+            .field("language", "Rust")
+            .option("git_init", true)
+            .dry_run(true)
+            .build()
+            .unwrap();
+
+        assert!(report.dry_run);
+        assert!(report.log_lines.iter().any(|line| line.contains("Would copy template")));
+        assert!(report.log_lines.iter().any(|line| line.contains("Would initialize git repository")));
+        assert!(!root.join("work").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn build_rejects_unknown_option() {
+        let root = preset_fixture("builder_unknown_option");
+        let error = ProjectBuilder::new(root.join("presets"), "demo")
+            .project_name("my_project")
+            .destination(root.join("work"))
+            .field("language", "Rust")
+            .option("no_such_option", true)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(error, "Unknown option 'no_such_option' for preset 'demo'");
+        assert!(!root.join("work").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn build_rejects_empty_required_field() {
+        let root = preset_fixture("builder_required");
+        let error = ProjectBuilder::new(root.join("presets"), "demo")
+            .project_name("my_project")
+            .destination(root.join("work"))
+            .field("language", "  ")
+            .build()
+            .unwrap_err();
+
+        assert_eq!(error, "Required field 'language' is empty");
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Пресет, второй шаблон которого не читается как файл: директория вместо
+    /// файла-источника, так что ошибка копирования не зависит от прав пользователя
+    fn failing_preset(root: &Path) -> PresetConfig {
+        let preset_dir = root.join("presets").join("demo");
+        fs::create_dir_all(preset_dir.join("unreadable")).unwrap();
+        fs::write(preset_dir.join("main.rs"), "fn main() {}\n").unwrap();
+        serde_json::from_str(r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src", "docs/api"], "empty_files": [], "fields": [], "options": [],
+            "templates": [
+                {"source": "main.rs", "destination": "src/main.rs"},
+                {"source": "unreadable", "destination": "config/settings.toml"}
+            ]
+        }"#).unwrap()
+    }
+
+    #[test]
+    fn failed_creation_removes_new_project() {
+        let root = preset_fixture("rollback_new");
+        let config = failing_preset(&root);
+        let project = root.join("work").join("my_project");
+        fs::create_dir_all(root.join("work")).unwrap();
+        // Без временной директории откат удаляет пути, созданные на месте
+        let options = HashMap::from([("no_atomic".to_string(), true)]);
+
+        let error = create_project(&project, &root.join("presets"), &config, "my_project", &HashMap::new(), &options)
+            .unwrap_err();
+
+        assert!(!project.exists());
+        // Родительская директория существовала до вызова и остается на месте
+        assert!(root.join("work").is_dir());
+        let removed: Vec<String> = error.lines()
+            .filter(|line| line.starts_with("Rolled back: removed "))
+            .map(str::to_string)
+            .collect();
+        // Удаление идет в обратном порядке, директория проекта - последней; файл
+        // назначения неудачного копирования на части систем успевает появиться
+        let expected: Vec<String> = [
+            project.join("config"),
+            project.join("src").join("main.rs"),
+            project.join("docs").join("api"),
+            project.join("docs"),
+            project.join("src"),
+            project.clone(),
+        ]
+            .iter()
+            .map(|path| format!("Rolled back: removed {:?}", path))
+            .collect();
+        assert!(removed.ends_with(&expected), "{:?}", removed);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn failed_creation_keeps_existing_project_dir() {
+        let root = preset_fixture("rollback_existing");
+        let config = failing_preset(&root);
+        let project = root.join("my_project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("notes.txt"), "keep me").unwrap();
+        let options = HashMap::from([("force".to_string(), true)]);
+
+        let error = create_project(&project, &root.join("presets"), &config, "my_project", &HashMap::new(), &options)
+            .unwrap_err();
+
+        assert!(!error.contains("Rolled back"));
+        assert_eq!(fs::read_to_string(project.join("notes.txt")).unwrap(), "keep me");
+        assert!(project.join("src").join("main.rs").is_file());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Различает ли регистр файловая система в `dir` (проверка без `is_case_insensitive_fs`)
+    fn host_is_case_insensitive(dir: &Path) -> bool {
+        let file = dir.join("host_case_check");
+        fs::write(&file, b"").unwrap();
+        let insensitive = dir.join("HOST_CASE_CHECK").exists();
+        fs::remove_file(&file).unwrap();
+        insensitive
+    }
+
+    #[test]
+    fn case_probe_matches_host_and_cleans_up() {
+        let root = std::env::temp_dir()
+            .join(format!("ai_project_template_case_probe_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("existing.txt"), "keep").unwrap();
+        let expected = host_is_case_insensitive(&root);
+
+        assert_eq!(is_case_insensitive_fs(&root).unwrap(), expected);
+        // Директория назначения еще не создана: проверяется ближайший существующий родитель
+        assert_eq!(is_case_insensitive_fs(&root.join("work").join("my_project")).unwrap(), expected);
+
+        let entries: Vec<_> = fs::read_dir(&root).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["existing.txt"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn templates_differing_only_by_case() {
+        let root = preset_fixture("case_collision");
+        let preset_dir = root.join("presets").join("demo");
+        fs::write(preset_dir.join("upper.md"), "upper").unwrap();
+        fs::write(preset_dir.join("lower.md"), "lower").unwrap();
+        fs::write(preset_dir.join("files_config.json"), r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": [], "fields": [], "options": [],
+            "templates": [
+                {"source": "upper.md", "destination": "docs/Notes.md"},
+                {"source": "lower.md", "destination": "docs/notes.md"}
+            ]
+        }"#).unwrap();
+        let work = root.join("work");
+        fs::create_dir_all(&work).unwrap();
+        let builder = || ProjectBuilder::new(root.join("presets"), "demo")
+            .project_name("my_project")
+            .destination(work.clone());
+        let warning = "Warning: Paths 'docs/Notes.md' and 'docs/notes.md' differ only by case".to_string();
+
+        // Пробный запуск только предупреждает на любой файловой системе
+        let report = builder().dry_run(true).build().unwrap();
+        assert!(report.warnings.contains(&warning), "{:?}", report.warnings);
+
+        let result = builder().build();
+        let project = work.join("my_project");
+        if host_is_case_insensitive(&work) {
+            assert_eq!(result.unwrap_err(), format!(
+                "Paths 'docs/Notes.md' and 'docs/notes.md' differ only by case, but the filesystem at {:?} is case-insensitive",
+                work
+            ));
+            assert!(!project.exists());
+        } else {
+            let report = result.unwrap();
+            assert!(report.warnings.contains(&warning), "{:?}", report.warnings);
+            assert_eq!(fs::read_to_string(project.join("docs").join("Notes.md")).unwrap(), "upper");
+            assert_eq!(fs::read_to_string(project.join("docs").join("notes.md")).unwrap(), "lower");
+        }
+        // Пробный файл проверки регистра не остается в директории назначения
+        let entries: Vec<_> = fs::read_dir(&work).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .filter(|name| name != "my_project")
+            .collect();
+        assert!(entries.is_empty(), "{:?}", entries);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Пресет `demo` в `root/presets` с файлами-источниками (имя, содержимое)
+    fn write_sources(root: &Path, sources: &[(&str, &str)]) -> PathBuf {
+        let presets_dir = root.join("presets");
+        let preset_dir = presets_dir.join("demo");
+        fs::create_dir_all(&preset_dir).unwrap();
+        for (name, content) in sources {
+            fs::write(preset_dir.join(name), content).unwrap();
+        }
+        presets_dir
+    }
+
+    /// Временная корневая директория теста (удаляется в конце теста)
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir()
+            .join(format!("ai_project_template_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn dry_run_writes_nothing() {
+        let root = temp_root("dry_run");
+        let presets_dir = write_sources(&root, &[("notes.md", "notes")]);
+        let config: PresetConfig = serde_json::from_str(r##"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src"], "empty_files": ["plan.md"], "fields": [], "options": [],
+            "templates": [{"source": "notes.md", "destination": "docs/notes.md"}],
+            "readme_template": "# {PROJECT_NAME}"
+        }"##).unwrap();
+        let project_path = root.join("my_project");
+        let options = HashMap::from([("dry_run".to_string(), true)]);
+
+        let log = create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &options).unwrap();
+
+        assert!(log.iter().any(|line| line.starts_with("[DRY RUN] Would create")));
+        assert!(log.iter().any(|line| line.starts_with("[DRY RUN] Would copy template")));
+        assert!(log.iter().any(|line| line.starts_with("[DRY RUN] Would generate README")));
+        assert!(!project_path.exists());
+        let entries: Vec<_> = fs::read_dir(&root).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(entries, ["presets"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn line_endings_are_normalized_per_template() {
+        let root = temp_root("line_endings");
+        // Файлы из Windows-редактора: BOM и смешанные переводы строк
+        let presets_dir = write_sources(&root, &[
+            ("crlf.txt", "\u{feff}a\r\nb\n"),
+            ("lf.txt", "\u{feff}a\r\nb\n"),
+            ("raw.txt", "\u{feff}a\r\nb\n"),
+        ]);
+        let config: PresetConfig = serde_json::from_str(r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": [], "empty_files": [], "fields": [], "options": [],
+            "templates": [
+                {"source": "crlf.txt", "destination": "crlf.txt", "line_endings": "crlf"},
+                {"source": "lf.txt", "destination": "lf.txt", "line_endings": "lf"},
+                {"source": "raw.txt", "destination": "raw.txt"}
+            ],
+            "generate_readme": true, "readme_template": "\ufeffline 1\r\nline 2\r\n"
+        }"#).unwrap();
+        let project_path = root.join("my_project");
+
+        create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &HashMap::new()).unwrap();
+
+        let read = |name: &str| fs::read_to_string(project_path.join(name)).unwrap();
+        assert_eq!(read("crlf.txt"), "a\r\nb\r\n");
+        assert_eq!(read("lf.txt"), "a\nb\n");
+        assert_eq!(read("raw.txt"), "\u{feff}a\r\nb\n");
+        let readme = read("README.md");
+        assert!(readme.ends_with("line 1\nline 2\n"), "{:?}", readme);
+        assert!(!readme.contains('\r') && !readme.contains('\u{feff}'));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn failed_creation_leaves_no_staging_dir() {
+        let root = temp_root("atomic_failure");
+        let presets_dir = write_sources(&root, &[]);
+        // Директория вместо файла: копирование шаблона завершится ошибкой
+        fs::create_dir_all(presets_dir.join("demo").join("broken")).unwrap();
+        let config: PresetConfig = serde_json::from_str(r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src"], "empty_files": [], "fields": [], "options": [],
+            "templates": [{"source": "broken", "destination": "broken.txt"}]
+        }"#).unwrap();
+        let project_path = root.join("my_project");
+
+        assert!(create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &HashMap::new()).is_err());
+
+        let entries: Vec<_> = fs::read_dir(&root).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(entries, ["presets"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn staged_creation_logs_final_paths() {
+        let root = temp_root("atomic_success");
+        let presets_dir = write_sources(&root, &[]);
+        let config: PresetConfig = serde_json::from_str(r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src"], "empty_files": [], "fields": [], "options": [], "templates": []
+        }"#).unwrap();
+        let project_path = root.join("my_project");
+
+        let log = create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &HashMap::new()).unwrap();
+
+        assert!(project_path.join("src").is_dir());
+        // Лог указывает на путь проекта, а не на временную директорию
+        assert!(log.contains(&format!("Creating subdirectory: {:?}", project_path.join("src"))), "{:?}", log);
+        let staged_paths = log.iter()
+            .filter(|line| !line.starts_with("Moved project into place"))
+            .any(|line| line.contains(".__tmp_"));
+        assert!(!staged_paths, "{:?}", log);
+        let mut entries: Vec<_> = fs::read_dir(&root).unwrap().map(|e| e.unwrap().file_name()).collect();
+        entries.sort();
+        assert_eq!(entries, ["my_project", "presets"]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Пресет `demo` с шаблоном `data.bin`, связанным жесткой ссылкой
+    fn linked_template(root: &Path) -> (PathBuf, TemplateConfig) {
+        let presets_dir = write_sources(root, &[("data.bin", "weights")]);
+        let template: TemplateConfig = serde_json::from_str(
+            r#"{"source": "data.bin", "destination": "data/data.bin", "link": true}"#
+        ).unwrap();
+        (presets_dir, template)
+    }
+
+    #[test]
+    fn linked_template_shares_the_preset_file() {
+        let root = temp_root("hard_link");
+        let (presets_dir, _) = linked_template(&root);
+        let config: PresetConfig = serde_json::from_str(r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": [], "empty_files": [], "fields": [], "options": [],
+            "templates": [{"source": "data.bin", "destination": "data/data.bin", "link": true}]
+        }"#).unwrap();
+        let project_path = root.join("my_project");
+
+        let log = create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &HashMap::new()).unwrap();
+
+        let linked = project_path.join("data").join("data.bin");
+        assert!(log.iter().any(|line| line.starts_with("Linking template")), "{:?}", log);
+        assert!(!log.iter().any(|line| line.contains("Hard link not possible")), "{:?}", log);
+        assert_eq!(fs::read_to_string(&linked).unwrap(), "weights");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let source = fs::metadata(presets_dir.join("demo").join("data.bin")).unwrap();
+            let target = fs::metadata(&linked).unwrap();
+            assert_eq!(source.ino(), target.ino());
+            assert_eq!(target.nlink(), 2);
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn link_falls_back_to_copy_when_linking_fails() {
+        let root = temp_root("hard_link_fallback");
+        let (presets_dir, template) = linked_template(&root);
+        let source = presets_dir.join("demo").join("data.bin");
+        let dest = root.join("my_project").join("data").join("data.bin");
+        let mut manifest = ProjectManifest::default();
+        let mut log = Vec::new();
+        // Как при проекте на другой файловой системе, чем пресеты
+        let cross_device = |_: &Path, _: &Path| Err(io::Error::other("cross-device link"));
+
+        copy_template_with(&template, &source, &dest, &mut manifest, &mut PhaseTimer::new(),
+            &RetryPolicy::none(), &mut log, cross_device).unwrap();
+
+        assert_eq!(log, [
+            format!("Note: Hard link not possible for {:?} (cross-device link), copying instead", dest),
+            format!("Copying template: {:?} -> {:?}", source, dest),
+        ]);
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "weights");
+        assert!(manifest.files.contains_key("data/data.bin"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(fs::metadata(&source).unwrap().nlink(), 1);
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Пресет `demo` с шаблоном `README.md` и заданным `generate_readme`
+    fn readme_template_config(generate_readme: bool) -> PresetConfig {
+        serde_json::from_str(&format!(r##"{{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": [], "empty_files": [], "fields": [], "options": [],
+            "templates": [{{"source": "readme.md", "destination": "README.md"}}],
+            "generate_readme": {}, "readme_template": "# generated"
+        }}"##, generate_readme)).unwrap()
+    }
+
+    #[test]
+    fn readme_template_is_kept_whatever_generate_readme_says() {
+        for generate_readme in [true, false] {
+            let root = temp_root(&format!("readme_flag_{}", generate_readme));
+            let presets_dir = write_sources(&root, &[("readme.md", "# from template v1")]);
+            let config = readme_template_config(generate_readme);
+            let project_path = root.join("my_project");
+            let skipped = "Skipping README generation (disabled by preset)".to_string();
+            let kept = format!("Keeping README from preset template: {:?}", project_path.join("README.md"));
+
+            let log = create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &HashMap::new()).unwrap();
+
+            assert_eq!(fs::read_to_string(project_path.join("README.md")).unwrap(), "# from template v1");
+            assert_eq!(log.contains(&skipped), !generate_readme, "{:?}", log);
+            assert_eq!(log.contains(&kept), generate_readme, "{:?}", log);
+
+            // С "refresh" README обновляется из шаблона, а не генерируется
+            fs::write(presets_dir.join("demo").join("readme.md"), "# from template v2").unwrap();
+            let refresh = HashMap::from([("force".to_string(), true), ("refresh".to_string(), true)]);
+            let log = create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &refresh).unwrap();
+
+            assert_eq!(fs::read_to_string(project_path.join("README.md")).unwrap(), "# from template v2");
+            assert_eq!(log.contains(&skipped), !generate_readme, "{:?}", log);
+            assert_eq!(log.contains(&kept), generate_readme, "{:?}", log);
+            assert!(!log.iter().any(|line| line.starts_with("Generating README")), "{:?}", log);
+            fs::remove_dir_all(&root).unwrap();
+        }
+    }
+
+    #[test]
+    fn readme_is_generated_only_when_enabled() {
+        for generate_readme in [true, false] {
+            let root = temp_root(&format!("readme_generated_{}", generate_readme));
+            let presets_dir = write_sources(&root, &[]);
+            let mut config = readme_template_config(generate_readme);
+            config.templates.clear();
+            let project_path = root.join("my_project");
+
+            let refresh = HashMap::from([("force".to_string(), true), ("refresh".to_string(), true)]);
+            for options in [HashMap::new(), refresh] {
+                let log = create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &options).unwrap();
+
+                let readme = fs::read_to_string(project_path.join("README.md")).ok();
+                assert_eq!(readme.is_some_and(|readme| readme.contains("# generated")), generate_readme);
+                assert_eq!(log.contains(&"Skipping README generation (disabled by preset)".to_string()), !generate_readme);
+            }
+            fs::remove_dir_all(&root).unwrap();
+        }
+    }
+
+    #[test]
+    fn gitkeep_only_in_dirs_left_empty() {
+        let root = temp_root("gitkeep");
+        let presets_dir = write_sources(&root, &[("main.rs", "fn main() {}\n")]);
+        let config: PresetConfig = serde_json::from_str(r##"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src", "docs", "assets"], "empty_files": ["docs/notes.md"],
+            "templates": [{"source": "main.rs", "destination": "src/main.rs"}],
+            "fields": [], "options": [], "keep_empty_dirs": true,
+            "generate_readme": false, "readme_template": ""
+        }"##).unwrap();
+        let project_path = root.join("my_project");
+
+        let log = create_project(&project_path, &presets_dir, &config, "my_project", &HashMap::new(), &HashMap::new()).unwrap();
+
+        let keep_path = project_path.join("assets").join(GITKEEP_FILE_NAME);
+        assert_eq!(fs::read_to_string(&keep_path).unwrap(), "");
+        assert_eq!(fs::read_dir(project_path.join("assets")).unwrap().count(), 1);
+        assert!(log.contains(&format!("Creating {}: {:?}", GITKEEP_FILE_NAME, keep_path)), "{:?}", log);
+        let manifest = ProjectManifest::load(&project_path).unwrap().unwrap();
+        assert!(manifest.files.contains_key("assets/.gitkeep"));
+
+        // В директориях с шаблоном или пустым файлом .gitkeep не нужен
+        for dir in ["src", "docs"] {
+            assert!(!project_path.join(dir).join(GITKEEP_FILE_NAME).exists(), "{}", dir);
+            assert!(!manifest.files.contains_key(&format!("{}/.gitkeep", dir)), "{}", dir);
+        }
+        assert_eq!(log.iter().filter(|line| line.contains(GITKEEP_FILE_NAME)).count(), 1, "{:?}", log);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,457 @@
+//! # Модуль диагностики пресетов
+//!
+//! Объясняет, почему директория пресета не появляется в списке (или появляется,
+//! но не работает). `diagnose_preset` проходит правила обнаружения по порядку
+//! и для каждого возвращает пункт чек-листа с объяснением.
+//!
+//! Проверки вызывают те же функции, что и приложение (`scan_presets`,
+//! `discover_presets_merged`, `load_preset_config`, `validate_presets`), поэтому
+//! диагностика не может разойтись с реальным поведением.
+//!
+//! ## Правила
+//!
+//! 1. Директория существует.
+//! 2. Она лежит непосредственно в директории пресетов (основной, пользовательской
+//!    или поддиректории источника из `preset_sources`): вложенные директории не сканируются.
+//! 3. В ней есть `files_config.json` (имя файла с учетом регистра; для
+//!    `Files_Config.JSON` выводится подсказка).
+//! 4. `files_config.json` разбирается.
+//! 5. Пресет не скрыт: сканирование не остановилось по лимиту `max_scanned_dirs`
+//!    раньше, и его не заменяет пресет с тем же именем из пользовательской директории.
+//!    Пресет источника, имя которого совпало с другим, виден как `id@источник`.
+//! 6. `preset_id` уникален и совпадает с именем директории.
+//! 7. Остальные проверки `validate_presets` пройдены.
+//!
+//! Если не выполнено одно из правил 1-4, следующие проверки не выполняются.
+//!
+//! ## Пример
+//!
+//! ```
+//! use ai_project_template::doctor::{diagnose_preset, CheckStatus};
+//! use std::fs;
+//!
+//! let presets = std::env::temp_dir().join(format!("ai_project_template_doctor_{}", std::process::id()));
+//! fs::create_dir_all(presets.join("broken")).unwrap();
+//! fs::write(presets.join("broken").join("files_config.json"), "{").unwrap();
+//!
+//! let checks = diagnose_preset(&presets.join("broken"), &presets, None, &[], 500);
+//! let failed = checks.iter().find(|check| check.status == CheckStatus::Fail).unwrap();
+//! assert_eq!(failed.title, "files_config.json parses");
+//! for check in &checks {
+//!     println!("{}", check);
+//! }
+//! fs::remove_dir_all(&presets).unwrap();
+//! ```
+
+use crate::presets::{discover_presets_merged, load_preset_config, scan_presets, source_preset_id, ScanOptions};
+use crate::settings::PresetSource;
+use crate::validation::{validate_presets, Severity, ValidationIssue};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Имя файла конфигурации пресета
+const CONFIG_FILE_NAME: &str = "files_config.json";
+
+/// Результат одной проверки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// Правило выполнено
+    Pass,
+    /// Пресет виден, но с ним есть проблема
+    Warn,
+    /// Из-за этого пресет не появляется в списке или не работает
+    Fail,
+}
+
+/// Пункт чек-листа диагностики
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorCheck {
+    /// Результат проверки
+    pub status: CheckStatus,
+    /// Проверяемое правило
+    pub title: String,
+    /// Объяснение и подсказка, как исправить (для невыполненных правил)
+    pub detail: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(title: impl Into<String>) -> Self {
+        Self { status: CheckStatus::Pass, title: title.into(), detail: None }
+    }
+
+    fn with(status: CheckStatus, title: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { status, title: title.into(), detail: Some(detail.into()) }
+    }
+}
+
+impl fmt::Display for DoctorCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self.status {
+            CheckStatus::Pass => "[ok]",
+            CheckStatus::Warn => "[warning]",
+            CheckStatus::Fail => "[failed]",
+        };
+        write!(f, "{} {}", mark, self.title)?;
+        if let Some(ref detail) = self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Проверить, почему директория пресета не появляется в списке
+///
+/// # Arguments
+///
+/// * `preset_dir` - директория пресета, выбранная пользователем
+/// * `presets_dir` - основная директория пресетов
+/// * `user_presets_dir` - директория пользовательских пресетов
+/// * `sources` - дополнительные источники пресетов (`preset_sources` из настроек)
+/// * `max_dirs` - лимит сканирования (`max_scanned_dirs` из настроек)
+///
+/// # Returns
+///
+/// Пункты чек-листа в порядке правил (см. документацию модуля)
+pub fn diagnose_preset(
+    preset_dir: &Path,
+    presets_dir: &Path,
+    user_presets_dir: Option<&Path>,
+    sources: &[PresetSource],
+    max_dirs: usize,
+) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+
+    // 1. Директория существует
+    if !preset_dir.is_dir() {
+        checks.push(DoctorCheck::with(
+            CheckStatus::Fail,
+            "Folder exists",
+            format!("{:?} is not a folder", preset_dir),
+        ));
+        return checks;
+    }
+    checks.push(DoctorCheck::pass("Folder exists"));
+
+    // 2. Директория лежит непосредственно в директории пресетов
+    let preset_dir = canonical(preset_dir);
+    let source_dirs: Vec<(PathBuf, &PresetSource)> = sources.iter()
+        .map(|source| (canonical(&presets_dir.join(&source.subdir)), source))
+        .collect();
+    let roots: Vec<PathBuf> = std::iter::once(presets_dir)
+        .chain(user_presets_dir)
+        .map(canonical)
+        .chain(source_dirs.iter().map(|(dir, _)| dir.clone()))
+        .collect();
+    let (Some(root), Some(name)) = (
+        preset_dir.parent().filter(|parent| roots.iter().any(|r| r == parent)),
+        preset_dir.file_name().and_then(|n| n.to_str()),
+    ) else {
+        let detail = if roots.contains(&preset_dir) {
+            "This is the presets folder itself; choose one of the preset folders inside it".to_string()
+        } else if let Some(root) = roots.iter().find(|r| preset_dir.starts_with(r)) {
+            format!("Only folders directly inside {:?} are scanned; move the preset up to that level", root)
+        } else {
+            format!("Presets are only read from {:?}; move or import the folder there", roots[0])
+        };
+        checks.push(DoctorCheck::with(CheckStatus::Fail, "Inside the presets folder", detail));
+        return checks;
+    };
+    checks.push(DoctorCheck::pass("Inside the presets folder"));
+
+    // 3. Есть files_config.json - по тем же правилам, что и при сканировании
+    let listed = scan_presets(root, &ScanOptions::unbounded())
+        .map(|scan| scan.presets.iter().any(|(id, _)| id == name))
+        .unwrap_or(false);
+    if !listed {
+        let similar = fs::read_dir(&preset_dir).into_iter().flatten().flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .find(|file| file.eq_ignore_ascii_case(CONFIG_FILE_NAME));
+        let detail = match similar {
+            Some(file) => format!("Found {:?}; the file name is case-sensitive, rename it to {}", file, CONFIG_FILE_NAME),
+            None => format!("The folder has no {}", CONFIG_FILE_NAME),
+        };
+        checks.push(DoctorCheck::with(CheckStatus::Fail, "Contains files_config.json", detail));
+        return checks;
+    }
+    checks.push(DoctorCheck::pass("Contains files_config.json"));
+
+    // 4. Конфигурация разбирается
+    if let Err(e) = load_preset_config(root, name) {
+        checks.push(DoctorCheck::with(
+            CheckStatus::Fail,
+            "files_config.json parses",
+            format!("The preset is listed but cannot be used: {}", e),
+        ));
+        return checks;
+    }
+    checks.push(DoctorCheck::pass("files_config.json parses"));
+
+    // 5. Пресет не скрыт лимитом сканирования или пользовательским пресетом
+    let options = ScanOptions { max_dirs, cancel: None };
+    let presets = discover_presets_merged(presets_dir, user_presets_dir, sources, &options)
+        .map(|scan| scan.presets)
+        .unwrap_or_default();
+    // Пресет источника при совпадении имени получает идентификатор `id@источник`
+    let source_id = source_dirs.iter()
+        .find(|(dir, _)| dir == root)
+        .map(|(_, source)| source_preset_id(name, &source.name));
+    let shown = presets.iter()
+        .any(|(id, found_root)| (id == name || Some(id) == source_id.as_ref()) && canonical(found_root) == root);
+    // Пресет источника не заменяется другими: его скрывает только лимит сканирования
+    let replaced_by = presets.into_iter()
+        .filter(|_| source_id.is_none())
+        .find(|(id, _)| id == name)
+        .map(|(_, found_root)| canonical(&found_root));
+    checks.push(match replaced_by {
+        _ if shown => DoctorCheck::pass("Not hidden"),
+        Some(found_root) => DoctorCheck::with(
+            CheckStatus::Fail,
+            "Not hidden",
+            format!("A preset with the same folder name in {:?} is shown instead", found_root),
+        ),
+        None => DoctorCheck::with(
+            CheckStatus::Fail,
+            "Not hidden",
+            format!(
+                "Scanning stops after {} folders and this one was not reached; increase max_scanned_dirs in settings",
+                max_dirs
+            ),
+        ),
+    });
+
+    // 6 и 7. preset_id и остальные проверки validate_presets
+    let issues = validate_presets(root)
+        .ok()
+        .and_then(|mut report| report.remove(name))
+        .unwrap_or_default();
+    let (id_issues, other_issues): (Vec<ValidationIssue>, Vec<ValidationIssue>) =
+        issues.into_iter().partition(|issue| issue.path == "preset_id");
+    checks.push(issues_check("preset_id is unique and matches the folder name", &id_issues));
+    checks.push(issues_check("Passes preset validation", &other_issues));
+
+    checks
+}
+
+/// Пункт чек-листа по проблемам валидации: самая серьезная определяет результат
+fn issues_check(title: &str, issues: &[ValidationIssue]) -> DoctorCheck {
+    let status = match issues.iter().map(|issue| issue.severity).max() {
+        None => return DoctorCheck::pass(title),
+        Some(Severity::Error) => CheckStatus::Fail,
+        Some(Severity::Warning) => CheckStatus::Warn,
+    };
+    let detail = issues.iter()
+        .map(|issue| issue.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+    DoctorCheck::with(status, title, detail)
+}
+
+/// Канонический путь для сравнения (исходный путь, если канонизация не удалась)
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Временная директория теста (удаляется в конце теста)
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir()
+            .join(format!("ai_project_template_doctor_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    /// Записать в `dir` пресет с идентификатором `preset_id`
+    fn write_preset(dir: &Path, preset_id: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(CONFIG_FILE_NAME), format!(
+            r#"{{"preset_id": "{}", "preset_name": "{}", "description": "", "directories": []}}"#,
+            preset_id, preset_id
+        )).unwrap();
+    }
+
+    /// Первая невыполненная проверка
+    fn first_failure(checks: &[DoctorCheck]) -> Option<&DoctorCheck> {
+        checks.iter().find(|check| check.status != CheckStatus::Pass)
+    }
+
+    /// Директория пресетов с рабочим пресетом `ok`
+    fn presets_fixture(name: &str) -> (PathBuf, PathBuf) {
+        let root = temp_root(name);
+        let presets = root.join("presets");
+        write_preset(&presets.join("ok"), "ok");
+        (root, presets)
+    }
+
+    /// Невыполненная проверка: название и объяснение
+    fn failure(checks: &[DoctorCheck]) -> (String, String) {
+        let check = first_failure(checks).expect("a failed check");
+        assert_eq!(check.status, CheckStatus::Fail, "{:?}", checks);
+        (check.title.clone(), check.detail.clone().unwrap_or_default())
+    }
+
+    #[test]
+    fn working_preset_passes_every_rule() {
+        let (root, presets) = presets_fixture("ok");
+
+        let checks = diagnose_preset(&presets.join("ok"), &presets, None, &[], 500);
+
+        assert_eq!(first_failure(&checks), None, "{:?}", checks);
+        assert_eq!(checks.len(), 7);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn missing_folder_fails_first_rule() {
+        let (root, presets) = presets_fixture("missing");
+        let dir = presets.join("missing");
+
+        let checks = diagnose_preset(&dir, &presets, None, &[], 500);
+
+        assert_eq!(checks.len(), 1);
+        assert_eq!(failure(&checks), ("Folder exists".to_string(), format!("{:?} is not a folder", dir)));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn folder_outside_presets_is_rejected() {
+        let (root, presets) = presets_fixture("outside");
+        let elsewhere = root.join("elsewhere");
+        write_preset(&elsewhere, "elsewhere");
+
+        let checks = diagnose_preset(&elsewhere, &presets, None, &[], 500);
+
+        assert_eq!(failure(&checks), (
+            "Inside the presets folder".to_string(),
+            format!("Presets are only read from {:?}; move or import the folder there", canonical(&presets)),
+        ));
+        let checks = diagnose_preset(&presets, &presets, None, &[], 500);
+        assert_eq!(failure(&checks).1, "This is the presets folder itself; choose one of the preset folders inside it");
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn nested_folder_is_not_scanned() {
+        let (root, presets) = presets_fixture("nested");
+        let nested = presets.join("group").join("nested");
+        write_preset(&nested, "nested");
+
+        let checks = diagnose_preset(&nested, &presets, None, &[], 500);
+
+        assert_eq!(failure(&checks), (
+            "Inside the presets folder".to_string(),
+            format!("Only folders directly inside {:?} are scanned; move the preset up to that level", canonical(&presets)),
+        ));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn config_file_name_in_other_case_gets_a_hint() {
+        let (root, presets) = presets_fixture("config_case");
+        let dir = presets.join("upper");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Files_Config.JSON"), "{}").unwrap();
+        fs::create_dir_all(presets.join("empty")).unwrap();
+
+        // На системах без учета регистра файл найден под правильным именем
+        if !dir.join(CONFIG_FILE_NAME).exists() {
+            let checks = diagnose_preset(&dir, &presets, None, &[], 500);
+            assert_eq!(failure(&checks), (
+                "Contains files_config.json".to_string(),
+                "Found \"Files_Config.JSON\"; the file name is case-sensitive, rename it to files_config.json".to_string(),
+            ));
+        }
+        let checks = diagnose_preset(&presets.join("empty"), &presets, None, &[], 500);
+        assert_eq!(failure(&checks), (
+            "Contains files_config.json".to_string(),
+            "The folder has no files_config.json".to_string(),
+        ));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn unparsable_config_is_listed_but_unusable() {
+        let (root, presets) = presets_fixture("unparsable");
+        fs::create_dir_all(presets.join("broken")).unwrap();
+        fs::write(presets.join("broken").join(CONFIG_FILE_NAME), "{").unwrap();
+
+        let checks = diagnose_preset(&presets.join("broken"), &presets, None, &[], 500);
+
+        let (title, detail) = failure(&checks);
+        assert_eq!(title, "files_config.json parses");
+        assert!(detail.starts_with("The preset is listed but cannot be used: Failed to parse preset config:"), "{}", detail);
+        assert_eq!(checks.len(), 4);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn preset_hidden_by_user_dir_or_scan_limit() {
+        let (root, presets) = presets_fixture("hidden");
+        let user = root.join("user");
+        write_preset(&user.join("ok"), "ok");
+
+        let checks = diagnose_preset(&presets.join("ok"), &presets, Some(&user), &[], 500);
+        assert_eq!(failure(&checks), (
+            "Not hidden".to_string(),
+            format!("A preset with the same folder name in {:?} is shown instead", canonical(&user)),
+        ));
+        // Пользовательский пресет, наоборот, виден
+        let checks = diagnose_preset(&user.join("ok"), &presets, Some(&user), &[], 500);
+        assert_eq!(first_failure(&checks), None, "{:?}", checks);
+
+        let checks = diagnose_preset(&presets.join("ok"), &presets, None, &[], 0);
+        assert_eq!(failure(&checks), (
+            "Not hidden".to_string(),
+            "Scanning stops after 0 folders and this one was not reached; increase max_scanned_dirs in settings".to_string(),
+        ));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn duplicate_preset_id_fails_id_rule() {
+        let (root, presets) = presets_fixture("duplicate_id");
+        write_preset(&presets.join("copy"), "ok");
+
+        let checks = diagnose_preset(&presets.join("copy"), &presets, None, &[], 500);
+
+        assert_eq!(failure(&checks), (
+            "preset_id is unique and matches the folder name".to_string(),
+            "preset_id 'ok' does not match directory name 'copy'; Duplicate preset_id 'ok' (declared in: copy, ok)".to_string(),
+        ));
+        assert_eq!(checks.last(), Some(&DoctorCheck::pass("Passes preset validation")));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn source_presets_are_found_like_in_the_list() {
+        let root = temp_root("sources");
+        let presets = root.join("presets");
+        write_preset(&presets.join("software"), "software");
+        write_preset(&presets.join("company").join("software"), "software");
+        write_preset(&presets.join("company").join("extra"), "extra");
+        let source = PresetSource {
+            name: "company".to_string(),
+            zip_url: "https://git.example.com/presets.zip".to_string(),
+            subdir: "company".to_string(),
+        };
+
+        // `extra` виден под своим именем, `software` - как `software@company`
+        for preset in ["software", "extra"] {
+            let dir = presets.join("company").join(preset);
+            let checks = diagnose_preset(&dir, &presets, None, std::slice::from_ref(&source), 500);
+            assert_eq!(first_failure(&checks), None, "{}: {:?}", preset, checks);
+            assert_eq!(checks.len(), 7);
+        }
+
+        // Без настроенного источника директория считается вложенной
+        let checks = diagnose_preset(&presets.join("company").join("extra"), &presets, None, &[], 500);
+        let failure = first_failure(&checks).unwrap();
+        assert_eq!(failure.title, "Inside the presets folder");
+        assert!(failure.detail.as_deref().unwrap().starts_with("Only folders directly inside"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
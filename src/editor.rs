@@ -0,0 +1,162 @@
+//! # Модуль открытия проекта в редакторе
+//!
+//! После создания проекта его директорию можно открыть в редакторе пользователя.
+//! Команда задается шаблоном в настройках (`AppSettings::open_in_editor`, например
+//! `code {path}`): шаблон разбивается на аргументы с учетом кавычек, затем `{path}`
+//! в каждом аргументе заменяется путем к проекту. Так путь с пробелами остается
+//! одним аргументом. Если `{path}` в шаблоне нет, путь добавляется последним аргументом.
+//!
+//! Редактор запускается без ожидания завершения; ошибка запуска не считается
+//! ошибкой создания проекта.
+
+use std::path::Path;
+use std::process;
+
+/// Шаблон команды редактора по умолчанию
+pub const DEFAULT_EDITOR_COMMAND: &str = "code {path}";
+
+/// Место подстановки пути к проекту в шаблоне команды
+pub const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Разбить строку команды на аргументы с учетом кавычек
+///
+/// - аргументы разделяются пробельными символами;
+/// - в одинарных кавычках все символы берутся как есть;
+/// - в двойных кавычках `\"` и `\\` дают `"` и `\`, остальные `\` сохраняются;
+/// - вне кавычек `\` экранирует только пробельный символ, кавычку или `\`,
+///   поэтому пути Windows (`C:\tools\edit.exe`) можно писать без кавычек.
+///
+/// # Returns
+///
+/// Аргументы или `Err`, если кавычка не закрыта
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::editor::split_command_line;
+///
+/// assert_eq!(split_command_line("code -n {path}").unwrap(), ["code", "-n", "{path}"]);
+/// assert_eq!(
+///     split_command_line(r#""C:\Program Files\Editor\edit.exe" --goto '{path}'"#).unwrap(),
+///     [r"C:\Program Files\Editor\edit.exe", "--goto", "{path}"],
+/// );
+/// assert_eq!(split_command_line(r"my\ editor a\\b C:\bin\ed").unwrap(), ["my editor", r"a\b", r"C:\bin\ed"]);
+/// assert_eq!(split_command_line(r#"say "a \"b\"" ''"#).unwrap(), ["say", r#"a "b""#, ""]);
+/// assert!(split_command_line("code 'unclosed").is_err());
+/// ```
+pub fn split_command_line(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // Начат ли аргумент (пустые кавычки дают пустой аргумент)
+    let mut in_word = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Unclosed single quote in command".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.peek(), Some('"' | '\\')) => word.extend(chars.next()),
+                        Some(c) => word.push(c),
+                        None => return Err("Unclosed double quote in command".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.peek() {
+                    Some(&next) if next.is_whitespace() || matches!(next, '\'' | '"' | '\\') => {
+                        word.push(next);
+                        chars.next();
+                    }
+                    _ => word.push('\\'),
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Построить команду редактора для директории проекта
+///
+/// # Arguments
+///
+/// * `template` - шаблон команды (см. `DEFAULT_EDITOR_COMMAND`)
+/// * `project_path` - директория созданного проекта
+///
+/// # Returns
+///
+/// Программа и ее аргументы или `Err`, если шаблон пуст или не разбирается
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use ai_project_template::editor::editor_command;
+///
+/// let (program, args) = editor_command("code -n {path}", Path::new("/home/ann/My Book")).unwrap();
+/// assert_eq!(program, "code");
+/// assert_eq!(args, ["-n", "/home/ann/My Book"]);
+///
+/// // Без {path} путь добавляется в конец
+/// let (_, args) = editor_command("subl", Path::new("/tmp/book")).unwrap();
+/// assert_eq!(args, ["/tmp/book"]);
+///
+/// assert!(editor_command("  ", Path::new("/tmp/book")).is_err());
+/// ```
+pub fn editor_command(template: &str, project_path: &Path) -> Result<(String, Vec<String>), String> {
+    let mut words = split_command_line(template)?;
+    if words.is_empty() {
+        return Err("Editor command is empty".to_string());
+    }
+    let path = project_path.display().to_string();
+    if words.iter().any(|word| word.contains(PATH_PLACEHOLDER)) {
+        for word in &mut words {
+            *word = word.replace(PATH_PLACEHOLDER, &path);
+        }
+    } else {
+        words.push(path);
+    }
+    let program = words.remove(0);
+    Ok((program, words))
+}
+
+/// Запустить редактор для директории проекта, не дожидаясь его завершения
+///
+/// # Returns
+///
+/// Запущенная команда (для лога) или `Err` с описанием ошибки запуска
+pub fn open_in_editor(template: &str, project_path: &Path) -> Result<String, String> {
+    let (program, args) = editor_command(template, project_path)?;
+    let description = std::iter::once(&program).chain(&args).cloned().collect::<Vec<_>>().join(" ");
+    process::Command::new(&program)
+        .args(&args)
+        .current_dir(project_path)
+        .spawn()
+        .map(|_| description)
+        .map_err(|e| format!("Failed to run {}: {}", program, e))
+}
@@ -0,0 +1,272 @@
+//! # Модуль выражений вычисляемых полей
+//!
+//! Вычисляемое поле пресета (`ComputedFieldConfig`) задается выражением, значение
+//! которого подставляется в шаблоны как значение обычного поля. Язык выражений
+//! намеренно минимален:
+//!
+//! - `@{field_id}` - значение поля (или `@{project_name}`); регистр идентификатора
+//!   не важен, если точного совпадения нет;
+//! - `"текст"` или `'текст'` - строковая константа (в двойных кавычках `\"` и `\\`);
+//! - `slug(x)`, `upper(x)`, `lower(x)` - преобразования, как у `FieldTransform`;
+//! - `year()`, `date()` - текущий год и дата (`YYYY-MM-DD`);
+//! - `a + b` - конкатенация.
+//!
+//! Выражение разбирается рекурсивным спуском в дерево и затем вычисляется; ошибки
+//! разбора и ссылки на неизвестные поля возвращаются как `Err`.
+
+use crate::presets::FieldTransform;
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Узел разобранного выражения
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    /// Строковая константа
+    Literal(String),
+    /// Ссылка на поле `@{id}`
+    Field(String),
+    /// Вызов встроенной функции с аргументами
+    Call(Function, Vec<Expr>),
+    /// Конкатенация `a + b + ...`
+    Concat(Vec<Expr>),
+}
+
+/// Встроенная функция
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Slug,
+    Upper,
+    Lower,
+    Year,
+    Date,
+}
+
+impl Function {
+    /// Функция по имени и число ее аргументов
+    fn parse(name: &str) -> Option<(Function, usize)> {
+        match name {
+            "slug" => Some((Function::Slug, 1)),
+            "upper" => Some((Function::Upper, 1)),
+            "lower" => Some((Function::Lower, 1)),
+            "year" => Some((Function::Year, 0)),
+            "date" => Some((Function::Date, 0)),
+            _ => None,
+        }
+    }
+}
+
+/// Вычислить выражение на текущую дату (см. `eval_expression_at`)
+pub fn eval_expression(expression: &str, values: &HashMap<String, String>) -> Result<String, String> {
+    eval_expression_at(expression, values, chrono::Local::now().date_naive())
+}
+
+/// Вычислить выражение
+///
+/// # Arguments
+///
+/// * `expression` - выражение (см. описание модуля)
+/// * `values` - значения полей по идентификатору
+/// * `today` - дата для `year()` и `date()`
+///
+/// # Returns
+///
+/// Значение или `Err` с описанием ошибки разбора либо неизвестного поля
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use chrono::NaiveDate;
+/// use ai_project_template::expression::eval_expression_at;
+///
+/// let values = HashMap::from([
+///     ("project_name".to_string(), "My Cool App".to_string()),
+///     ("org".to_string(), "Acme".to_string()),
+/// ]);
+/// let today = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+///
+/// assert_eq!(eval_expression_at("slug(@{project_name})", &values, today).unwrap(), "my-cool-app");
+/// assert_eq!(eval_expression_at("lower(@{ORG}) + '.' + slug(@{project_name})", &values, today).unwrap(), "acme.my-cool-app");
+/// assert_eq!(eval_expression_at(r#"upper("v") + year()"#, &values, today).unwrap(), "V2024");
+/// assert_eq!(eval_expression_at("date()", &values, today).unwrap(), "2024-03-09");
+///
+/// assert!(eval_expression_at("@{missing}", &values, today).is_err());
+/// assert!(eval_expression_at("shout(@{org})", &values, today).is_err());
+/// assert!(eval_expression_at("slug(@{org}", &values, today).is_err());
+/// ```
+pub fn eval_expression_at(
+    expression: &str,
+    values: &HashMap<String, String>,
+    today: NaiveDate,
+) -> Result<String, String> {
+    eval(&parse(expression)?, values, today)
+}
+
+/// Идентификаторы полей, на которые ссылается выражение (в порядке появления)
+///
+/// # Returns
+///
+/// Идентификаторы или `Err`, если выражение не разбирается
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::expression::expression_refs;
+///
+/// assert_eq!(expression_refs("@{org} + '/' + slug(@{project_name})").unwrap(), ["org", "project_name"]);
+/// assert!(expression_refs("@{org} +").is_err());
+/// ```
+pub fn expression_refs(expression: &str) -> Result<Vec<String>, String> {
+    fn collect(expr: &Expr, refs: &mut Vec<String>) {
+        match expr {
+            Expr::Literal(_) => {}
+            Expr::Field(id) => {
+                if !refs.contains(id) {
+                    refs.push(id.clone());
+                }
+            }
+            Expr::Call(_, args) | Expr::Concat(args) => {
+                for arg in args {
+                    collect(arg, refs);
+                }
+            }
+        }
+    }
+    let mut refs = Vec::new();
+    collect(&parse(expression)?, &mut refs);
+    Ok(refs)
+}
+
+/// Разобрать выражение целиком
+fn parse(expression: &str) -> Result<Expr, String> {
+    let mut parser = Parser { chars: expression.chars().peekable() };
+    let expr = parser.concat()?;
+    parser.skip_whitespace();
+    match parser.chars.next() {
+        None => Ok(expr),
+        Some(c) => Err(format!("Unexpected '{}' in expression '{}'", c, expression)),
+    }
+}
+
+/// Разбор рекурсивным спуском:
+///
+/// ```text
+/// concat := atom ("+" atom)*
+/// atom   := "@{" id "}" | string | name "(" [concat ("," concat)*] ")"
+/// ```
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn concat(&mut self) -> Result<Expr, String> {
+        let mut parts = vec![self.atom()?];
+        loop {
+            self.skip_whitespace();
+            if self.chars.next_if_eq(&'+').is_none() {
+                break;
+            }
+            parts.push(self.atom()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Expr::Concat(parts) })
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('@') => {
+                self.chars.next();
+                if self.chars.next() != Some('{') {
+                    return Err("Expected '{' after '@'".to_string());
+                }
+                let mut id = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some('}') => break,
+                        Some(c) => id.push(c),
+                        None => return Err("Unclosed field reference '@{'".to_string()),
+                    }
+                }
+                let id = id.trim();
+                if id.is_empty() {
+                    return Err("Empty field reference '@{}'".to_string());
+                }
+                Ok(Expr::Field(id.to_string()))
+            }
+            Some(quote @ ('"' | '\'')) => {
+                self.chars.next();
+                let mut literal = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some(c) if c == quote => break,
+                        Some('\\') if quote == '"' && matches!(self.chars.peek(), Some('"' | '\\')) => {
+                            literal.extend(self.chars.next());
+                        }
+                        Some(c) => literal.push(c),
+                        None => return Err("Unclosed string in expression".to_string()),
+                    }
+                }
+                Ok(Expr::Literal(literal))
+            }
+            Some(c) if c.is_alphabetic() => {
+                let mut name = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                let (function, arity) = Function::parse(&name)
+                    .ok_or_else(|| format!("Unknown function '{}'", name))?;
+                self.skip_whitespace();
+                if self.chars.next() != Some('(') {
+                    return Err(format!("Expected '(' after '{}'", name));
+                }
+                let mut args = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&')').is_none() {
+                    loop {
+                        args.push(self.concat()?);
+                        self.skip_whitespace();
+                        match self.chars.next() {
+                            Some(',') => continue,
+                            Some(')') => break,
+                            _ => return Err(format!("Expected ',' or ')' in call to '{}'", name)),
+                        }
+                    }
+                }
+                if args.len() != arity {
+                    return Err(format!("Function '{}' takes {} argument(s), got {}", name, arity, args.len()));
+                }
+                Ok(Expr::Call(function, args))
+            }
+            Some(c) => Err(format!("Unexpected '{}' in expression", c)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+/// Вычислить разобранное выражение
+fn eval(expr: &Expr, values: &HashMap<String, String>, today: NaiveDate) -> Result<String, String> {
+    match expr {
+        Expr::Literal(text) => Ok(text.clone()),
+        Expr::Field(id) => values.get(id)
+            .or_else(|| values.iter().find(|(k, _)| k.eq_ignore_ascii_case(id)).map(|(_, v)| v))
+            .cloned()
+            .ok_or_else(|| format!("Unknown field '@{{{}}}'", id)),
+        Expr::Concat(parts) => parts.iter().map(|part| eval(part, values, today)).collect(),
+        Expr::Call(function, args) => {
+            let arg = |i: usize| eval(&args[i], values, today);
+            Ok(match function {
+                Function::Slug => FieldTransform::Slug.apply(&arg(0)?),
+                Function::Upper => FieldTransform::Uppercase.apply(&arg(0)?),
+                Function::Lower => FieldTransform::Lowercase.apply(&arg(0)?),
+                Function::Year => today.year().to_string(),
+                Function::Date => today.format("%Y-%m-%d").to_string(),
+            })
+        }
+    }
+}
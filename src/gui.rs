@@ -0,0 +1,5679 @@
+
+//! # Графическое приложение
+//!
+//! Окно приложения на [Iced](https://github.com/iced-rs/iced) по архитектуре
+//! Model-View-Update (MVU): `AppState` - состояние, `Msg` - сообщения для его обновления.
+//! Диалоги выбора папок и файлов, системные уведомления и тема ОС есть только здесь;
+//! модуль собирается с фичей `gui`.
+
+use crate::cli::LaunchOptions;
+use crate::markdown;
+use crate::menu::{MenuEntry, MenuKind};
+use crate::services::Services;
+use crate::tray::{self, Tray, TrayAction};
+#[cfg(feature = "automation")]
+use crate::automation::{self, AutomationAction, AutomationEvent, AutomationReply, AutomationServer, CreateRequest};
+
+use iced::theme::{self, Theme};
+use iced::widget::{button, checkbox, column, container, pick_list, progress_bar, row, scrollable, text, text_editor, text_input, tooltip, Column, Row, Space};
+use iced::{event, keyboard, window, Application, Color, Command, Element, Event, Length, Settings, Subscription};
+use std::time::{Duration, Instant};
+use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use ai_project_template::presets::{self, *};
+use ai_project_template::command::*;
+use ai_project_template::answers::SavedAnswers;
+use ai_project_template::history::RecentProjects;
+use ai_project_template::editor::{open_in_editor, DEFAULT_EDITOR_COMMAND};
+use ai_project_template::locale::{set_language, tr, trf, Language};
+use ai_project_template::cloud::{detect_cloud_sync, CloudSync};
+use ai_project_template::operation::{OperationPlan, PhaseKind, CANCELLED};
+use ai_project_template::retry::RetryPolicy;
+use ai_project_template::session_log::{LogExportHeader, Outcome, SessionLog, PROJECT_LOG_FILE_NAME};
+use ai_project_template::validation::{case_collisions, validate_preset, Severity, ValidationIssue};
+use ai_project_template::integrity::{damaged_presets, verify_presets, PresetIntegrity};
+use ai_project_template::doctor::{diagnose_preset, CheckStatus, DoctorCheck};
+use ai_project_template::settings::{check_presets_zip_url, config_dir, is_portable_mode, AppSettings, SortOrder, ThemeChoice, MIN_BUSY_MS_STEPS, UI_SCALE_STEPS};
+use ai_project_template::update::{check_for_update, ReleaseInfo, UPDATE_CHECK_INTERVAL_HOURS};
+use notify_rust::Notification;
+
+/// Ширина окна по умолчанию (совпадает с `window::Settings::default()`)
+const DEFAULT_WINDOW_WIDTH: f32 = 1024.0;
+/// Ширина окна, начиная с которой динамические поля выводятся в две колонки
+const TWO_COLUMN_BREAKPOINT: f32 = 760.0;
+/// Максимальная ширина полей ввода и выпадающих списков
+const MAX_INPUT_WIDTH: f32 = 480.0;
+/// Имя файла лога сессии в директории конфигурации (пункт меню "Open log file")
+const LOG_FILE_NAME: &str = "session.log";
+/// Ширина окна, ниже которой меню сворачивается в одну кнопку "≡"
+const MENU_COMPACT_WIDTH: f32 = 560.0;
+/// Ширина колонки подписей слева от полей
+const LABEL_WIDTH: f32 = 90.0;
+/// Ширина описания пресета под списком (около 60 символов при размере шрифта 12)
+const DESCRIPTION_WIDTH: f32 = 420.0;
+
+/// Максимальная длина пути в строке состояния (длиннее - обрезается посередине)
+const STATUS_PATH_MAX_CHARS: usize = 48;
+
+/// Сколько показывается всплывающее уведомление в окне
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+/// Задержка проверки директории проекта после ввода имени (не проверять на каждое нажатие)
+const TARGET_CHECK_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Задержка обновления предпросмотра README после изменения полей
+const README_PREVIEW_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Задержка перед обработкой сброшенных файлов (собирает события одного перетаскивания)
+const DROP_BATCH_DELAY: Duration = Duration::from_millis(100);
+
+/// Количество колонок для динамических полей при заданной ширине окна
+///
+/// Узкие окна получают одну колонку, чтобы поля не обрезались.
+fn field_columns(window_width: f32) -> usize {
+    if window_width >= TWO_COLUMN_BREAKPOINT { 2 } else { 1 }
+}
+
+/// Меню полосы меню при заданной ширине окна
+///
+/// В узком окне все меню сворачиваются в одну кнопку "≡".
+fn menu_kinds(window_width: f32) -> &'static [MenuKind] {
+    if window_width < MENU_COMPACT_WIDTH { &[MenuKind::Compact] } else { &MenuKind::BAR }
+}
+
+/// Значение масштаба интерфейса для списка выбора (отображается в процентах)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct UiScale(f32);
+
+impl std::fmt::Display for UiScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0}%", self.0 * 100.0)
+    }
+}
+
+/// Минимальное время показа индикатора выполнения для списка выбора
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BusyDelay(u64);
+
+impl std::fmt::Display for BusyDelay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "{}", tr("settings.busy_off")),
+            ms => write!(f, "{}", trf("settings.seconds", &[&format!("{:.1}", ms as f32 / 1000.0)])),
+        }
+    }
+}
+
+/// Пункт списка категорий пресетов: `None` - все пресеты, иначе тег
+///
+/// Пункт "все" отличается от тегов значением, а не текстом, поэтому тег,
+/// совпадающий с переводом `preset.category_all`, тоже можно выбрать.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CategoryFilter(Option<String>);
+
+impl std::fmt::Display for CategoryFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(ref tag) => write!(f, "{}", tag),
+            None => write!(f, "{}", tr("preset.category_all")),
+        }
+    }
+}
+
+/// Ошибка уровня операции, показываемая в модальном диалоге
+///
+/// Используется только для сбоев целых операций (загрузка пресетов, создание проекта,
+/// недоступная директория пресетов). Предупреждения по отдельным файлам остаются в логе,
+/// а ошибки валидации формы показываются рядом с полями.
+#[derive(Debug, Clone)]
+struct ErrorDialog {
+    /// Заголовок диалога
+    title: String,
+    /// Подробности ошибки
+    detail: String,
+    /// Рекомендуемое действие (опционально)
+    suggestion: Option<String>,
+}
+
+impl ErrorDialog {
+    fn new(title: impl Into<String>, detail: impl Into<String>, suggestion: Option<&str>) -> Self {
+        Self {
+            title: title.into(),
+            detail: detail.into(),
+            suggestion: suggestion.map(str::to_string),
+        }
+    }
+
+    /// Текст для копирования в буфер обмена
+    fn details_text(&self) -> String {
+        match &self.suggestion {
+            Some(suggestion) => format!("{}\n\n{}\n\n{}", self.title, self.detail, suggestion),
+            None => format!("{}\n\n{}", self.title, self.detail),
+        }
+    }
+}
+
+/// Содержимое модального слоя поверх формы
+///
+/// Одновременно показывается только один слой. Индикатор выполнения операции
+/// не является слоем и остается видимым под ним.
+#[derive(Debug, Clone)]
+enum Overlay {
+    /// Диалог ошибки операции
+    Error(ErrorDialog),
+    /// Диалог About
+    About,
+    /// Панель настроек
+    Settings,
+    /// Полное значение текстового поля пресета (только чтение)
+    FieldValue {
+        /// Идентификатор поля
+        field_id: String,
+    },
+}
+
+/// Причина, по которой проект сейчас нельзя создать (см. `AppState::problems`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Problem {
+    /// Выполняется другая операция
+    Busy,
+    /// Директория пресетов не задана
+    NoPresetsFolder,
+    /// Пресет не выбран или его конфигурация еще не загружена
+    NoPresetSelected,
+    /// Имя проекта не введено
+    MissingName,
+    /// Имя проекта некорректно
+    InvalidName,
+    /// Не заполнено обязательное поле
+    MissingRequiredField {
+        /// Идентификатор поля
+        id: String,
+        /// Метка поля
+        label: String,
+    },
+    /// Директория назначения не выбрана
+    NoDestination,
+    /// Директорию назначения нельзя использовать (причина)
+    InvalidDestination(String),
+    /// Пресет требует более новой версии приложения (минимальная версия)
+    AppTooOld(String),
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::Busy => write!(f, "{}", tr("problem.busy")),
+            Problem::NoPresetsFolder => write!(f, "{}", tr("problem.no_presets_folder")),
+            Problem::NoPresetSelected => write!(f, "{}", tr("problem.no_preset")),
+            Problem::MissingName => write!(f, "{}", tr("problem.missing_name")),
+            Problem::InvalidName => write!(f, "{}", tr("problem.invalid_name")),
+            Problem::MissingRequiredField { label, .. } => write!(f, "{}", trf("problem.missing_field", &[label])),
+            Problem::NoDestination => write!(f, "{}", tr("problem.no_destination")),
+            Problem::InvalidDestination(reason) => write!(f, "{}", trf("problem.invalid_destination", &[reason])),
+            Problem::AppTooOld(required) => write!(
+                f, "{}", trf("problem.app_too_old", &[required, &env!("CARGO_PKG_VERSION")])
+            ),
+        }
+    }
+}
+
+/// Длина значения поля, начиная с которой рядом показывается счетчик символов
+const FIELD_COUNTER_MIN_CHARS: usize = 40;
+
+/// Высота строки многострочного поля ("textarea") в логических пикселях
+const TEXTAREA_ROW_HEIGHT: f32 = 21.0;
+
+/// Сообщения для обновления состояния приложения (MVU паттерн)
+#[derive(Clone, Debug)]
+enum Msg {
+    /// Изменено имя проекта
+    NameChanged(String),
+    /// Выбран пресет из списка доступных
+    PresetSelected(Option<String>),
+    /// Изменено значение динамического поля пресета
+    FieldChanged(String, String), // field_id, value
+    /// Переключена опция поля "multi_select"
+    MultiSelectToggled(String, String, bool), // field_id, option, enabled
+    /// Действие в многострочном поле "textarea" (ввод, перемещение курсора, выделение)
+    TextAreaAction(String, text_editor::Action), // field_id, action
+    /// Переключена опция пресета
+    OptionToggled(String, bool), // option_id, enabled
+    /// Переключена встроенная опция "Initialize git repository"
+    GitInitToggled(bool),
+    /// Переключена встроенная опция "Open in editor when done"
+    OpenInEditorToggled(bool),
+    /// Изменен шаблон команды редактора в настройках
+    EditorCommandChanged(String),
+    /// Применить рекомендуемые значения опций и значения полей по умолчанию
+    ApplyRecommendedSettings,
+    /// Запрошено создание проекта
+    Create,
+    /// Запрошен пробный запуск создания (план действий в логе)
+    Preview,
+    /// Завершен пробный запуск создания
+    PreviewFinished {
+        /// Поколение операции, которая выдала результат
+        generation: u64,
+        /// Отчет пробного запуска или описание ошибки
+        result: Result<CreationReport, String>,
+    },
+    /// Подтверждено создание проекта с большими файлами
+    ConfirmLargeCreate,
+    /// Отменено создание проекта с большими файлами
+    CancelLargeCreate,
+    /// Завершено выполнение операции создания проекта
+    ProcessFinished { 
+        /// Поколение операции, которая выдала результат
+        generation: u64,
+        /// Отчет о созданном проекте или описание ошибки
+        result: Result<CreationReport, String>,
+    },
+    /// Показать или скрыть список пропущенных шаблонов в панели успеха
+    ToggleMissingTemplates,
+    /// Развернуть или свернуть список директорий выбранного пресета
+    ToggleProjectStructure,
+    /// Развернуть или свернуть раздел недавних проектов
+    ToggleRecentProjects,
+    /// Проверено существование директорий недавних проектов (отсутствующие пути)
+    RecentProjectsChecked(HashSet<PathBuf>),
+    /// Открыть директорию недавнего проекта в файловом менеджере
+    OpenRecentProject(PathBuf),
+    /// Удалить проект из истории недавних проектов
+    RemoveRecentProject(PathBuf),
+    /// Очистить историю недавних проектов
+    ClearRecentProjects,
+    /// Докопировать шаблоны, пропущенные при последнем создании
+    RerunMissingTemplates,
+    /// Завершено докопирование пропущенных шаблонов
+    MissingTemplatesRerun {
+        /// Поколение операции, которая выдала результат
+        generation: u64,
+        /// Лог и шаблоны, которые по-прежнему не найдены, или описание ошибки
+        result: Result<(Vec<String>, Vec<MissingTemplate>), String>,
+    },
+    /// Обновить прогресс диалога (для анимации)
+    Tick,
+    /// Получена часть архива пресетов
+    DownloadProgress(u64, Option<u64>), // скачано байт, размер архива (если известен)
+    /// Распаковывается архив пресетов
+    ExtractProgress(usize, usize), // распаковано элементов, всего элементов
+    /// Начат шаг создания проекта
+    CreateProgress(usize, usize, String), // шаг, всего шагов, описание
+    /// Выбрана директория для установки пресетов
+    PresetsPathSelected(Option<PathBuf>),
+    /// Завершена загрузка пресетов из GitHub
+    PresetsDownloaded(u64, Result<(PathBuf, usize), String>), // generation, (путь, число измененных пресетов)
+    /// Загружен список доступных пресетов
+    PresetsLoaded(u64, Result<PresetScan, String>), // generation, найденные пресеты
+    /// Загружена конфигурация выбранного пресета
+    PresetConfigLoaded(Result<PresetConfig, String>),
+    /// Обновить список доступных пресетов (загрузить заново из GitHub)
+    RefreshPresets,
+    /// Повторить первоначальную настройку (выбор папки и загрузку пресетов)
+    RetrySetup,
+    /// Пользователь пытается закрыть окно
+    CloseRequested,
+    /// Выход подтвержден в диалоге подтверждения
+    QuitConfirmed,
+    /// Выход отменен в диалоге подтверждения
+    QuitCancelled,
+    /// Изменен размер окна (ширина для адаптивной раскладки, размер сохраняется при выходе)
+    WindowResized(f32, f32),
+    /// Закрыть диалог ошибки
+    ErrorDismissed,
+    /// Скопировать подробности ошибки в буфер обмена
+    CopyErrorDetails,
+    /// Открыть папку созданного проекта в файловом менеджере
+    OpenProjectFolder,
+    /// Скопировать путь созданного проекта в буфер обмена
+    CopyProjectPath,
+    /// Закрыть панель успеха и начать новый проект
+    NewProject,
+    /// Сбросить форму: имя, поля и опции (пресет и лог не меняются)
+    ResetForm,
+    /// Запрошена смена директории пресетов
+    ChangePresetsFolder,
+    /// Выбрана новая директория пресетов (путь и результат ее сканирования)
+    PresetsFolderPicked(Option<(PathBuf, PresetScan)>),
+    /// Проверены правила обнаружения для выбранной директории пресета
+    PresetDiagnosed(Option<(PathBuf, Vec<DoctorCheck>)>),
+    /// Проверены файлы пресетов, распакованных из архива
+    PresetsVerified(Result<Vec<PresetIntegrity>, String>),
+    /// Восстановить поврежденные пресеты (подтверждение после проверки)
+    RepairPresets,
+    /// Не восстанавливать поврежденные пресеты
+    SkipRepair,
+    /// Поврежденные пресеты распакованы заново (generation, (пресеты, которых нет в
+    /// архиве, результаты повторной проверки восстановленных пресетов))
+    PresetsRepaired(u64, Result<(Vec<String>, Vec<PresetIntegrity>), String>),
+    /// Проверить конфигурацию выбранного пресета (кнопка Validate preset)
+    ValidatePreset,
+    /// Удалить сохраненные значения полей выбранного пресета
+    ClearSavedAnswers,
+    /// Завершена проверка конфигурации пресета
+    PresetValidated {
+        /// Идентификатор проверенного пресета
+        preset_id: String,
+        /// Найденные проблемы
+        issues: Vec<ValidationIssue>,
+        /// Проверка запрошена кнопкой (сообщить и об отсутствии проблем)
+        requested: bool,
+    },
+    /// Сохранена JSON Schema конфигурации пресета (`None` - диалог отменен)
+    SchemaExported(Option<Result<PathBuf, String>>),
+    /// Сохранить лог в выбранный файл
+    SaveLog,
+    /// Скопировать лог в буфер обмена
+    CopyLog,
+    /// Скопировать в буфер обмена последнюю строку ошибки из лога
+    CopyLastError,
+    /// Лог сохранен в файл (`None` - диалог отменен)
+    LogSaved(Option<Result<PathBuf, String>>),
+    /// Использовать пресеты, уже находящиеся в выбранной директории
+    UseExistingPresets,
+    /// Скачать свежие пресеты в выбранную директорию
+    DownloadPresetsHere,
+    /// Удалить временные архивы, оставшиеся от прошлых запусков
+    DeleteStaleTempFiles,
+    /// Оставить временные архивы прошлых запусков
+    KeepStaleTempFiles,
+    /// Отменить сканирование директории пресетов (кнопка в индикаторе выполнения)
+    CancelScan,
+    /// Прервать загрузку пресетов или создание проекта (кнопка в индикаторе выполнения)
+    CancelOperation,
+    /// Отменить смену директории пресетов
+    CancelPresetsFolderChange,
+    /// Открыть директорию пресетов в файловом менеджере
+    RevealPresetsFolder,
+    /// Показать диалог About
+    ShowAbout,
+    /// Закрыть диалог About
+    CloseAbout,
+    /// Показать панель настроек
+    ShowSettings,
+    /// Закрыть панель настроек
+    CloseSettings,
+    /// Изменены настройки в панели настроек (сохраняются сразу)
+    SettingsChanged(AppSettings),
+    /// Показать полное значение текстового поля
+    ShowFieldValue(String),
+    /// Закрыть просмотр значения поля
+    CloseFieldValue,
+    /// Скопировать диагностическую информацию в буфер обмена
+    CopyDiagnostics,
+    /// Переключена настройка ежедневной проверки обновлений
+    UpdateCheckToggled(bool),
+    /// Периодическая проверка, не пора ли искать обновления
+    UpdateCheckTick,
+    /// Завершена проверка обновлений (`Ok(None)` - обновлений нет)
+    UpdateCheckFinished(Result<Option<ReleaseInfo>, String>),
+    /// Открыть страницу нового релиза в браузере
+    OpenReleasePage,
+    /// Скрыть баннер о новой версии
+    DismissUpdateBanner,
+    /// Открыть страницу выбранного пресета (`metadata.homepage`) в браузере
+    OpenPresetHomepage,
+    /// Опросить меню трея (первый вызов создает иконку)
+    TrayPoll,
+    /// Открыть или закрыть выпадающее меню
+    ToggleMenu(MenuKind),
+    /// Выбран пункт меню (мышью или клавиатурным сокращением)
+    MenuSelected(MenuEntry),
+    /// Переключена настройка сворачивания в трей при закрытии
+    MinimizeToTrayToggled(bool),
+    /// Переключена настройка системных уведомлений
+    NotificationsToggled(bool),
+    /// Переключена запись лога в директорию созданного проекта
+    ProjectLogToggled(bool),
+    /// Переключено предупреждение о папках облачной синхронизации
+    CloudSyncWarningToggled(bool),
+    /// Изменен URL архива пресетов в диалоге About (еще не сохранен)
+    PresetsUrlChanged(String),
+    /// Сохранить введенный URL архива пресетов
+    SavePresetsUrl,
+    /// Вернуть URL архива пресетов по умолчанию (`PRESETS_ZIP_URL`)
+    ResetPresetsUrl,
+    /// На окно перетащен файл или директория
+    FileDropped(PathBuf),
+    /// Обработать накопленные перетащенные элементы
+    ProcessDroppedFiles,
+    /// Импортировать перетащенную директорию как пресет
+    ImportDroppedPreset,
+    /// Использовать перетащенную директорию пресета как директорию назначения
+    UseDroppedAsDestination,
+    /// Изменен путь к директории назначения в поле ввода
+    DestinationInputChanged(String),
+    /// Проверить введенную директорию назначения (номер проверки, устаревшие игнорируются)
+    CheckDestination(u64),
+    /// Открыть диалог выбора директории назначения
+    BrowseDestination,
+    /// Директория назначения выбрана в диалоге (`None` - диалог отменен)
+    DestinationPicked(Option<PathBuf>),
+    /// Отменить обработку перетащенной директории пресета
+    CancelDroppedFolder,
+    /// Показать выбор источника импорта пресета (папка или ZIP-архив)
+    ShowImportPreset,
+    /// Выбрать папку пресета для импорта
+    BrowseImportFolder,
+    /// Выбрать ZIP-архив пресета для импорта
+    BrowseImportArchive,
+    /// Закрыть выбор источника импорта или отказаться от перезаписи
+    CancelImport,
+    /// Выбран источник импорта (`None` - диалог отменен)
+    ImportSourcePicked(Option<PathBuf>),
+    /// Прочитан идентификатор импортируемого пресета (источник, `preset_id` или ошибка)
+    ImportChecked(PathBuf, Result<String, String>),
+    /// Подтверждена замена существующего пресета при импорте
+    ConfirmImportOverwrite,
+    /// Завершен импорт пресета (идентификатор пресета или ошибка)
+    PresetImported(Result<String, String>),
+    /// Скрыть всплывающее уведомление с указанным номером
+    ToastExpired(u64),
+    /// Пора проверить директорию проекта (номер проверки для устранения дребезга)
+    CheckTargetDir(u64),
+    /// Получено состояние директории проекта
+    TargetDirChecked(u64, TargetDirStatus),
+    /// Выбрана тема оформления
+    ThemeSelected(ThemeChoice),
+    /// Выбран масштаб интерфейса
+    UiScaleSelected(UiScale),
+    /// Выбран язык интерфейса
+    LanguageSelected(Language),
+    /// Выбран порядок пресетов в списке
+    PresetSortSelected(SortOrder),
+    /// Выбрана категория пресетов (`None` - все пресеты)
+    TagSelected(Option<String>),
+    /// Изменена строка поиска пресета
+    PresetSearchChanged(String),
+    /// Развернуть или свернуть раздел замеров времени под логом
+    ToggleTiming,
+    /// Развернуть или свернуть раздел лога операции (номер операции)
+    ToggleLogSection(u64),
+    /// Показать или скрыть предпросмотр README
+    ToggleReadmePreview,
+    /// Пора обновить предпросмотр README (номер запроса для устранения дребезга)
+    RefreshReadmePreview(u64),
+    /// Нажат Tab (`true` - вместе с Shift, т.е. назад)
+    FocusNext(bool),
+    /// Найдено поле с фокусом - перевести фокус на следующее (или предыдущее)
+    FocusFound(iced::advanced::widget::Id, bool),
+    /// Окно получило фокус (повод перепроверить тему ОС)
+    WindowFocused,
+    /// Окно потеряло фокус
+    WindowUnfocused,
+    /// Определена тема ОС (`true` - темная)
+    SystemThemeDetected(bool),
+    /// Событие сервера автоматизации (запуск или запрос)
+    #[cfg(feature = "automation")]
+    Automation(AutomationEvent),
+}
+
+impl Msg {
+    /// Ввод в форму, который игнорируется во время операции
+    ///
+    /// Операция уже захватила значения формы; сообщение могло прийти от виджета
+    /// до того, как модальный слой скрыл форму.
+    fn is_form_input(&self) -> bool {
+        matches!(self,
+            Msg::NameChanged(_)
+            | Msg::PresetSelected(_)
+            | Msg::TagSelected(_)
+            | Msg::FieldChanged(..)
+            | Msg::MultiSelectToggled(..)
+            | Msg::TextAreaAction(..)
+            | Msg::OptionToggled(..)
+            | Msg::GitInitToggled(_)
+            | Msg::OpenInEditorToggled(_)
+            | Msg::ApplyRecommendedSettings
+            | Msg::DestinationInputChanged(_)
+            | Msg::BrowseDestination
+            | Msg::ResetForm
+            | Msg::NewProject
+        )
+    }
+}
+
+/// Основное состояние приложения
+///
+/// Хранит все данные, необходимые для работы GUI, включая:
+/// - состояние пресетов и их конфигурации
+/// - введенные пользователем данные
+/// - состояние UI (диалоги, прогресс, логи)
+#[derive(Debug)]
+struct AppState {
+    // Пресеты
+    presets_dir: Option<PathBuf>,
+    available_presets: Vec<String>, // preset_id
+    preset_roots: HashMap<String, PathBuf>, // preset_id -> директория, где найден пресет
+    preset_origins: HashMap<String, PresetOrigin>, // preset_id -> происхождение
+    /// Основная директория пресетов доступна только для чтения
+    presets_read_only: bool,
+    preset_names: HashMap<String, String>, // preset_id -> preset_name (для отображения)
+    preset_display_names: Vec<String>, // Список имен для отображения (синхронизирован с available_presets)
+    preset_tags: HashMap<String, Vec<String>>, // preset_id -> теги из metadata
+    /// Отсортированное объединение тегов всех пресетов (пункты списка "Category:")
+    available_tags: Vec<String>,
+    /// Выбранная категория; список пресетов показывает только пресеты с этим тегом
+    selected_tag: Option<String>,
+    /// Строка поиска пресета (подстрока идентификатора или имени, без учета регистра)
+    preset_search: String,
+    selected_preset: Option<String>, // preset_id
+    selected_preset_display_name: Option<String>, // Имя выбранного пресета для отображения в UI
+    preset_config: Option<PresetConfig>,
+    dynamic_fields: HashMap<String, String>, // field_id -> value
+    dynamic_options: HashMap<String, bool>, // option_id -> enabled
+    /// Состояние редакторов полей "textarea" (см. `sync_textareas`)
+    textareas: HashMap<String, text_editor::Content>, // field_id -> content
+    
+    // Проект
+    project_name: String,
+    
+    // UI состояние
+    project_name_error: String,
+    is_busy: bool,
+    /// Лог сессии, сгруппированный по операциям
+    log: SessionLog,
+    /// Номера свернутых разделов лога
+    collapsed_log_sections: HashSet<u64>,
+    show_dialog: bool,
+    dialog_progress: f32,
+    dialog_start: Option<Instant>,
+    /// Текущий шаг операции с реальным прогрессом (загрузка пресетов, создание
+    /// проекта); `None` - прогресс не поступал, диалог анимируется по `Tick`
+    progress_label: Option<String>,
+    /// Размер операции неизвестен (архив без `Content-Length`): полоса прогресса
+    /// движется по кругу, проценты не показываются
+    progress_indeterminate: bool,
+    /// Этапы текущей операции (обновление пресетов, создание проекта); по ним
+    /// считается общий прогресс и подпись этапа в индикаторе
+    operation: Option<OperationPlan>,
+    /// Показан запрос подтверждения выхода во время операции
+    confirm_quit: bool,
+    /// Выход подтвержден - закрыть окно, когда операция завершится
+    quit_when_idle: bool,
+    /// Поколение текущей операции; результаты других поколений игнорируются
+    operation_generation: u64,
+    /// Пресет, запрошенный аргументом `--preset` (применяется после загрузки списка)
+    requested_preset: Option<String>,
+    /// Значения полей из `--field` (применяются после загрузки конфигурации пресета)
+    pending_fields: Vec<(String, String)>,
+    /// Выбранная, но еще не подтвержденная директория пресетов и результат ее сканирования
+    pending_presets_dir: Option<(PathBuf, PresetScan)>,
+    /// Флаг отмены идущего сканирования пресетов
+    scan_cancel: Option<Arc<AtomicBool>>,
+    /// Флаг отмены идущей загрузки пресетов или создания проекта (`Msg::CancelOperation`)
+    operation_cancel: Option<Arc<AtomicBool>>,
+    /// Временные архивы прошлых запусков, ожидающие подтверждения удаления (путь, размер)
+    stale_temp_files: Vec<(PathBuf, u64)>,
+    /// Сеть и системные диалоги
+    services: Services,
+    /// Первоначальная настройка не завершена: причина (выбор папки отменен,
+    /// загрузка не удалась) показывается вместе с кнопкой повтора
+    setup_incomplete: Option<String>,
+    /// Текущая ширина окна
+    window_width: f32,
+    /// Окно в фокусе (уведомления об обновлении пресетов показываются только в фоне)
+    window_focused: bool,
+    /// Открытое выпадающее меню
+    open_menu: Option<MenuKind>,
+    /// Модальный слой поверх формы (ошибка, About, просмотр значения поля)
+    overlay: Option<Overlay>,
+    /// Настройки приложения (сохраняются в конфиг-файл при изменении)
+    settings: AppSettings,
+    /// Значения полей и опций, введенные при последнем создании проекта по каждому пресету
+    saved_answers: SavedAnswers,
+    /// Недавно созданные проекты (раздел "Recent projects")
+    recent_projects: RecentProjects,
+    /// Раздел "Recent projects" развернут
+    show_recent_projects: bool,
+    /// Поврежденные пресеты, ожидающие подтверждения восстановления
+    pending_repair: Option<BTreeSet<String>>,
+    /// Недавние проекты, директорий которых больше нет (по последней проверке)
+    missing_recent_projects: HashSet<PathBuf>,
+    /// Редактируемый URL архива пресетов (сохраняется в настройки после проверки)
+    presets_url_input: String,
+    /// Ошибка проверки введенного URL архива пресетов
+    presets_url_error: Option<String>,
+    /// Найденный более новый релиз приложения (источник данных баннера обновления)
+    available_update: Option<ReleaseInfo>,
+    /// Проверка обновлений выполняется в фоне
+    update_check_running: bool,
+    /// Иконка в трее (`None` если трей недоступен или фича `tray` выключена)
+    tray: Option<Tray>,
+    /// Доступность трея уже проверена
+    tray_checked: bool,
+    /// Последняя установленная подсказка иконки трея
+    tray_tooltip: String,
+    /// Сервер автоматизации (`None` - флаг `--automation-port` не указан)
+    #[cfg(feature = "automation")]
+    automation: Option<AutomationServer>,
+    /// Создать проект, как только загрузится пресет из запроса `POST /create`
+    /// (с опциями из запроса)
+    #[cfg(feature = "automation")]
+    automation_create: Option<Vec<(String, bool)>>,
+    /// Описание выполняемой операции для подсказки трея ("Creating 'name'")
+    busy_label: String,
+    /// Директория, в которой создается проект (`None` - не выбрана или недоступна)
+    destination_dir: Option<PathBuf>,
+    /// Текст поля ввода директории назначения
+    destination_input: String,
+    /// Почему введенную директорию назначения нельзя использовать (показывается под полем)
+    destination_error: Option<String>,
+    /// Номер последней запрошенной проверки директории назначения
+    destination_check_id: u64,
+    /// Директория назначения находится в папке облачной синхронизации
+    destination_cloud_sync: Option<CloudSync>,
+    /// Перетащенные на окно элементы, ожидающие обработки
+    dropped_paths: Vec<PathBuf>,
+    /// Перетащенная директория пресета: ждет выбора "импортировать" или "назначение"
+    dropped_preset_dir: Option<PathBuf>,
+    /// Показан выбор источника импорта пресета
+    import_prompt: bool,
+    /// Импорт, ожидающий подтверждения перезаписи (источник, `preset_id`)
+    pending_import: Option<(PathBuf, String)>,
+    /// Всплывающее уведомление в окне
+    toast: Option<String>,
+    /// Номер последнего уведомления (устаревшие таймеры не скрывают новое уведомление)
+    toast_id: u64,
+    /// Ошибки обязательных полей, показанные после нажатия Create (field_id -> текст)
+    field_errors: HashMap<String, String>,
+    /// Состояние директории `destination/project_name` (`None` - еще не проверено)
+    target_status: Option<TargetDirStatus>,
+    /// Номер последней запрошенной проверки директории проекта
+    target_check_id: u64,
+    /// План создания проекта с большими файлами, ожидающий подтверждения
+    pending_large_plan: Option<ProjectPlan>,
+    /// Раздел замеров времени последнего создания развернут
+    show_timing: bool,
+    /// Список пропущенных шаблонов в панели успеха развернут
+    show_missing_templates: bool,
+    /// Список директорий выбранного пресета ("Project structure") развернут
+    show_project_structure: bool,
+    /// Конфигурация выбранного пресета загружается (между `PresetSelected` и `PresetConfigLoaded`)
+    preset_loading: bool,
+    /// Сгенерированный README для предпросмотра (`None` - предпросмотр скрыт)
+    readme_preview: Option<String>,
+    /// Номер последнего запроса на обновление предпросмотра README
+    readme_preview_id: u64,
+    /// В ОС включена темная тема (используется при `ThemeChoice::System`)
+    system_dark: bool,
+    /// Отчет о последнем успешно созданном проекте (источник данных панели успеха)
+    last_report: Option<CreationReport>,
+    
+    // Инициализация
+    presets_initialized: bool,
+    /// Время последнего успешного сканирования пресетов
+    last_refresh: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl AppState {
+    /// Причины, по которым проект сейчас нельзя создать
+    ///
+    /// Единственный источник правды для кнопки Create (`can_create`) и панели
+    /// проблем над ней, поэтому состояние кнопки и показанные причины всегда совпадают.
+    fn problems(&self) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        if self.is_busy {
+            problems.push(Problem::Busy);
+        }
+        if self.presets_dir.is_none() {
+            problems.push(Problem::NoPresetsFolder);
+        }
+        if self.project_name.trim().is_empty() {
+            problems.push(Problem::MissingName);
+        } else if !is_valid_project_name(&self.project_name) {
+            problems.push(Problem::InvalidName);
+        }
+        match self.preset_config {
+            Some(ref config) => {
+                problems.extend(config.fields.iter()
+                    .filter(|field| field.required && field_visible(config, field, &self.dynamic_options))
+                    .filter(|field| !field.is_filled(self.dynamic_fields.get(&field.id)))
+                    .map(|field| Problem::MissingRequiredField { id: field.id.clone(), label: field.label.clone() }));
+                if let Some(required) = config.metadata.unsupported_app_version(env!("CARGO_PKG_VERSION")) {
+                    problems.push(Problem::AppTooOld(required.to_string()));
+                }
+            }
+            None => problems.push(Problem::NoPresetSelected),
+        }
+        match self.destination_error {
+            Some(ref reason) => problems.push(Problem::InvalidDestination(reason.clone())),
+            None if self.destination_dir.is_none() => problems.push(Problem::NoDestination),
+            None => {}
+        }
+        problems
+    }
+
+    /// Можно ли создать проект в текущий момент (нет ни одной проблемы из `problems`)
+    fn can_create(&self) -> bool {
+        self.problems().is_empty()
+    }
+
+    /// Отметить незаполненные обязательные поля после нажатия Create
+    ///
+    /// # Returns
+    ///
+    /// Команда перевода фокуса на первое отмеченное текстовое поле
+    fn highlight_missing_fields(&mut self) -> Command<Msg> {
+        self.field_errors = self.problems().into_iter()
+            .filter_map(|problem| match problem {
+                Problem::MissingRequiredField { id, .. } => Some((id, "This field is required".to_string())),
+                _ => None,
+            })
+            .collect();
+        let first_text_field = self.preset_config.as_ref().and_then(|config| config.fields.iter()
+            .find(|field| is_text_field(field) && self.field_errors.contains_key(&field.id)));
+        match first_text_field {
+            Some(field) => text_input::focus(field_input_id(&field.id)),
+            None => Command::none(),
+        }
+    }
+
+    /// Начать новую операцию
+    ///
+    /// Увеличивает счетчик поколений. Асинхронная операция захватывает возвращенное
+    /// значение и передает его обратно в сообщении о завершении, а `handle_message`
+    /// отбрасывает результаты устаревших поколений.
+    ///
+    /// # Returns
+    ///
+    /// Поколение новой операции
+    fn begin_operation(&mut self) -> u64 {
+        self.operation_cancel = None;
+        self.operation_generation += 1;
+        self.operation_generation
+    }
+
+    /// Завершить раздел лога текущей операции (если он открыт)
+    fn finish_log_operation(&mut self, outcome: Outcome) {
+        if let Some(id) = self.log.current() {
+            self.log.finish(id, outcome);
+        }
+    }
+
+    /// Запросить отмену текущей операции (кнопка Cancel или выход во время операции)
+    ///
+    /// Операция остановится на ближайшей проверке флага и откатит изменения;
+    /// индикатор остается до ее результата (CANCELLED).
+    fn request_cancel(&mut self) {
+        if let Some(ref cancel) = self.operation_cancel {
+            if !cancel.swap(true, Ordering::Relaxed) {
+                self.log.push("Cancelling...".to_string());
+            }
+        }
+    }
+
+    /// Завершить операцию, прерванную кнопкой Cancel (`Msg::CancelOperation`)
+    ///
+    /// Операция к этому моменту уже откатила свои изменения; индикатор
+    /// выполнения закрывается по `Msg::Tick`, ошибка не показывается.
+    fn finish_cancelled_operation(&mut self) {
+        self.is_busy = false;
+        self.log.push("Operation cancelled".to_string());
+        self.finish_log_operation(Outcome::Cancelled);
+    }
+
+    /// Относится ли результат к текущей операции
+    fn is_current_operation(&self, generation: u64) -> bool {
+        generation == self.operation_generation
+    }
+
+    /// Упорядочить список пресетов по настройке `preset_sort`
+    ///
+    /// `available_presets` и `preset_display_names` сортируются вместе, чтобы
+    /// индексы в списке выбора по-прежнему совпадали.
+    fn sort_presets(&mut self) {
+        let mut presets: Vec<(String, String)> = self.available_presets.drain(..)
+            .zip(self.preset_display_names.drain(..))
+            .collect();
+        self.settings.preset_sort.sort(&mut presets, &self.settings.preset_last_used);
+        let (ids, names): (Vec<String>, Vec<String>) = presets.into_iter().unzip();
+        self.available_presets = ids;
+        self.preset_display_names = names;
+    }
+
+    /// Пресеты, показываемые в списке выбора: идентификаторы и имена в порядке сортировки
+    ///
+    /// При выбранной категории остаются только пресеты с этим тегом, при непустой строке
+    /// поиска - пресеты, идентификатор или имя которых содержит ее. Полные списки
+    /// `available_presets` и `preset_display_names` не меняются: по ним проверяется
+    /// наличие пресета (`--preset`, запомненный пресет, автоматизация).
+    fn visible_presets(&self) -> (Vec<String>, Vec<String>) {
+        let search = self.preset_search.trim().to_lowercase();
+        self.available_presets.iter()
+            .zip(&self.preset_display_names)
+            .filter(|(id, _)| match self.selected_tag {
+                Some(ref tag) => self.preset_tags.get(*id)
+                    .is_some_and(|tags| tags.iter().any(|own| own.eq_ignore_ascii_case(tag))),
+                None => true,
+            })
+            .filter(|(id, name)| {
+                search.is_empty() || id.to_lowercase().contains(&search) || name.to_lowercase().contains(&search)
+            })
+            .map(|(id, name)| (id.clone(), name.clone()))
+            .unzip()
+    }
+
+    /// Забыть запомненный пресет, если директория пресетов меняется на другую
+    fn forget_last_preset_if_dir_changes(&mut self, new_dir: &std::path::Path) {
+        if self.presets_dir.as_deref() != Some(new_dir) && self.settings.last_preset.is_some() {
+            self.settings.last_preset = None;
+            self.save_settings();
+        }
+    }
+
+    /// Директория, содержащая пресет (основная или пользовательская)
+    fn preset_root(&self, preset_id: &str) -> Option<PathBuf> {
+        self.preset_roots.get(preset_id).cloned()
+            .or_else(|| self.presets_dir.clone())
+    }
+
+    /// Открыть выбор директории для установки пресетов (первый запуск)
+    fn pick_presets_folder(&self) -> Command<Msg> {
+        let picker = self.services.picker.clone();
+        let start_dir = get_default_presets_path();
+        Command::perform(picker.pick_folder(start_dir), Msg::PresetsPathSelected)
+    }
+
+    /// Скачать пресеты в `dir` через источник пресетов
+    ///
+    /// Источники загружаются по очереди: основной архив в `dir`, дополнительные
+    /// (`preset_sources`) - в свои поддиректории. Отпечатки пресетов до и после загрузки сравниваются, чтобы сообщить
+    /// число изменившихся пресетов. Прогресс загрузки приходит сообщениями
+    /// `DownloadProgress` и `ExtractProgress` из канала, который закрывается вместе с загрузкой.
+    fn fetch_presets(&mut self, dir: PathBuf, generation: u64) -> Command<Msg> {
+        self.progress_label = None;
+        self.progress_indeterminate = false;
+        self.log.begin("Refresh presets", dir.display().to_string());
+        let source_names: Vec<&str> = self.settings.preset_sources.iter().map(|source| source.name.as_str()).collect();
+        self.operation = Some(OperationPlan::refresh_presets(&source_names));
+        let (sender, receiver) = iced::futures::channel::mpsc::unbounded();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.operation_cancel = Some(cancel.clone());
+        // Основной архив распаковывается в корень, дополнительные источники - в свои поддиректории
+        let mut targets = vec![(None, dir.clone(), self.settings.presets_zip_url.clone())];
+        targets.extend(self.settings.preset_sources.iter().map(|source| {
+            (Some(source.name.clone()), dir.join(&source.subdir), source.zip_url.clone())
+        }));
+        let fetches: Vec<_> = targets.into_iter()
+            .map(|(name, target_dir, zip_url)| {
+                let sender = sender.clone();
+                let progress = Box::new(move |step: DownloadProgress| {
+                    let _ = sender.unbounded_send(step);
+                });
+                let fetch = self.services.fetcher.fetch(target_dir.clone(), zip_url, self.settings.network.clone(), cancel.clone(), progress);
+                (name, target_dir, fetch)
+            })
+            .collect();
+        Command::batch(vec![
+            Command::perform(async move {
+                let mut changed = 0;
+                for (name, target_dir, fetch) in fetches {
+                    std::fs::create_dir_all(&target_dir)
+                        .map_err(|e| format!("Failed to create {:?}: {}", target_dir, e))?;
+                    let before = preset_fingerprints(&target_dir);
+                    fetch.await.map_err(|e| match name {
+                        // Отмена не относится к конкретному источнику
+                        Some(name) if e != CANCELLED => format!("Preset source '{}': {}", name, e),
+                        _ => e,
+                    })?;
+                    changed += count_changed_presets(&before, &preset_fingerprints(&target_dir));
+                }
+                Ok((dir, changed))
+            }, move |result| Msg::PresetsDownloaded(generation, result)),
+            Command::run(receiver, download_progress_msg),
+        ])
+    }
+
+    /// Проверить файлы пресетов, распакованных из архива (`integrity::verify_presets`)
+    ///
+    /// Поврежденные пресеты предлагается восстановить (`Msg::RepairPresets`).
+    fn verify_managed_presets(&mut self) -> Command<Msg> {
+        let Some(dir) = self.presets_dir.clone() else { return Command::none() };
+        self.pending_repair = None;
+        self.log.begin("Verify presets", dir.display().to_string());
+        Command::perform(async move { verify_presets(&dir) }, Msg::PresetsVerified)
+    }
+
+    /// Скачать архив пресетов заново и распаковать в `dir` только пресеты `presets`
+    ///
+    /// Пользовательские и неповрежденные пресеты не затрагиваются. После распаковки
+    /// восстановленные пресеты проверяются повторно, результат приходит сообщением
+    /// `PresetsRepaired`.
+    fn repair_presets(&mut self, dir: PathBuf, presets: BTreeSet<String>, generation: u64) -> Command<Msg> {
+        self.progress_label = None;
+        self.progress_indeterminate = false;
+        self.log.begin("Repair presets", presets.iter().cloned().collect::<Vec<_>>().join(", "));
+        self.operation = Some(OperationPlan::refresh_presets(&[]));
+        let (sender, receiver) = iced::futures::channel::mpsc::unbounded();
+        let progress = Box::new(move |step: DownloadProgress| {
+            let _ = sender.unbounded_send(step);
+        });
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.operation_cancel = Some(cancel.clone());
+        let repair = self.services.fetcher.repair(
+            dir.clone(),
+            self.settings.presets_zip_url.clone(),
+            self.settings.network.clone(),
+            presets.clone(),
+            cancel,
+            progress,
+        );
+        Command::batch(vec![
+            Command::perform(async move {
+                let repaired = repair.await?;
+                let not_in_archive: Vec<String> = presets.difference(&repaired).cloned().collect();
+                let results: Vec<PresetIntegrity> = verify_presets(&dir)?.into_iter()
+                    .filter(|result| repaired.contains(&result.preset_id))
+                    .collect();
+                Ok((not_in_archive, results))
+            }, move |result| Msg::PresetsRepaired(generation, result)),
+            Command::run(receiver, download_progress_msg),
+        ])
+    }
+
+    /// Найти временные архивы и директории импорта, оставшиеся от аварийно
+    /// завершенных запусков
+    ///
+    /// Найденные элементы записываются в лог и удаляются сразу или после
+    /// подтверждения (настройка `confirm_temp_cleanup`).
+    fn find_stale_temp_files(&mut self, presets_dir: Option<&std::path::Path>) {
+        let mut dirs = vec![temp_zip_dir(&get_default_presets_path()), std::env::temp_dir()];
+        dirs.extend(presets_dir.map(temp_zip_dir));
+        let stale = presets::find_stale_temp_files(&dirs, STALE_TEMP_AGE);
+        for path in &stale {
+            self.log.push(format!("Found leftover temporary file from a previous run: {:?}", path));
+        }
+        self.stale_temp_files = stale.into_iter()
+            .map(|path| {
+                let bytes = disk_usage(&path);
+                (path, bytes)
+            })
+            .collect();
+        if !self.settings.confirm_temp_cleanup {
+            self.delete_stale_temp_files();
+        }
+    }
+
+    /// Удалить найденные временные архивы прошлых запусков
+    fn delete_stale_temp_files(&mut self) {
+        for (path, _) in std::mem::take(&mut self.stale_temp_files) {
+            let removed = if path.is_dir() { std::fs::remove_dir_all(&path) } else { std::fs::remove_file(&path) };
+            match removed {
+                Ok(()) => self.log.push(format!("Removed leftover temporary file: {:?}", path)),
+                Err(e) => self.log.push(format!("Warning: Failed to remove {:?}: {}", path, e)),
+            }
+        }
+    }
+
+    /// Показать системное уведомление, если они включены в настройках
+    fn notify(&self, event: NotificationEvent) {
+        if self.settings.notifications {
+            show_notification(&event);
+        }
+    }
+
+    /// Просканировать основную и пользовательскую директории пресетов
+    ///
+    /// Сканирование ограничено настройкой `max_scanned_dirs` и может быть отменено
+    /// кнопкой Cancel в индикаторе выполнения.
+    fn rescan_presets(&mut self) -> Command<Msg> {
+        let Some(dir) = self.presets_dir.clone() else { return Command::none() };
+        let user_dir = self.settings.user_presets_path();
+        let sources = self.settings.preset_sources.clone();
+        let generation = self.begin_operation();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.scan_cancel = Some(cancel.clone());
+        let options = ScanOptions { max_dirs: self.settings.max_scanned_dirs, cancel: Some(cancel) };
+        Command::perform(async move {
+            discover_presets_merged(&dir, user_dir.as_deref(), &sources, &options)
+        }, move |result| Msg::PresetsLoaded(generation, result))
+    }
+
+    /// Выбрать директорию пресета и объяснить, почему она не появляется в списке
+    ///
+    /// Результаты проверок (`doctor::diagnose_preset`) записываются в лог.
+    fn diagnose_preset(&self) -> Command<Msg> {
+        let Some(presets_dir) = self.presets_dir.clone() else { return Command::none() };
+        let user_dir = self.settings.user_presets_path();
+        let sources = self.settings.preset_sources.clone();
+        let max_dirs = self.settings.max_scanned_dirs;
+        let pick = self.services.picker.pick_folder(presets_dir.clone());
+        Command::perform(async move {
+            let path = pick.await?;
+            let checks = diagnose_preset(&path, &presets_dir, user_dir.as_deref(), &sources, max_dirs);
+            Some((path, checks))
+        }, Msg::PresetDiagnosed)
+    }
+
+    /// Проверить конфигурацию выбранного пресета (`validation::validate_preset`)
+    ///
+    /// # Arguments
+    ///
+    /// * `requested` - проверка запрошена пользователем; при автоматической проверке
+    ///   после выбора пресета в лог попадают только найденные проблемы
+    fn validate_selected_preset(&self, requested: bool) -> Command<Msg> {
+        let Some(preset_id) = self.selected_preset.clone() else { return Command::none() };
+        let Some(dir) = self.preset_root(&preset_id) else { return Command::none() };
+        Command::perform(async move {
+            let issues = validate_preset(&dir, &preset_id);
+            (preset_id, issues)
+        }, move |(preset_id, issues)| Msg::PresetValidated { preset_id, issues, requested })
+    }
+
+    /// Предложить путь и сохранить JSON Schema `files_config.json`
+    ///
+    /// По умолчанию файл предлагается сохранить в директорию пресетов, чтобы
+    /// конфигурации могли ссылаться на него относительным `"$schema"`.
+    fn export_schema(&self) -> Command<Msg> {
+        let start_dir = self.presets_dir.clone()
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+        let save = self.services.picker.save_file(start_dir, PRESET_SCHEMA_FILE_NAME.to_string());
+        Command::perform(async move {
+            let path = save.await?;
+            Some(export_preset_config_schema(&path).map(|()| path))
+        }, Msg::SchemaExported)
+    }
+
+    /// Текст лога для сохранения в файл с заголовком (время, версия, пресет, проект)
+    fn log_export_text(&self, preset: Option<String>, project_path: Option<PathBuf>) -> String {
+        self.log.export_text(&LogExportHeader {
+            saved_at: chrono::Local::now(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            preset,
+            project_path,
+        })
+    }
+
+    /// Предложить путь и сохранить лог сессии
+    ///
+    /// Текст снимается в момент нажатия кнопки; по умолчанию файл предлагается
+    /// сохранить рядом с последним созданным проектом.
+    fn save_log(&self) -> Command<Msg> {
+        let project_path = self.last_report.as_ref().map(|report| report.project_path.clone());
+        let content = self.log_export_text(self.selected_preset.clone(), project_path.clone());
+        let start_dir = project_path.as_deref().and_then(|path| path.parent()).map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_default();
+        let file_name = format!("project_creator_{}.log", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+        let save = self.services.picker.save_file(start_dir, file_name);
+        Command::perform(async move {
+            let path = save.await?;
+            Some(std::fs::write(&path, content)
+                .map(|()| path.clone())
+                .map_err(|e| format!("Failed to write {:?}: {}", path, e)))
+        }, Msg::LogSaved)
+    }
+
+    /// Записать лог в директорию созданного проекта (`PROJECT_LOG_FILE_NAME`),
+    /// ошибку записать в лог как предупреждение
+    fn write_project_log(&mut self, report: &CreationReport) {
+        let path = report.project_path.join(PROJECT_LOG_FILE_NAME);
+        let content = self.log_export_text(Some(report.preset_id.clone()), Some(report.project_path.clone()));
+        if let Err(e) = std::fs::write(&path, content) {
+            self.log.push(format!("Warning: Failed to write {:?}: {}", path, e));
+        }
+    }
+
+    /// Директория, в которую импортируются пресеты
+    ///
+    /// Если основная директория пресетов только для чтения - пользовательская директория.
+    fn import_target_dir(&self) -> Option<PathBuf> {
+        if self.presets_read_only {
+            self.settings.user_presets_path()
+        } else {
+            self.presets_dir.clone()
+        }
+    }
+
+    /// Открыть диалог выбора папки (или ZIP-архива, если `archive`) пресета для импорта
+    fn browse_import(&mut self, archive: bool) -> Command<Msg> {
+        self.import_prompt = false;
+        let start_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let pick = if archive {
+            self.services.picker.pick_file(start_dir, "Preset archive".to_string(), vec!["zip".to_string()])
+        } else {
+            self.services.picker.pick_folder(start_dir)
+        };
+        Command::perform(pick, Msg::ImportSourcePicked)
+    }
+
+    /// Прочитать идентификатор импортируемого пресета перед копированием
+    fn check_import(&self, source: PathBuf) -> Command<Msg> {
+        Command::perform(async move {
+            let id = import_preset_id(&source);
+            (source, id)
+        }, |(source, id)| Msg::ImportChecked(source, id))
+    }
+
+    /// Импортировать пресет (с заменой существующего, если `overwrite`)
+    fn run_import(&mut self, source: PathBuf, overwrite: bool) -> Command<Msg> {
+        let Some(presets_dir) = self.import_target_dir() else { return Command::none() };
+        self.log.begin("Import", source.display().to_string());
+        Command::perform(async move {
+            import_preset(&presets_dir, &source, overwrite)
+        }, Msg::PresetImported)
+    }
+
+    /// Параметры сканирования выбранной пользователем директории (без отмены)
+    fn scan_options(&self) -> ScanOptions {
+        ScanOptions { max_dirs: self.settings.max_scanned_dirs, cancel: None }
+    }
+
+    /// Скачать пресеты в выбранную директорию (или использовать существующие,
+    /// если она доступна только для чтения)
+    fn install_presets_into(&mut self, target_dir: PathBuf) -> Command<Msg> {
+        // Общая папка только для чтения: использовать пресеты как есть
+        if let Err(e) = check_writable_dir(&target_dir) {
+            let scan = scan_presets(&target_dir, &self.scan_options()).unwrap_or_default();
+            if !scan.presets.is_empty() {
+                self.pending_presets_dir = Some((target_dir, scan));
+                return self.handle_message(Msg::UseExistingPresets);
+            }
+            self.is_busy = false;
+            self.show_dialog = false;
+            self.log.push(format!("Error: Presets folder is not writable: {}", e));
+            if self.presets_dir.is_none() {
+                self.setup_incomplete = Some("The selected presets folder is read-only and empty.".to_string());
+            }
+            self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                "Presets folder is read-only",
+                e,
+                Some("Choose a folder you can write to, or ask your administrator to install the presets there."),
+            )));
+            return Command::none();
+        }
+        let generation = self.begin_operation();
+        // Скачать и распаковать пресеты
+        self.fetch_presets(target_dir, generation)
+    }
+
+    /// Закрыть окно приложения, сохранив настройки (в том числе размер окна)
+    fn close_window(&mut self) -> Command<Msg> {
+        self.save_settings();
+        window::close(window::Id::MAIN)
+    }
+
+    /// Сохранить настройки, записав ошибку в лог
+    fn save_settings(&mut self) {
+        if let Err(e) = self.settings.save() {
+            self.log.push(format!("Warning: Failed to save settings: {}", e));
+        }
+    }
+
+    /// Запомнить значения полей и опций выбранного пресета для следующих сессий
+    fn remember_answers(&mut self) {
+        let Some(preset_id) = self.selected_preset.clone() else { return };
+        self.saved_answers.remember(&preset_id, &self.dynamic_fields, &self.dynamic_options);
+        self.save_answers();
+    }
+
+    /// Сохранить историю недавних проектов, ошибку записать в лог
+    fn save_recent_projects(&mut self) {
+        if let Err(e) = self.recent_projects.save() {
+            self.log.push(format!("Warning: Failed to save recent projects: {}", e));
+        }
+    }
+
+    /// Проверить в фоне, какие директории недавних проектов больше не существуют
+    fn check_recent_projects(&self) -> Command<Msg> {
+        let paths: Vec<PathBuf> = self.recent_projects.entries().iter()
+            .map(|project| project.path.clone())
+            .collect();
+        Command::perform(async move {
+            paths.into_iter().filter(|path| !path.is_dir()).collect()
+        }, Msg::RecentProjectsChecked)
+    }
+
+    /// Открыть созданный проект в редакторе из настроек, записав результат в лог
+    ///
+    /// Ошибка запуска редактора не делает создание проекта неудачным.
+    fn open_project_in_editor(&mut self, project_path: &std::path::Path) {
+        match open_in_editor(&self.settings.open_in_editor, project_path) {
+            Ok(command) => self.log.push(format!("Opened project in editor: `{}`", command)),
+            Err(e) => self.log.push(format!("Warning: Failed to open editor: {}", e)),
+        }
+    }
+
+    /// Сохранить `saved_answers`, ошибку записать в лог
+    fn save_answers(&mut self) {
+        if let Err(e) = self.saved_answers.save() {
+            self.log.push(format!("Warning: Failed to save field values: {}", e));
+        }
+    }
+
+    /// Запустить фоновую проверку обновлений, если она включена и прошли сутки
+    /// с предыдущей проверки
+    ///
+    /// Не блокирует интерфейс и не влияет на `is_busy`.
+    fn start_update_check_if_due(&mut self) -> Command<Msg> {
+        let now = chrono::Utc::now();
+        let due = self.settings.check_for_updates
+            && !self.update_check_running
+            && self.settings.last_update_check
+                .is_none_or(|last| now - last >= chrono::Duration::hours(UPDATE_CHECK_INTERVAL_HOURS));
+        if !due {
+            return Command::none();
+        }
+        
+        self.update_check_running = true;
+        self.settings.last_update_check = Some(now);
+        self.save_settings();
+        
+        let network = self.settings.network.clone();
+        Command::perform(async move {
+            check_for_update(&network).await
+        }, Msg::UpdateCheckFinished)
+    }
+
+    /// Показать всплывающее уведомление на `TOAST_DURATION`
+    fn show_toast(&mut self, message: impl Into<String>) -> Command<Msg> {
+        self.toast_id += 1;
+        self.toast = Some(message.into());
+        let id = self.toast_id;
+        Command::perform(tokio::time::sleep(TOAST_DURATION), move |_| Msg::ToastExpired(id))
+    }
+
+    /// Запланировать проверку директории проекта после паузы во вводе
+    ///
+    /// Предыдущие запланированные проверки становятся устаревшими.
+    fn schedule_target_check(&mut self) -> Command<Msg> {
+        self.target_check_id += 1;
+        self.target_status = None;
+        if !is_valid_project_name(&self.project_name) {
+            return Command::none();
+        }
+        let id = self.target_check_id;
+        Command::perform(tokio::time::sleep(TARGET_CHECK_DEBOUNCE), move |_| Msg::CheckTargetDir(id))
+    }
+
+    /// Запустить создание проекта (проверки `can_create` выполняет вызывающий код)
+    fn start_creation(&mut self) -> Command<Msg> {
+        let Some(builder) = self.project_builder() else { return Command::none() };
+        // Шаги создания приходят сообщениями CreateProgress из канала,
+        // который закрывается вместе с построителем
+        let (sender, receiver) = iced::futures::channel::mpsc::unbounded();
+        let builder = builder.verbose(true)
+            .on_progress(move |step, total, message| {
+                let _ = sender.unbounded_send((step, total, message.to_string()));
+            });
+        
+        // Кнопка Create блокируется в этом же обновлении через is_busy
+        let generation = self.begin_operation();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let builder = builder.cancel_flag(cancel.clone());
+        self.operation_cancel = Some(cancel);
+        self.is_busy = true;
+        self.busy_label = format!("Creating '{}'", self.project_name);
+        self.log.begin("Create", self.project_name.clone());
+        self.show_dialog = true;
+        self.dialog_progress = 0.0;
+        self.dialog_start = Some(Instant::now());
+        self.progress_label = None;
+        self.progress_indeterminate = false;
+        self.operation = self.preset_config.as_ref().map(OperationPlan::create_project);
+        
+        self.last_report = None;
+        
+        Command::batch(vec![
+            Command::perform(async move {
+                builder.build()
+            }, move |result| Msg::ProcessFinished { generation, result }),
+            Command::run(receiver, |(step, total, message)| Msg::CreateProgress(step, total, message)),
+        ])
+    }
+
+    /// Отличаются ли текущие значения формы от рекомендуемых (`apply_recommended_settings`)
+    fn recommended_settings_differ(&self) -> bool {
+        self.preset_config.as_ref().is_some_and(|config| {
+            let mut fields = self.dynamic_fields.clone();
+            let mut options = self.dynamic_options.clone();
+            apply_recommended_settings(config, &mut fields, &mut options)
+        })
+    }
+
+    /// Построитель проекта из текущего состояния формы (`None`, если пресет
+    /// или директория назначения не выбраны)
+    fn project_builder(&self) -> Option<ProjectBuilder> {
+        let preset_config = self.preset_config.as_ref()?;
+        let preset_id = self.selected_preset.clone()
+            .unwrap_or_else(|| preset_config.id.clone());
+        let presets_dir = self.preset_root(&preset_id)?;
+        let destination = self.destination_dir.clone()?;
+        
+        Some(ProjectBuilder::new(presets_dir, preset_id)
+            .project_name(self.project_name.clone())
+            .destination(destination)
+            .fields(self.dynamic_fields.clone())
+            .options(self.dynamic_options.clone())
+            .option("git_init", self.settings.init_git)
+            .cloud_sync_warning(self.settings.warn_cloud_sync))
+    }
+
+    /// Сгенерировать README для предпросмотра тем же кодом, что и `create_project`
+    ///
+    /// `None`, если пресет не генерирует README (`generate_readme` выключен,
+    /// README копируется из шаблона или `readme_template` пустой).
+    fn render_readme_preview(&self) -> Option<String> {
+        let config = self.preset_config.as_ref()
+            .filter(|c| c.generates_readme())?;
+        let project_name = if self.project_name.trim().is_empty() { "project_name" } else { &self.project_name };
+        // Ошибка в выражении вычисляемого поля покажется при создании проекта;
+        // в предпросмотре его плейсхолдеры остаются как есть
+        let fields = transformed_field_values(config, &visible_field_values(config, &self.dynamic_fields, &self.dynamic_options));
+        let fields = computed_field_values(config, project_name, &fields).unwrap_or(fields);
+        Some(render_readme(config, project_name, &fields, &readme_datetime()))
+    }
+
+    /// Запланировать обновление предпросмотра README после паузы во вводе
+    fn schedule_readme_preview(&mut self) -> Command<Msg> {
+        if self.readme_preview.is_none() {
+            return Command::none();
+        }
+        self.readme_preview_id += 1;
+        let id = self.readme_preview_id;
+        Command::perform(tokio::time::sleep(README_PREVIEW_DEBOUNCE), move |_| Msg::RefreshReadmePreview(id))
+    }
+
+    /// Установить директорию назначения (перетаскивание или диалог выбора)
+    ///
+    /// Путь подставляется в поле ввода и сразу проверяется; ошибка показывается под полем.
+    fn set_destination(&mut self, dir: PathBuf) -> Command<Msg> {
+        self.destination_input = dir.display().to_string();
+        self.destination_check_id += 1;
+        let check = self.apply_destination_input();
+        match self.destination_dir {
+            Some(ref dir) => {
+                let message = format!("Projects will be created in {}", dir.display());
+                Command::batch(vec![self.show_toast(message), check])
+            }
+            None => check,
+        }
+    }
+
+    /// Проверить путь из поля ввода и сделать его директорией назначения
+    ///
+    /// Директория должна существовать и быть доступной для записи. Подходящая
+    /// директория запоминается в настройках и восстанавливается при следующем запуске.
+    fn apply_destination_input(&mut self) -> Command<Msg> {
+        self.destination_cloud_sync = None;
+        let input = self.destination_input.trim();
+        if input.is_empty() {
+            self.destination_dir = None;
+            self.destination_error = None;
+            return self.schedule_target_check();
+        }
+        let dir = PathBuf::from(input);
+        let checked = if !dir.is_dir() {
+            Err("Folder does not exist".to_string())
+        } else {
+            check_writable_dir(&dir).map_err(|e| {
+                self.log.push(format!("Warning: {}", e));
+                "Folder is not writable".to_string()
+            })
+        };
+        match checked {
+            Ok(()) => {
+                self.destination_cloud_sync = detect_cloud_sync(&dir);
+                self.destination_error = None;
+                if self.destination_dir.as_ref() != Some(&dir) {
+                    self.log.push(format!("Destination set to {:?}", dir));
+                }
+                if self.settings.last_destination.as_ref() != Some(&dir) {
+                    self.settings.last_destination = Some(dir.clone());
+                    self.save_settings();
+                }
+                self.destination_dir = Some(dir);
+            }
+            Err(reason) => {
+                self.destination_dir = None;
+                self.destination_error = Some(reason);
+            }
+        }
+        self.schedule_target_check()
+    }
+
+    /// Порядок обхода полей ввода клавишей Tab
+    ///
+    /// Имя проекта, затем текстовые поля пресета в порядке объявления в конфигурации.
+    /// Выпадающие списки не получают фокус через `text_input::focus` и пропускаются.
+    fn focus_order(&self) -> Vec<text_input::Id> {
+        let mut order = vec![project_name_input_id()];
+        if let Some(ref config) = self.preset_config {
+            order.extend(config.fields.iter()
+                .filter(|field| is_text_field(field) && field_visible(config, field, &self.dynamic_options))
+                .map(|field| field_input_id(&field.id)));
+        }
+        order
+    }
+
+    /// Привести редакторы полей "textarea" к значениям `dynamic_fields`
+    ///
+    /// Значения меняются не только вводом (значения по умолчанию, сохраненные ответы,
+    /// сброс формы), а `text_editor` хранит собственное содержимое. Редактор пересоздается
+    /// только при расхождении, поэтому при вводе курсор и выделение сохраняются.
+    fn sync_textareas(&mut self) {
+        let Some(ref config) = self.preset_config else {
+            self.textareas.clear();
+            return;
+        };
+        self.textareas.retain(|id, _| config.fields.iter().any(|field| field.is_textarea() && field.id == *id));
+        for field in config.fields.iter().filter(|field| field.is_textarea()) {
+            let value = self.dynamic_fields.get(&field.id).map(String::as_str).unwrap_or_default();
+            if self.textareas.get(&field.id).is_none_or(|content| textarea_value(content) != value) {
+                self.textareas.insert(field.id.clone(), text_editor::Content::with_text(value));
+            }
+        }
+    }
+
+    /// Подсказка иконки трея для текущего состояния
+    fn current_tray_tooltip(&self) -> String {
+        if self.is_busy {
+            format!("{}… {:.0}%", self.busy_label, self.dialog_progress * 100.0)
+        } else {
+            tray::IDLE_TOOLTIP.to_string()
+        }
+    }
+
+    /// Создать состояние приложения с заданными сервисами (сеть, диалоги)
+    ///
+    /// `Application::new` передает реальные сервисы; подставные реализации
+    /// позволяют пройти поток первого запуска без сети и окон.
+    fn with_services(flags: LaunchOptions, services: Services) -> (Self, Command<Msg>) {
+        let mut state = Self {
+            // Пресеты
+            presets_dir: None,
+            preset_roots: HashMap::new(),
+            preset_origins: HashMap::new(),
+            presets_read_only: false,
+            available_presets: Vec::new(),
+            preset_names: HashMap::new(),
+            preset_display_names: Vec::new(),
+            preset_tags: HashMap::new(),
+            available_tags: Vec::new(),
+            selected_tag: None,
+            preset_search: String::new(),
+            selected_preset: None,
+            selected_preset_display_name: None,
+            preset_config: None,
+            dynamic_fields: HashMap::new(),
+            dynamic_options: HashMap::new(),
+            textareas: HashMap::new(),
+            
+            // Проект
+                project_name: flags.name.unwrap_or_default(),
+            
+            // UI состояние
+                project_name_error: String::new(),
+                is_busy: false,
+                log: SessionLog::default(),
+                collapsed_log_sections: HashSet::new(),
+                show_dialog: false,
+                dialog_progress: 0.0,
+                dialog_start: None,
+                progress_label: None,
+                progress_indeterminate: false,
+                operation: None,
+                confirm_quit: false,
+                quit_when_idle: false,
+                operation_generation: 0,
+                requested_preset: flags.preset,
+                pending_fields: flags.fields,
+                pending_presets_dir: None,
+                scan_cancel: None,
+                operation_cancel: None,
+                stale_temp_files: Vec::new(),
+                services,
+                setup_incomplete: None,
+                window_width: DEFAULT_WINDOW_WIDTH,
+                window_focused: true,
+                open_menu: None,
+                overlay: None,
+                settings: AppSettings::load(),
+                saved_answers: SavedAnswers::load(),
+                recent_projects: RecentProjects::load(),
+                show_recent_projects: false,
+                pending_repair: None,
+                missing_recent_projects: HashSet::new(),
+                presets_url_input: String::new(),
+                presets_url_error: None,
+                available_update: None,
+                update_check_running: false,
+                tray: None,
+                tray_checked: false,
+                tray_tooltip: tray::IDLE_TOOLTIP.to_string(),
+                #[cfg(feature = "automation")]
+                automation: flags.automation,
+                #[cfg(feature = "automation")]
+                automation_create: None,
+                busy_label: String::new(),
+                destination_dir: None,
+                destination_input: String::new(),
+                destination_error: None,
+                destination_check_id: 0,
+                destination_cloud_sync: None,
+                dropped_paths: Vec::new(),
+                dropped_preset_dir: None,
+                import_prompt: false,
+                pending_import: None,
+                toast: None,
+                toast_id: 0,
+                field_errors: HashMap::new(),
+                target_status: None,
+                target_check_id: 0,
+                system_dark: true,
+                pending_large_plan: None,
+                show_timing: false,
+                show_missing_templates: false,
+                show_project_structure: false,
+                preset_loading: false,
+                readme_preview: None,
+                readme_preview_id: 0,
+                last_report: None,
+            
+            // Инициализация
+            presets_initialized: false,
+            last_refresh: None,
+        };
+        
+        // Последняя использованная директория назначения проверяется заново: ее могли удалить
+        if let Some(dir) = state.settings.last_destination.clone() {
+            state.destination_input = dir.display().to_string();
+            let _ = state.apply_destination_input();
+        } else if let Some(dir) = std::env::current_dir().ok().filter(|dir| check_writable_dir(dir).is_ok()) {
+            // Без сохраненной директории проекты, как и раньше, создаются в текущей;
+            // она не запоминается в настройках, пока пользователь не выберет ее сам
+            state.destination_input = dir.display().to_string();
+            state.destination_cloud_sync = detect_cloud_sync(&dir);
+            state.destination_dir = Some(dir);
+        }
+        
+        // Тема ОС определяется до первой отрисовки, чтобы окно не мигало
+        if state.settings.theme == ThemeChoice::System {
+            state.system_dark = detect_system_dark();
+        }
+        
+        // Проверка обновлений выполняется в фоне и не задерживает запуск
+        let update_check = state.start_update_check_if_due();
+        
+        if let Some(dir) = config_dir().filter(|_| is_portable_mode()) {
+            state.log.push(format!("Portable mode: state is stored in {}", dir.display()));
+        }
+        
+        // Попытаться загрузить путь к пресетам
+        let presets_dir = load_presets_path_from_global_namespace();
+        state.find_stale_temp_files(presets_dir.as_deref());
+        
+        let startup = if let Some(dir) = presets_dir {
+            // Путь найден - загрузить пресеты
+            state.presets_dir = Some(dir);
+            state.rescan_presets()
+        } else {
+            // Путь не найден - запросить выбор папки
+            state.pick_presets_folder()
+        };
+        
+        if let Some((width, _)) = state.settings.window_size {
+            state.window_width = width;
+        }
+        
+        // Имя из --name: сразу проверить директорию проекта
+        let target_check = state.schedule_target_check();
+        
+        (state, Command::batch(vec![startup, update_check, target_check]))
+    }
+
+    /// Доступен ли пункт меню в текущем состоянии
+    fn menu_entry_enabled(&self, entry: MenuEntry) -> bool {
+        match entry {
+            MenuEntry::NewProject | MenuEntry::ChangePresetsFolder => !self.is_busy,
+            MenuEntry::RefreshPresets => {
+                !self.is_busy && !self.presets_read_only && self.presets_dir.is_some()
+            }
+            MenuEntry::ImportPreset => !self.is_busy && self.import_target_dir().is_some(),
+            MenuEntry::PresetDoctor => self.presets_dir.is_some(),
+            MenuEntry::VerifyPresets => !self.is_busy && self.presets_dir.is_some(),
+            MenuEntry::Settings | MenuEntry::OpenLogFile | MenuEntry::ExportSchema
+            | MenuEntry::About | MenuEntry::Quit => true,
+        }
+    }
+
+    /// Выполнить пункт меню приложения
+    fn handle_menu_entry(&mut self, entry: MenuEntry) -> Command<Msg> {
+        self.open_menu = None;
+        if !self.menu_entry_enabled(entry) {
+            return Command::none();
+        }
+        match entry {
+            MenuEntry::NewProject => self.handle_message(Msg::NewProject),
+            MenuEntry::ChangePresetsFolder => self.handle_message(Msg::ChangePresetsFolder),
+            MenuEntry::RefreshPresets => self.handle_message(Msg::RefreshPresets),
+            MenuEntry::ImportPreset => self.handle_message(Msg::ShowImportPreset),
+            MenuEntry::Settings => self.handle_message(Msg::ShowSettings),
+            MenuEntry::About => self.handle_message(Msg::ShowAbout),
+            MenuEntry::PresetDoctor => self.diagnose_preset(),
+            MenuEntry::VerifyPresets => self.verify_managed_presets(),
+            MenuEntry::ExportSchema => self.export_schema(),
+            MenuEntry::OpenLogFile => {
+                if let Err(e) = self.open_log_file() {
+                    self.log.push(format!("Warning: Failed to open log file: {}", e));
+                }
+                Command::none()
+            }
+            MenuEntry::Quit => {
+                if self.is_busy {
+                    self.confirm_quit = true;
+                    Command::none()
+                } else {
+                    self.close_window()
+                }
+            }
+        }
+    }
+
+    /// Записать лог текущей сессии в файл и открыть его
+    fn open_log_file(&self) -> Result<(), String> {
+        let dir = config_dir()
+            .ok_or_else(|| "Could not determine config directory".to_string())?;
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create config dir: {}", e))?;
+        let path = dir.join(LOG_FILE_NAME);
+        let mut content = self.log.to_text();
+        content.push('\n');
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        open_in_file_manager(&path)
+    }
+
+    /// Построить свернутый раздел недавних проектов (пусто, если история пуста)
+    ///
+    /// Проекты, директорий которых больше нет, выводятся серым с пометкой
+    /// "missing" и кнопкой удаления из истории.
+    fn view_recent_projects(&self) -> Element<'_, Msg> {
+        if self.recent_projects.is_empty() {
+            return column![].into();
+        }
+        let header = button(text(format!(
+            "{} {}",
+            if self.show_recent_projects { "▾" } else { "▸" },
+            trf("recent.header", &[&self.recent_projects.entries().len()])
+        )).size(12))
+        .style(theme::Button::Text)
+        .padding(0)
+        .on_press(Msg::ToggleRecentProjects);
+        if !self.show_recent_projects {
+            return header.into();
+        }
+
+        let mut entries = column![].spacing(2).padding([0, 0, 0, 14]);
+        for project in self.recent_projects.entries() {
+            let created = project.created.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string();
+            let details = text(format!("{}, {}", project.preset_id, created)).size(11);
+            let entry: Element<Msg> = if self.missing_recent_projects.contains(&project.path) {
+                let grey = Color::from_rgb(0.5, 0.5, 0.5);
+                row![
+                    text(project.path.display().to_string()).size(11).style(grey),
+                    details.style(grey),
+                    text(tr("recent.missing")).size(11).style(Color::from_rgb(0.9, 0.75, 0.2)),
+                    button(text(tr("common.remove")).size(11))
+                        .style(theme::Button::Text)
+                        .padding(0)
+                        .on_press(Msg::RemoveRecentProject(project.path.clone())),
+                ].spacing(8).align_items(iced::Alignment::Center).into()
+            } else {
+                row![
+                    button(text(project.path.display().to_string()).size(11))
+                        .style(theme::Button::Text)
+                        .padding(0)
+                        .on_press(Msg::OpenRecentProject(project.path.clone())),
+                    details,
+                ].spacing(8).align_items(iced::Alignment::Center).into()
+            };
+            entries = entries.push(entry);
+        }
+        entries = entries.push(button(text(tr("recent.clear")).size(11)).on_press(Msg::ClearRecentProjects));
+        column![header, entries].spacing(2).into()
+    }
+
+    /// Построить лог по разделам: у раздела операции - заголовок-кнопка, которая
+    /// сворачивает его строки; общие строки выводятся без заголовка
+    fn view_log_sections(&self) -> Element<'_, Msg> {
+        let mut sections = column![].spacing(2);
+        for section in self.log.sections() {
+            let entries = section.entries.join("\n");
+            match section.operation {
+                Some(ref info) => {
+                    let collapsed = self.collapsed_log_sections.contains(&info.id);
+                    let header = button(text(format!(
+                        "{} {}",
+                        if collapsed { "▸" } else { "▾" },
+                        info.header()
+                    )).size(11))
+                    .style(theme::Button::Text)
+                    .padding(0)
+                    .on_press(Msg::ToggleLogSection(info.id));
+                    sections = sections.push(header);
+                    if !collapsed && !entries.is_empty() {
+                        sections = sections.push(text(entries).size(11));
+                    }
+                }
+                None => sections = sections.push(text(entries).size(11)),
+            }
+        }
+        sections.into()
+    }
+
+    /// Построить полосу меню (или кнопку "≡" в узком окне) с открытым меню под ней
+    fn view_menu_bar(&self) -> Element<'_, Msg> {
+        let kinds = menu_kinds(self.window_width / self.settings.ui_scale);
+        let bar = Row::with_children(kinds.iter().map(|&kind| {
+            button(text(kind.title()).size(12))
+                .style(if self.open_menu == Some(kind) { theme::Button::Secondary } else { theme::Button::Text })
+                .padding([2, 8])
+                .on_press(Msg::ToggleMenu(kind))
+                .into()
+        }))
+        .spacing(2);
+
+        let Some(kind) = self.open_menu else { return bar.into() };
+        let items = Column::with_children(kind.entries().iter().map(|&entry| {
+            let label = row![
+                text(entry.label()).size(12).width(Length::Fill),
+                text(entry.shortcut_label().unwrap_or_default()).size(11),
+            ].spacing(16);
+            let item = button(label)
+                .style(theme::Button::Text)
+                .width(Length::Fixed(240.0))
+                .padding([3, 8]);
+            if self.menu_entry_enabled(entry) {
+                item.on_press(Msg::MenuSelected(entry)).into()
+            } else {
+                item.into()
+            }
+        }));
+        column![
+            bar,
+            container(items).padding(4).style(theme::Container::Box),
+        ].spacing(2).into()
+    }
+
+    /// Выполнить действие из меню трея
+    fn handle_tray_action(&mut self, action: TrayAction) -> Command<Msg> {
+        match action {
+            TrayAction::ShowWindow => show_window(),
+            TrayAction::CreateProject => Command::batch(vec![
+                show_window(),
+                text_input::focus(project_name_input_id()),
+            ]),
+            TrayAction::RefreshPresets => {
+                if self.is_busy {
+                    return Command::none();
+                }
+                self.handle_message(Msg::RefreshPresets)
+            }
+            TrayAction::Quit => {
+                if self.is_busy {
+                    // Окно может быть скрыто - показать запрос подтверждения
+                    self.confirm_quit = true;
+                    show_window()
+                } else {
+                    self.close_window()
+                }
+            }
+        }
+    }
+
+    /// Обработать событие сервера автоматизации
+    ///
+    /// Запросы выполняются через те же сообщения, что и действия пользователя;
+    /// `POST /create` отвечает сразу, результат виден в `GET /state`.
+    #[cfg(feature = "automation")]
+    fn handle_automation(&mut self, event: AutomationEvent) -> Command<Msg> {
+        let request = match event {
+            AutomationEvent::Started(addr) => {
+                self.log.push(format!("Automation server listening on http://{}", addr));
+                return Command::none();
+            }
+            AutomationEvent::Failed(e) => {
+                self.log.push(format!("Error: Automation server: {}", e));
+                return Command::none();
+            }
+            AutomationEvent::Request(request) => request,
+        };
+        let (reply, command) = match request.action.clone() {
+            AutomationAction::State => (AutomationReply::ok(self.automation_state()), Command::none()),
+            AutomationAction::RefreshPresets if self.is_busy => {
+                (AutomationReply::error(409, format!("Busy: {}", self.busy_label)), Command::none())
+            }
+            AutomationAction::RefreshPresets if self.presets_dir.is_none() => {
+                (AutomationReply::error(409, "Presets directory is not set"), Command::none())
+            }
+            AutomationAction::RefreshPresets => {
+                (AutomationReply::accepted(), self.handle_message(Msg::RefreshPresets))
+            }
+            AutomationAction::Create(create) => match self.automation_create(create) {
+                Ok(command) => (AutomationReply::accepted(), command),
+                Err(reply) => (reply, Command::none()),
+            },
+        };
+        request.respond(reply);
+        command
+    }
+
+    /// Заполнить форму из запроса `POST /create` и запустить создание
+    ///
+    /// Если запрошен другой пресет, создание начнется после загрузки его конфигурации
+    /// (см. `Msg::PresetConfigLoaded`).
+    #[cfg(feature = "automation")]
+    fn automation_create(&mut self, create: CreateRequest) -> Result<Command<Msg>, AutomationReply> {
+        if self.is_busy {
+            return Err(AutomationReply::error(409, format!("Busy: {}", self.busy_label)));
+        }
+        if !self.available_presets.contains(&create.preset) {
+            return Err(AutomationReply::error(404, format!("Preset '{}' not found", create.preset)));
+        }
+        let options: Vec<(String, bool)> = create.options.into_iter().collect();
+        if !options.is_empty() {
+            let config = self.preset_root(&create.preset)
+                .ok_or_else(|| "No presets directory set".to_string())
+                .and_then(|root| load_preset_config(&root, &create.preset))
+                .map_err(|e| AutomationReply::error(400, e))?;
+            let unknown = options.iter().map(|(id, _)| id.as_str()).find(|id| {
+                !BUILTIN_OPTIONS.contains(id) && !config.options.iter().any(|option| option.id == *id)
+            });
+            if let Some(id) = unknown {
+                return Err(AutomationReply::error(400, format!(
+                    "Unknown option '{}' for preset '{}'", id, create.preset
+                )));
+            }
+        }
+        let mut commands = Vec::new();
+        if let Some(dir) = create.destination {
+            commands.push(self.set_destination(dir));
+            if let Some(ref reason) = self.destination_error {
+                return Err(AutomationReply::error(400, format!("Invalid destination: {}", reason)));
+            }
+        }
+        commands.push(self.handle_message(Msg::NameChanged(create.name)));
+        let fields: Vec<(String, String)> = create.fields.into_iter().collect();
+        let loaded = self.selected_preset.as_deref() == Some(create.preset.as_str())
+            && self.preset_config.is_some();
+        if loaded {
+            for (field_id, value) in fields {
+                commands.push(self.handle_message(Msg::FieldChanged(field_id, value)));
+            }
+            commands.push(self.apply_automation_options(options));
+            commands.push(self.handle_message(Msg::Create));
+        } else {
+            self.pending_fields = fields;
+            self.automation_create = Some(options);
+            commands.push(self.handle_message(Msg::PresetSelected(Some(create.preset))));
+        }
+        Ok(Command::batch(commands))
+    }
+
+    /// Включить или выключить опции из запроса `POST /create`, как это сделал бы пользователь
+    ///
+    /// `git_init` переключает флажок "Initialize git repository" (выбор запоминается в настройках).
+    #[cfg(feature = "automation")]
+    fn apply_automation_options(&mut self, options: Vec<(String, bool)>) -> Command<Msg> {
+        let commands = options.into_iter()
+            .map(|(option_id, enabled)| match option_id.as_str() {
+                "git_init" => self.handle_message(Msg::GitInitToggled(enabled)),
+                _ => self.handle_message(Msg::OptionToggled(option_id, enabled)),
+            })
+            .collect::<Vec<_>>();
+        Command::batch(commands)
+    }
+
+    /// Снимок состояния для `GET /state`
+    #[cfg(feature = "automation")]
+    fn automation_state(&self) -> serde_json::Value {
+        let snapshot = automation::StateSnapshot {
+            presets_dir: self.presets_dir.clone(),
+            presets: self.available_presets.iter()
+                .zip(&self.preset_display_names)
+                .map(|(id, name)| automation::PresetEntry { id: id.clone(), name: name.clone() })
+                .collect(),
+            selected_preset: self.selected_preset.clone(),
+            project_name: self.project_name.clone(),
+            destination: self.destination_dir.clone(),
+            busy: self.is_busy,
+            busy_label: self.busy_label.clone(),
+            problems: self.problems().iter().map(ToString::to_string).collect(),
+            last_project: self.last_report.as_ref()
+                .filter(|report| !report.dry_run)
+                .map(|report| report.project_path.clone()),
+            last_log: self.log.last().cloned(),
+        };
+        serde_json::to_value(snapshot).unwrap_or_default()
+    }
+
+    /// Диагностическая информация для диалога About и отчетов об ошибках
+    ///
+    /// Версия, коммит и дата сборки встраиваются `build.rs` при компиляции.
+    fn diagnostics_text(&self) -> String {
+        let presets_dir = self.presets_dir.as_ref()
+            .map(|d| d.display().to_string())
+            .unwrap_or_else(|| "not set".to_string());
+        format!(
+            "AI Project Template {}\nCommit: {}\nBuilt: {}\nPresets source: {}\nPresets folder: {}\nOS: {} ({})",
+            env!("CARGO_PKG_VERSION"),
+            env!("BUILD_GIT_COMMIT"),
+            env!("BUILD_DATE"),
+            self.settings.presets_zip_url,
+            presets_dir,
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+        )
+    }
+
+    /// Построить индикатор выполнения операции
+    fn view_busy_dialog(&self) -> Element<'_, Msg> {
+        if self.scan_cancel.is_some() {
+            // Сканирование после загрузки - последний этап обновления пресетов
+            let phase: Element<Msg> = match self.operation {
+                Some(ref plan) => column![
+                    text(plan.label()).size(12),
+                    progress_bar(0.0..=1.0, plan.progress()),
+                ].spacing(4).into(),
+                None => column![].into(),
+            };
+            container(
+                column![
+                    phase,
+                    row![
+                        text(tr("busy.scanning")).size(14),
+                        button(tr("common.cancel")).on_press(Msg::CancelScan),
+                    ]
+                    .spacing(8)
+                    .align_items(iced::Alignment::Center),
+                ]
+                .spacing(4)
+            )
+            .padding(8)
+            .into()
+        } else if self.show_dialog {
+            let label = self.progress_label.as_deref().unwrap_or(tr("busy.processing"));
+            let phase: Element<Msg> = match self.operation {
+                Some(ref plan) => text(plan.label()).size(12).into(),
+                None => column![].into(),
+            };
+            // Прерывать можно загрузку пресетов и создание проекта; после нажатия
+            // кнопка блокируется до отката изменений
+            let cancel: Element<Msg> = match self.operation_cancel {
+                Some(ref flag) if flag.load(Ordering::Relaxed) => {
+                    button(tr("busy.cancelling")).into()
+                }
+                Some(_) => button(tr("common.cancel")).on_press(Msg::CancelOperation).into(),
+                None => column![].into(),
+            };
+            container(
+                column![
+                    phase,
+                    text(label).size(14),
+                    progress_bar(0.0..=1.0, self.dialog_progress),
+                    row![
+                        text(if self.progress_indeterminate {
+                            String::new()
+                        } else {
+                            format!("{:.0}%", self.dialog_progress * 100.0)
+                        }).size(11),
+                        cancel,
+                    ]
+                    .spacing(8)
+                    .align_items(iced::Alignment::Center),
+                ]
+                .spacing(4)
+            )
+            .padding(8)
+            .into()
+        } else { container(column![]).into() }
+    }
+
+    /// Построить запрос на выход во время операции
+    fn view_quit_prompt(&self) -> Element<'_, Msg> {
+        if self.confirm_quit {
+            container(
+                column![
+                    text(tr("quit.prompt")).size(14),
+                    row![
+                        button(tr("quit.quit")).on_press(Msg::QuitConfirmed),
+                        button(tr("quit.keep_working")).on_press(Msg::QuitCancelled),
+                    ].spacing(6),
+                ]
+                .spacing(4)
+            )
+            .padding(8)
+            .into()
+        } else { container(column![]).into() }
+    }
+
+    /// Построить модальный слой выполняющейся операции
+    ///
+    /// Заменяет форму, пока идет создание проекта или сканирование пресетов:
+    /// элементы формы недоступны, а индикатор (с кнопкой Cancel при сканировании),
+    /// запрос на выход и лог остаются видны.
+    fn view_busy_modal(&self) -> Element<'_, Msg> {
+        let modal = container(
+            column![self.view_busy_dialog(), self.view_quit_prompt()]
+                .spacing(6)
+                .max_width(MAX_INPUT_WIDTH * 1.5)
+        )
+        .padding(12)
+        .style(theme::Container::Box);
+        
+        container(column![
+            text(tr("app.title")).size(16),
+            container(modal).width(Length::Fill).center_x(),
+            text(tr("app.log")).size(12),
+            scrollable(text(self.log.to_text()).size(11)).height(Length::Fill),
+            self.view_status_bar(),
+        ].spacing(6).padding(10))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// Построить просмотр полного значения поля пресета (только чтение)
+    fn view_field_value(&self, field_id: &str) -> Element<'_, Msg> {
+        let label = self.preset_config.as_ref()
+            .and_then(|config| config.fields.iter().find(|f| f.id == field_id))
+            .map(|field| field.label.as_str())
+            .unwrap_or(field_id);
+        let value = self.dynamic_fields.get(field_id).map(String::as_str).unwrap_or_default();
+        
+        let content = column![
+            text(label).size(16),
+            text(trf("common.characters", &[&value.chars().count()])).size(11),
+            container(scrollable(text(value).size(12)).height(Length::Fixed(300.0)))
+                .padding(6)
+                .style(theme::Container::Box),
+            button(tr("common.close")).on_press(Msg::CloseFieldValue),
+        ]
+        .spacing(8)
+        .max_width(MAX_INPUT_WIDTH * 1.5);
+        
+        container(container(content).padding(12).style(theme::Container::Box))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Построить диалог About
+    fn view_about(&self) -> Element<'_, Msg> {
+        let content = column![
+            text(tr("about.title")).size(16),
+            text(self.diagnostics_text()).size(12),
+            row![
+                button(tr("about.settings")).on_press(Msg::ShowSettings),
+                button(tr("about.copy_diagnostics")).on_press(Msg::CopyDiagnostics),
+                button(tr("common.close")).on_press(Msg::CloseAbout),
+            ]
+            .spacing(6),
+        ]
+        .spacing(8)
+        .max_width(MAX_INPUT_WIDTH);
+        
+        container(container(content).padding(12).style(theme::Container::Box))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Построить панель настроек
+    ///
+    /// Изменения сохраняются в `settings.toml` сразу, кроме URL архива пресетов,
+    /// который сохраняется кнопкой после проверки.
+    fn view_settings(&self) -> Element<'_, Msg> {
+        let settings = self.settings.clone();
+        let content = column![
+            text(tr("settings.title")).size(16),
+            row![
+                text(tr("settings.theme")).size(12),
+                pick_list(&ThemeChoice::ALL[..], Some(self.settings.theme), Msg::ThemeSelected),
+                text(tr("settings.scale")).size(12),
+                pick_list(
+                    UI_SCALE_STEPS.iter().copied().map(UiScale).collect::<Vec<_>>(),
+                    Some(UiScale(self.settings.ui_scale)),
+                    Msg::UiScaleSelected,
+                ),
+                text(tr("settings.language")).size(12),
+                pick_list(&Language::ALL[..], Some(self.settings.language), Msg::LanguageSelected),
+            ].spacing(6).align_items(iced::Alignment::Center),
+            row![
+                text(tr("settings.sort")).size(12),
+                pick_list(&SortOrder::ALL[..], Some(self.settings.preset_sort), Msg::PresetSortSelected),
+            ].spacing(6).align_items(iced::Alignment::Center),
+            checkbox(tr("settings.check_updates"), self.settings.check_for_updates)
+                .on_toggle(Msg::UpdateCheckToggled),
+            checkbox(tr("settings.notifications"), self.settings.notifications)
+                .on_toggle(Msg::NotificationsToggled),
+            checkbox(tr("settings.project_log"), self.settings.save_project_log)
+                .on_toggle(Msg::ProjectLogToggled),
+            checkbox(tr("settings.cloud_warning"), self.settings.warn_cloud_sync)
+                .on_toggle(Msg::CloudSyncWarningToggled),
+            if self.tray.is_some() {
+                checkbox(tr("settings.tray"), self.settings.minimize_to_tray)
+                    .on_toggle(Msg::MinimizeToTrayToggled)
+            } else {
+                checkbox(tr("settings.tray_unavailable"), false)
+            },
+            row![
+                text(tr("settings.min_busy")).size(12),
+                pick_list(
+                    MIN_BUSY_MS_STEPS.iter().copied().map(BusyDelay).collect::<Vec<_>>(),
+                    Some(BusyDelay(self.settings.min_busy_ms)),
+                    move |BusyDelay(ms)| Msg::SettingsChanged(AppSettings { min_busy_ms: ms, ..settings.clone() }),
+                ),
+            ].spacing(6).align_items(iced::Alignment::Center),
+            row![
+                text(tr("settings.editor")).size(12),
+                text_input(DEFAULT_EDITOR_COMMAND, &self.settings.open_in_editor)
+                    .on_input(Msg::EditorCommandChanged)
+                    .size(12),
+            ].spacing(6).align_items(iced::Alignment::Center),
+            text(tr("settings.editor_hint")).size(11),
+            text(tr("settings.presets_url")).size(12),
+            text_input(PRESETS_ZIP_URL, &self.presets_url_input)
+                .on_input(Msg::PresetsUrlChanged)
+                .on_submit(Msg::SavePresetsUrl)
+                .size(12),
+            text(self.presets_url_error.as_deref().unwrap_or_default()).size(11).style(Color::from_rgb(0.9, 0.3, 0.3)),
+            row![
+                button(tr("settings.save_url")).on_press_maybe(
+                    (self.presets_url_input.trim() != self.settings.presets_zip_url).then_some(Msg::SavePresetsUrl)
+                ),
+                button(tr("settings.reset_url")).on_press_maybe(
+                    (self.settings.presets_zip_url != PRESETS_ZIP_URL).then_some(Msg::ResetPresetsUrl)
+                ),
+            ]
+            .spacing(6),
+            self.preset_sources_list(),
+            button(tr("common.close")).on_press(Msg::CloseSettings),
+        ]
+        .spacing(8)
+        .max_width(MAX_INPUT_WIDTH);
+        
+        container(container(content).padding(12).style(theme::Container::Box))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Построить строку состояния: директория пресетов, их количество,
+    /// время последнего обновления и индикатор занятости
+    ///
+    /// Использует только данные из состояния, без обращений к файловой системе.
+    fn view_status_bar(&self) -> Element<'_, Msg> {
+        let path_segment: Element<Msg> = match self.presets_dir {
+            Some(ref dir) => {
+                let full = dir.display().to_string();
+                tooltip(
+                    button(text(truncate_middle(&full, STATUS_PATH_MAX_CHARS)).size(11))
+                        .style(theme::Button::Text)
+                        .padding(0)
+                        .on_press(Msg::RevealPresetsFolder),
+                    text(full).size(11),
+                    tooltip::Position::Top,
+                )
+                .into()
+            }
+            None => text(tr("status.no_folder")).size(11).into(),
+        };
+        
+        let last_refresh = match self.last_refresh {
+            Some(time) => trf("status.refreshed", &[&time.format("%Y-%m-%d %H:%M")]),
+            None => tr("status.not_refreshed").to_string(),
+        };
+        
+        row![
+            path_segment,
+            text(trf("status.presets", &[&self.available_presets.len()])).size(11),
+            text(last_refresh).size(11),
+            text(tr(if self.is_busy { "status.working" } else { "status.ready" })).size(11),
+            button(text(tr("status.about")).size(11))
+                .style(theme::Button::Text)
+                .padding(0)
+                .on_press(Msg::ShowAbout),
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// Дополнительные источники пресетов (редактируются в `settings.toml`)
+    fn preset_sources_list(&self) -> Element<'_, Msg> {
+        if self.settings.preset_sources.is_empty() {
+            return column![].into();
+        }
+        let mut list = column![
+            text(tr("settings.sources")).size(12),
+        ].spacing(2);
+        for source in &self.settings.preset_sources {
+            list = list.push(text(format!(
+                "• {} → {}/ ({})", source.name, source.subdir, truncate_middle(&source.zip_url, 60)
+            )).size(11));
+        }
+        list.into()
+    }
+
+    /// Обработать одно сообщение (вызывается из `Application::update`)
+    fn handle_message(&mut self, message: Msg) -> Command<Msg> {
+        if self.is_busy && message.is_form_input() {
+            return Command::none();
+        }
+        match message {
+            Msg::NameChanged(s) => {
+                self.last_report = None;
+                self.project_name = s;
+                self.project_name_error = if is_valid_project_name(&self.project_name) { String::new() } else { tr("name.invalid").into() };
+                return Command::batch(vec![self.schedule_target_check(), self.schedule_readme_preview()]);
+            }
+            Msg::PresetSelected(preset_id) => {
+                self.last_report = None;
+                self.selected_preset = preset_id.clone();
+                // Обновить отображаемое имя выбранного пресета
+                self.selected_preset_display_name = preset_id.as_ref()
+                    .and_then(|id| self.preset_names.get(id).cloned());
+                
+                if let Some(id) = preset_id {
+                    if let Some(dir) = self.preset_root(&id) {
+                        self.log.push(format!("Loading preset config: {} from {:?}", id, dir));
+                        self.preset_loading = true;
+                        return Command::batch(vec![
+                            Command::perform(async move {
+                                load_preset_config(&dir, &id).map_err(|e| e.to_string())
+                            }, Msg::PresetConfigLoaded),
+                            self.validate_selected_preset(false),
+                        ]);
+                    }
+                } else {
+                    self.preset_config = None;
+                    self.dynamic_fields.clear();
+                    self.dynamic_options.clear();
+                    self.readme_preview = None;
+                }
+            }
+            Msg::FieldChanged(field_id, value) => {
+                self.last_report = None;
+                self.field_errors.remove(&field_id);
+                self.dynamic_fields.insert(field_id, value);
+                return self.schedule_readme_preview();
+            }
+            Msg::MultiSelectToggled(field_id, option, enabled) => {
+                let Some(field) = self.preset_config.as_ref()
+                    .and_then(|config| config.fields.iter().find(|f| f.id == field_id)) else { return Command::none() };
+                let current = self.dynamic_fields.get(&field_id).map(String::as_str).unwrap_or_default();
+                let value = field.toggle_option(current, &option, enabled);
+                return self.handle_message(Msg::FieldChanged(field_id, value));
+            }
+            Msg::TextAreaAction(field_id, action) => {
+                let is_edit = action.is_edit();
+                let content = self.textareas.entry(field_id.clone()).or_default();
+                content.perform(action);
+                if is_edit {
+                    let value = textarea_value(content);
+                    return self.handle_message(Msg::FieldChanged(field_id, value));
+                }
+            }
+            Msg::OptionToggled(option_id, enabled) => {
+                self.last_report = None;
+                self.dynamic_options.insert(option_id, enabled);
+                // Зависимые опции выключенной опции скрываются и возвращаются к значениям по умолчанию
+                if let Some(ref config) = self.preset_config {
+                    reset_hidden_options(config, &mut self.dynamic_options);
+                }
+                // Скрытые опцией поля подставляются в README пустой строкой
+                return self.schedule_readme_preview();
+            }
+            Msg::ApplyRecommendedSettings => {
+                if let Some(ref config) = self.preset_config {
+                    if apply_recommended_settings(config, &mut self.dynamic_fields, &mut self.dynamic_options) {
+                        reset_hidden_options(config, &mut self.dynamic_options);
+                        self.last_report = None;
+                        self.field_errors.clear();
+                        self.log.push("Applied recommended settings".to_string());
+                        return self.schedule_readme_preview();
+                    }
+                }
+            }
+            Msg::GitInitToggled(enabled) => {
+                self.last_report = None;
+                // Выбор запоминается: обычно он одинаков для всех проектов
+                self.settings.init_git = enabled;
+                self.save_settings();
+            }
+            Msg::OpenInEditorToggled(enabled) => {
+                self.settings.open_in_editor_when_done = enabled;
+                self.save_settings();
+            }
+            Msg::EditorCommandChanged(command) => {
+                self.settings.open_in_editor = command;
+                self.save_settings();
+            }
+            Msg::PresetsPathSelected(path) => {
+                if let Some(target_dir) = path {
+                    // Папка с множеством посторонних элементов (например, Documents) -
+                    // вероятно, выбрана по ошибке: запросить подтверждение
+                    let scan = scan_presets(&target_dir, &self.scan_options()).unwrap_or_default();
+                    if scan.looks_like_wrong_folder() {
+                        self.log.push(format!(
+                            "Warning: {:?} contains {} items that are not presets",
+                            target_dir, scan.non_preset_entries
+                        ));
+                        self.pending_presets_dir = Some((target_dir, scan));
+                        return Command::none();
+                    }
+                    return self.install_presets_into(target_dir);
+                } else if self.presets_dir.is_none() {
+                    // Без папки пресетов работать нельзя - оставить возможность повторить
+                    self.log.push("Presets folder selection cancelled".to_string());
+                    self.setup_incomplete = Some("No presets folder was selected.".to_string());
+                }
+            }
+            Msg::PresetsDownloaded(generation, result) => {
+                if !self.is_current_operation(generation) {
+                    return Command::none();
+                }
+                self.operation_cancel = None;
+                match result {
+                    Ok((path, changed)) => {
+                        self.log.push(format!("Presets changed: {}", changed));
+                        // Окно в фоне (например, обновление из трея) - сообщить уведомлением
+                        if !self.window_focused {
+                            self.notify(NotificationEvent::PresetsUpdated { changed });
+                        }
+                        // Сохранить путь в глобальное пространство имен
+                        if let Err(e) = save_presets_path_to_global_namespace(&path) {
+                            self.log.push(format!("Warning: Failed to save presets path: {}", e));
+                        }
+                        // Путь уже записан в файл настроек - обновить копию в памяти, чтобы
+                        // следующее сохранение настроек его не затерло
+                        self.settings.set_presets_path(&path);
+                        self.presets_dir = Some(path);
+                        self.log.push("Presets downloaded successfully. Scanning for available presets...".to_string());
+                        if let Some(ref mut plan) = self.operation {
+                            plan.report(PhaseKind::Rediscover, 0.0);
+                        }
+                        // Загрузить список пресетов
+                        return self.rescan_presets();
+                    }
+                    Err(e) if e == CANCELLED => {
+                        self.show_dialog = false;
+                        self.finish_cancelled_operation();
+                        if self.presets_dir.is_none() {
+                            self.setup_incomplete = Some("Downloading presets was cancelled.".to_string());
+                        }
+                    }
+                    Err(e) => {
+                        self.is_busy = false;
+                        self.show_dialog = false;
+                        self.log.push(format!("Error downloading presets: {}", e));
+                        self.finish_log_operation(Outcome::Failed);
+                        if !self.window_focused {
+                            self.notify(NotificationEvent::PresetsUpdateFailed { error: e.clone() });
+                        }
+                        if self.presets_dir.is_none() {
+                            self.setup_incomplete = Some(format!("Downloading presets failed: {}", e));
+                        }
+                        self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                            "Failed to download presets",
+                            e,
+                            Some("Check your internet connection and try Refresh Presets again."),
+                        )));
+                    }
+                }
+            }
+            Msg::PresetsLoaded(generation, result) => {
+                if !self.is_current_operation(generation) {
+                    return Command::none();
+                }
+                self.scan_cancel = None;
+                match result {
+                    Ok(scan) => {
+                        if scan.truncated {
+                            self.log.push(format!(
+                                "Warning: Stopped scanning after {} folders; some presets may be missing (max_scanned_dirs in settings)",
+                                self.settings.max_scanned_dirs
+                            ));
+                        }
+                        if scan.looks_like_wrong_folder() {
+                            self.log.push(format!(
+                                "Warning: The presets folder contains {} items that are not presets. Use File → Change presets folder… if this is the wrong folder.",
+                                scan.non_preset_entries
+                            ));
+                        }
+                        let presets = scan.presets;
+                        let sources = scan.sources;
+                        self.setup_incomplete = None;
+                        self.available_presets = presets.iter().map(|(id, _)| id.clone()).collect();
+                        // Загрузить имена пресетов для отображения
+                        self.preset_names.clear();
+                        self.preset_display_names.clear();
+                        self.preset_origins.clear();
+                        self.preset_tags.clear();
+                        let user_dir = self.settings.user_presets_path();
+                        for (preset_id, root) in &presets {
+                            // Имя и теги берутся из одного чтения конфигурации
+                            let (display_name, tags) = match load_preset_config(root, preset_id) {
+                                Ok(config) => (config.name, config.metadata.tags),
+                                Err(_) => (preset_id.clone(), Vec::new()),
+                            };
+                            self.preset_tags.insert(preset_id.clone(), tags);
+                            self.preset_names.insert(preset_id.clone(), display_name.clone());
+                            self.preset_display_names.push(display_name);
+                            let preset_dir = find_preset_dir(root, preset_id).unwrap_or_else(|| root.join(preset_id));
+                            let origin = preset_origin(&preset_dir, user_dir.as_deref());
+                            self.preset_origins.insert(preset_id.clone(), origin);
+                        }
+                        // Одинаковые имена пресетов из разных источников различаются именем источника
+                        let mut name_counts: HashMap<String, usize> = HashMap::new();
+                        for name in &self.preset_display_names {
+                            *name_counts.entry(name.clone()).or_default() += 1;
+                        }
+                        for (preset_id, name) in self.available_presets.iter().zip(self.preset_display_names.iter_mut()) {
+                            if let Some(source) = sources.get(preset_id).filter(|_| name_counts[name.as_str()] > 1) {
+                                *name = format!("{} ({})", name, source);
+                                self.preset_names.insert(preset_id.clone(), name.clone());
+                            }
+                        }
+                        self.preset_roots = presets.into_iter().collect();
+                        self.sort_presets();
+                        let tags: BTreeSet<String> = self.preset_tags.values().flatten()
+                            .map(|tag| tag.trim().to_string())
+                            .filter(|tag| !tag.is_empty())
+                            .collect();
+                        self.available_tags = tags.into_iter().collect();
+                        if self.selected_tag.as_ref().is_some_and(|tag| !self.available_tags.contains(tag)) {
+                            self.selected_tag = None;
+                        }
+                        // Пресеты только читаются при создании проекта, поэтому папка
+                        // только для чтения не мешает работе - отключается лишь обновление
+                        let read_only = self.presets_dir.as_deref()
+                            .is_some_and(|dir| check_writable_dir(dir).is_err());
+                        if read_only && !self.presets_read_only {
+                            self.log.push("Presets folder is read-only: refresh is disabled, imported presets go to the user presets folder".to_string());
+                        }
+                        self.presets_read_only = read_only;
+                        self.presets_initialized = true;
+                        self.last_refresh = Some(chrono::Local::now());
+                        self.is_busy = false;
+                        self.show_dialog = false;
+                        self.log.push(format!("Found {} preset(s)", self.available_presets.len()));
+                        // Сканирование завершает обновление пресетов
+                        self.finish_log_operation(Outcome::Succeeded);
+                        // Пресет из аргумента --preset имеет приоритет над выбором по умолчанию
+                        if let Some(requested) = self.requested_preset.take() {
+                            if self.available_presets.contains(&requested) {
+                                return self.handle_message(Msg::PresetSelected(Some(requested)));
+                            }
+                            self.log.push(format!(
+                                "Requested preset '{}' not found, using default selection", requested
+                            ));
+                        }
+                        // Сохранить текущий выбор, если пресет по-прежнему доступен
+                        // (конфигурация перечитывается, т.к. директория могла смениться)
+                        if let Some(current) = self.selected_preset.clone() {
+                            if self.available_presets.contains(&current) {
+                                return self.handle_message(Msg::PresetSelected(Some(current)));
+                            }
+                            self.log.push(format!(
+                                "Preset '{}' is no longer available, selection cleared", current
+                            ));
+                            self.selected_preset = None;
+                            self.selected_preset_display_name = None;
+                            self.preset_config = None;
+                            self.dynamic_fields.clear();
+                            self.dynamic_options.clear();
+                        }
+                        // Пресет, выбранный в прошлой сессии
+                        if let Some(remembered) = self.settings.last_preset.clone() {
+                            if self.available_presets.contains(&remembered) {
+                                return self.handle_message(Msg::PresetSelected(Some(remembered)));
+                            }
+                        }
+                        // Выбрать первый пресет по умолчанию (или "software" если есть)
+                        if let Some(software_idx) = self.available_presets.iter().position(|p| p == "software") {
+                            let preset_id = self.available_presets[software_idx].clone();
+                            return self.handle_message(Msg::PresetSelected(Some(preset_id)));
+                        } else if !self.available_presets.is_empty() {
+                            let preset_id = self.available_presets[0].clone();
+                            return self.handle_message(Msg::PresetSelected(Some(preset_id)));
+                        }
+                    }
+                    Err(e) => {
+                        self.is_busy = false;
+                        self.show_dialog = false;
+                        self.log.push(format!("Error loading presets: {}", e));
+                        self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                            "Presets folder is not available",
+                            e,
+                            Some("Make sure the presets folder exists and is readable."),
+                        )));
+                    }
+                }
+            }
+            Msg::PresetConfigLoaded(result) => {
+                self.preset_loading = false;
+                match result {
+                    Ok(config) => {
+                        self.preset_config = Some(config.clone());
+                        self.field_errors.clear();
+                        // Запомнить выбор для следующего запуска
+                        if self.selected_preset.is_some() && self.settings.last_preset != self.selected_preset {
+                            self.settings.last_preset = self.selected_preset.clone();
+                            self.save_settings();
+                        }
+                        self.log.push(format!(
+                            "Preset loaded: {} (fields: {}, options: {})",
+                            config.name,
+                            config.fields.len(),
+                            config.options.len()
+                        ));
+                        // Поле неизвестного типа показывается как текстовое поле
+                        for warning in config.fields.iter().filter_map(FieldConfig::unknown_type_warning) {
+                            self.log.push(warning);
+                        }
+                        // Инициализировать опции из конфига
+                        for opt in &config.options {
+                            self.dynamic_options.insert(
+                                opt.id.clone(),
+                                opt.default,
+                            );
+                        }
+                        // Значения, введенные при прошлом создании проекта по этому пресету
+                        // (не перезаписывают уже введенные значения полей)
+                        let preset_id = self.selected_preset.clone().unwrap_or_else(|| config.id.clone());
+                        if let Some(saved) = self.saved_answers.get(&preset_id).map(|saved| saved.for_config(&config)) {
+                            for (field_id, value) in saved.fields {
+                                let current = self.dynamic_fields.entry(field_id).or_default();
+                                if current.is_empty() {
+                                    *current = value;
+                                }
+                            }
+                            self.dynamic_options.extend(saved.options);
+                        }
+                        reset_hidden_options(&config, &mut self.dynamic_options);
+                        // Применить значения полей из аргументов --field (один раз)
+                        for (field_id, value) in std::mem::take(&mut self.pending_fields) {
+                            if config.fields.iter().any(|f| f.id == field_id) {
+                                self.dynamic_fields.insert(field_id, value);
+                            } else {
+                                self.log.push(format!(
+                                    "Warning: Field '{}' from --field is not defined in preset '{}'",
+                                    field_id, config.id
+                                ));
+                            }
+                        }
+                        // Начальные значения полей (не перезаписывают введенные и очищенные пользователем)
+                        apply_field_defaults(&config, &mut self.dynamic_fields);
+                        // Создание, запрошенное через сервер автоматизации до загрузки пресета
+                        #[cfg(feature = "automation")]
+                        {
+                            if let Some(options) = self.automation_create.take() {
+                                let options = self.apply_automation_options(options);
+                                return Command::batch(vec![options, self.handle_message(Msg::Create)]);
+                            }
+                        }
+                        // Значения select-полей, исчезнувшие из обновленного пресета, не должны попасть в шаблоны
+                        for (field_id, value) in reconcile_select_values(&config, &mut self.dynamic_fields) {
+                            self.log.push(format!(
+                                "Warning: Value '{}' of field '{}' is no longer available in preset '{}', cleared",
+                                value, field_id, config.id
+                            ));
+                        }
+                        if self.readme_preview.is_some() {
+                            self.readme_preview = self.render_readme_preview();
+                        }
+                        // Перевести фокус на первое незаполненное обязательное поле
+                        let first_empty_required = config.fields.iter()
+                            .filter(|field| field.required && is_text_field(field))
+                            .filter(|field| field_visible(&config, field, &self.dynamic_options))
+                            .find(|field| !field.is_filled(self.dynamic_fields.get(&field.id)));
+                        if let Some(field) = first_empty_required {
+                            return text_input::focus(field_input_id(&field.id));
+                        }
+                    }
+                    Err(e) => {
+                        self.log.push(format!("Error loading preset config: {}", e));
+                        self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                            "Failed to load preset",
+                            e,
+                            Some("Fix files_config.json of this preset or choose another preset."),
+                        )));
+                    }
+                }
+            }
+            Msg::RefreshPresets => {
+                if self.presets_read_only {
+                    self.log.push("Presets folder is read-only, refresh skipped".to_string());
+                } else if let Some(ref dir) = self.presets_dir {
+                    let dir = dir.clone();
+                    let generation = self.begin_operation();
+                    self.is_busy = true;
+                    self.busy_label = "Refreshing presets".to_string();
+                    self.log.push("Downloading and updating presets from GitHub...".to_string());
+                    self.show_dialog = true;
+                    self.dialog_progress = 0.0;
+                    self.dialog_start = Some(Instant::now());
+                    return self.fetch_presets(dir, generation);
+                } else {
+                    self.log.push("No presets directory set".to_string());
+                }
+            }
+            Msg::RetrySetup => {
+                if !self.is_busy {
+                    return self.pick_presets_folder();
+                }
+            }
+            Msg::Create => {
+                if !self.can_create() { return self.highlight_missing_fields(); }
+                self.remember_answers();
+                
+                // Большие файлы шаблонов легко случайно размножить, а большой проект -
+                // не уместить на диске: спросить подтверждение
+                let preset_config = self.preset_config.as_ref().unwrap();
+                let preset_id = self.selected_preset.as_deref().unwrap_or(&preset_config.id);
+                let Some(presets_dir) = self.preset_root(preset_id) else { return Command::none() };
+                let mut plan = plan_project(&presets_dir, preset_config);
+                if let Some(ref destination) = self.destination_dir {
+                    let refresh = self.dynamic_options.get("refresh").copied().unwrap_or(false);
+                    plan.skip_existing(&destination.join(&self.project_name), refresh);
+                }
+                if !plan.large_files(self.settings.large_file_threshold()).is_empty()
+                    || plan.projected_bytes() > self.settings.project_size_limit()
+                {
+                    self.pending_large_plan = Some(plan);
+                    return Command::none();
+                }
+                return self.start_creation();
+            }
+            Msg::Preview => {
+                if !self.can_create() {
+                    return self.highlight_missing_fields();
+                }
+                let Some(builder) = self.project_builder() else { return Command::none() };
+                let generation = self.begin_operation();
+                self.is_busy = true;
+                self.busy_label = format!("Previewing '{}'", self.project_name);
+                self.log.begin("Preview", self.project_name.clone());
+                return Command::perform(async move {
+                    builder.dry_run(true).build()
+                }, move |result| Msg::PreviewFinished { generation, result });
+            }
+            Msg::PreviewFinished { generation, result } => {
+                if !self.is_current_operation(generation) {
+                    return Command::none();
+                }
+                self.is_busy = false;
+                match result {
+                    Ok(report) => {
+                        self.log.push(format!("Preview of '{}' (nothing is written):", report.project_name));
+                        self.log.extend(report.log_lines);
+                        self.finish_log_operation(Outcome::Succeeded);
+                    }
+                    Err(e) => {
+                        self.log.push(format!("Preview failed: {}", e));
+                        self.finish_log_operation(Outcome::Failed);
+                    }
+                }
+            }
+            Msg::ConfirmLargeCreate => {
+                if self.pending_large_plan.take().is_some() && self.can_create() {
+                    return self.start_creation();
+                }
+            }
+            Msg::CancelLargeCreate => {
+                self.pending_large_plan = None;
+            }
+            Msg::ProcessFinished { generation, result } => {
+                if !self.is_current_operation(generation) {
+                    return Command::none();
+                }
+                self.operation_cancel = None;
+                match result {
+                    Ok(report) => {
+                        self.log.extend(report.log_lines.iter().cloned());
+                        self.log.push("Project created successfully!".to_string());
+                        if !report.dry_run && self.settings.open_in_editor_when_done {
+                            self.open_project_in_editor(&report.project_path);
+                        }
+                        self.finish_log_operation(Outcome::Succeeded);
+                        if !report.dry_run && self.settings.save_project_log {
+                            self.write_project_log(&report);
+                        }
+                        // Отправить системное уведомление
+                        self.notify(NotificationEvent::CreationSucceeded {
+                            project_name: report.project_name.clone(),
+                        });
+                        self.show_missing_templates = false;
+                        if !report.dry_run {
+                            self.settings.preset_last_used.insert(report.preset_id.clone(), chrono::Utc::now());
+                            self.settings.last_project_name = report.project_name.clone();
+                            self.save_settings();
+                            self.recent_projects.add(report.project_path.clone(), &report.preset_id, chrono::Utc::now());
+                            self.missing_recent_projects.remove(&report.project_path);
+                            self.save_recent_projects();
+                            if self.settings.preset_sort == SortOrder::ByLastUsed {
+                                self.sort_presets();
+                            }
+                        }
+                        self.last_report = Some(report);
+                        self.is_busy = false;
+                        // Директория проекта теперь существует - обновить подсказку
+                        return self.schedule_target_check();
+                    }
+                    // Строки после первой - откат проекта, записанного на месте ("no_atomic")
+                    Err(e) if e.lines().next() == Some(CANCELLED) => {
+                        // Staging-директория уже удалена - проект не создан
+                        self.log.extend(e.lines().skip(1).map(str::to_string));
+                        self.finish_cancelled_operation();
+                    }
+                    Err(e) => {
+                        // За ошибкой могут следовать строки отката ("Rolled back: removed ...")
+                        let mut lines = e.lines();
+                        self.log.push(format!("Error: {}", lines.next().unwrap_or_default()));
+                        self.log.extend(lines.map(str::to_string));
+                        self.log.push("Project creation failed!".to_string());
+                        self.finish_log_operation(Outcome::Failed);
+                        self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                            "Project creation failed",
+                            e,
+                            Some("If the project folder already exists, choose another name or enable overwriting."),
+                        )));
+                        // Отправить уведомление об ошибке
+                        self.notify(NotificationEvent::CreationFailed {
+                            project_name: self.project_name.clone(),
+                        });
+                    }
+                }
+                self.is_busy = false;
+            }
+            Msg::ToggleProjectStructure => {
+                self.show_project_structure = !self.show_project_structure;
+            }
+            Msg::ToggleRecentProjects => {
+                self.show_recent_projects = !self.show_recent_projects;
+                if self.show_recent_projects {
+                    return self.check_recent_projects();
+                }
+            }
+            Msg::RecentProjectsChecked(missing) => {
+                self.missing_recent_projects = missing;
+            }
+            Msg::OpenRecentProject(path) => {
+                if let Err(e) = open_in_file_manager(&path) {
+                    self.log.push(format!("Warning: Failed to open folder: {}", e));
+                }
+            }
+            Msg::RemoveRecentProject(path) => {
+                if self.recent_projects.remove(&path) {
+                    self.missing_recent_projects.remove(&path);
+                    self.save_recent_projects();
+                }
+            }
+            Msg::ClearRecentProjects => {
+                self.recent_projects.clear();
+                self.missing_recent_projects.clear();
+                self.save_recent_projects();
+                self.log.push("Recent projects history cleared".to_string());
+            }
+            Msg::ToggleMissingTemplates => {
+                self.show_missing_templates = !self.show_missing_templates;
+            }
+            Msg::RerunMissingTemplates => {
+                if self.is_busy {
+                    return Command::none();
+                }
+                let Some(ref report) = self.last_report else { return Command::none() };
+                let Some(presets_dir) = self.preset_root(&report.preset_id) else { return Command::none() };
+                let project_path = report.project_path.clone();
+                let missing = report.missing_templates.clone();
+                let retry = if report.cloud_sync.is_some() { RetryPolicy::cloud_sync() } else { RetryPolicy::transient() };
+                let busy_label = format!("Re-running missing templates of '{}'", report.project_name);
+                let generation = self.begin_operation();
+                self.is_busy = true;
+                self.busy_label = busy_label;
+                self.log.push(format!("Re-running {} missing template(s)", missing.len()));
+                return Command::perform(async move {
+                    rerun_missing_templates(&presets_dir, &project_path, &missing, &retry)
+                }, move |result| Msg::MissingTemplatesRerun { generation, result });
+            }
+            Msg::MissingTemplatesRerun { generation, result } => {
+                if !self.is_current_operation(generation) {
+                    return Command::none();
+                }
+                self.is_busy = false;
+                match result {
+                    Ok((log_lines, still_missing)) => {
+                        self.log.extend(log_lines.iter().cloned());
+                        let message = if still_missing.is_empty() {
+                            "All missing templates copied".to_string()
+                        } else {
+                            format!("{} template(s) still missing", still_missing.len())
+                        };
+                        // Результат дописывается в отчет того же создания
+                        if let Some(ref mut report) = self.last_report {
+                            report.warnings.retain(|w| !w.starts_with("Warning: Template source not found"));
+                            report.warnings.extend(log_lines.iter().filter(|l| l.starts_with("Warning:")).cloned());
+                            report.log_lines.extend(log_lines);
+                            report.missing_templates = still_missing;
+                        }
+                        return self.show_toast(message);
+                    }
+                    Err(e) => {
+                        self.log.push(format!("Error: {}", e));
+                        self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                            "Failed to re-run missing templates",
+                            e,
+                            Some("Check that the project folder and its preset still exist."),
+                        )));
+                    }
+                }
+            }
+            Msg::OpenProjectFolder => {
+                if let Some(ref report) = self.last_report {
+                    if let Err(e) = open_in_file_manager(&report.project_path) {
+                        self.log.push(format!("Warning: Failed to open folder: {}", e));
+                    }
+                }
+            }
+            Msg::CopyProjectPath => {
+                if let Some(ref report) = self.last_report {
+                    return iced::clipboard::write(report.project_path.display().to_string());
+                }
+            }
+            Msg::ChangePresetsFolder => {
+                if self.is_busy {
+                    return Command::none();
+                }
+                let start_dir = self.presets_dir.clone().unwrap_or_else(get_default_presets_path);
+                let pick = self.services.picker.pick_folder(start_dir);
+                let options = self.scan_options();
+                return Command::perform(async move {
+                    let path = pick.await?;
+                    // Найти пресеты, чтобы предложить использовать существующие
+                    let scan = scan_presets(&path, &options).unwrap_or_default();
+                    Some((path, scan))
+                }, Msg::PresetsFolderPicked);
+            }
+            Msg::PresetsFolderPicked(picked) => {
+                self.pending_presets_dir = picked;
+            }
+            Msg::ValidatePreset => {
+                return self.validate_selected_preset(true);
+            }
+            Msg::ClearSavedAnswers => {
+                if let Some(preset_id) = self.selected_preset.clone() {
+                    if self.saved_answers.forget(&preset_id) {
+                        self.save_answers();
+                        self.log.push(format!("Saved values of preset '{}' cleared", preset_id));
+                    }
+                }
+            }
+            Msg::PresetValidated { preset_id, issues, requested } => {
+                // Пока шла проверка, мог быть выбран другой пресет
+                if self.selected_preset.as_deref() != Some(preset_id.as_str()) {
+                    return Command::none();
+                }
+                if issues.is_empty() {
+                    if requested {
+                        self.log.push(format!("Preset '{}' is valid", preset_id));
+                    }
+                } else {
+                    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+                    self.log.push(format!(
+                        "Preset '{}': {} error(s), {} warning(s)", preset_id, errors, issues.len() - errors
+                    ));
+                    self.log.extend(issues.iter().map(|issue| format!("  {}", issue)));
+                }
+            }
+            Msg::PresetsVerified(result) => {
+                match result {
+                    Ok(results) => {
+                        if results.is_empty() {
+                            self.log.push("No presets downloaded from the archive to verify".to_string());
+                        }
+                        for result in &results {
+                            self.log.push(result.to_string());
+                        }
+                        let damaged = damaged_presets(&results);
+                        if damaged.is_empty() {
+                            if !results.is_empty() {
+                                self.log.push(format!("All {} preset(s) are intact", results.len()));
+                            }
+                        } else if self.presets_read_only {
+                            self.log.push("Presets folder is read-only, damaged presets cannot be repaired".to_string());
+                        } else {
+                            self.pending_repair = Some(damaged);
+                        }
+                        self.finish_log_operation(Outcome::Succeeded);
+                    }
+                    Err(e) => {
+                        self.log.push(format!("Error verifying presets: {}", e));
+                        self.finish_log_operation(Outcome::Failed);
+                    }
+                }
+            }
+            Msg::RepairPresets => {
+                if self.is_busy {
+                    return Command::none();
+                }
+                let Some(presets) = self.pending_repair.take() else { return Command::none() };
+                let Some(dir) = self.presets_dir.clone() else { return Command::none() };
+                let generation = self.begin_operation();
+                self.is_busy = true;
+                self.busy_label = "Repairing presets".to_string();
+                self.show_dialog = true;
+                self.dialog_progress = 0.0;
+                self.dialog_start = Some(Instant::now());
+                return self.repair_presets(dir, presets, generation);
+            }
+            Msg::SkipRepair => {
+                self.pending_repair = None;
+            }
+            Msg::PresetsRepaired(generation, result) => {
+                if !self.is_current_operation(generation) {
+                    return Command::none();
+                }
+                self.operation_cancel = None;
+                match result {
+                    Ok((not_in_archive, results)) => {
+                        let mut failed = !not_in_archive.is_empty();
+                        for preset_id in &not_in_archive {
+                            self.log.push(format!("Preset '{}': not found in the archive, left as is", preset_id));
+                        }
+                        for result in &results {
+                            if result.needs_repair() {
+                                failed = true;
+                                self.log.push(format!("{} after repair", result));
+                            } else {
+                                self.log.push(format!("Preset '{}': repaired", result.preset_id));
+                            }
+                        }
+                        if failed {
+                            self.finish_log_operation(Outcome::Failed);
+                        }
+                        if let Some(ref mut plan) = self.operation {
+                            plan.report(PhaseKind::Rediscover, 0.0);
+                        }
+                        // Восстановленные пресеты могли снова появиться в списке
+                        return self.rescan_presets();
+                    }
+                    Err(e) if e == CANCELLED => {
+                        self.show_dialog = false;
+                        self.finish_cancelled_operation();
+                    }
+                    Err(e) => {
+                        self.is_busy = false;
+                        self.show_dialog = false;
+                        self.log.push(format!("Error repairing presets: {}", e));
+                        self.finish_log_operation(Outcome::Failed);
+                        self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                            "Failed to repair presets",
+                            e,
+                            Some("Check your internet connection and try Verify presets again."),
+                        )));
+                    }
+                }
+            }
+            Msg::PresetDiagnosed(result) => {
+                if let Some((path, checks)) = result {
+                    self.log.push(format!("Preset doctor: {:?}", path));
+                    self.log.extend(checks.iter().map(|check| format!("  {}", check)));
+                    let verdict = if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+                        "Preset doctor found a problem, see the log"
+                    } else {
+                        "Preset doctor found no problems"
+                    };
+                    return self.show_toast(verdict);
+                }
+            }
+            Msg::SchemaExported(result) => match result {
+                Some(Ok(path)) => {
+                    self.log.push(format!("Config schema saved to {:?}", path));
+                    return self.show_toast("Config schema saved");
+                }
+                Some(Err(e)) => self.log.push(format!("Error: Failed to export config schema: {}", e)),
+                None => {}
+            },
+            Msg::SaveLog => return self.save_log(),
+            Msg::CopyLog => {
+                if !self.log.is_empty() {
+                    let text = self.log.to_text();
+                    return Command::batch(vec![iced::clipboard::write(text), self.show_toast("Copied!")]);
+                }
+            }
+            Msg::CopyLastError => {
+                if let Some(error) = self.log.last_error().map(str::to_string) {
+                    return Command::batch(vec![iced::clipboard::write(error), self.show_toast("Copied!")]);
+                }
+            }
+            Msg::LogSaved(result) => match result {
+                Some(Ok(path)) => {
+                    self.log.push(format!("Log saved to {:?}", path));
+                    return self.show_toast("Log saved");
+                }
+                Some(Err(e)) => self.log.push(format!("Error: Failed to save log: {}", e)),
+                None => {}
+            },
+            Msg::UseExistingPresets => {
+                if let Some((path, _)) = self.pending_presets_dir.take() {
+                    self.forget_last_preset_if_dir_changes(&path);
+                    // Старая директория остается нетронутой - меняется только сохраненный путь
+                    if let Err(e) = save_presets_path_to_global_namespace(&path) {
+                        self.log.push(format!("Warning: Failed to save presets path: {}", e));
+                    }
+                    // Путь уже записан в файл настроек - обновить копию в памяти, чтобы
+                    // следующее сохранение настроек его не затерло
+                    self.settings.set_presets_path(&path);
+                    self.log.push(format!("Presets folder changed to {:?}", path));
+                    self.presets_dir = Some(path);
+                    return self.rescan_presets();
+                }
+            }
+            Msg::DownloadPresetsHere => {
+                if let Some((path, _)) = self.pending_presets_dir.take() {
+                    self.forget_last_preset_if_dir_changes(&path);
+                    self.is_busy = true;
+                    self.busy_label = "Downloading presets".to_string();
+                    self.show_dialog = true;
+                    self.dialog_progress = 0.0;
+                    self.dialog_start = Some(Instant::now());
+                    self.log.push(format!("Downloading presets into {:?}...", path));
+                    // Путь сохраняется в обработчике PresetsDownloaded после успешной загрузки
+                    return self.install_presets_into(path);
+                }
+            }
+            Msg::CancelPresetsFolderChange => {
+                self.pending_presets_dir = None;
+                if self.presets_dir.is_none() {
+                    self.setup_incomplete = Some("No presets folder was selected.".to_string());
+                }
+            }
+            Msg::DeleteStaleTempFiles => {
+                self.delete_stale_temp_files();
+            }
+            Msg::KeepStaleTempFiles => {
+                self.stale_temp_files.clear();
+            }
+            Msg::CancelOperation => {
+                self.request_cancel();
+            }
+            Msg::CancelScan => {
+                if let Some(cancel) = self.scan_cancel.take() {
+                    cancel.store(true, Ordering::Relaxed);
+                    // Результат отмененного сканирования будет проигнорирован
+                    self.begin_operation();
+                    self.is_busy = false;
+                    self.show_dialog = false;
+                    self.log.push(SCAN_CANCELLED.to_string());
+                    if !self.presets_initialized {
+                        self.setup_incomplete = Some("Scanning the presets folder was cancelled.".to_string());
+                    }
+                }
+            }
+            Msg::ShowAbout => {
+                self.overlay = Some(Overlay::About);
+            }
+            Msg::CloseAbout => {
+                if matches!(self.overlay, Some(Overlay::About)) {
+                    self.overlay = None;
+                }
+            }
+            Msg::ShowSettings => {
+                self.presets_url_input = self.settings.presets_zip_url.clone();
+                self.presets_url_error = None;
+                self.overlay = Some(Overlay::Settings);
+            }
+            Msg::CloseSettings => {
+                if matches!(self.overlay, Some(Overlay::Settings)) {
+                    self.overlay = None;
+                }
+            }
+            Msg::SettingsChanged(settings) => {
+                self.settings = settings;
+                self.save_settings();
+            }
+            Msg::ShowFieldValue(field_id) => {
+                self.overlay = Some(Overlay::FieldValue { field_id });
+            }
+            Msg::CloseFieldValue => {
+                if matches!(self.overlay, Some(Overlay::FieldValue { .. })) {
+                    self.overlay = None;
+                }
+            }
+            Msg::CopyDiagnostics => {
+                return iced::clipboard::write(self.diagnostics_text());
+            }
+            Msg::UpdateCheckToggled(enabled) => {
+                self.settings.check_for_updates = enabled;
+                self.save_settings();
+                return self.start_update_check_if_due();
+            }
+            Msg::UpdateCheckTick => {
+                return self.start_update_check_if_due();
+            }
+            Msg::UpdateCheckFinished(result) => {
+                self.update_check_running = false;
+                // Сетевые ошибки не показываются: проверка обновлений не должна мешать работе
+                if let Ok(Some(release)) = result {
+                    self.available_update = Some(release);
+                }
+            }
+            Msg::OpenReleasePage => {
+                if let Some(ref release) = self.available_update {
+                    if let Err(e) = open_url(&release.url) {
+                        self.log.push(format!("Warning: Failed to open release page: {}", e));
+                    }
+                }
+            }
+            Msg::DismissUpdateBanner => {
+                self.available_update = None;
+            }
+            Msg::OpenPresetHomepage => {
+                let homepage = self.preset_config.as_ref().and_then(|config| config.metadata.homepage.as_deref());
+                if let Some(url) = homepage {
+                    if let Err(e) = open_url(url) {
+                        self.log.push(format!("Warning: Failed to open preset homepage: {}", e));
+                    }
+                }
+            }
+            Msg::ToggleMenu(kind) => {
+                self.open_menu = if self.open_menu == Some(kind) { None } else { Some(kind) };
+            }
+            Msg::MenuSelected(entry) => {
+                return self.handle_menu_entry(entry);
+            }
+            #[cfg(feature = "automation")]
+            Msg::Automation(event) => {
+                return self.handle_automation(event);
+            }
+            Msg::TrayPoll => {
+                if !self.tray_checked {
+                    // Иконка создается при первом опросе - цикл событий уже запущен
+                    self.tray_checked = true;
+                    self.tray = Tray::start();
+                    if self.tray.is_none() && cfg!(feature = "tray") {
+                        self.log.push("System tray is not available, tray icon disabled".to_string());
+                    }
+                }
+                let actions = self.tray.as_ref().map(Tray::poll).unwrap_or_default();
+                let commands = actions.into_iter()
+                    .map(|action| self.handle_tray_action(action))
+                    .collect::<Vec<_>>();
+                return Command::batch(commands);
+            }
+            Msg::FileDropped(path) => {
+                if self.is_busy {
+                    self.log.push(format!("Ignored dropped item while busy: {:?}", path));
+                    return Command::none();
+                }
+                // Каждый элемент приходит отдельным событием - собрать их и обработать вместе
+                self.dropped_paths.push(path);
+                if self.dropped_paths.len() == 1 {
+                    return Command::perform(tokio::time::sleep(DROP_BATCH_DELAY), |_| Msg::ProcessDroppedFiles);
+                }
+            }
+            Msg::ProcessDroppedFiles => {
+                let dropped = std::mem::take(&mut self.dropped_paths);
+                let Some(index) = dropped.iter().position(|p| p.is_dir()) else {
+                    self.log.push(format!("Ignored {} dropped file(s): only folders can be used", dropped.len()));
+                    return self.show_toast("Drop a folder to set the destination");
+                };
+                if dropped.len() > 1 {
+                    self.log.push(format!(
+                        "Ignored {} other dropped item(s), using the first folder",
+                        dropped.len() - 1
+                    ));
+                }
+                let dir = dropped[index].clone();
+                // Директория с files_config.json похожа на пресет - спросить, что с ней делать
+                if self.presets_dir.is_some() && dir.join("files_config.json").is_file() {
+                    self.dropped_preset_dir = Some(dir);
+                    return Command::none();
+                }
+                return self.set_destination(dir);
+            }
+            Msg::ImportDroppedPreset => {
+                if let Some(source) = self.dropped_preset_dir.take() {
+                    return self.check_import(source);
+                }
+            }
+            Msg::ShowImportPreset => {
+                self.pending_import = None;
+                self.import_prompt = true;
+            }
+            Msg::BrowseImportFolder => return self.browse_import(false),
+            Msg::BrowseImportArchive => return self.browse_import(true),
+            Msg::CancelImport => {
+                self.import_prompt = false;
+                self.pending_import = None;
+            }
+            Msg::ImportSourcePicked(picked) => {
+                if let Some(source) = picked {
+                    return self.check_import(source);
+                }
+            }
+            Msg::ImportChecked(source, result) => {
+                match result {
+                    Ok(preset_id) => {
+                        let exists = self.import_target_dir()
+                            .is_some_and(|dir| dir.join(&preset_id).exists());
+                        if exists {
+                            // Пресет с таким идентификатором уже установлен - спросить перед заменой
+                            self.pending_import = Some((source, preset_id));
+                            return Command::none();
+                        }
+                        return self.run_import(source, false);
+                    }
+                    Err(e) => return self.handle_message(Msg::PresetImported(Err(e))),
+                }
+            }
+            Msg::ConfirmImportOverwrite => {
+                if let Some((source, _)) = self.pending_import.take() {
+                    return self.run_import(source, true);
+                }
+            }
+            Msg::UseDroppedAsDestination => {
+                if let Some(dir) = self.dropped_preset_dir.take() {
+                    return self.set_destination(dir);
+                }
+            }
+            Msg::DestinationInputChanged(value) => {
+                self.destination_input = value;
+                // Проверка пишет пробный файл, поэтому выполняется после паузы во вводе
+                self.destination_check_id += 1;
+                let id = self.destination_check_id;
+                return Command::perform(tokio::time::sleep(TARGET_CHECK_DEBOUNCE), move |_| Msg::CheckDestination(id));
+            }
+            Msg::CheckDestination(id) => {
+                if id == self.destination_check_id {
+                    return self.apply_destination_input();
+                }
+            }
+            Msg::BrowseDestination => {
+                if self.is_busy {
+                    return Command::none();
+                }
+                let start_dir = self.destination_dir.clone()
+                    .or_else(|| std::env::current_dir().ok())
+                    .unwrap_or_else(|| PathBuf::from("."));
+                return Command::perform(self.services.picker.pick_folder(start_dir), Msg::DestinationPicked);
+            }
+            Msg::DestinationPicked(picked) => {
+                if let Some(dir) = picked {
+                    return self.set_destination(dir);
+                }
+            }
+            Msg::CancelDroppedFolder => {
+                self.dropped_preset_dir = None;
+            }
+            Msg::PresetImported(result) => {
+                match result {
+                    Ok(preset_id) => {
+                        self.log.push(format!("Preset '{}' imported", preset_id));
+                        self.finish_log_operation(Outcome::Succeeded);
+                        let toast = self.show_toast(format!("Preset '{}' imported", preset_id));
+                        // Выбрать импортированный пресет, когда список обновится
+                        self.requested_preset = Some(preset_id);
+                        let rescan = self.rescan_presets();
+                        return Command::batch(vec![toast, rescan]);
+                    }
+                    Err(e) => {
+                        self.log.push(format!("Error importing preset: {}", e));
+                        self.finish_log_operation(Outcome::Failed);
+                        self.overlay = Some(Overlay::Error(ErrorDialog::new(
+                            "Failed to import preset",
+                            e,
+                            Some("Check files_config.json of the imported folder or archive."),
+                        )));
+                    }
+                }
+            }
+            Msg::ToastExpired(id) => {
+                if id == self.toast_id {
+                    self.toast = None;
+                }
+            }
+            Msg::MinimizeToTrayToggled(enabled) => {
+                self.settings.minimize_to_tray = enabled;
+                self.save_settings();
+            }
+            Msg::NotificationsToggled(enabled) => {
+                self.settings.notifications = enabled;
+                self.save_settings();
+            }
+            Msg::ProjectLogToggled(enabled) => {
+                self.settings.save_project_log = enabled;
+                self.save_settings();
+            }
+            Msg::CloudSyncWarningToggled(enabled) => {
+                self.settings.warn_cloud_sync = enabled;
+                self.save_settings();
+            }
+            Msg::PresetsUrlChanged(url) => {
+                self.presets_url_input = url;
+                self.presets_url_error = None;
+            }
+            Msg::SavePresetsUrl => {
+                let url = self.presets_url_input.trim().to_string();
+                match check_presets_zip_url(&url) {
+                    Ok(()) if url == self.settings.presets_zip_url => {}
+                    Ok(()) => {
+                        self.settings.presets_zip_url = url;
+                        self.save_settings();
+                        self.log.push(format!("Presets source changed to {}", self.settings.presets_zip_url));
+                        return self.show_toast("Presets source saved; use Refresh presets to download");
+                    }
+                    Err(e) => self.presets_url_error = Some(e),
+                }
+            }
+            Msg::ResetPresetsUrl => {
+                self.presets_url_input = PRESETS_ZIP_URL.to_string();
+                return self.handle_message(Msg::SavePresetsUrl);
+            }
+            Msg::RevealPresetsFolder => {
+                if let Some(ref dir) = self.presets_dir {
+                    if let Err(e) = open_in_file_manager(dir) {
+                        self.log.push(format!("Warning: Failed to open folder: {}", e));
+                    }
+                }
+            }
+            Msg::NewProject => {
+                self.last_report = None;
+                self.project_name.clear();
+                self.project_name_error.clear();
+                self.target_check_id += 1;
+                self.target_status = None;
+            }
+            Msg::CheckTargetDir(id) => {
+                if let Some(destination) = self.destination_dir.as_ref().filter(|_| id == self.target_check_id) {
+                    let path = destination.join(&self.project_name);
+                    return Command::perform(async move {
+                        target_dir_status(&path)
+                    }, move |status| Msg::TargetDirChecked(id, status));
+                }
+            }
+            Msg::TargetDirChecked(id, status) => {
+                if id == self.target_check_id {
+                    self.target_status = Some(status);
+                }
+            }
+            Msg::ThemeSelected(choice) => {
+                self.settings.theme = choice;
+                self.save_settings();
+                if choice == ThemeChoice::System {
+                    return detect_system_theme();
+                }
+            }
+            Msg::UiScaleSelected(UiScale(scale)) => {
+                // Применяется сразу через `scale_factor`, перезапуск не нужен
+                self.settings.ui_scale = scale;
+                self.save_settings();
+            }
+            Msg::LanguageSelected(language) => {
+                // Строки интерфейса берутся из таблицы при следующей отрисовке;
+                // открытый предпросмотр README перестраивается с новым заголовком
+                self.settings.language = language;
+                set_language(language);
+                self.save_settings();
+                if self.readme_preview.is_some() {
+                    self.readme_preview = self.render_readme_preview();
+                }
+            }
+            Msg::PresetSortSelected(order) => {
+                self.settings.preset_sort = order;
+                self.save_settings();
+                self.sort_presets();
+            }
+            Msg::PresetSearchChanged(search) => {
+                self.preset_search = search;
+            }
+            Msg::TagSelected(tag) => {
+                self.selected_tag = tag;
+                // Выбранный пресет скрыт фильтром - сбросить выбор
+                if let Some(ref current) = self.selected_preset {
+                    if !self.visible_presets().0.contains(current) {
+                        return self.handle_message(Msg::PresetSelected(None));
+                    }
+                }
+            }
+            Msg::ToggleTiming => {
+                self.show_timing = !self.show_timing;
+            }
+            Msg::ToggleLogSection(id) => {
+                if !self.collapsed_log_sections.remove(&id) {
+                    self.collapsed_log_sections.insert(id);
+                }
+            }
+            Msg::ToggleReadmePreview => {
+                self.readme_preview = match self.readme_preview {
+                    Some(_) => None,
+                    None => self.render_readme_preview(),
+                };
+            }
+            Msg::RefreshReadmePreview(id) => {
+                if id == self.readme_preview_id && self.readme_preview.is_some() {
+                    self.readme_preview = self.render_readme_preview();
+                }
+            }
+            Msg::FocusNext(backwards) => {
+                if self.overlay.is_some() {
+                    return Command::none();
+                }
+                return Command::widget(iced::advanced::widget::operation::focusable::find_focused())
+                    .map(move |id| Msg::FocusFound(id, backwards));
+            }
+            Msg::FocusFound(current, backwards) => {
+                let order = self.focus_order();
+                let position = order.iter()
+                    .position(|id| iced::advanced::widget::Id::from(id.clone()) == current);
+                if let Some(index) = next_focus_index(order.len(), position, backwards) {
+                    return text_input::focus(order[index].clone());
+                }
+            }
+            Msg::WindowUnfocused => {
+                self.window_focused = false;
+            }
+            Msg::WindowFocused => {
+                self.window_focused = true;
+                // Директории недавних проектов могли удалить, пока окно было неактивно
+                let recent_check = if self.show_recent_projects {
+                    self.check_recent_projects()
+                } else {
+                    Command::none()
+                };
+                // Тема ОС могла смениться, пока окно было неактивно
+                if self.settings.theme == ThemeChoice::System {
+                    return Command::batch(vec![recent_check, detect_system_theme()]);
+                }
+                return recent_check;
+            }
+            Msg::SystemThemeDetected(dark) => {
+                self.system_dark = dark;
+            }
+            Msg::ResetForm => {
+                if self.is_busy {
+                    return Command::none();
+                }
+                self.last_report = None;
+                self.project_name.clear();
+                self.project_name_error.clear();
+                self.target_check_id += 1;
+                self.target_status = None;
+                // Поля пресета возвращаются к значениям по умолчанию
+                self.dynamic_fields.clear();
+                self.field_errors.clear();
+                self.dynamic_options.clear();
+                if let Some(ref config) = self.preset_config {
+                    apply_field_defaults(config, &mut self.dynamic_fields);
+                    for opt in &config.options {
+                        self.dynamic_options.insert(opt.id.clone(), opt.default);
+                    }
+                }
+                return self.schedule_readme_preview();
+            }
+            Msg::CloseRequested => {
+                if self.settings.minimize_to_tray && self.tray.is_some() {
+                    return window::change_mode(window::Id::MAIN, window::Mode::Hidden);
+                }
+                if self.is_busy {
+                    // Не закрывать окно посреди операции без подтверждения
+                    self.confirm_quit = true;
+                } else {
+                    return self.close_window();
+                }
+            }
+            Msg::QuitConfirmed => {
+                self.confirm_quit = false;
+                if self.is_busy {
+                    // Окно закроется в `update`, когда операция откатит изменения и вернет CANCELLED
+                    self.quit_when_idle = true;
+                    if self.operation_cancel.is_some() {
+                        self.log.push("Quit requested: cancelling the running operation...".to_string());
+                        self.request_cancel();
+                    } else {
+                        self.log.push("Quit requested: waiting for the running operation to finish...".to_string());
+                    }
+                } else {
+                    return self.close_window();
+                }
+            }
+            Msg::QuitCancelled => {
+                self.confirm_quit = false;
+            }
+            Msg::WindowResized(width, height) => {
+                self.window_width = width;
+                self.settings.window_size = Some((width, height));
+            }
+            Msg::ErrorDismissed => {
+                if matches!(self.overlay, Some(Overlay::Error(_))) {
+                    self.overlay = None;
+                }
+            }
+            Msg::CopyErrorDetails => {
+                if let Some(Overlay::Error(ref dialog)) = self.overlay {
+                    return iced::clipboard::write(dialog.details_text());
+                }
+            }
+            Msg::DownloadProgress(received, total) => {
+                if self.is_busy {
+                    self.progress_label = Some(match total {
+                        Some(total) => format!("Downloading… {} of {}", format_bytes(received), format_bytes(total)),
+                        None => format!("Downloading… {}", format_bytes(received)),
+                    });
+                    let fraction = total.map(|total| (received as f32 / total as f32).min(1.0));
+                    if let Some(ref mut plan) = self.operation {
+                        // Общий прогресс известен и без размера архива: этап стоит на месте
+                        plan.report(PhaseKind::Download, fraction.unwrap_or(0.0));
+                        self.progress_indeterminate = false;
+                        self.dialog_progress = plan.progress();
+                    } else {
+                        self.progress_indeterminate = total.is_none();
+                        if let Some(fraction) = fraction {
+                            self.dialog_progress = fraction;
+                        }
+                    }
+                    self.show_dialog = true;
+                }
+            }
+            Msg::ExtractProgress(done, total) => {
+                if self.is_busy {
+                    self.progress_label = Some(format!("Extracting… ({}/{})", done, total));
+                    self.progress_indeterminate = false;
+                    self.dialog_progress = done as f32 / total.max(1) as f32;
+                    if let Some(ref mut plan) = self.operation {
+                        plan.report(PhaseKind::Extract, self.dialog_progress);
+                        self.dialog_progress = plan.progress();
+                    }
+                    self.show_dialog = true;
+                }
+            }
+            Msg::CreateProgress(step, total, message) => {
+                if self.is_busy {
+                    self.progress_label = Some(format!("{} ({}/{})", message, step, total));
+                    self.dialog_progress = step as f32 / total.max(1) as f32;
+                    if let Some(ref mut plan) = self.operation {
+                        plan.report_step(step);
+                        self.dialog_progress = plan.progress();
+                    }
+                }
+            }
+            Msg::Tick => {
+                // Диалог закрывается только после завершения операции; min_busy_ms -
+                // минимальное время показа, чтобы быстрая операция не мелькала
+                let Some(start) = self.dialog_start else { return Command::none() };
+                let elapsed = start.elapsed().as_millis() as f32;
+                let total = self.settings.min_busy_ms as f32;
+                if !self.is_busy {
+                    self.dialog_progress = 1.0;
+                    if elapsed >= total {
+                        self.show_dialog = false;
+                        self.dialog_start = None;
+                        self.dialog_progress = 0.0;
+                        self.progress_label = None;
+                        self.progress_indeterminate = false;
+                        self.operation = None;
+                    }
+                } else if self.progress_indeterminate {
+                    // Размер неизвестен: полоса проходит по кругу раз в секунду
+                    self.dialog_progress = (elapsed % 1000.0) / 1000.0;
+                } else if self.progress_label.is_none() && self.operation.is_none() {
+                    // Операция без реального прогресса: анимация не доходит до конца
+                    self.dialog_progress = (elapsed / total).clamp(0.0, 0.9);
+                }
+            }
+        }
+        Command::none()
+    }
+}
+
+impl Application for AppState {
+    type Executor = iced::executor::Default;
+    type Message = Msg;
+    type Theme = Theme;
+    type Flags = LaunchOptions;
+
+    fn new(flags: LaunchOptions) -> (Self, Command<Self::Message>) {
+        Self::with_services(flags, Services::default())
+    }
+
+    /// Заголовок окна приложения
+    fn title(&self) -> String { 
+        "Project Creator".into() 
+    }
+    
+    /// Тема оформления приложения
+    ///
+    /// Ручной выбор Dark/Light имеет приоритет, System следует теме ОС.
+    fn theme(&self) -> Theme { 
+        match self.settings.theme {
+            ThemeChoice::Dark => Theme::Dark,
+            ThemeChoice::Light => Theme::Light,
+            ThemeChoice::System if self.system_dark => Theme::Dark,
+            ThemeChoice::System => Theme::Light,
+        }
+    }
+
+    /// Масштаб интерфейса
+    ///
+    /// Масштабирует весь интерфейс (размеры текста и фиксированные ширины виджетов)
+    /// одинаково, поэтому подписи не обрезаются при увеличении.
+    fn scale_factor(&self) -> f64 {
+        self.settings.ui_scale as f64
+    }
+
+    /// Подписка на периодические события
+    ///
+    /// Используется для обновления прогресс-бара диалога во время выполнения операций.
+    /// Обновление происходит каждые 50 мс пока активен диалог.
+    /// Также перехватывает запросы закрытия окна (см. `exit_on_close_request` в `main`).
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let tick = if self.show_dialog {
+            iced::time::every(std::time::Duration::from_millis(50)).map(|_| Msg::Tick)
+        } else {
+            Subscription::none()
+        };
+        
+        let window_events = event::listen_with(|event, _status| match event {
+            Event::Window(_, window::Event::CloseRequested) => Some(Msg::CloseRequested),
+            Event::Window(_, window::Event::Resized { width, height }) => {
+                Some(Msg::WindowResized(width as f32, height as f32))
+            }
+            Event::Window(_, window::Event::FileDropped(path)) => Some(Msg::FileDropped(path)),
+            Event::Window(_, window::Event::Focused) => Some(Msg::WindowFocused),
+            Event::Window(_, window::Event::Unfocused) => Some(Msg::WindowUnfocused),
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(keyboard::key::Named::Tab),
+                modifiers,
+                ..
+            }) => Some(Msg::FocusNext(modifiers.shift())),
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                MenuEntry::from_key(&key, modifiers).map(Msg::MenuSelected)
+            }
+            _ => None,
+        });
+        
+        // Раз в час проверять, не прошли ли сутки с последней проверки обновлений
+        let update_tick = if self.settings.check_for_updates {
+            iced::time::every(std::time::Duration::from_secs(3600)).map(|_| Msg::UpdateCheckTick)
+        } else {
+            Subscription::none()
+        };
+        
+        // Меню трея опрашивается, пока иконка есть (или еще не проверена доступность трея)
+        let tray_poll = if !self.tray_checked || self.tray.is_some() {
+            iced::time::every(std::time::Duration::from_millis(250)).map(|_| Msg::TrayPoll)
+        } else {
+            Subscription::none()
+        };
+        
+        #[cfg(feature = "automation")]
+        let automation = match self.automation {
+            Some(ref server) => server.subscription().map(Msg::Automation),
+            None => Subscription::none(),
+        };
+        #[cfg(not(feature = "automation"))]
+        let automation = Subscription::none();
+        
+        Subscription::batch(vec![tick, window_events, update_tick, tray_poll, automation])
+    }
+
+    /// Обработать сообщение и обновить состояние приложения
+    ///
+    /// Это центральная функция паттерна MVU. Она обрабатывает все события пользователя
+    /// и асинхронные операции, возвращая команды для выполнения дополнительных действий.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - сообщение для обработки
+    ///
+    /// # Returns
+    ///
+    /// Команда для выполнения асинхронных операций или `Command::none()` если синхронной обработки достаточно
+    fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        let command = self.handle_message(message);
+        self.sync_textareas();
+        // Выход подтвержден во время операции - закрыть окно, как только она завершится
+        if self.quit_when_idle && !self.is_busy {
+            return self.close_window();
+        }
+        if let Some(ref tray) = self.tray {
+            let tooltip = self.current_tray_tooltip();
+            if tooltip != self.tray_tooltip {
+                tray.set_tooltip(&tooltip);
+                self.tray_tooltip = tooltip;
+            }
+        }
+        command
+    }
+
+    /// Построить UI представление текущего состояния
+    ///
+    /// Создает иерархию виджетов Iced на основе текущего состояния приложения.
+    /// UI динамически адаптируется в зависимости от выбранного пресета.
+    ///
+    /// # Returns
+    ///
+    /// Корневой элемент UI дерева
+    fn view(&self) -> Element<'_, Self::Message> {
+        // Модальный слой заменяет форму, пока пользователь его не закроет;
+        // индикатор выполняющейся операции остается виден под ним
+        if let Some(ref overlay) = self.overlay {
+            let content = match overlay {
+                Overlay::Error(dialog) => view_error_dialog(dialog),
+                Overlay::About => self.view_about(),
+                Overlay::Settings => self.view_settings(),
+                Overlay::FieldValue { field_id } => self.view_field_value(field_id),
+            };
+            return column![content, self.view_busy_dialog()].padding(10).into();
+        }
+        // Во время создания проекта и сканирования форма недоступна
+        if self.show_dialog || self.scan_cancel.is_some() {
+            return self.view_busy_modal();
+        }
+        
+        // Выбор пресета - показываем человекочитаемые имена
+        let preset_selector: Element<Msg> = if !self.available_presets.is_empty() {
+            // Создать копию данных для использования в замыкании (только видимые пресеты,
+            // чтобы индекс имени совпадал с индексом идентификатора)
+            let (presets_ids, preset_display_names) = self.visible_presets();
+            
+            pick_list(
+                preset_display_names.clone(),
+                self.selected_preset_display_name.as_ref(),
+                move |display_name: String| {
+                    // Найти ID по индексу отображаемого имени
+                    let idx = preset_display_names.iter()
+                        .position(|name| name == &display_name)
+                        .unwrap_or(0);
+                    
+                    // Получим ID по индексу
+                    let preset_id = if idx < presets_ids.len() {
+                        presets_ids[idx].clone()
+                    } else {
+                        display_name.clone() // fallback
+                    };
+                    
+                    Msg::PresetSelected(Some(preset_id))
+                },
+            )
+            .width(Length::Fill)
+            .into()
+        } else {
+            text(tr("preset.none_available")).size(12).into()
+        };
+        
+        // Поиск и категории пресетов (теги из metadata); первый пункт - без фильтра
+        let category_row: Element<Msg> = if self.available_presets.is_empty() {
+            column![].into()
+        } else {
+            let search = text_input(tr("preset.search"), &self.preset_search)
+                .on_input(Msg::PresetSearchChanged)
+                .size(12)
+                .width(Length::Fill);
+            let mut filters = row![
+                text(tr("preset.find")).width(Length::Fixed(LABEL_WIDTH)).size(12),
+                container(search).width(Length::Fill).max_width(MAX_INPUT_WIDTH),
+            ].spacing(6).align_items(iced::Alignment::Center);
+            if !self.available_tags.is_empty() {
+                let categories: Vec<CategoryFilter> = std::iter::once(None)
+                    .chain(self.available_tags.iter().cloned().map(Some))
+                    .map(CategoryFilter)
+                    .collect();
+                let selected = CategoryFilter(self.selected_tag.clone());
+                filters = filters
+                    .push(text(tr("preset.category")).size(12))
+                    .push(pick_list(categories, Some(selected), |category: CategoryFilter| Msg::TagSelected(category.0)));
+            }
+            filters.into()
+        };
+        
+        // Метка происхождения выбранного пресета
+        let origin_tag: Element<Msg> = match self.selected_preset.as_ref().and_then(|id| self.preset_origins.get(id)) {
+            Some(PresetOrigin::Imported { path }) => tooltip(
+                text(tr("origin.imported")).size(11),
+                text(trf("preset.imported_from", &[&path.display()])).size(11),
+                tooltip::Position::Bottom,
+            ).into(),
+            Some(origin) => text(origin_label(origin)).size(11).into(),
+            None => column![].into(),
+        };
+        
+        // Автор, версия и теги выбранного пресета
+        let preset_info: Element<Msg> = match (self.selected_preset.as_ref(), self.preset_config.as_ref()) {
+            (Some(_), Some(config)) => {
+                let metadata = &config.metadata;
+                let mut info = row![].spacing(8).align_items(iced::Alignment::Center);
+                if let Some(summary) = metadata.summary() {
+                    info = info.push(text(summary).size(11));
+                }
+                if !metadata.tags.is_empty() {
+                    info = info.push(text(metadata.tags.join(", ")).size(11));
+                }
+                if metadata.homepage.is_some() {
+                    info = info.push(button(text(tr("preset.homepage")).size(11))
+                        .style(theme::Button::Text)
+                        .padding(0)
+                        .on_press(Msg::OpenPresetHomepage));
+                }
+                if let Some(required) = metadata.unsupported_app_version(env!("CARGO_PKG_VERSION")) {
+                    info = info.push(text(trf(
+                        "preset.requires_version", &[&required]
+                    )).size(11).style(Color::from_rgb(0.9, 0.75, 0.2)));
+                }
+                info = info.push(button(text(tr("preset.validate")).size(11)).on_press(Msg::ValidatePreset));
+                if self.selected_preset.as_ref().is_some_and(|id| self.saved_answers.get(id).is_some()) {
+                    info = info.push(button(text(tr("preset.clear_saved")).size(11)).on_press(Msg::ClearSavedAnswers));
+                }
+                row![Space::with_width(Length::Fixed(LABEL_WIDTH)), info].spacing(6).into()
+            }
+            (Some(_), None) => row![
+                Space::with_width(Length::Fixed(LABEL_WIDTH)),
+                button(text(tr("preset.validate")).size(11)).on_press(Msg::ValidatePreset),
+            ].spacing(6).into(),
+            (None, _) => column![].into(),
+        };
+
+        // Описание выбранного пресета и директории, которые будут созданы
+        let preset_description: Element<Msg> = match self.preset_config {
+            _ if self.selected_preset.is_none() => column![].into(),
+            _ if self.preset_loading => row![
+                Space::with_width(Length::Fixed(LABEL_WIDTH)),
+                text(tr("preset.loading")).size(11),
+            ].spacing(6).into(),
+            Some(ref config) => {
+                let mut details = column![].spacing(2);
+                if !config.description.trim().is_empty() {
+                    details = details.push(container(text(config.description.trim()).size(12))
+                        .max_width(DESCRIPTION_WIDTH));
+                }
+                if !config.directories.is_empty() {
+                    details = details.push(button(text(format!(
+                        "{} {}",
+                        if self.show_project_structure { "▾" } else { "▸" },
+                        trf("preset.structure", &[&config.directories.len()])
+                    )).size(11))
+                    .style(theme::Button::Text)
+                    .padding(0)
+                    .on_press(Msg::ToggleProjectStructure));
+                    if self.show_project_structure {
+                        let mut folders = column![].spacing(1).padding([0, 0, 0, 14]);
+                        for directory in &config.directories {
+                            folders = folders.push(text(format!("{}/", directory)).size(11));
+                        }
+                        details = details.push(folders);
+                    }
+                }
+                row![Space::with_width(Length::Fixed(LABEL_WIDTH)), details].spacing(6).into()
+            }
+            None => column![].into(),
+        };
+        
+        // Кнопка обновления списка пресетов
+        let refresh_presets_btn: Element<Msg> = if self.presets_read_only {
+            tooltip(
+                button(tr("preset.refresh")).width(Length::Fixed(120.0)),
+                text(tr("preset.read_only")).size(11),
+                tooltip::Position::Bottom,
+            ).into()
+        } else {
+            button(tr("preset.refresh"))
+                .on_press(Msg::RefreshPresets)
+                .width(Length::Fixed(120.0))
+                .into()
+        };
+        
+        let import_preset_btn = button(tr("preset.import"))
+            .on_press_maybe(self.menu_entry_enabled(MenuEntry::ImportPreset).then_some(Msg::ShowImportPreset));
+        
+        let change_folder_btn = if self.is_busy {
+            button(tr("preset.change_folder"))
+        } else {
+            button(tr("preset.change_folder")).on_press(Msg::ChangePresetsFolder)
+        };
+        
+        let setup_prompt: Element<Msg> = match self.setup_incomplete {
+            Some(ref reason) => container(
+                column![
+                    text(tr("setup.incomplete")).size(12),
+                    text(reason).size(11),
+                    button(tr("setup.choose_folder")).on_press_maybe((!self.is_busy).then_some(Msg::RetrySetup)),
+                ].spacing(4)
+            ).padding(8).style(theme::Container::Box).into(),
+            None => column![].into(),
+        };
+
+        let folder_prompt: Element<Msg> = match self.pending_presets_dir {
+            Some((ref path, ref scan)) => {
+                let count = scan.presets.len();
+                let (message, actions) = if count > 0 {
+                    (
+                        trf("folder.found", &[&count, &path.display()]),
+                        row![
+                            button(tr("folder.use_existing")).on_press(Msg::UseExistingPresets),
+                            button(tr("folder.download_fresh")).on_press(Msg::DownloadPresetsHere),
+                            button(tr("common.cancel")).on_press(Msg::CancelPresetsFolderChange),
+                        ],
+                    )
+                } else {
+                    (
+                        trf("folder.none_found", &[&path.display()]),
+                        row![
+                            button(tr("folder.download")).on_press(Msg::DownloadPresetsHere),
+                            button(tr("common.cancel")).on_press(Msg::CancelPresetsFolderChange),
+                        ],
+                    )
+                };
+                let mut prompt = column![text(message).size(12)].spacing(4);
+                if scan.looks_like_wrong_folder() {
+                    prompt = prompt.push(text(trf(
+                        "folder.wrong", &[&scan.non_preset_entries]
+                    )).size(11).style(Color::from_rgb(0.9, 0.75, 0.2)));
+                }
+                container(prompt.push(actions.spacing(6)))
+                    .padding(8).style(theme::Container::Box).into()
+            }
+            None => column![].into(),
+        };
+        
+        let placeholder = if self.settings.last_project_name.is_empty() {
+            tr("name.placeholder").to_string()
+        } else {
+            trf("name.placeholder_last", &[&self.settings.last_project_name])
+        };
+        let name = text_input(&placeholder, &self.project_name)
+            .id(project_name_input_id())
+            .on_input(Msg::NameChanged)
+            .width(Length::Fill);
+        let name_err: Element<Msg> = if !self.project_name_error.is_empty() {
+            text(&self.project_name_error).size(11).into()
+        } else if let Some(status) = self.target_status {
+            let force = self.dynamic_options.get("force").copied().unwrap_or(false);
+            let hint = match status {
+                TargetDirStatus::Missing => text(tr("target.will_create")).style(Color::from_rgb(0.3, 0.8, 0.4)),
+                TargetDirStatus::Empty => text(tr("target.empty")).style(Color::from_rgb(0.9, 0.75, 0.2)),
+                TargetDirStatus::NotEmpty => text(tr("target.not_empty")).style(Color::from_rgb(0.9, 0.3, 0.3)),
+            };
+            if status == TargetDirStatus::NotEmpty {
+                row![
+                    hint.size(11),
+                    checkbox(tr("target.overwrite"), force)
+                        .size(14)
+                        .text_size(11)
+                        .on_toggle(|enabled| Msg::OptionToggled("force".to_string(), enabled)),
+                ].spacing(8).into()
+            } else {
+                hint.size(11).into()
+            }
+        } else {
+            container(text("")).height(Length::Fixed(0.0)).width(Length::Shrink).into()
+        };
+
+        // Динамические поля из конфига пресета
+        let mut dynamic_fields_vec: Vec<Element<Msg>> = Vec::new();
+        if let Some(ref config) = self.preset_config {
+            for field in &config.fields {
+                // Поле, зависящее от выключенной опции, скрыто
+                if !field_visible(config, field, &self.dynamic_options) {
+                    continue;
+                }
+                let field_value = self.dynamic_fields.get(&field.id).cloned().unwrap_or_default();
+                let field_widget: Element<Msg> = match field.field_type.as_str() {
+                    "multi_select" if field.options.is_some() => {
+                        // Группа флажков: значение поля - выбранные опции через разделитель
+                        let selected = field.selected_values(&field_value);
+                        let mut group = Row::new().spacing(10);
+                        for option in field.options.iter().flatten() {
+                            let (field_id, option_value) = (field.id.clone(), option.clone());
+                            group = group.push(
+                                checkbox(option, selected.contains(&option.as_str()))
+                                    .size(14)
+                                    .text_size(12)
+                                    .on_toggle(move |enabled| Msg::MultiSelectToggled(field_id.clone(), option_value.clone(), enabled)),
+                            );
+                        }
+                        column![text(&field.label).size(12), group].spacing(3).into()
+                    }
+                    "textarea" => {
+                        // Высота по числу видимых строк, длинный текст прокручивается внутри
+                        let rows = f32::from(field.textarea_rows());
+                        let editor: Element<Msg> = match self.textareas.get(&field.id) {
+                            Some(content) => {
+                                let field_id = field.id.clone();
+                                text_editor(content)
+                                    .height(rows * TEXTAREA_ROW_HEIGHT + 10.0)
+                                    .on_action(move |action| Msg::TextAreaAction(field_id.clone(), action))
+                                    .into()
+                            }
+                            None => Space::with_height(Length::Fixed(rows * TEXTAREA_ROW_HEIGHT + 10.0)).into(),
+                        };
+                        column![text(&field.label).size(12), editor].spacing(3).into()
+                    }
+                    "select" => {
+                        if let Some(ref options) = field.options {
+                            let field_id_clone = field.id.clone();
+                            let field_value_clone = field_value.clone();
+                            pick_list(
+                                &options[..],
+                                if field_value_clone.is_empty() { None } else { Some(field_value_clone.clone()) },
+                                move |val| Msg::FieldChanged(field_id_clone.clone(), val.clone()),
+                            )
+                            .width(Length::Fill)
+                            .into()
+                        } else {
+                            text_input(&field.label, &field_value)
+                                .id(field_input_id(&field.id))
+                                .on_input(move |val| Msg::FieldChanged(field.id.clone(), val))
+                                .width(Length::Fill)
+                                .into()
+                        }
+                    }
+                    _ => {
+                        text_input(&field.label, &field_value)
+                            .id(field_input_id(&field.id))
+                            .on_input(move |val| Msg::FieldChanged(field.id.clone(), val))
+                            .width(Length::Fill)
+                            .into()
+                    }
+                };
+                // Незаполненное обязательное поле помечается справа
+                let field_widget: Element<Msg> = if field.required && !field.is_filled(self.dynamic_fields.get(&field.id)) {
+                    row![
+                        field_widget,
+                        text(tr("fields.required")).size(10).style(Color::from_rgb(0.9, 0.3, 0.3)),
+                    ].spacing(4).align_items(iced::Alignment::Center).into()
+                } else {
+                    field_widget
+                };
+                let mut field_column = column![field_widget].spacing(2);
+                if let Some(error) = self.field_errors.get(&field.id) {
+                    field_column = field_column.push(text(error).size(11).style(Color::from_rgb(0.9, 0.3, 0.3)));
+                }
+                // Значение с `transform` подставляется преобразованным - показать его
+                if field.transform.is_some() && !field_value.trim().is_empty() {
+                    let substituted = field.substituted_value(&field_value);
+                    if substituted != field_value {
+                        field_column = field_column.push(
+                            text(format!("→ {}", substituted)).size(11).style(Color::from_rgb(0.5, 0.5, 0.5)),
+                        );
+                    }
+                }
+                // Длинные значения: счетчик символов и просмотр целиком
+                let char_count = field_value.chars().count();
+                if (is_text_field(field) || field.is_textarea()) && char_count >= FIELD_COUNTER_MIN_CHARS {
+                    field_column = field_column.push(row![
+                        text(trf("common.characters", &[&char_count])).size(11),
+                        button(text(tr("common.view")).size(11))
+                            .style(theme::Button::Text)
+                            .padding(0)
+                            .on_press(Msg::ShowFieldValue(field.id.clone())),
+                    ].spacing(6));
+                }
+                // Описание переносится по ширине колонки
+                if let Some(ref description) = field.description {
+                    field_column = field_column.push(text(description).size(11));
+                }
+                dynamic_fields_vec.push(
+                    container(field_column).width(Length::Fill).max_width(MAX_INPUT_WIDTH).into()
+                );
+            }
+        }
+        let dynamic_fields_empty = dynamic_fields_vec.is_empty();
+        let dynamic_fields = if !dynamic_fields_empty {
+            // В широком окне поля раскладываются по строкам из нескольких колонок
+            // Ширина окна приходит в логических пикселях без учета масштаба интерфейса
+            let columns = field_columns(self.window_width / self.settings.ui_scale);
+            let mut col = column![];
+            let mut pending: Vec<Element<Msg>> = Vec::new();
+            for widget in dynamic_fields_vec {
+                pending.push(widget);
+                if pending.len() == columns {
+                    col = col.push(Row::with_children(std::mem::take(&mut pending)).spacing(8));
+                }
+            }
+            if !pending.is_empty() {
+                col = col.push(Row::with_children(pending).spacing(8));
+            }
+            col.spacing(4)
+        } else {
+            column![]
+        };
+
+        // Динамические опции из конфига пресета
+        let mut dynamic_opts_vec: Vec<Element<Msg>> = Vec::new();
+        if let Some(ref config) = self.preset_config {
+            for opt in &config.options {
+                // Зависимая опция показывается, только пока включена управляющая
+                if !option_visible(config, &opt.id, &self.dynamic_options) {
+                    continue;
+                }
+                let opt_enabled = self.dynamic_options.get(&opt.id).copied().unwrap_or(opt.default);
+                let opt_msg = opt.id.clone();
+                let option_checkbox = checkbox(&opt.label, opt_enabled)
+                    .on_toggle(move |v| Msg::OptionToggled(opt_msg.clone(), v));
+                // Метка рядом с опцией, значение которой расходится с рекомендацией автора
+                let option_checkbox: Element<Msg> = match opt.recommended {
+                    Some(recommended) if recommended != opt_enabled => row![
+                        option_checkbox,
+                        text(tr(if recommended { "options.recommended_on" } else { "options.recommended_off" }))
+                            .size(10)
+                            .style(Color::from_rgb(0.3, 0.6, 0.9)),
+                    ].spacing(4).align_items(iced::Alignment::Center).into(),
+                    _ => option_checkbox.into(),
+                };
+                let option_widget: Element<Msg> = match opt.description {
+                    Some(ref description) => column![
+                        option_checkbox,
+                        container(text(description).size(11))
+                            .padding([0, 0, 0, 26])
+                            .max_width(MAX_INPUT_WIDTH * 1.5),
+                    ].spacing(2).into(),
+                    None => option_checkbox,
+                };
+                // Зависимые опции сдвинуты под управляющую
+                dynamic_opts_vec.push(if opt.depends_on.is_some() {
+                    container(option_widget).padding([0, 0, 0, 26]).into()
+                } else {
+                    option_widget
+                });
+            }
+            if self.recommended_settings_differ() {
+                dynamic_opts_vec.push(
+                    button(text(tr("options.apply_recommended")).size(12))
+                        .on_press(Msg::ApplyRecommendedSettings)
+                        .style(theme::Button::Secondary)
+                        .into(),
+                );
+            }
+        }
+        // Встроенная опция, не зависящая от пресета
+        dynamic_opts_vec.push(
+            checkbox(tr("options.git_init"), self.settings.init_git)
+                .on_toggle(Msg::GitInitToggled)
+                .into(),
+        );
+        let dynamic_opts_empty = dynamic_opts_vec.is_empty();
+        let dynamic_opts = if !dynamic_opts_empty {
+            let mut col = column![];
+            for widget in dynamic_opts_vec {
+                col = col.push(widget);
+            }
+            col.spacing(3)
+        } else {
+            column![]
+        };
+
+        let temp_files_prompt: Element<Msg> = if self.stale_temp_files.is_empty() {
+            column![].into()
+        } else {
+            let total: u64 = self.stale_temp_files.iter().map(|(_, bytes)| bytes).sum();
+            container(
+                column![
+                    text(trf(
+                        "temp.found",
+                        &[&self.stale_temp_files.len(), &format_bytes(total)]
+                    )).size(12),
+                    row![
+                        button(tr("common.delete")).on_press(Msg::DeleteStaleTempFiles),
+                        button(tr("common.keep")).on_press(Msg::KeepStaleTempFiles),
+                    ].spacing(6),
+                ].spacing(4)
+            ).padding(8).style(theme::Container::Box).into()
+        };
+
+        // Поврежденные пресеты, найденные Verify presets
+        let repair_prompt: Element<Msg> = match self.pending_repair {
+            Some(ref presets) => container(
+                column![
+                    text(trf(
+                        "repair.prompt",
+                        &[&presets.len(), &presets.iter().cloned().collect::<Vec<_>>().join(", ")]
+                    )).size(12),
+                    row![
+                        button(tr("repair.repair")).on_press(Msg::RepairPresets),
+                        button(tr("repair.not_now")).on_press(Msg::SkipRepair),
+                    ].spacing(6),
+                ].spacing(4)
+            ).padding(8).style(theme::Container::Box).into(),
+            None => column![].into(),
+        };
+
+        let destination = tooltip(
+            text_input(tr("destination.placeholder"), &self.destination_input)
+                .on_input(Msg::DestinationInputChanged)
+                .width(Length::Fill),
+            text(tr("destination.tooltip")).size(11),
+            tooltip::Position::Bottom,
+        );
+        let destination_err: Element<Msg> = match self.destination_error {
+            Some(ref reason) => text(reason).size(11).style(Color::from_rgb(0.9, 0.3, 0.3)).into(),
+            None => column![].into(),
+        };
+        let browse_destination_btn = if self.is_busy {
+            button(tr("destination.browse"))
+        } else {
+            button(tr("destination.browse")).on_press(Msg::BrowseDestination)
+        };
+
+        let cloud_warning: Element<Msg> = match self.destination_cloud_sync {
+            Some(ref sync) if self.settings.warn_cloud_sync => {
+                text(format!("⚠ {}", sync.warning())).size(11).style(Color::from_rgb(0.9, 0.75, 0.2)).into()
+            }
+            _ => column![].into(),
+        };
+
+        // Причины, по которым кнопка Create неактивна (панель скрыта, если их нет)
+        let problems = self.problems();
+        let problems_panel: Element<Msg> = if problems.is_empty() {
+            column![].into()
+        } else {
+            let mut list = column![].spacing(2);
+            for problem in &problems {
+                list = list.push(text(format!("• {}", problem)).size(11).style(Color::from_rgb(0.9, 0.75, 0.2)));
+            }
+            container(list).padding(6).style(theme::Container::Box).into()
+        };
+
+        // Если не хватает только обязательных полей, нажатие Create подсвечивает их
+        let only_missing_fields = problems.iter().all(|p| matches!(p, Problem::MissingRequiredField { .. }));
+        let create_btn = if only_missing_fields {
+            button(tr("form.create")).on_press(Msg::Create)
+                .width(Length::Fixed(130.0))
+        } else {
+            button(tr("form.create")).width(Length::Fixed(130.0))
+        };
+        
+        let preview_btn = if only_missing_fields {
+            button(tr("form.preview")).on_press(Msg::Preview)
+        } else {
+            button(tr("form.preview"))
+        };
+        
+        let reset_btn = if self.is_busy {
+            button(tr("form.reset"))
+        } else {
+            button(tr("form.reset")).on_press(Msg::ResetForm)
+        };
+
+        let log = scrollable(self.view_log_sections())
+            .height(Length::Fixed(80.0));
+
+        // Замеры последнего создания свернуты в одну строку под логом
+        let timing_section: Element<Msg> = match self.last_report {
+            Some(ref report) if !report.timing.phases.is_empty() => {
+                let slowest = report.timing.slowest_phase()
+                    .map(|phase| trf("timing.slowest", &[&phase.name, &format!("{:.2}", phase.duration.as_secs_f32())]))
+                    .unwrap_or_default();
+                let header = button(text(format!(
+                    "{} {}{}",
+                    if self.show_timing { "▾" } else { "▸" },
+                    tr("timing.header"),
+                    slowest
+                )).size(11))
+                .style(theme::Button::Text)
+                .padding(0)
+                .on_press(Msg::ToggleTiming);
+                if self.show_timing {
+                    column![header, text(report.timing.log_lines().join("\n")).size(11)]
+                        .spacing(2)
+                        .into()
+                } else {
+                    header.into()
+                }
+            }
+            _ => column![].into(),
+        };
+
+        let quit_prompt = self.view_quit_prompt();
+
+        let import_prompt: Element<Msg> = match (self.import_prompt, &self.pending_import) {
+            (_, Some((source, preset_id))) => container(
+                column![
+                    text(trf(
+                        "import.replace_prompt", &[preset_id, &source.display()]
+                    )).size(12),
+                    row![
+                        button(tr("import.replace")).on_press(Msg::ConfirmImportOverwrite),
+                        button(tr("common.cancel")).on_press(Msg::CancelImport),
+                    ].spacing(6),
+                ].spacing(4)
+            ).padding(8).style(theme::Container::Box).into(),
+            (true, None) => container(
+                column![
+                    text(tr("import.prompt")).size(12),
+                    row![
+                        button(tr("import.folder")).on_press(Msg::BrowseImportFolder),
+                        button(tr("import.zip")).on_press(Msg::BrowseImportArchive),
+                        button(tr("common.cancel")).on_press(Msg::CancelImport),
+                    ].spacing(6),
+                ].spacing(4)
+            ).padding(8).style(theme::Container::Box).into(),
+            (false, None) => column![].into(),
+        };
+
+        let dropped_prompt: Element<Msg> = match self.dropped_preset_dir {
+            Some(ref dir) => container(
+                column![
+                    text(trf("drop.prompt", &[&dir.display()])).size(12),
+                    row![
+                        button(tr("drop.import")).on_press(Msg::ImportDroppedPreset),
+                        button(tr("drop.destination")).on_press(Msg::UseDroppedAsDestination),
+                        button(tr("common.cancel")).on_press(Msg::CancelDroppedFolder),
+                    ].spacing(6),
+                ].spacing(4)
+            ).padding(8).style(theme::Container::Box).into(),
+            None => column![].into(),
+        };
+
+        let toast: Element<Msg> = match self.toast {
+            Some(ref message) => container(text(message).size(12))
+                .padding(6)
+                .style(theme::Container::Box)
+                .into(),
+            None => column![].into(),
+        };
+
+        let preview_toggle = if self.preset_config.as_ref().is_some_and(|c| c.generates_readme()) {
+            checkbox(tr("readme.preview"), self.readme_preview.is_some())
+                .on_toggle(|_| Msg::ToggleReadmePreview)
+        } else {
+            checkbox(tr("readme.preview"), false)
+        };
+        let readme_preview: Element<Msg> = match self.readme_preview {
+            Some(ref readme) => container(
+                scrollable(markdown::view(readme, 12.0)).height(Length::Fixed(200.0))
+            )
+            .padding(8)
+            .width(Length::Fill)
+            .style(theme::Container::Box)
+            .into(),
+            None => column![].into(),
+        };
+
+        let large_files_prompt: Element<Msg> = match self.pending_large_plan {
+            Some(ref plan) => {
+                let threshold = self.settings.large_file_threshold();
+                let limit = self.settings.project_size_limit();
+                let mut content = column![].spacing(4);
+                // Превышен лимит размера - показать самые большие файлы, иначе только большие
+                let listed = if plan.projected_bytes() > limit {
+                    content = content.push(text(trf(
+                        "large.over_limit",
+                        &[&format_bytes(plan.projected_bytes()), &format_bytes(limit)]
+                    )).size(12));
+                    plan.largest_files(5)
+                } else {
+                    content = content.push(text(trf(
+                        "large.files",
+                        &[&plan.files.len(), &format_bytes(plan.projected_bytes())]
+                    )).size(12));
+                    plan.large_files(threshold)
+                };
+                for file in listed {
+                    let mode = tr(if file.link { "large.hard_link" } else { "large.copy" });
+                    content = content.push(text(format!(
+                        "⚠ {} — {} ({})", file.destination.display(), format_bytes(file.bytes), mode
+                    )).size(11));
+                }
+                for (first, second) in &plan.case_collisions {
+                    content = content.push(text(trf(
+                        "large.case", &[first, second]
+                    )).size(11));
+                }
+                content = content.push(row![
+                    button(tr("large.create_anyway")).on_press(Msg::ConfirmLargeCreate),
+                    button(tr("common.cancel")).on_press(Msg::CancelLargeCreate),
+                ].spacing(6));
+                container(content).padding(8).style(theme::Container::Box).into()
+            }
+            None => column![].into(),
+        };
+
+        // Конфликты регистра видны сразу после выбора пресета, до создания проекта
+        let case_warning: Element<Msg> = match self.preset_config {
+            Some(ref config) => {
+                let collisions = case_collisions(config);
+                if collisions.is_empty() {
+                    column![].into()
+                } else {
+                    let pairs: Vec<String> = collisions.iter()
+                        .map(|(first, second)| format!("'{}' / '{}'", first, second))
+                        .collect();
+                    text(trf("case.warning", &[&pairs.join(", ")]))
+                    .size(11)
+                    .style(Color::from_rgb(0.9, 0.75, 0.2))
+                    .into()
+                }
+            }
+            None => column![].into(),
+        };
+
+        let success_panel: Element<Msg> = match self.last_report {
+            Some(ref report) => view_success_panel(report, self.show_missing_templates, self.is_busy),
+            None => column![].into(),
+        };
+
+        // Поля и опции прокручиваются, чтобы узкое или низкое окно не обрезало форму
+        let form = scrollable(column![
+            if !dynamic_fields_empty {
+                column![
+                    text(tr("fields.title")).size(12),
+                    dynamic_fields,
+                ].spacing(3)
+            } else {
+                column![]
+            },
+            if !dynamic_opts_empty {
+                column![
+                    text(tr("options.title")).size(12),
+                    dynamic_opts,
+                ].spacing(3)
+            } else {
+                column![]
+            },
+        ].spacing(6))
+        .height(Length::Fill);
+
+        let update_banner: Element<Msg> = match self.available_update {
+            Some(ref release) => view_update_banner(release),
+            None => column![].into(),
+        };
+
+        container(column![
+            self.view_menu_bar(),
+            text(tr("app.title")).size(16),
+            update_banner,
+            category_row,
+            row![ 
+                text(tr("preset.label")).width(Length::Fixed(LABEL_WIDTH)).size(12), 
+                container(preset_selector).width(Length::Fill).max_width(MAX_INPUT_WIDTH),
+                origin_tag,
+                refresh_presets_btn,
+                import_preset_btn,
+                change_folder_btn,
+            ].spacing(6),
+            preset_info,
+            preset_description,
+            case_warning,
+            setup_prompt,
+            folder_prompt,
+            temp_files_prompt,
+            repair_prompt,
+            problems_panel,
+            row![ 
+                text(tr("name.label")).width(Length::Fixed(LABEL_WIDTH)).size(12), 
+                column![name, name_err].spacing(2).width(Length::Fill).max_width(MAX_INPUT_WIDTH),
+                create_btn,
+                preview_btn,
+                reset_btn,
+            ].spacing(6),
+            row![
+                Space::with_width(Length::Fixed(LABEL_WIDTH)),
+                checkbox(tr("options.open_in_editor"), self.settings.open_in_editor_when_done)
+                    .size(14)
+                    .text_size(12)
+                    .on_toggle(Msg::OpenInEditorToggled),
+            ].spacing(6),
+            row![
+                text(tr("destination.label")).width(Length::Fixed(LABEL_WIDTH)).size(12),
+                column![destination, destination_err].spacing(2).width(Length::Fill).max_width(MAX_INPUT_WIDTH),
+                browse_destination_btn,
+            ].spacing(6),
+            cloud_warning,
+            dropped_prompt,
+            import_prompt,
+            large_files_prompt,
+            toast,
+            success_panel,
+            form,
+            preview_toggle,
+            readme_preview,
+            self.view_recent_projects(),
+            quit_prompt,
+            text(tr("app.log")).size(12),
+            log,
+            row![
+                button(text(tr("log.save")).size(11)).on_press(Msg::SaveLog),
+                button(text(tr("log.copy")).size(11))
+                    .on_press_maybe((!self.log.is_empty()).then_some(Msg::CopyLog)),
+                Space::with_width(Length::Fill),
+                button(text(tr("log.copy_last_error")).size(11))
+                    .on_press_maybe(self.log.last_error().is_some().then_some(Msg::CopyLastError)),
+            ].spacing(6),
+            timing_section,
+            self.view_status_bar(),
+        ].spacing(6).padding(10).height(Length::Fill))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+/// Построить панель успешного создания проекта
+///
+/// Все данные берутся из `CreationReport` последней операции.
+fn view_success_panel(report: &CreationReport, show_missing: bool, busy: bool) -> Element<'_, Msg> {
+    let warnings = if report.warnings.is_empty() {
+        tr("success.no_warnings").to_string()
+    } else {
+        trf("success.warnings", &[&report.warnings.len()])
+    };
+    
+    // Пропущенные шаблоны - не просто строка лога: их можно докопировать после исправления пресета
+    let missing_templates: Element<Msg> = if report.missing_templates.is_empty() {
+        column![].into()
+    } else {
+        let mut banner = column![
+            row![
+                text(trf("success.missing", &[&report.missing_templates.len()])).size(12).style(Color::from_rgb(0.9, 0.75, 0.2)),
+                button(text(tr(if show_missing { "success.hide_details" } else { "success.view_details" })).size(11))
+                    .style(theme::Button::Text)
+                    .padding(0)
+                    .on_press(Msg::ToggleMissingTemplates),
+            ].spacing(8).align_items(iced::Alignment::Center),
+        ].spacing(2);
+        if show_missing {
+            for missing in &report.missing_templates {
+                banner = banner.push(text(format!("• {} → {}", missing.source, missing.destination)).size(11));
+            }
+        }
+        let rerun = button(text(tr("success.rerun")).size(12));
+        banner.push(if busy { rerun } else { rerun.on_press(Msg::RerunMissingTemplates) }).into()
+    };
+    
+    container(
+        column![
+            text(trf("success.created", &[&report.project_name])).size(14),
+            text(report.project_path.display().to_string()).size(11),
+            text(trf(
+                "success.summary",
+                &[
+                    &format!("{:.1}", report.duration.as_secs_f32()),
+                    &report.files.len(),
+                    &format_bytes(report.total_bytes),
+                    &warnings,
+                ]
+            )).size(11),
+            missing_templates,
+            row![
+                button(tr("success.open_folder")).on_press(Msg::OpenProjectFolder),
+                button(tr("success.copy_path")).on_press(Msg::CopyProjectPath),
+                button(tr("success.new_project")).on_press(Msg::NewProject),
+            ]
+            .spacing(6),
+        ]
+        .spacing(4)
+    )
+    .padding(8)
+    .style(theme::Container::Box)
+    .into()
+}
+
+/// Метка происхождения пресета рядом со списком выбора
+fn origin_label(origin: &PresetOrigin) -> &'static str {
+    match origin {
+        PresetOrigin::Managed => tr("origin.managed"),
+        PresetOrigin::User => tr("origin.user"),
+        PresetOrigin::Imported { .. } => tr("origin.imported"),
+        PresetOrigin::Unknown => tr("origin.unknown"),
+    }
+}
+
+/// Обрезать строку посередине до `max` символов, сохранив начало и конец
+///
+/// Для путей важнее всего корень и имя последней директории.
+fn truncate_middle(value: &str, max: usize) -> String {
+    let len = value.chars().count();
+    if len <= max || max < 3 {
+        return value.to_string();
+    }
+    let head = (max - 1) / 2;
+    let tail = max - 1 - head;
+    let start: String = value.chars().take(head).collect();
+    let end: String = value.chars().skip(len - tail).collect();
+    format!("{}…{}", start, end)
+}
+
+/// Сообщение о прогрессе загрузки или распаковки архива пресетов
+fn download_progress_msg(step: DownloadProgress) -> Msg {
+    match step {
+        DownloadProgress::Downloading { received, total } => Msg::DownloadProgress(received, total),
+        DownloadProgress::Extracting { done, total } => Msg::ExtractProgress(done, total),
+    }
+}
+
+/// Открыть директорию в системном файловом менеджере
+///
+/// - **Windows**: `explorer`
+/// - **macOS**: `open`
+/// - **Linux**: `xdg-open`
+fn open_in_file_manager(path: &std::path::Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+    
+    std::process::Command::new(program)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run {}: {}", program, e))
+}
+
+/// Определить, включена ли в ОС темная тема
+///
+/// Если определить тему не удалось, считается, что она темная.
+fn detect_system_dark() -> bool {
+    !matches!(dark_light::detect(), dark_light::Mode::Light)
+}
+
+/// Определить тему ОС в фоне (на Linux запрос идет через D-Bus)
+fn detect_system_theme() -> Command<Msg> {
+    Command::perform(async { detect_system_dark() }, Msg::SystemThemeDetected)
+}
+
+/// Идентификатор поля имени проекта (для перевода фокуса)
+fn project_name_input_id() -> text_input::Id {
+    text_input::Id::new("project_name")
+}
+
+/// Идентификатор текстового поля пресета (стабилен, выводится из id поля)
+fn field_input_id(field_id: &str) -> text_input::Id {
+    text_input::Id::new(format!("field:{}", field_id))
+}
+
+/// Отображается ли поле пресета как однострочное текстовое поле ввода (`text_input`)
+///
+/// Поля `"select"` и `"multi_select"` без списка опций и поля неизвестного типа
+/// показываются как текстовые поля; `"textarea"` - многострочный `text_editor`.
+fn is_text_field(field: &FieldConfig) -> bool {
+    !field.is_textarea()
+        && !((field.field_type == "select" || field.is_multi_select()) && field.options.is_some())
+}
+
+/// Значение поля "textarea" из содержимого редактора
+///
+/// `Content::text()` всегда завершает текст переводом строки - он не входит в значение.
+fn textarea_value(content: &text_editor::Content) -> String {
+    let mut value = content.text();
+    if value.ends_with('\n') {
+        value.pop();
+    }
+    value
+}
+
+/// Размер файла или суммарный размер файлов директории (ошибки чтения не учитываются)
+fn disk_usage(path: &std::path::Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else { return 0 };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| disk_usage(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Индекс поля, на которое переходит фокус по Tab
+///
+/// # Arguments
+///
+/// * `len` - количество полей в порядке обхода
+/// * `current` - индекс поля с фокусом (`None` - фокус вне формы)
+/// * `backwards` - обход в обратном направлении (Shift+Tab)
+///
+/// # Returns
+///
+/// Индекс следующего поля с переходом по кругу или `None`, если полей нет
+fn next_focus_index(len: usize, current: Option<usize>, backwards: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(match (current, backwards) {
+        (None, false) => 0,
+        (None, true) => len - 1,
+        (Some(i), false) => (i + 1) % len,
+        (Some(i), true) => (i + len - 1) % len,
+    })
+}
+
+/// Показать окно (в том числе скрытое в трей) и перевести на него фокус
+fn show_window() -> Command<Msg> {
+    Command::batch(vec![
+        window::change_mode(window::Id::MAIN, window::Mode::Windowed),
+        window::minimize(window::Id::MAIN, false),
+        window::gain_focus(window::Id::MAIN),
+    ])
+}
+
+/// Открыть URL в браузере по умолчанию
+fn open_url(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let (program, args) = ("cmd", vec!["/C", "start", "", url]);
+    #[cfg(target_os = "macos")]
+    let (program, args) = ("open", vec![url]);
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let (program, args) = ("xdg-open", vec![url]);
+    
+    std::process::Command::new(program)
+        .args(args)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run {}: {}", program, e))
+}
+
+/// Построить баннер о доступной новой версии приложения
+///
+/// Баннер не блокирует форму: его можно скрыть или не замечать.
+fn view_update_banner(release: &ReleaseInfo) -> Element<'_, Msg> {
+    container(
+        row![
+            text(trf(
+                "update.available",
+                &[&release.name, &env!("CARGO_PKG_VERSION"), &release.version]
+            )).size(12).width(Length::Fill),
+            button(tr("update.open")).on_press(Msg::OpenReleasePage),
+            button(tr("update.dismiss")).style(theme::Button::Text).on_press(Msg::DismissUpdateBanner),
+        ]
+        .spacing(6)
+    )
+    .padding(8)
+    .style(theme::Container::Box)
+    .into()
+}
+
+/// Построить модальный диалог ошибки
+fn view_error_dialog(dialog: &ErrorDialog) -> Element<'_, Msg> {
+    let mut content = column![
+        text(&dialog.title).size(16),
+        scrollable(text(&dialog.detail).size(12)).height(Length::Shrink),
+    ]
+    .spacing(8)
+    .max_width(MAX_INPUT_WIDTH);
+    
+    if let Some(ref suggestion) = dialog.suggestion {
+        content = content.push(text(suggestion).size(12));
+    }
+    
+    content = content.push(
+        row![
+            button(tr("error.copy_details")).on_press(Msg::CopyErrorDetails),
+            button("Close").on_press(Msg::ErrorDismissed),
+        ]
+        .spacing(6)
+    );
+    
+    container(container(content).padding(12).style(theme::Container::Box))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+}
+
+/// Запустить графический интерфейс
+///
+/// `args` - аргументы командной строки без подкоманды: параметры запуска
+/// (см. `cli::parse_launch_args`) и `--automation-port`.
+pub fn run(mut args: Vec<String>) -> iced::Result {
+    // Сервер автоматизации: порт из флага, токен выводится для тестового стенда
+    #[cfg(feature = "automation")]
+    let automation = match automation::take_port_flag(&mut args)
+        .and_then(|port| port.map(AutomationServer::new).transpose())
+    {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    };
+    #[cfg(not(feature = "automation"))]
+    let _ = &mut args;
+    
+    #[cfg_attr(not(feature = "automation"), allow(unused_mut))]
+    let mut launch = match crate::cli::parse_launch_args(&args) {
+        Ok(launch) => launch,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    };
+    #[cfg(feature = "automation")]
+    {
+        if let Some(ref server) = automation {
+            println!("Automation server on 127.0.0.1:{} (token: {})", server.port, server.token);
+        }
+        launch.automation = automation;
+    }
+    
+    // Размер окна из прошлой сессии
+    let size = AppSettings::load().window_size
+        .map(|(width, height)| iced::Size::new(width, height))
+        .unwrap_or(window::Settings::default().size);
+    
+    // Закрытие окна обрабатывается в `update`, чтобы не прерывать операцию молча
+    AppState::run(Settings {
+        window: window::Settings {
+            size,
+            exit_on_close_request: false,
+            ..window::Settings::default()
+        },
+        ..Settings::with_flags(launch)
+    })
+}
+
+/// Событие, о котором сообщает системное уведомление
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NotificationEvent {
+    /// Проект создан
+    CreationSucceeded { project_name: String },
+    /// Создание проекта завершилось ошибкой
+    CreationFailed { project_name: String },
+    /// Пресеты обновлены (`changed` - число добавленных, измененных и удаленных)
+    PresetsUpdated { changed: usize },
+    /// Обновление пресетов завершилось ошибкой
+    PresetsUpdateFailed { error: String },
+}
+
+impl NotificationEvent {
+    /// Заголовок уведомления
+    fn summary(&self) -> &'static str {
+        match self {
+            NotificationEvent::CreationSucceeded { .. } => tr("notify.created"),
+            NotificationEvent::CreationFailed { .. } => tr("notify.failed"),
+            NotificationEvent::PresetsUpdated { .. } => tr("notify.presets_updated"),
+            NotificationEvent::PresetsUpdateFailed { .. } => tr("notify.presets_failed"),
+        }
+    }
+
+    /// Текст уведомления
+    fn body(&self) -> String {
+        match self {
+            NotificationEvent::CreationSucceeded { project_name } => {
+                trf("notify.created_body", &[project_name])
+            }
+            NotificationEvent::CreationFailed { project_name } => {
+                trf("notify.failed_body", &[project_name])
+            }
+            NotificationEvent::PresetsUpdated { changed: 0 } => tr("notify.up_to_date").to_string(),
+            NotificationEvent::PresetsUpdated { changed: 1 } => tr("notify.one_changed").to_string(),
+            NotificationEvent::PresetsUpdated { changed } => trf("notify.changed", &[changed]),
+            NotificationEvent::PresetsUpdateFailed { error } => trf("notify.presets_failed_body", &[error]),
+        }
+    }
+}
+
+/// Показать системное уведомление о событии
+///
+/// Использует кроссплатформенную библиотеку `notify-rust` для показа
+/// системных уведомлений с автоматической поддержкой звуков.
+/// Настройка `notifications` проверяется в `AppState::notify`.
+///
+/// # Платформенные особенности
+///
+/// - **Windows**: Toast уведомление в правом нижнем углу с системным звуком
+/// - **macOS**: Уведомление в Центре уведомлений (Notification Center) со звуком
+/// - **Linux**: Desktop Notification через DBus со звуком (требует сервер уведомлений)
+///
+/// # Note
+///
+/// Ошибки показа уведомлений логируются в stderr, но не прерывают работу приложения.
+/// На macOS может потребоваться разрешение на уведомления в системных настройках.
+fn show_notification(event: &NotificationEvent) {
+    let notification = Notification::new()
+        .summary(event.summary())
+        .body(&event.body())
+        .appname("AI Project Template")
+        .finalize();
+    
+    // Попытка показать уведомление
+    // На Windows: покажет всплывающее уведомление с системным звуком
+    // На macOS: покажет уведомление в Центре уведомлений со звуком
+    // На Linux: покажет уведомление через DBus со звуком
+    // Игнорируем ошибки если система не поддерживает уведомления
+    if let Err(e) = notification.show() {
+        eprintln!("Failed to show notification: {}", e);
+        // На macOS может потребоваться разрешение на уведомления в системных настройках
+        // На Linux должен быть установлен сервер уведомлений (например, notify-osd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced_runtime::command::Action;
+    use crate::services::{BoxFuture, FolderPicker, PresetFetcher, ProgressFn};
+    use ai_project_template::settings::NetworkSettings;
+    use std::collections::VecDeque;
+    use std::fs;
+    use std::path::Path;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    /// Тесты меняют `HOME` процесса и поэтому выполняются по одному
+    static HOME_LOCK: Mutex<()> = Mutex::new(());
+
+    thread_local! {
+        /// Среда выполнения теста с остановленным временем (у каждого теста свой поток)
+        static RUNTIME: &'static tokio::runtime::Runtime = Box::leak(Box::new(
+            tokio::runtime::Builder::new_current_thread()
+                .enable_time()
+                .start_paused(true)
+                .build()
+                .unwrap(),
+        ));
+    }
+
+    /// Блокировка `HOME` и вход в среду выполнения теста: таймеры (debounce, уведомления)
+    /// создаются прямо в `update()`
+    struct TestGuard {
+        _context: tokio::runtime::EnterGuard<'static>,
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    /// Пустой `HOME` во временной директории: настройки и путь к пресетам не берутся из системы
+    fn isolated_home(name: &str) -> (TestGuard, PathBuf) {
+        let lock = HOME_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let guard = TestGuard { _context: RUNTIME.with(|runtime| runtime.enter()), _lock: lock };
+        let home = std::env::temp_dir()
+            .join(format!("ai_project_template_gui_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+        std::env::set_var("HOME", &home);
+        std::env::remove_var(PRESETS_PATH_ENV_VAR);
+        (guard, home)
+    }
+
+    /// Выполнить `step` и его команды: результаты задач возвращаются в `update()`,
+    /// пока команды не закончатся
+    ///
+    /// Потоки прогресса и действия с окном пропускаются; таймеры (debounce, уведомления)
+    /// срабатывают сразу благодаря остановленному времени.
+    fn run(state: &mut AppState, step: impl FnOnce(&mut AppState) -> Command<Msg>) {
+        let runtime = RUNTIME.with(|runtime| *runtime);
+        let mut pending: VecDeque<_> = step(state).actions().into();
+        while let Some(action) = pending.pop_front() {
+            if let Action::Future(future) = action {
+                let message = runtime.block_on(future);
+                pending.extend(state.update(message).actions());
+            }
+        }
+    }
+
+    /// Диалог выбора папки с заранее заданными ответами (по одному на вызов)
+    #[derive(Default)]
+    struct MockPicker {
+        answers: Mutex<VecDeque<Option<PathBuf>>>,
+    }
+
+    impl MockPicker {
+        fn answering(answers: impl IntoIterator<Item = Option<PathBuf>>) -> Arc<Self> {
+            Arc::new(Self { answers: Mutex::new(answers.into_iter().collect()) })
+        }
+    }
+
+    impl FolderPicker for MockPicker {
+        fn pick_folder(&self, _start_dir: PathBuf) -> BoxFuture<Option<PathBuf>> {
+            let answer = self.answers.lock().unwrap().pop_front().expect("unexpected folder dialog");
+            Box::pin(async move { answer })
+        }
+
+        fn save_file(&self, _start_dir: PathBuf, _file_name: String) -> BoxFuture<Option<PathBuf>> {
+            Box::pin(async { None })
+        }
+
+        fn pick_file(&self, _start_dir: PathBuf, _filter_name: String, _extensions: Vec<String>) -> BoxFuture<Option<PathBuf>> {
+            Box::pin(async { None })
+        }
+    }
+
+    /// Источник пресетов с заранее заданными результатами; при успехе создает пресет `demo`
+    #[derive(Default)]
+    struct MockFetcher {
+        results: Mutex<VecDeque<Result<(), String>>>,
+        targets: Mutex<Vec<PathBuf>>,
+    }
+
+    impl MockFetcher {
+        fn returning(results: impl IntoIterator<Item = Result<(), String>>) -> Arc<Self> {
+            Arc::new(Self { results: Mutex::new(results.into_iter().collect()), ..Self::default() })
+        }
+
+        fn targets(&self) -> Vec<PathBuf> {
+            self.targets.lock().unwrap().clone()
+        }
+    }
+
+    impl PresetFetcher for MockFetcher {
+        fn fetch(&self, target_dir: PathBuf, _zip_url: String, _network: NetworkSettings, _cancel: Arc<AtomicBool>, _progress: ProgressFn) -> BoxFuture<Result<(), String>> {
+            self.targets.lock().unwrap().push(target_dir.clone());
+            let result = self.results.lock().unwrap().pop_front().expect("unexpected download");
+            Box::pin(async move {
+                result?;
+                write_demo_preset(&target_dir);
+                Ok(())
+            })
+        }
+
+        fn repair(&self, _target_dir: PathBuf, _zip_url: String, _network: NetworkSettings, _presets: BTreeSet<String>, _cancel: Arc<AtomicBool>, _progress: ProgressFn) -> BoxFuture<Result<BTreeSet<String>, String>> {
+            Box::pin(async { Err("unexpected repair".to_string()) })
+        }
+    }
+
+    /// Пустая папка для пресетов (диалог выбора возвращает только существующие папки)
+    fn empty_folder(home: &Path) -> PathBuf {
+        let dir = home.join("presets");
+        fs::create_dir(&dir).unwrap();
+        dir
+    }
+
+    fn write_demo_preset(presets_dir: &Path) {
+        let preset_dir = presets_dir.join("demo");
+        fs::create_dir_all(&preset_dir).unwrap();
+        fs::write(preset_dir.join("main.rs"), "fn main() {}\n").unwrap();
+        fs::write(preset_dir.join("files_config.json"), r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src"],
+            "templates": [{"source": "main.rs", "destination": "src/main.rs"}],
+            "fields": [
+                {"id": "language", "label": "Language", "required": true, "type": "text"},
+                {"id": "license", "label": "License", "required": false, "type": "text", "default": "MIT"}
+            ],
+            "options": [{"id": "ci", "label": "CI", "default": false}]
+        }"#).unwrap();
+    }
+
+    /// Запустить приложение с подставными сервисами и выполнить команды запуска
+    fn start(picker: Arc<MockPicker>, fetcher: Arc<MockFetcher>) -> AppState {
+        let (mut state, command) = AppState::with_services(LaunchOptions::default(), Services { fetcher, picker });
+        state.settings.notifications = false;
+        run(&mut state, |_| command);
+        state
+    }
+
+    /// Приложение без пресетов; команды запуска (выбор папки) не выполняются
+    fn idle_state() -> AppState {
+        let services = Services { fetcher: MockFetcher::returning([]), picker: MockPicker::answering([None]) };
+        AppState::with_services(LaunchOptions::default(), services).0
+    }
+
+    /// Приложение с выбранным пресетом `demo` и заполненной формой: проект можно создавать
+    fn ready_state(home: &Path) -> AppState {
+        let presets_dir = empty_folder(home);
+        let mut state = start(MockPicker::answering([Some(presets_dir)]), MockFetcher::returning([Ok(())]));
+        let work = home.join("work");
+        fs::create_dir(&work).unwrap();
+        state.destination_dir = Some(work);
+        state.destination_error = None;
+        run(&mut state, |state| state.update(Msg::NameChanged("my_project".to_string())));
+        run(&mut state, |state| state.update(Msg::FieldChanged("language".to_string(), "Rust".to_string())));
+        assert_eq!(state.problems(), Vec::new());
+        state
+    }
+
+    fn closes_window(command: Command<Msg>) -> bool {
+        command.actions().into_iter()
+            .any(|action| matches!(action, Action::Window(iced_runtime::window::Action::Close(_))))
+    }
+
+    /// Первый запуск завершен: пресеты загружены в `presets_dir` и выбран пресет `demo`
+    fn assert_setup_complete(state: &AppState, presets_dir: &Path) {
+        assert_eq!(state.setup_incomplete, None);
+        assert_eq!(state.presets_dir.as_deref(), Some(presets_dir));
+        assert_eq!(state.available_presets, vec!["demo".to_string()]);
+        assert_eq!(state.selected_preset.as_deref(), Some("demo"));
+        assert_eq!(state.preset_config.as_ref().map(|config| config.name.as_str()), Some("Demo"));
+        assert!(!state.is_busy);
+        assert_eq!(AppSettings::load().presets_path().as_deref(), Some(presets_dir));
+    }
+
+    #[test]
+    fn first_run_downloads_and_selects_preset() {
+        let (_guard, home) = isolated_home("first_run");
+        let presets_dir = empty_folder(&home);
+        let fetcher = MockFetcher::returning([Ok(())]);
+
+        let state = start(MockPicker::answering([Some(presets_dir.clone())]), fetcher.clone());
+
+        assert_eq!(fetcher.targets(), vec![presets_dir.clone()]);
+        assert_setup_complete(&state, &presets_dir);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn first_run_picker_cancel_offers_retry() {
+        let (_guard, home) = isolated_home("picker_cancel");
+        let presets_dir = empty_folder(&home);
+        let fetcher = MockFetcher::returning([Ok(())]);
+
+        let mut state = start(MockPicker::answering([None, Some(presets_dir.clone())]), fetcher.clone());
+
+        assert_eq!(state.setup_incomplete.as_deref(), Some("No presets folder was selected."));
+        assert_eq!(state.presets_dir, None);
+        assert!(state.available_presets.is_empty());
+        assert!(fetcher.targets().is_empty());
+        assert_eq!(AppSettings::load().presets_path(), None);
+
+        run(&mut state, |state| state.update(Msg::RetrySetup));
+
+        assert_eq!(fetcher.targets(), vec![presets_dir.clone()]);
+        assert_setup_complete(&state, &presets_dir);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn first_run_fetch_failure_offers_retry() {
+        let (_guard, home) = isolated_home("fetch_failure");
+        let presets_dir = empty_folder(&home);
+        let fetcher = MockFetcher::returning([Err("connection refused".to_string()), Ok(())]);
+        let picker = MockPicker::answering([Some(presets_dir.clone()), Some(presets_dir.clone())]);
+
+        let mut state = start(picker, fetcher.clone());
+
+        assert_eq!(state.setup_incomplete.as_deref(), Some("Downloading presets failed: connection refused"));
+        assert!(matches!(state.overlay, Some(Overlay::Error(_))));
+        assert_eq!(state.presets_dir, None);
+        assert!(state.available_presets.is_empty());
+        assert!(!state.is_busy);
+        assert_eq!(AppSettings::load().presets_path(), None);
+
+        run(&mut state, |state| state.update(Msg::RetrySetup));
+
+        assert_eq!(fetcher.targets(), vec![presets_dir.clone(), presets_dir.clone()]);
+        assert_setup_complete(&state, &presets_dir);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn read_only_presets_disable_refresh_and_import_into_user_dir() {
+        let (_guard, home) = isolated_home("read_only_presets");
+        let presets_dir = empty_folder(&home);
+        write_demo_preset(&presets_dir);
+        // Пробный файл не записывается на месте директории (даже от root, для
+        // которого права доступа не мешают записи)
+        fs::create_dir(presets_dir.join(".ai_project_template_write_test")).unwrap();
+        let fetcher = MockFetcher::returning([]);
+
+        let mut state = start(MockPicker::answering([Some(presets_dir.clone())]), fetcher.clone());
+
+        assert!(state.presets_read_only);
+        assert_eq!(state.presets_dir.as_deref(), Some(presets_dir.as_path()));
+        assert_eq!(state.available_presets, vec!["demo".to_string()]);
+        assert!(state.log.to_text().contains("Presets folder is read-only: refresh is disabled"));
+
+        let generation = state.operation_generation;
+        run(&mut state, |state| state.update(Msg::RefreshPresets));
+        assert!(fetcher.targets().is_empty());
+        assert_eq!(state.operation_generation, generation);
+        assert!(!state.is_busy);
+        assert_eq!(state.log.last().map(String::as_str), Some("Presets folder is read-only, refresh skipped"));
+
+        // Импорт идет в пользовательскую директорию, а основная не меняется
+        let user_dir = state.settings.user_presets_path().unwrap();
+        assert_eq!(state.import_target_dir(), Some(user_dir.clone()));
+        let source = home.join("shared");
+        fs::create_dir(&source).unwrap();
+        fs::write(source.join("files_config.json"),
+            r#"{"preset_id": "shared", "preset_name": "Shared", "description": "", "directories": []}"#).unwrap();
+        run(&mut state, |state| state.update(Msg::ImportSourcePicked(Some(source))));
+
+        assert!(user_dir.join("shared").join("files_config.json").is_file());
+        assert!(!presets_dir.join("shared").exists());
+        assert_eq!(state.available_presets, vec!["demo".to_string(), "shared".to_string()]);
+        assert_eq!(state.selected_preset.as_deref(), Some("shared"));
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn quit_during_operation_cancels_it_and_closes_window() {
+        let (_guard, home) = isolated_home("quit_cancel");
+        let mut state = idle_state();
+        let generation = state.begin_operation();
+        let cancel = Arc::new(AtomicBool::new(false));
+        state.is_busy = true;
+        state.operation_cancel = Some(cancel.clone());
+
+        assert!(!closes_window(state.update(Msg::CloseRequested)));
+        assert!(state.confirm_quit);
+
+        assert!(!closes_window(state.update(Msg::QuitConfirmed)));
+        assert!(cancel.load(Ordering::Relaxed));
+        assert!(state.quit_when_idle);
+
+        // Окно закрывается только после отката отмененной операции
+        let finished = Msg::ProcessFinished { generation, result: Err(CANCELLED.to_string()) };
+        assert!(closes_window(state.update(finished)));
+        assert!(!state.is_busy);
+        assert!(state.log.to_text().contains("Operation cancelled"));
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn quit_when_idle_closes_window_immediately() {
+        let (_guard, home) = isolated_home("quit_idle");
+        let mut state = idle_state();
+
+        assert!(closes_window(state.update(Msg::CloseRequested)));
+        assert!(!state.confirm_quit);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    /// Запрос `POST /create` к приложению, в котором уже выбран пресет `demo`
+    #[cfg(feature = "automation")]
+    fn automation_create(home: &Path, options: &[(&str, bool)]) -> (AppState, PathBuf, automation::AutomationReply) {
+        use automation::{AutomationAction, AutomationRequest};
+
+        let presets_dir = empty_folder(home);
+        let mut state = start(MockPicker::answering([Some(presets_dir)]), MockFetcher::returning([Ok(())]));
+        let work = home.join("work");
+        fs::create_dir(&work).unwrap();
+        let create = CreateRequest {
+            preset: "demo".to_string(),
+            name: "auto_project".to_string(),
+            fields: [("language".to_string(), "Rust".to_string())].into(),
+            options: options.iter().map(|(id, enabled)| (id.to_string(), *enabled)).collect(),
+            destination: Some(work.clone()),
+        };
+        let (request, mut reply) = AutomationRequest::detached(AutomationAction::Create(create));
+        run(&mut state, |state| state.update(Msg::Automation(AutomationEvent::Request(request))));
+        let reply = reply.try_recv().unwrap();
+        (state, work.join("auto_project"), reply)
+    }
+
+    #[cfg(feature = "automation")]
+    #[test]
+    fn automation_create_builds_project_with_options() {
+        let (_guard, home) = isolated_home("automation_create");
+
+        let (state, project, reply) = automation_create(&home, &[("ci", true)]);
+
+        assert_eq!(reply.status, 202);
+        assert_eq!(state.dynamic_options.get("ci"), Some(&true));
+        assert_eq!(fs::read_to_string(project.join("src").join("main.rs")).unwrap(), "fn main() {}\n");
+        assert_eq!(state.last_report.as_ref().map(|report| report.project_path.clone()), Some(project));
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[cfg(feature = "automation")]
+    #[test]
+    fn automation_create_rejects_unknown_option() {
+        let (_guard, home) = isolated_home("automation_unknown_option");
+
+        let (_, project, reply) = automation_create(&home, &[("no_such_option", true)]);
+
+        assert_eq!(reply.status, 400);
+        assert_eq!(reply.body["error"], "Unknown option 'no_such_option' for preset 'demo'");
+        assert!(!project.exists());
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn double_click_on_create_starts_one_operation() {
+        let (_guard, home) = isolated_home("double_create");
+        let mut state = ready_state(&home);
+        let generation = state.operation_generation;
+
+        let first = state.update(Msg::Create);
+        // Кнопка заблокирована в том же обновлении: второй клик ничего не запускает
+        assert!(state.is_busy);
+        assert!(!state.can_create());
+        let second = state.update(Msg::Create);
+        assert!(second.actions().is_empty());
+        assert_eq!(state.operation_generation, generation + 1);
+
+        run(&mut state, |_| first);
+
+        assert!(!state.is_busy);
+        assert_eq!(state.log.to_text().matches("Creating project directory").count(), 1);
+        assert!(home.join("work").join("my_project").join("src").join("main.rs").is_file());
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn stale_create_result_is_ignored() {
+        let (_guard, home) = isolated_home("stale_result");
+        let mut state = ready_state(&home);
+        let stale = state.operation_generation + 1;
+        run(&mut state, |state| state.update(Msg::Create));
+        // Следующая операция уже идет, когда приходит запоздавший результат первой
+        let preview = state.update(Msg::Preview);
+        assert!(state.is_busy);
+
+        let late = Msg::ProcessFinished { generation: stale, result: Err("Failed to copy template".to_string()) };
+        assert!(state.update(late).actions().is_empty());
+
+        assert!(state.is_busy);
+        assert!(state.overlay.is_none());
+        assert!(!state.log.to_text().contains("Failed to copy template"));
+
+        run(&mut state, |_| preview);
+        assert!(!state.is_busy);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn problems_list_each_blocker_and_gate_create() {
+        type Block = fn(&mut AppState);
+        let cases: [(Block, Problem, &str); 8] = [
+            (|state| state.is_busy = true, Problem::Busy, "Another operation is in progress"),
+            (|state| state.presets_dir = None, Problem::NoPresetsFolder, "No presets folder is set"),
+            (|state| state.preset_config = None, Problem::NoPresetSelected, "No preset is selected"),
+            (|state| state.project_name.clear(), Problem::MissingName, "Enter a project name"),
+            (|state| state.project_name = "bad/name".to_string(), Problem::InvalidName, "Project name is invalid"),
+            (
+                |state| { state.dynamic_fields.insert("language".to_string(), "  ".to_string()); },
+                Problem::MissingRequiredField { id: "language".to_string(), label: "Language".to_string() },
+                "Required field 'Language' is empty",
+            ),
+            (|state| state.destination_dir = None, Problem::NoDestination, "Choose a destination folder"),
+            (
+                |state| state.destination_error = Some("Folder is read-only".to_string()),
+                Problem::InvalidDestination("Folder is read-only".to_string()),
+                "Destination: Folder is read-only",
+            ),
+        ];
+        for (index, (block, problem, text)) in cases.into_iter().enumerate() {
+            let (_guard, home) = isolated_home(&format!("problems_{}", index));
+            let mut state = ready_state(&home);
+            assert!(state.can_create());
+
+            block(&mut state);
+
+            assert_eq!(state.problems(), vec![problem.clone()]);
+            assert_eq!(problem.to_string(), text);
+            assert!(!state.can_create(), "{:?} must disable Create", problem);
+            // Create с проблемой не запускает операцию
+            let generation = state.operation_generation;
+            let _ = state.update(Msg::Create);
+            assert_eq!(state.operation_generation, generation, "{:?}", problem);
+            fs::remove_dir_all(&home).unwrap();
+        }
+    }
+
+    #[test]
+    fn problems_accumulate_on_startup() {
+        let (_guard, home) = isolated_home("problems_startup");
+        let mut state = idle_state();
+        state.destination_dir = None;
+
+        assert_eq!(state.problems(), vec![
+            Problem::NoPresetsFolder,
+            Problem::MissingName,
+            Problem::NoPresetSelected,
+            Problem::NoDestination,
+        ]);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn reset_form_restores_defaults_and_keeps_preset_and_log() {
+        let (_guard, home) = isolated_home("reset_form");
+        let mut state = ready_state(&home);
+        run(&mut state, |state| state.update(Msg::FieldChanged("license".to_string(), "GPL-3.0".to_string())));
+        run(&mut state, |state| state.update(Msg::OptionToggled("ci".to_string(), true)));
+        run(&mut state, |state| state.update(Msg::Create));
+        assert!(state.last_report.is_some());
+        state.field_errors.insert("language".to_string(), "This field is required".to_string());
+        let log = state.log.to_text();
+
+        run(&mut state, |state| state.update(Msg::ResetForm));
+
+        assert_eq!(state.project_name, "");
+        assert_eq!(state.project_name_error, "");
+        assert_eq!(state.dynamic_fields, HashMap::from([("license".to_string(), "MIT".to_string())]));
+        assert_eq!(state.dynamic_options, HashMap::from([("ci".to_string(), false)]));
+        assert!(state.field_errors.is_empty());
+        assert!(state.last_report.is_none());
+        assert!(state.target_status.is_none());
+        assert_eq!(state.selected_preset.as_deref(), Some("demo"));
+        assert!(state.preset_config.is_some());
+        assert_eq!(state.log.to_text(), log);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn reset_form_is_ignored_while_busy() {
+        let (_guard, home) = isolated_home("reset_form_busy");
+        let mut state = ready_state(&home);
+        state.is_busy = true;
+
+        let _ = state.update(Msg::ResetForm);
+
+        assert_eq!(state.project_name, "my_project");
+        assert_eq!(state.dynamic_fields.get("language").map(String::as_str), Some("Rust"));
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn next_focus_index_wraps_in_both_directions() {
+        assert_eq!(next_focus_index(0, None, false), None);
+        assert_eq!(next_focus_index(0, None, true), None);
+        assert_eq!(next_focus_index(3, None, false), Some(0));
+        assert_eq!(next_focus_index(3, None, true), Some(2));
+        assert_eq!(next_focus_index(3, Some(0), false), Some(1));
+        assert_eq!(next_focus_index(3, Some(2), false), Some(0));
+        assert_eq!(next_focus_index(3, Some(0), true), Some(2));
+        assert_eq!(next_focus_index(3, Some(2), true), Some(1));
+        assert_eq!(next_focus_index(1, Some(0), false), Some(0));
+    }
+
+    #[test]
+    fn focus_order_follows_declared_text_fields() {
+        let (_guard, home) = isolated_home("focus_order");
+        let mut state = idle_state();
+        assert_eq!(state.focus_order(), vec![project_name_input_id()]);
+
+        state.preset_config = Some(serde_json::from_str(r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": [], "templates": [],
+            "fields": [
+                {"id": "author", "label": "Author", "required": false, "type": "text"},
+                {"id": "kind", "label": "Kind", "required": false, "type": "select", "options": ["lib", "bin"]},
+                {"id": "notes", "label": "Notes", "required": false, "type": "textarea"},
+                {"id": "workflow", "label": "Workflow", "required": false, "type": "text", "depends_on_option": "ci"},
+                {"id": "tags", "label": "Tags", "required": false, "type": "multi_select"},
+                {"id": "license", "label": "License", "required": false, "type": "text"}
+            ],
+            "options": [{"id": "ci", "label": "CI", "default": false}]
+        }"#).unwrap());
+        assert_eq!(state.focus_order(), vec![
+            project_name_input_id(),
+            field_input_id("author"),
+            field_input_id("tags"),
+            field_input_id("license"),
+        ]);
+
+        state.dynamic_options.insert("ci".to_string(), true);
+        assert_eq!(state.focus_order(), vec![
+            project_name_input_id(),
+            field_input_id("author"),
+            field_input_id("workflow"),
+            field_input_id("tags"),
+            field_input_id("license"),
+        ]);
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn focus_found_moves_to_next_field_and_is_ignored_under_overlay() {
+        let (_guard, home) = isolated_home("focus_found");
+        let mut state = idle_state();
+        let current = iced::advanced::widget::Id::from(project_name_input_id());
+        assert!(!state.update(Msg::FocusFound(current, false)).actions().is_empty());
+        // Фокус вне формы (например, на кнопке): Tab переводит его в начало обхода
+        let outside = iced::advanced::widget::Id::new("create_button");
+        assert!(!state.update(Msg::FocusFound(outside, true)).actions().is_empty());
+
+        state.overlay = Some(Overlay::Settings);
+        assert!(state.update(Msg::FocusNext(false)).actions().is_empty());
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn stale_select_values_are_cleared_after_preset_update() {
+        let (_guard, home) = isolated_home("stale_select");
+        let mut state = ready_state(&home);
+        state.dynamic_fields.insert("edition".to_string(), "2018".to_string());
+        state.dynamic_fields.insert("targets".to_string(), "linux, macos".to_string());
+        state.dynamic_fields.insert("kind".to_string(), "bin".to_string());
+
+        // Обновленный пресет: опции "2018" и "macos" удалены
+        let updated: PresetConfig = serde_json::from_str(r#"{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src"],
+            "templates": [{"source": "main.rs", "destination": "src/main.rs"}],
+            "fields": [
+                {"id": "language", "label": "Language", "required": true, "type": "text"},
+                {"id": "edition", "label": "Edition", "required": true, "type": "select", "options": ["2021", "2024"]},
+                {"id": "targets", "label": "Targets", "required": false, "type": "multi_select", "options": ["linux", "windows"]},
+                {"id": "kind", "label": "Kind", "required": true, "type": "select", "options": ["lib", "bin"]}
+            ],
+            "options": []
+        }"#).unwrap();
+        run(&mut state, |state| state.update(Msg::PresetConfigLoaded(Ok(updated))));
+
+        assert_eq!(state.dynamic_fields.get("edition"), None);
+        assert_eq!(state.dynamic_fields.get("targets").map(String::as_str), Some("linux"));
+        assert_eq!(state.dynamic_fields.get("kind").map(String::as_str), Some("bin"));
+        let log = state.log.to_text();
+        assert!(log.contains("Warning: Value '2018' of field 'edition' is no longer available in preset 'demo', cleared"), "{}", log);
+        assert!(log.contains("Warning: Value 'macos' of field 'targets' is no longer available in preset 'demo', cleared"), "{}", log);
+        assert!(!log.contains("field 'kind'"), "{}", log);
+        assert_eq!(state.problems(), vec![Problem::MissingRequiredField {
+            id: "edition".to_string(),
+            label: "Edition".to_string(),
+        }]);
+        assert!(!state.can_create());
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn form_input_is_ignored_while_busy() {
+        let (_guard, home) = isolated_home("busy_input");
+        let mut state = ready_state(&home);
+        let destination = state.destination_dir.clone();
+
+        let create = state.update(Msg::Create);
+        assert!(state.is_busy);
+        for message in [
+            Msg::NameChanged("other".to_string()),
+            Msg::FieldChanged("language".to_string(), "Go".to_string()),
+            Msg::OptionToggled("ci".to_string(), true),
+            Msg::PresetSelected(None),
+            Msg::DestinationInputChanged("/elsewhere".to_string()),
+            Msg::BrowseDestination,
+            Msg::ResetForm,
+            Msg::NewProject,
+        ] {
+            assert!(message.is_form_input());
+            assert!(state.update(message).actions().is_empty());
+        }
+        assert_eq!(state.project_name, "my_project");
+        assert_eq!(state.dynamic_fields.get("language").map(String::as_str), Some("Rust"));
+        assert_eq!(state.dynamic_options.get("ci"), Some(&false));
+        assert_eq!(state.selected_preset.as_deref(), Some("demo"));
+        assert_eq!(state.destination_dir, destination);
+        // Отмена и выход остаются доступны
+        assert!(!Msg::CancelOperation.is_form_input());
+        assert!(!Msg::QuitConfirmed.is_form_input());
+
+        run(&mut state, |_| create);
+
+        assert!(!state.is_busy);
+        assert!(home.join("work").join("my_project").join("src").join("main.rs").is_file());
+        let _ = state.update(Msg::NameChanged("other".to_string()));
+        assert_eq!(state.project_name, "other");
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn notification_text_per_event() {
+        // Язык общий для процесса: блокировка не дает другим тестам его сменить
+        let (_guard, home) = isolated_home("notifications");
+        set_language(Language::En);
+        let text = |event: NotificationEvent| (event.summary(), event.body());
+
+        assert_eq!(
+            text(NotificationEvent::CreationSucceeded { project_name: "demo".to_string() }),
+            ("Project Created", "Project 'demo' has been created successfully!".to_string())
+        );
+        assert_eq!(
+            text(NotificationEvent::CreationFailed { project_name: "demo".to_string() }),
+            ("Project Creation Failed", "Failed to create project 'demo'".to_string())
+        );
+        assert_eq!(
+            text(NotificationEvent::PresetsUpdated { changed: 0 }),
+            ("Presets Updated", "All presets are up to date.".to_string())
+        );
+        assert_eq!(
+            text(NotificationEvent::PresetsUpdated { changed: 1 }),
+            ("Presets Updated", "1 preset changed.".to_string())
+        );
+        assert_eq!(
+            text(NotificationEvent::PresetsUpdated { changed: 7 }),
+            ("Presets Updated", "7 presets changed.".to_string())
+        );
+        assert_eq!(
+            text(NotificationEvent::PresetsUpdateFailed { error: "timeout".to_string() }),
+            ("Presets Update Failed", "Failed to update presets: timeout".to_string())
+        );
+
+        set_language(Language::Ru);
+        let changed = text(NotificationEvent::PresetsUpdated { changed: 7 });
+        set_language(Language::En);
+        assert_eq!(changed, ("Пресеты обновлены", "Изменено пресетов: 7.".to_string()));
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn filtering_by_unknown_tag_empties_the_list_and_clears_selection() {
+        let (_guard, home) = isolated_home("tag_filter");
+        set_language(Language::En);
+        let mut state = ready_state(&home);
+        // Тег, совпадающий с текстом пункта "все категории"
+        let book = home.join("presets").join("book");
+        fs::create_dir(&book).unwrap();
+        fs::write(book.join("files_config.json"), r#"{"preset_id": "book", "preset_name": "Book",
+            "description": "", "directories": [], "metadata": {"tags": ["All"]}}"#).unwrap();
+        run(&mut state, |state| state.rescan_presets());
+        assert_eq!(state.available_tags, vec!["All".to_string()]);
+        assert_eq!(state.selected_preset.as_deref(), Some("demo"));
+
+        run(&mut state, |state| state.update(Msg::TagSelected(Some("missing".to_string()))));
+
+        assert_eq!(state.visible_presets(), (Vec::new(), Vec::new()));
+        assert_eq!(state.selected_preset, None);
+        assert!(state.preset_config.is_none());
+
+        run(&mut state, |state| state.update(Msg::TagSelected(Some("All".to_string()))));
+        assert_eq!(state.visible_presets().0, vec!["book".to_string()]);
+        run(&mut state, |state| state.update(Msg::TagSelected(None)));
+        assert_eq!(state.visible_presets().0, vec!["book".to_string(), "demo".to_string()]);
+
+        // Пункт "все" и тег с тем же текстом - разные значения списка
+        assert_eq!(CategoryFilter(None).to_string(), "All");
+        assert_eq!(CategoryFilter(Some("All".to_string())).to_string(), "All");
+        assert_ne!(CategoryFilter(None), CategoryFilter(Some("All".to_string())));
+        fs::remove_dir_all(&home).unwrap();
+    }
+
+    #[test]
+    fn layout_switches_at_breakpoints() {
+        assert_eq!(field_columns(TWO_COLUMN_BREAKPOINT - 1.0), 1);
+        assert_eq!(field_columns(TWO_COLUMN_BREAKPOINT), 2);
+        assert_eq!(field_columns(TWO_COLUMN_BREAKPOINT + 1.0), 2);
+
+        assert_eq!(menu_kinds(MENU_COMPACT_WIDTH - 1.0), [MenuKind::Compact]);
+        assert_eq!(menu_kinds(MENU_COMPACT_WIDTH), MenuKind::BAR);
+        assert_eq!(menu_kinds(MENU_COMPACT_WIDTH + 1.0), MenuKind::BAR);
+    }
+}
@@ -0,0 +1,145 @@
+//! # Модуль истории созданных проектов
+//!
+//! `RecentProjects` хранит последние созданные проекты (путь, пресет, время
+//! создания) в JSON файле `recent_projects.json` в директории конфигурации.
+//! Список упорядочен от новых к старым и ограничен `MAX_RECENT_PROJECTS`
+//! записями; повторное создание проекта по тому же пути поднимает запись наверх.
+
+use crate::persist::{read_with_backup, write_with_backup};
+use crate::settings::config_dir;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Имя файла истории в директории конфигурации
+pub const RECENT_PROJECTS_FILE_NAME: &str = "recent_projects.json";
+
+/// Максимальное число проектов в истории
+pub const MAX_RECENT_PROJECTS: usize = 20;
+
+/// Созданный проект
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentProject {
+    /// Директория проекта
+    pub path: PathBuf,
+    /// Идентификатор пресета, по которому создан проект
+    pub preset_id: String,
+    /// Время создания
+    pub created: DateTime<Utc>,
+}
+
+/// История созданных проектов
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::history::{RecentProjects, MAX_RECENT_PROJECTS};
+///
+/// let mut history = RecentProjects::default();
+/// for index in 0..MAX_RECENT_PROJECTS + 2 {
+///     history.add(format!("/projects/p{}", index).into(), "book", chrono::Utc::now());
+/// }
+/// assert_eq!(history.entries().len(), MAX_RECENT_PROJECTS);
+/// assert_eq!(history.entries()[0].path.to_str(), Some("/projects/p21"));
+///
+/// // Повторное создание поднимает запись наверх без дубликата
+/// history.add("/projects/p5".into(), "notes", chrono::Utc::now());
+/// assert_eq!(history.entries().len(), MAX_RECENT_PROJECTS);
+/// assert_eq!(history.entries()[0].preset_id, "notes");
+///
+/// let restored = RecentProjects::from_json(&history.to_json().unwrap()).unwrap();
+/// assert_eq!(restored, history);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentProjects {
+    /// Проекты от новых к старым
+    projects: Vec<RecentProject>,
+}
+
+impl RecentProjects {
+    /// Загрузить историю из директории конфигурации
+    ///
+    /// Отсутствующий или поврежденный файл не является ошибкой: поврежденный файл
+    /// восстанавливается из резервной копии, иначе история пуста.
+    pub fn load() -> Self {
+        config_dir()
+            .map(|dir| Self::load_from(&dir.join(RECENT_PROJECTS_FILE_NAME)))
+            .unwrap_or_default()
+    }
+
+    /// Загрузить историю из файла `path` (см. `load`)
+    pub fn load_from(path: &Path) -> Self {
+        let mut history = read_with_backup(path, Self::from_json).unwrap_or_default();
+        // Отредактированный вручную файл может быть длиннее лимита
+        history.projects.truncate(MAX_RECENT_PROJECTS);
+        history
+    }
+
+    /// Сохранить историю в директорию конфигурации
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` если история сохранена, иначе `Err` с описанием ошибки
+    pub fn save(&self) -> Result<(), String> {
+        let dir = config_dir()
+            .ok_or_else(|| "Could not determine config directory".to_string())?;
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create config dir: {}", e))?;
+        self.save_to(&dir.join(RECENT_PROJECTS_FILE_NAME))
+    }
+
+    /// Сохранить историю в файл `path` (атомарно, с копией `.bak`)
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        let content = self.to_json()?;
+        write_with_backup(path, content.as_bytes())
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    /// Разобрать историю из JSON
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        serde_json::from_str(content).map_err(|e| format!("Failed to parse recent projects: {}", e))
+    }
+
+    /// Записать историю в JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize recent projects: {}", e))
+    }
+
+    /// Проекты от новых к старым
+    pub fn entries(&self) -> &[RecentProject] {
+        &self.projects
+    }
+
+    /// Добавить созданный проект в начало списка
+    ///
+    /// Прежняя запись с тем же путем удаляется, самые старые записи сверх
+    /// `MAX_RECENT_PROJECTS` отбрасываются.
+    pub fn add(&mut self, path: PathBuf, preset_id: &str, created: DateTime<Utc>) {
+        self.projects.retain(|project| project.path != path);
+        self.projects.insert(0, RecentProject { path, preset_id: preset_id.to_string(), created });
+        self.projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    /// Удалить проект из истории (сама директория не затрагивается)
+    ///
+    /// # Returns
+    ///
+    /// `true`, если запись была в истории
+    pub fn remove(&mut self, path: &Path) -> bool {
+        let before = self.projects.len();
+        self.projects.retain(|project| project.path != path);
+        self.projects.len() != before
+    }
+
+    /// Очистить историю
+    pub fn clear(&mut self) {
+        self.projects.clear();
+    }
+
+    /// История пуста
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+    }
+}
@@ -0,0 +1,227 @@
+//! # Модуль целостности управляемых пресетов
+//!
+//! При распаковке архива пресетов (`presets::download_and_extract_presets`) в
+//! директорию каждого распакованного пресета записывается `.preset_integrity.json`
+//! с SHA-256 всех файлов, пришедших из архива. `verify_presets` сверяет с ним файлы
+//! на диске и находит пресеты с удаленными или измененными файлами (например,
+//! после прерванной распаковки). Такие пресеты восстанавливает
+//! `presets::repair_presets`: архив скачивается заново, но распаковываются только
+//! элементы поврежденных пресетов.
+//!
+//! Пользовательские и импортированные пресеты не проверяются: архив их не
+//! перезаписывает. Файлы, добавленные в пресет после распаковки, не учитываются.
+
+use crate::manifest::hash_file;
+use crate::persist::atomic_write;
+use crate::presets::{preset_origin, PresetOrigin};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Имя файла с хешами файлов пресета внутри его директории
+pub const PRESET_INTEGRITY_FILE_NAME: &str = ".preset_integrity.json";
+
+/// Сколько путей перечислять в строке лога о поврежденном пресете
+const LISTED_PATHS: usize = 5;
+
+/// Хеши файлов пресета, распакованных из архива
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+    /// SHA-256 (hex) по пути относительно директории пресета (через `/`)
+    pub files: BTreeMap<String, String>,
+}
+
+impl IntegrityManifest {
+    /// Загрузить манифест пресета
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(manifest))`, `Ok(None)` если манифеста нет, `Err` если он поврежден
+    pub fn load(preset_dir: &Path) -> Result<Option<Self>, String> {
+        let path = preset_dir.join(PRESET_INTEGRITY_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+    }
+
+    /// Атомарно сохранить манифест в директорию пресета
+    pub fn save(&self, preset_dir: &Path) -> Result<(), String> {
+        let path = preset_dir.join(PRESET_INTEGRITY_FILE_NAME);
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize integrity manifest: {}", e))?;
+        atomic_write(&path, content.as_bytes())
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+}
+
+/// Состояние файлов пресета относительно его манифеста
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityStatus {
+    /// Все файлы на месте и не изменены
+    Intact,
+    /// Часть файлов удалена или изменена (пути относительно пресета, через `/`)
+    Damaged {
+        /// Файлы из манифеста, которых нет на диске
+        missing: Vec<String>,
+        /// Файлы, содержимое которых отличается от распакованного
+        modified: Vec<String>,
+    },
+    /// Манифест не читается; проверить файлы нельзя, пресет можно только восстановить
+    Unreadable(String),
+}
+
+/// Результат проверки одного пресета
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetIntegrity {
+    /// Идентификатор пресета (имя директории)
+    pub preset_id: String,
+    /// Состояние файлов
+    pub status: IntegrityStatus,
+}
+
+impl PresetIntegrity {
+    /// Пресет нужно восстановить из архива
+    pub fn needs_repair(&self) -> bool {
+        self.status != IntegrityStatus::Intact
+    }
+}
+
+impl fmt::Display for PresetIntegrity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            IntegrityStatus::Intact => write!(f, "Preset '{}': intact", self.preset_id),
+            IntegrityStatus::Damaged { ref missing, ref modified } => {
+                write!(f, "Preset '{}': {} missing, {} modified file(s)", self.preset_id, missing.len(), modified.len())?;
+                let paths: Vec<&str> = missing.iter().chain(modified).map(String::as_str).collect();
+                write!(f, " ({}", paths.iter().take(LISTED_PATHS).copied().collect::<Vec<_>>().join(", "))?;
+                if paths.len() > LISTED_PATHS {
+                    write!(f, ", …")?;
+                }
+                write!(f, ")")
+            }
+            IntegrityStatus::Unreadable(ref error) => write!(f, "Preset '{}': cannot verify: {}", self.preset_id, error),
+        }
+    }
+}
+
+/// Сверить файлы пресета с его манифестом
+///
+/// # Returns
+///
+/// `None`, если у пресета нет манифеста (пресет не из архива или распакован
+/// версией приложения без манифестов)
+pub fn verify_preset(preset_dir: &Path) -> Option<IntegrityStatus> {
+    let manifest = match IntegrityManifest::load(preset_dir) {
+        Ok(manifest) => manifest?,
+        Err(e) => return Some(IntegrityStatus::Unreadable(e)),
+    };
+    let mut missing = Vec::new();
+    let mut modified = Vec::new();
+    for (key, expected) in &manifest.files {
+        let path = preset_dir.join(key);
+        if !path.is_file() {
+            missing.push(key.clone());
+        } else if hash_file(&path).ok().as_ref() != Some(expected) {
+            // Нечитаемый файл тоже восстанавливается из архива
+            modified.push(key.clone());
+        }
+    }
+    if missing.is_empty() && modified.is_empty() {
+        Some(IntegrityStatus::Intact)
+    } else {
+        Some(IntegrityStatus::Damaged { missing, modified })
+    }
+}
+
+/// Проверить все пресеты директории, распакованные из архива
+///
+/// Проверяются поддиректории первого уровня с манифестом целостности, кроме
+/// пользовательских и импортированных пресетов. Пресет без `files_config.json`
+/// тоже проверяется: именно так выглядит прерванная распаковка.
+///
+/// # Arguments
+///
+/// * `presets_dir` - директория, в которую распаковывается архив
+///
+/// # Returns
+///
+/// Результаты по пресетам в порядке идентификаторов, `Err` если директория не читается
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write;
+/// use ai_project_template::integrity::{damaged_presets, verify_presets, IntegrityStatus};
+/// use ai_project_template::presets::extract_presets_archive;
+///
+/// let dir = std::env::temp_dir().join(format!("integrity_doc_{}", std::process::id()));
+/// let presets_dir = dir.join("presets");
+/// std::fs::create_dir_all(&presets_dir).unwrap();
+///
+/// // Архив с двумя пресетами в корневой папке, как у архивов GitHub
+/// let archive = dir.join("presets.zip");
+/// let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+/// for (name, content) in [
+///     ("repo-main/book/files_config.json", "{}"),
+///     ("repo-main/book/templates/intro.md", "# Intro"),
+///     ("repo-main/notes/files_config.json", "{}"),
+/// ] {
+///     zip.start_file(name, zip::write::FileOptions::default()).unwrap();
+///     zip.write_all(content.as_bytes()).unwrap();
+/// }
+/// zip.finish().unwrap();
+/// extract_presets_archive(&archive, &presets_dir, None, None, |_| {}).unwrap();
+///
+/// // Один файл удален, другой изменен
+/// std::fs::remove_file(presets_dir.join("book/templates/intro.md")).unwrap();
+/// std::fs::write(presets_dir.join("book/files_config.json"), "{broken").unwrap();
+///
+/// let results = verify_presets(&presets_dir).unwrap();
+/// assert_eq!(results[0].status, IntegrityStatus::Damaged {
+///     missing: vec!["templates/intro.md".to_string()],
+///     modified: vec!["files_config.json".to_string()],
+/// });
+/// assert_eq!(results[1].status, IntegrityStatus::Intact);
+///
+/// // Повторная распаковка только поврежденного пресета
+/// let damaged = damaged_presets(&results);
+/// let repaired = extract_presets_archive(&archive, &presets_dir, Some(&damaged), None, |_| {}).unwrap();
+/// assert_eq!(repaired, damaged);
+/// assert!(verify_presets(&presets_dir).unwrap().iter().all(|result| !result.needs_repair()));
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn verify_presets(presets_dir: &Path) -> Result<Vec<PresetIntegrity>, String> {
+    let entries = fs::read_dir(presets_dir)
+        .map_err(|e| format!("Failed to read directory {:?}: {}", presets_dir, e))?;
+    let mut results = Vec::new();
+    for entry in entries.flatten() {
+        let preset_dir = entry.path();
+        if !preset_dir.is_dir() {
+            continue;
+        }
+        if matches!(preset_origin(&preset_dir, None), PresetOrigin::User | PresetOrigin::Imported { .. }) {
+            continue;
+        }
+        if let Some(status) = verify_preset(&preset_dir) {
+            let preset_id = entry.file_name().to_string_lossy().into_owned();
+            results.push(PresetIntegrity { preset_id, status });
+        }
+    }
+    results.sort_by(|a, b| a.preset_id.cmp(&b.preset_id));
+    Ok(results)
+}
+
+/// Идентификаторы пресетов, которые нужно восстановить
+pub fn damaged_presets(results: &[PresetIntegrity]) -> BTreeSet<String> {
+    results.iter()
+        .filter(|result| result.needs_repair())
+        .map(|result| result.preset_id.clone())
+        .collect()
+}
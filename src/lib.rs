@@ -0,0 +1,51 @@
+//! # AI Project Template - библиотека
+//!
+//! Ядро приложения, не зависящее от GUI: работа с пресетами и создание проектов.
+//! Используется графическим приложением (`gui.rs`) и может подключаться другими
+//! инструментами для программного создания проектов.
+//!
+//! Библиотека не зависит от GUI-крейтов (iced, rfd, notify-rust, dark-light): они
+//! подключаются фичей `gui` (включена по умолчанию) и нужны только исполняемому файлу.
+//! Для использования только ядра подключите крейт с `default-features = false`.
+//!
+//! - `presets` - модуль для работы с конфигурациями пресетов
+//! - `command` - модуль для создания проектов (включая `ProjectBuilder`)
+//! - `validation` - модуль валидации конфигураций пресетов
+//! - `doctor` - диагностика: почему директория пресета не появляется в списке
+//! - `expression` - выражения вычисляемых полей пресета (`computed_fields`)
+//! - `substitution` - правила подстановки значений полей в шаблоны и пути
+//! - `manifest` - манифест созданного проекта с хешами файлов и его сверка
+//! - `integrity` - проверка файлов пресетов, распакованных из архива
+//! - `timing` - замер длительности этапов создания проекта
+//! - `operation` - этапы длительных операций и их общий прогресс
+//! - `session_log` - лог сессии, сгруппированный по операциям
+//! - `cloud` - обнаружение папок облачной синхронизации (OneDrive, Dropbox)
+//! - `retry` - повтор файловых операций при временных ошибках
+//! - `persist` - атомарная запись файлов состояния и восстановление из резервной копии
+//! - `answers` - значения полей, сохраненные по пресетам между сессиями
+//! - `history` - история недавно созданных проектов
+//! - `editor` - открытие созданного проекта в редакторе пользователя
+//! - `locale` - локализация интерфейса (таблицы строк `en` и `ru`)
+//! - `settings` - настройки приложения (сеть, проверка обновлений)
+//! - `update` - проверка новых релизов приложения
+
+pub mod presets;
+pub mod command;
+pub mod validation;
+pub mod doctor;
+pub mod expression;
+pub mod substitution;
+pub mod manifest;
+pub mod integrity;
+pub mod timing;
+pub mod operation;
+pub mod session_log;
+pub mod cloud;
+pub mod retry;
+pub mod persist;
+pub mod answers;
+pub mod history;
+pub mod editor;
+pub mod locale;
+pub mod settings;
+pub mod update;
@@ -0,0 +1,514 @@
+//! # Модуль локализации интерфейса
+//!
+//! Строки интерфейса ищутся по ключу (`tr("form.create")`) во встроенных таблицах
+//! `en` и `ru`. Язык выбирается в настройках (`AppSettings::language`) и задается
+//! для всего процесса (`set_language`), как и портативный режим: так заголовок
+//! README, который пишет `create_project`, следует выбранному языку без передачи
+//! языка через все функции создания проекта.
+//!
+//! Ключ, которого нет в таблице выбранного языка, берется из английской таблицы,
+//! а неизвестный ключ возвращается как есть, поэтому пропущенный перевод не
+//! приводит к панике. Аргументы подставляются функцией `trf` вместо `{}` по порядку.
+//!
+//! Лог сессии, сообщения об ошибках и вывод командной строки не переводятся.
+//!
+//! ## Пример
+//!
+//! ```
+//! use ai_project_template::locale::{tr_in, untranslated_keys, Language};
+//!
+//! assert_eq!(tr_in(Language::En, "form.create"), "Create project");
+//! assert_eq!(tr_in(Language::Ru, "form.create"), "Создать проект");
+//! // Неизвестный ключ возвращается как есть
+//! assert_eq!(tr_in(Language::Ru, "no.such.key"), "no.such.key");
+//!
+//! // Все английские строки переведены, число аргументов `{}` совпадает
+//! assert!(untranslated_keys(Language::Ru).is_empty());
+//! for (key, english) in Language::En.strings() {
+//!     assert_eq!(
+//!         tr_in(Language::Ru, key).matches("{}").count(),
+//!         english.matches("{}").count(),
+//!         "{}", key
+//!     );
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Язык интерфейса
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// Английский
+    #[default]
+    En,
+    /// Русский
+    Ru,
+}
+
+impl Language {
+    /// Все языки в порядке отображения в списке выбора
+    pub const ALL: [Language; 2] = [Language::En, Language::Ru];
+
+    /// Строки языка: пары (ключ, перевод)
+    pub fn strings(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::En => EN,
+            Language::Ru => RU,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    // Название языка на самом языке, чтобы его можно было найти в любом интерфейсе
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::En => write!(f, "English"),
+            Language::Ru => write!(f, "Русский"),
+        }
+    }
+}
+
+/// Текущий язык процесса (индекс в `Language::ALL`)
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Выбрать язык интерфейса и README для всего процесса
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// Текущий язык (по умолчанию английский)
+pub fn current_language() -> Language {
+    Language::ALL.get(LANGUAGE.load(Ordering::Relaxed) as usize).copied().unwrap_or_default()
+}
+
+/// Строка по ключу на текущем языке (см. `tr_in`)
+pub fn tr(key: &'static str) -> &'static str {
+    tr_in(current_language(), key)
+}
+
+/// Строка по ключу на языке `language`
+///
+/// # Returns
+///
+/// Перевод, иначе английская строка, иначе сам ключ
+pub fn tr_in(language: Language, key: &'static str) -> &'static str {
+    lookup(language.strings(), key)
+        .or_else(|| lookup(EN, key))
+        .unwrap_or(key)
+}
+
+/// Строка по ключу на текущем языке с аргументами вместо `{}`
+///
+/// Лишние `{}` остаются в тексте, лишние аргументы отбрасываются.
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::locale::trf;
+///
+/// assert_eq!(trf("status.presets", &[&3]), "3 preset(s)");
+/// ```
+pub fn trf(key: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let template = tr(key);
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        result.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => {
+                let _ = write!(result, "{}", arg);
+            }
+            None => result.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Ключи английской таблицы, которых нет в таблице языка `language`
+pub fn untranslated_keys(language: Language) -> Vec<&'static str> {
+    EN.iter()
+        .map(|(key, _)| *key)
+        .filter(|key| lookup(language.strings(), key).is_none())
+        .collect()
+}
+
+/// Найти перевод ключа в таблице
+fn lookup(table: &'static [(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, value)| *value)
+}
+
+/// Английские строки (основная таблица, в ней должны быть все ключи)
+const EN: &[(&str, &str)] = &[
+    ("common.cancel", "Cancel"),
+    ("common.close", "Close"),
+    ("common.view", "View"),
+    ("common.characters", "{} characters"),
+    ("common.delete", "Delete"),
+    ("common.keep", "Keep"),
+    ("common.remove", "Remove"),
+    ("app.title", "Project Creator"),
+    ("app.log", "Log"),
+    ("menu.file", "File"),
+    ("menu.edit", "Edit"),
+    ("menu.help", "Help"),
+    ("menu.new_project", "New project"),
+    ("menu.change_folder", "Change presets folder…"),
+    ("menu.refresh", "Refresh presets"),
+    ("menu.verify", "Verify presets"),
+    ("menu.import", "Import preset…"),
+    ("menu.settings", "Settings…"),
+    ("menu.open_log", "Open log file"),
+    ("menu.doctor", "Why isn't my preset listed?…"),
+    ("menu.export_schema", "Export config schema…"),
+    ("menu.about", "About"),
+    ("menu.quit", "Quit"),
+    ("preset.label", "Preset:"),
+    ("preset.none_available", "No presets available"),
+    ("preset.search", "Search presets"),
+    ("preset.find", "Find:"),
+    ("preset.category", "Category:"),
+    ("preset.category_all", "All"),
+    ("preset.imported_from", "Imported from {}"),
+    ("preset.homepage", "Homepage"),
+    ("preset.requires_version", "Requires app version {} or newer"),
+    ("preset.validate", "Validate preset"),
+    ("preset.clear_saved", "Clear saved values"),
+    ("preset.loading", "Loading…"),
+    ("preset.structure", "Project structure: {} folder(s)"),
+    ("preset.refresh", "Refresh Presets"),
+    ("preset.read_only", "The presets folder is read-only (e.g. managed by your administrator). Imported presets are stored in your user presets folder."),
+    ("preset.import", "Import preset…"),
+    ("preset.change_folder", "Change folder…"),
+    ("origin.managed", "managed"),
+    ("origin.user", "user"),
+    ("origin.imported", "imported"),
+    ("origin.unknown", "unknown"),
+    ("setup.incomplete", "Setup incomplete: no presets are available yet."),
+    ("setup.choose_folder", "Choose presets folder…"),
+    ("folder.found", "Found {} preset(s) in {}"),
+    ("folder.use_existing", "Use existing"),
+    ("folder.download_fresh", "Download fresh presets"),
+    ("folder.none_found", "No presets found in {}. Download presets there?"),
+    ("folder.download", "Download presets"),
+    ("folder.wrong", "This folder also contains {} other items — it may be the wrong folder."),
+    ("name.label", "Project name:"),
+    ("name.placeholder", "Project name"),
+    ("name.placeholder_last", "Project name (last: {})"),
+    ("name.invalid", "Invalid name"),
+    ("target.will_create", "Folder will be created"),
+    ("target.empty", "Folder exists but is empty"),
+    ("target.not_empty", "Folder exists and is not empty — enable overwrite"),
+    ("target.overwrite", "Overwrite"),
+    ("fields.title", "Fields:"),
+    ("fields.required", "required"),
+    ("options.title", "Options:"),
+    ("options.recommended_on", "recommended: on"),
+    ("options.recommended_off", "recommended: off"),
+    ("options.apply_recommended", "Apply recommended settings"),
+    ("options.git_init", "Initialize git repository"),
+    ("options.open_in_editor", "Open in editor when done"),
+    ("temp.found", "Found {} leftover temporary file(s) from earlier runs ({}). Delete them?"),
+    ("repair.prompt", "{} damaged preset(s): {}. Download the presets archive again and restore only these presets?"),
+    ("repair.repair", "Repair"),
+    ("repair.not_now", "Not now"),
+    ("destination.label", "Destination:"),
+    ("destination.placeholder", "Folder where the project will be created"),
+    ("destination.tooltip", "Type a path, browse or drop a folder onto the window"),
+    ("destination.browse", "Browse…"),
+    ("form.create", "Create project"),
+    ("form.preview", "Preview"),
+    ("form.reset", "Reset form"),
+    ("timing.header", "Timing"),
+    ("log.save", "Save log…"),
+    ("log.copy", "Copy log"),
+    ("log.copy_last_error", "Copy last error"),
+    ("timing.slowest", ", slowest: {} {} s"),
+    ("import.replace_prompt", "A preset '{}' is already installed. Replace it with {}?"),
+    ("import.replace", "Replace"),
+    ("import.prompt", "Import a preset from a folder or a .zip archive containing files_config.json"),
+    ("import.folder", "Folder…"),
+    ("import.zip", "ZIP archive…"),
+    ("drop.prompt", "{} looks like a preset. Import it into the presets folder?"),
+    ("drop.import", "Import as preset"),
+    ("drop.destination", "Use as destination"),
+    ("readme.preview", "Preview README"),
+    ("large.over_limit", "This project will take {} on disk, more than the {} limit. Largest files:"),
+    ("large.files", "This project includes large files: {} file(s), {} in total."),
+    ("large.hard_link", "hard link"),
+    ("large.copy", "copy"),
+    ("large.case", "⚠ '{}' and '{}' differ only by case"),
+    ("large.create_anyway", "Create anyway"),
+    ("case.warning", "⚠ Paths differ only by case and will clash on Windows/macOS: {}"),
+    ("success.no_warnings", "no warnings"),
+    ("success.warnings", "{} warning(s), see log"),
+    ("success.missing", "⚠ Project created with {} missing template(s)"),
+    ("success.hide_details", "Hide details"),
+    ("success.view_details", "View details"),
+    ("success.rerun", "Re-run missing templates"),
+    ("success.created", "Project '{}' created"),
+    ("success.summary", "Done in {} s, {} template file(s), {}, {}"),
+    ("success.open_folder", "Open folder"),
+    ("success.copy_path", "Copy path"),
+    ("success.new_project", "New project"),
+    ("recent.header", "Recent projects ({})"),
+    ("recent.missing", "missing"),
+    ("recent.clear", "Clear history"),
+    ("busy.scanning", "Scanning presets folder…"),
+    ("busy.processing", "Processing..."),
+    ("busy.cancelling", "Cancelling…"),
+    ("quit.prompt", "An operation is running. Cancel it and quit?"),
+    ("quit.quit", "Cancel and quit"),
+    ("quit.keep_working", "Keep working"),
+    ("about.title", "About Project Creator"),
+    ("about.settings", "Settings…"),
+    ("about.copy_diagnostics", "Copy diagnostics"),
+    ("settings.title", "Settings"),
+    ("settings.theme", "Theme:"),
+    ("settings.scale", "Scale:"),
+    ("settings.language", "Language:"),
+    ("settings.sort", "Sort presets by:"),
+    ("settings.check_updates", "Check for updates daily"),
+    ("settings.notifications", "Show desktop notifications"),
+    ("settings.project_log", "Save the log into created projects"),
+    ("settings.cloud_warning", "Warn about cloud-synced folders"),
+    ("settings.tray", "Minimize to tray on close"),
+    ("settings.tray_unavailable", "Minimize to tray on close (tray not available)"),
+    ("settings.min_busy", "Show progress for at least:"),
+    ("settings.busy_off", "Off"),
+    ("settings.seconds", "{} s"),
+    ("settings.editor", "Editor command:"),
+    ("settings.editor_hint", "{path} is replaced with the project folder; quote arguments with spaces"),
+    ("settings.presets_url", "Presets archive URL:"),
+    ("settings.save_url", "Save URL"),
+    ("settings.reset_url", "Reset to default"),
+    ("settings.sources", "Additional preset sources (edit settings.toml to change):"),
+    ("theme.system", "System"),
+    ("theme.dark", "Dark"),
+    ("theme.light", "Light"),
+    ("sort.by_id", "Folder name"),
+    ("sort.by_name", "Name"),
+    ("sort.by_last_used", "Recently used"),
+    ("status.no_folder", "Presets folder not set"),
+    ("status.refreshed", "Refreshed {}"),
+    ("status.not_refreshed", "Not refreshed yet"),
+    ("status.presets", "{} preset(s)"),
+    ("status.working", "Working…"),
+    ("status.ready", "Ready"),
+    ("status.about", "About"),
+    ("update.available", "New version available: {} ({} → {})"),
+    ("update.open", "Open release page"),
+    ("update.dismiss", "Dismiss"),
+    ("error.copy_details", "Copy details"),
+    ("problem.busy", "Another operation is in progress"),
+    ("problem.no_presets_folder", "No presets folder is set"),
+    ("problem.no_preset", "No preset is selected"),
+    ("problem.missing_name", "Enter a project name"),
+    ("problem.invalid_name", "Project name is invalid"),
+    ("problem.missing_field", "Required field '{}' is empty"),
+    ("problem.no_destination", "Choose a destination folder"),
+    ("problem.invalid_destination", "Destination: {}"),
+    ("problem.app_too_old", "This preset requires app version {} or newer (running {})"),
+    ("notify.created", "Project Created"),
+    ("notify.created_body", "Project '{}' has been created successfully!"),
+    ("notify.failed", "Project Creation Failed"),
+    ("notify.failed_body", "Failed to create project '{}'"),
+    ("notify.presets_updated", "Presets Updated"),
+    ("notify.up_to_date", "All presets are up to date."),
+    ("notify.one_changed", "1 preset changed."),
+    ("notify.changed", "{} presets changed."),
+    ("notify.presets_failed", "Presets Update Failed"),
+    ("notify.presets_failed_body", "Failed to update presets: {}"),
+    ("readme.created", "Created"),
+    ("readme.next_steps", "What's next"),
+];
+
+/// Русские строки
+const RU: &[(&str, &str)] = &[
+    ("common.cancel", "Отмена"),
+    ("common.close", "Закрыть"),
+    ("common.view", "Показать"),
+    ("common.characters", "Символов: {}"),
+    ("common.delete", "Удалить"),
+    ("common.keep", "Оставить"),
+    ("common.remove", "Убрать"),
+    ("app.title", "Создание проекта"),
+    ("app.log", "Лог"),
+    ("menu.file", "Файл"),
+    ("menu.edit", "Правка"),
+    ("menu.help", "Справка"),
+    ("menu.new_project", "Новый проект"),
+    ("menu.change_folder", "Сменить папку пресетов…"),
+    ("menu.refresh", "Обновить пресеты"),
+    ("menu.verify", "Проверить пресеты"),
+    ("menu.import", "Импорт пресета…"),
+    ("menu.settings", "Настройки…"),
+    ("menu.open_log", "Открыть файл лога"),
+    ("menu.doctor", "Почему пресета нет в списке?…"),
+    ("menu.export_schema", "Экспорт схемы конфигурации…"),
+    ("menu.about", "О программе"),
+    ("menu.quit", "Выход"),
+    ("preset.label", "Пресет:"),
+    ("preset.none_available", "Нет доступных пресетов"),
+    ("preset.search", "Поиск пресетов"),
+    ("preset.find", "Найти:"),
+    ("preset.category", "Категория:"),
+    ("preset.category_all", "Все"),
+    ("preset.imported_from", "Импортирован из {}"),
+    ("preset.homepage", "Домашняя страница"),
+    ("preset.requires_version", "Требуется версия приложения {} или новее"),
+    ("preset.validate", "Проверить пресет"),
+    ("preset.clear_saved", "Очистить сохраненные значения"),
+    ("preset.loading", "Загрузка…"),
+    ("preset.structure", "Структура проекта: папок - {}"),
+    ("preset.refresh", "Обновить пресеты"),
+    ("preset.read_only", "Папка пресетов доступна только для чтения (например, ее обслуживает администратор). Импортированные пресеты сохраняются в папку пользовательских пресетов."),
+    ("preset.import", "Импорт пресета…"),
+    ("preset.change_folder", "Сменить папку…"),
+    ("origin.managed", "управляемый"),
+    ("origin.user", "пользовательский"),
+    ("origin.imported", "импортирован"),
+    ("origin.unknown", "неизвестный"),
+    ("setup.incomplete", "Настройка не завершена: пресетов пока нет."),
+    ("setup.choose_folder", "Выбрать папку пресетов…"),
+    ("folder.found", "Найдено пресетов: {} в {}"),
+    ("folder.use_existing", "Использовать имеющиеся"),
+    ("folder.download_fresh", "Скачать свежие пресеты"),
+    ("folder.none_found", "В {} нет пресетов. Скачать пресеты туда?"),
+    ("folder.download", "Скачать пресеты"),
+    ("folder.wrong", "В этой папке есть еще посторонние элементы ({}) — возможно, выбрана не та папка."),
+    ("name.label", "Имя проекта:"),
+    ("name.placeholder", "Имя проекта"),
+    ("name.placeholder_last", "Имя проекта (последнее: {})"),
+    ("name.invalid", "Некорректное имя"),
+    ("target.will_create", "Папка будет создана"),
+    ("target.empty", "Папка существует, но пуста"),
+    ("target.not_empty", "Папка существует и не пуста — включите перезапись"),
+    ("target.overwrite", "Перезаписать"),
+    ("fields.title", "Поля:"),
+    ("fields.required", "обязательно"),
+    ("options.title", "Опции:"),
+    ("options.recommended_on", "рекомендуется: вкл."),
+    ("options.recommended_off", "рекомендуется: выкл."),
+    ("options.apply_recommended", "Применить рекомендуемые настройки"),
+    ("options.git_init", "Инициализировать git-репозиторий"),
+    ("options.open_in_editor", "Открыть в редакторе после создания"),
+    ("temp.found", "Найдены временные файлы, оставшиеся от прошлых запусков: {} ({}). Удалить их?"),
+    ("repair.prompt", "Поврежденные пресеты ({}): {}. Скачать архив пресетов заново и восстановить только их?"),
+    ("repair.repair", "Восстановить"),
+    ("repair.not_now", "Не сейчас"),
+    ("destination.label", "Расположение:"),
+    ("destination.placeholder", "Папка, в которой будет создан проект"),
+    ("destination.tooltip", "Введите путь, выберите папку или перетащите ее на окно"),
+    ("destination.browse", "Обзор…"),
+    ("form.create", "Создать проект"),
+    ("form.preview", "Предпросмотр"),
+    ("form.reset", "Сбросить форму"),
+    ("timing.header", "Замеры"),
+    ("log.save", "Сохранить лог…"),
+    ("log.copy", "Копировать лог"),
+    ("log.copy_last_error", "Копировать последнюю ошибку"),
+    ("timing.slowest", ", дольше всего: {} {} с"),
+    ("import.replace_prompt", "Пресет '{}' уже установлен. Заменить его на {}?"),
+    ("import.replace", "Заменить"),
+    ("import.prompt", "Импортировать пресет из папки или ZIP-архива с files_config.json"),
+    ("import.folder", "Папка…"),
+    ("import.zip", "ZIP-архив…"),
+    ("drop.prompt", "{} похоже на пресет. Импортировать его в папку пресетов?"),
+    ("drop.import", "Импортировать как пресет"),
+    ("drop.destination", "Использовать как расположение"),
+    ("readme.preview", "Предпросмотр README"),
+    ("large.over_limit", "Проект займет на диске {}, больше лимита {}. Самые большие файлы:"),
+    ("large.files", "В проекте есть большие файлы: {} шт., всего {}."),
+    ("large.hard_link", "жесткая ссылка"),
+    ("large.copy", "копия"),
+    ("large.case", "⚠ '{}' и '{}' различаются только регистром"),
+    ("large.create_anyway", "Все равно создать"),
+    ("case.warning", "⚠ Пути различаются только регистром и совпадут на Windows/macOS: {}"),
+    ("success.no_warnings", "без предупреждений"),
+    ("success.warnings", "предупреждений: {}, см. лог"),
+    ("success.missing", "⚠ Проект создан без части шаблонов: {}"),
+    ("success.hide_details", "Скрыть подробности"),
+    ("success.view_details", "Подробнее"),
+    ("success.rerun", "Повторить недостающие шаблоны"),
+    ("success.created", "Проект '{}' создан"),
+    ("success.summary", "Готово за {} с, файлов шаблонов: {}, {}, {}"),
+    ("success.open_folder", "Открыть папку"),
+    ("success.copy_path", "Копировать путь"),
+    ("success.new_project", "Новый проект"),
+    ("recent.header", "Недавние проекты ({})"),
+    ("recent.missing", "не найден"),
+    ("recent.clear", "Очистить историю"),
+    ("busy.scanning", "Сканирование папки пресетов…"),
+    ("busy.processing", "Выполняется..."),
+    ("busy.cancelling", "Отмена…"),
+    ("quit.prompt", "Выполняется операция. Отменить ее и выйти?"),
+    ("quit.quit", "Отменить и выйти"),
+    ("quit.keep_working", "Продолжить работу"),
+    ("about.title", "О программе"),
+    ("about.settings", "Настройки…"),
+    ("about.copy_diagnostics", "Копировать диагностику"),
+    ("settings.title", "Настройки"),
+    ("settings.theme", "Тема:"),
+    ("settings.scale", "Масштаб:"),
+    ("settings.language", "Язык:"),
+    ("settings.sort", "Сортировать пресеты:"),
+    ("settings.check_updates", "Ежедневно проверять обновления"),
+    ("settings.notifications", "Показывать системные уведомления"),
+    ("settings.project_log", "Сохранять лог в созданный проект"),
+    ("settings.cloud_warning", "Предупреждать о папках облачной синхронизации"),
+    ("settings.tray", "Сворачивать в трей при закрытии"),
+    ("settings.tray_unavailable", "Сворачивать в трей при закрытии (трей недоступен)"),
+    ("settings.min_busy", "Показывать прогресс не меньше:"),
+    ("settings.busy_off", "Выкл."),
+    ("settings.seconds", "{} с"),
+    ("settings.editor", "Команда редактора:"),
+    ("settings.editor_hint", "{path} заменяется папкой проекта; аргументы с пробелами заключайте в кавычки"),
+    ("settings.presets_url", "URL архива пресетов:"),
+    ("settings.save_url", "Сохранить URL"),
+    ("settings.reset_url", "По умолчанию"),
+    ("settings.sources", "Дополнительные источники пресетов (изменяются в settings.toml):"),
+    ("theme.system", "Системная"),
+    ("theme.dark", "Темная"),
+    ("theme.light", "Светлая"),
+    ("sort.by_id", "Имя папки"),
+    ("sort.by_name", "Название"),
+    ("sort.by_last_used", "Недавно использованные"),
+    ("status.no_folder", "Папка пресетов не задана"),
+    ("status.refreshed", "Обновлено {}"),
+    ("status.not_refreshed", "Еще не обновлялось"),
+    ("status.presets", "Пресетов: {}"),
+    ("status.working", "Выполняется…"),
+    ("status.ready", "Готово"),
+    ("status.about", "О программе"),
+    ("update.available", "Доступна новая версия: {} ({} → {})"),
+    ("update.open", "Открыть страницу релиза"),
+    ("update.dismiss", "Скрыть"),
+    ("error.copy_details", "Копировать подробности"),
+    ("problem.busy", "Выполняется другая операция"),
+    ("problem.no_presets_folder", "Папка пресетов не задана"),
+    ("problem.no_preset", "Пресет не выбран"),
+    ("problem.missing_name", "Введите имя проекта"),
+    ("problem.invalid_name", "Некорректное имя проекта"),
+    ("problem.missing_field", "Не заполнено обязательное поле '{}'"),
+    ("problem.no_destination", "Выберите папку для проекта"),
+    ("problem.invalid_destination", "Расположение: {}"),
+    ("problem.app_too_old", "Пресету нужна версия приложения {} или новее (запущена {})"),
+    ("notify.created", "Проект создан"),
+    ("notify.created_body", "Проект '{}' успешно создан!"),
+    ("notify.failed", "Не удалось создать проект"),
+    ("notify.failed_body", "Не удалось создать проект '{}'"),
+    ("notify.presets_updated", "Пресеты обновлены"),
+    ("notify.up_to_date", "Все пресеты актуальны."),
+    ("notify.one_changed", "Изменен 1 пресет."),
+    ("notify.changed", "Изменено пресетов: {}."),
+    ("notify.presets_failed", "Не удалось обновить пресеты"),
+    ("notify.presets_failed_body", "Не удалось обновить пресеты: {}"),
+    ("readme.created", "Создано"),
+    ("readme.next_steps", "Что дальше"),
+];
@@ -0,0 +1,228 @@
+//! # Модуль манифеста проекта
+//!
+//! При создании проекта в его корень записывается файл `.ai_project_template.json`
+//! с идентификатором пресета и SHA-256 каждого записанного файла (шаблоны, пустые
+//! файлы, README). По манифесту `verify_project` определяет, какие файлы остались
+//! без изменений, какие изменены или удалены и какие добавлены пользователем.
+//!
+//! Хеши считаются по байтам в момент записи, без повторного чтения файла.
+//! Большие файлы копируются и хешируются потоково, блоками по `HASH_BUFFER_SIZE`.
+
+use crate::session_log::PROJECT_LOG_FILE_NAME;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// Имя файла манифеста в корне проекта
+pub const MANIFEST_FILE_NAME: &str = ".ai_project_template.json";
+
+/// Размер блока при потоковом копировании и хешировании
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Манифест созданного проекта
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ProjectManifest {
+    /// Идентификатор пресета, из которого создан проект
+    pub preset_id: String,
+    /// Версия приложения, записавшего манифест
+    pub app_version: String,
+    /// Время последней записи манифеста
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// SHA-256 (hex) записанных файлов по пути относительно проекта (через `/`)
+    pub files: BTreeMap<String, String>,
+    /// Значения полей пресета, с которыми создан проект (для повторного копирования
+    /// шаблонов с подстановкой)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl ProjectManifest {
+    /// Создать пустой манифест для пресета
+    pub fn new(preset_id: impl Into<String>) -> Self {
+        Self {
+            preset_id: preset_id.into(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            updated_at: None,
+            files: BTreeMap::new(),
+            fields: BTreeMap::new(),
+        }
+    }
+
+    /// Загрузить манифест проекта
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(manifest))`, `Ok(None)` если манифеста нет, `Err` если он поврежден
+    pub fn load(project_path: &Path) -> Result<Option<Self>, String> {
+        let path = project_path.join(MANIFEST_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read manifest {:?}: {}", path, e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse manifest {:?}: {}", path, e))
+    }
+
+    /// Сохранить манифест в корень проекта
+    pub fn save(&mut self, project_path: &Path) -> Result<(), String> {
+        self.app_version = env!("CARGO_PKG_VERSION").to_string();
+        self.updated_at = Some(chrono::Utc::now());
+        let path = project_path.join(MANIFEST_FILE_NAME);
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write manifest {:?}: {}", path, e))
+    }
+
+    /// Запомнить хеш файла по пути относительно проекта
+    pub fn record(&mut self, relative_path: &Path, hash: String) {
+        self.files.insert(manifest_key(relative_path), hash);
+    }
+}
+
+/// Ключ манифеста для пути относительно проекта (`/` на всех платформах)
+pub fn manifest_key(relative_path: &Path) -> String {
+    relative_path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// SHA-256 данных в виде hex строки
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+/// Записать данные в файл и вернуть их SHA-256
+///
+/// Ошибка возвращается как `io::Error`, чтобы вызывающий код мог распознать
+/// временные ошибки (см. `retry::retry_io`) и добавить к ней контекст.
+pub fn write_hashed(path: &Path, bytes: &[u8]) -> io::Result<String> {
+    fs::write(path, bytes)?;
+    Ok(hash_bytes(bytes))
+}
+
+/// Скопировать файл, считая SHA-256 по ходу копирования
+///
+/// Файл читается блоками, поэтому большие шаблоны не загружаются в память целиком.
+/// Файл назначения создается заново, поэтому операцию можно безопасно повторить.
+pub fn copy_hashed(source: &Path, destination: &Path) -> io::Result<String> {
+    let mut input = fs::File::open(source)?;
+    let mut output = fs::File::create(destination)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read])?;
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Посчитать SHA-256 файла (потоково)
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let mut input = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    hash_reader(&mut input)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))
+}
+
+/// Посчитать SHA-256 потока (например, элемента ZIP архива), читая его блоками
+pub fn hash_reader<R: Read>(input: &mut R) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Преобразовать хеш в hex строку
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Результат сверки проекта с манифестом (пути относительно проекта, через `/`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Файлы, совпадающие с записанными
+    pub unchanged: Vec<String>,
+    /// Файлы, содержимое которых изменилось
+    pub modified: Vec<String>,
+    /// Файлы из манифеста, которых больше нет
+    pub missing: Vec<String>,
+    /// Файлы, которых нет в манифесте (добавлены после создания; манифест и лог
+    /// создания проекта не учитываются)
+    pub untracked: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Проект совпадает с манифестом (лишние файлы не учитываются)
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Сверить файлы проекта с его манифестом
+///
+/// # Arguments
+///
+/// * `project_path` - корневая директория проекта
+///
+/// # Returns
+///
+/// `Ok(VerifyReport)` или `Err`, если манифеста нет, он поврежден или проект
+/// не удалось прочитать
+pub fn verify_project(project_path: &Path) -> Result<VerifyReport, String> {
+    let manifest = ProjectManifest::load(project_path)?
+        .ok_or_else(|| format!("No {} in {:?}", MANIFEST_FILE_NAME, project_path))?;
+
+    let mut report = VerifyReport::default();
+    for (key, expected) in &manifest.files {
+        let path = project_path.join(key);
+        if !path.is_file() {
+            report.missing.push(key.clone());
+        } else if hash_file(&path)? == *expected {
+            report.unchanged.push(key.clone());
+        } else {
+            report.modified.push(key.clone());
+        }
+    }
+
+    let mut present = Vec::new();
+    collect_files(project_path, project_path, &mut present)?;
+    report.untracked = present.into_iter()
+        .filter(|key| key != MANIFEST_FILE_NAME && key != PROJECT_LOG_FILE_NAME && !manifest.files.contains_key(key))
+        .collect();
+    report.untracked.sort();
+
+    Ok(report)
+}
+
+/// Рекурсивно собрать ключи всех файлов директории
+pub(crate) fn collect_files(root: &Path, dir: &Path, keys: &mut Vec<String>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {:?}: {}", dir, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read directory {:?}: {}", dir, e))?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(root, &path, keys)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            keys.push(manifest_key(relative));
+        }
+    }
+    Ok(())
+}
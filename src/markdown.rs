@@ -0,0 +1,109 @@
+//! # Модуль отображения Markdown
+//!
+//! Упрощенный рендерер Markdown в виджеты Iced для предпросмотра README.
+//! Поддерживает заголовки (`#`..`######`), маркированные списки (`-`, `*`),
+//! нумерованные списки и жирный текст (`**...**`). Остальные конструкции
+//! показываются как обычный текст без изменений.
+
+use iced::font::{Font, Weight};
+use iced::widget::{column, text, Column, Row};
+use iced::Element;
+
+/// Блок Markdown документа
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// Заголовок: уровень (1-6) и текст
+    Heading(usize, String),
+    /// Элемент списка: маркер ("•" или "1.") и текст
+    ListItem(String, String),
+    /// Строка обычного текста (без разбора)
+    Paragraph(String),
+    /// Пустая строка
+    Blank,
+}
+
+/// Разобрать Markdown на блоки (построчно)
+pub fn parse(source: &str) -> Vec<Block> {
+    source.lines().map(parse_line).collect()
+}
+
+/// Разобрать одну строку
+fn parse_line(line: &str) -> Block {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() {
+        return Block::Blank;
+    }
+
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        return Block::Heading(level, trimmed[level..].trim().to_string());
+    }
+
+    if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+        return Block::ListItem("•".to_string(), item.to_string());
+    }
+
+    if let Some((number, item)) = trimmed.split_once(". ") {
+        if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+            return Block::ListItem(format!("{}.", number), item.to_string());
+        }
+    }
+
+    Block::Paragraph(line.to_string())
+}
+
+/// Разбить строку на фрагменты по маркерам `**` (нечетные фрагменты - жирные)
+///
+/// Незакрытый маркер оставляется в тексте как есть.
+fn inline_segments(line: &str) -> Vec<(String, bool)> {
+    let parts: Vec<&str> = line.split("**").collect();
+    if parts.len().is_multiple_of(2) {
+        return vec![(line.to_string(), false)];
+    }
+    parts.iter()
+        .enumerate()
+        .filter(|(_, part)| !part.is_empty())
+        .map(|(i, part)| (part.to_string(), i % 2 == 1))
+        .collect()
+}
+
+/// Построить строку текста с учетом жирных фрагментов
+fn inline<'a, Message: 'a>(line: &str, size: f32) -> Element<'a, Message> {
+    let segments = inline_segments(line);
+    if segments.iter().all(|(_, bold)| !bold) {
+        return text(line.to_string()).size(size).into();
+    }
+    let bold_font = Font { weight: Weight::Bold, ..Font::DEFAULT };
+    Row::with_children(segments.into_iter().map(|(segment, bold)| {
+        let widget = text(segment).size(size);
+        if bold { widget.font(bold_font).into() } else { widget.into() }
+    }))
+    .into()
+}
+
+/// Построить виджеты для Markdown документа
+///
+/// # Arguments
+///
+/// * `source` - исходный Markdown
+/// * `base_size` - размер обычного текста
+pub fn view<'a, Message: 'a>(source: &str, base_size: f32) -> Element<'a, Message> {
+    let heading_font = Font { weight: Weight::Bold, ..Font::DEFAULT };
+    let blocks = parse(source).into_iter().map(|block| -> Element<'a, Message> {
+        match block {
+            Block::Heading(level, title) => {
+                let size = base_size + (7 - level.min(6)) as f32 * 2.0;
+                text(title).size(size).font(heading_font).into()
+            }
+            Block::ListItem(marker, item) => Row::new()
+                .push(text(marker).size(base_size))
+                .push(inline(&item, base_size))
+                .spacing(6)
+                .padding([0, 0, 0, 12])
+                .into(),
+            Block::Paragraph(line) => inline(&line, base_size),
+            Block::Blank => column![].height(base_size / 2.0).into(),
+        }
+    });
+    Column::with_children(blocks).spacing(2).into()
+}
@@ -0,0 +1,155 @@
+//! # Модуль меню приложения
+//!
+//! Описание пунктов меню File/Edit/Help и их клавиатурных сокращений. Меню
+//! отображается полосой внутри окна (iced 0.12 не дает доступа к системному
+//! меню), а в узком окне сворачивается в одну кнопку "≡".
+//!
+//! Сокращения используют `Cmd` на macOS и `Ctrl` на остальных платформах;
+//! подписи в меню и обработка клавиш берутся из одной таблицы `MenuEntry::shortcut`.
+
+use ai_project_template::locale::tr;
+use iced::keyboard::{key::Named, Key, Modifiers};
+
+/// Выпадающее меню
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuKind {
+    /// Меню File
+    File,
+    /// Меню Edit
+    Edit,
+    /// Меню Help
+    Help,
+    /// Все пункты в одном списке (кнопка "≡" в узком окне)
+    Compact,
+}
+
+impl MenuKind {
+    /// Меню полосы в порядке отображения
+    pub const BAR: [MenuKind; 3] = [MenuKind::File, MenuKind::Edit, MenuKind::Help];
+
+    /// Заголовок меню
+    pub fn title(self) -> &'static str {
+        match self {
+            MenuKind::File => tr("menu.file"),
+            MenuKind::Edit => tr("menu.edit"),
+            MenuKind::Help => tr("menu.help"),
+            MenuKind::Compact => "≡",
+        }
+    }
+
+    /// Пункты меню в порядке отображения
+    pub fn entries(self) -> &'static [MenuEntry] {
+        match self {
+            MenuKind::File => &[
+                MenuEntry::NewProject,
+                MenuEntry::ChangePresetsFolder,
+                MenuEntry::RefreshPresets,
+                MenuEntry::VerifyPresets,
+                MenuEntry::ImportPreset,
+                MenuEntry::OpenLogFile,
+                MenuEntry::Quit,
+            ],
+            MenuKind::Edit => &[MenuEntry::Settings],
+            MenuKind::Help => &[MenuEntry::PresetDoctor, MenuEntry::ExportSchema, MenuEntry::About],
+            MenuKind::Compact => &MenuEntry::ALL,
+        }
+    }
+}
+
+/// Пункт меню
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEntry {
+    /// Очистить форму для нового проекта
+    NewProject,
+    /// Выбрать другую директорию пресетов
+    ChangePresetsFolder,
+    /// Обновить пресеты из GitHub
+    RefreshPresets,
+    /// Проверить файлы пресетов из архива и восстановить поврежденные
+    VerifyPresets,
+    /// Импортировать пресет из папки или ZIP-архива
+    ImportPreset,
+    /// Настройки (тема, масштаб, обновления, трей)
+    Settings,
+    /// Открыть файл лога текущей сессии
+    OpenLogFile,
+    /// Объяснить, почему директория пресета не появляется в списке
+    PresetDoctor,
+    /// Сохранить JSON Schema `files_config.json` для редакторов
+    ExportSchema,
+    /// Диалог About
+    About,
+    /// Выйти из приложения
+    Quit,
+}
+
+impl MenuEntry {
+    /// Все пункты в порядке отображения
+    pub const ALL: [MenuEntry; 11] = [
+        MenuEntry::NewProject,
+        MenuEntry::ChangePresetsFolder,
+        MenuEntry::RefreshPresets,
+        MenuEntry::VerifyPresets,
+        MenuEntry::ImportPreset,
+        MenuEntry::OpenLogFile,
+        MenuEntry::Settings,
+        MenuEntry::PresetDoctor,
+        MenuEntry::ExportSchema,
+        MenuEntry::About,
+        MenuEntry::Quit,
+    ];
+
+    /// Подпись пункта
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuEntry::NewProject => tr("menu.new_project"),
+            MenuEntry::ChangePresetsFolder => tr("menu.change_folder"),
+            MenuEntry::RefreshPresets => tr("menu.refresh"),
+            MenuEntry::VerifyPresets => tr("menu.verify"),
+            MenuEntry::ImportPreset => tr("menu.import"),
+            MenuEntry::Settings => tr("menu.settings"),
+            MenuEntry::OpenLogFile => tr("menu.open_log"),
+            MenuEntry::PresetDoctor => tr("menu.doctor"),
+            MenuEntry::ExportSchema => tr("menu.export_schema"),
+            MenuEntry::About => tr("menu.about"),
+            MenuEntry::Quit => tr("menu.quit"),
+        }
+    }
+
+    /// Клавиша сокращения: символ (с `Cmd`/`Ctrl`) или `F1`
+    fn shortcut(self) -> Option<&'static str> {
+        match self {
+            MenuEntry::NewProject => Some("n"),
+            MenuEntry::RefreshPresets => Some("r"),
+            MenuEntry::Settings => Some(","),
+            MenuEntry::OpenLogFile => Some("l"),
+            MenuEntry::About => Some("F1"),
+            MenuEntry::Quit => Some("q"),
+            MenuEntry::ChangePresetsFolder | MenuEntry::VerifyPresets | MenuEntry::ImportPreset
+            | MenuEntry::PresetDoctor | MenuEntry::ExportSchema => None,
+        }
+    }
+
+    /// Подпись сокращения для меню ("Ctrl+N", "⌘N", "F1")
+    pub fn shortcut_label(self) -> Option<String> {
+        let key = self.shortcut()?;
+        if key == "F1" {
+            return Some(key.to_string());
+        }
+        let prefix = if cfg!(target_os = "macos") { "⌘" } else { "Ctrl+" };
+        Some(format!("{}{}", prefix, key.to_uppercase()))
+    }
+
+    /// Найти пункт меню по нажатой клавише
+    pub fn from_key(key: &Key, modifiers: Modifiers) -> Option<MenuEntry> {
+        match key {
+            Key::Named(Named::F1) => Some(MenuEntry::About),
+            Key::Character(c) if modifiers.command() => {
+                let c = c.to_lowercase();
+                Self::ALL.into_iter()
+                    .find(|entry| entry.shortcut() == Some(c.as_str()))
+            }
+            _ => None,
+        }
+    }
+}
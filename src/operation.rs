@@ -0,0 +1,279 @@
+//! # Модуль этапов длительных операций
+//!
+//! `OperationPlan` описывает операцию из нескольких этапов (загрузка, распаковка,
+//! повторное сканирование пресетов; этапы создания проекта) с весами. По прогрессу
+//! текущего этапа план считает общий прогресс операции и подпись этапа для
+//! индикатора выполнения. Для каждого вида этапа задано, что означает отмена
+//! (`CancelPolicy`), а флаг отмены проверяется `check_cancelled`. Модуль не зависит от GUI.
+
+use crate::presets::PresetConfig;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Ошибка операции, прерванной флагом отмены (см. `check_cancelled`)
+pub const CANCELLED: &str = "Cancelled";
+
+/// Проверить флаг отмены операции
+///
+/// Длительные операции (загрузка и распаковка пресетов, создание проекта) вызывают
+/// проверку между шагами; флаг устанавливает GUI (`Msg::CancelOperation`).
+///
+/// # Returns
+///
+/// `Err(CANCELLED)`, если флаг установлен; без флага операция не отменяется
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use ai_project_template::operation::{check_cancelled, CANCELLED};
+///
+/// let cancel = AtomicBool::new(false);
+/// assert!(check_cancelled(Some(&cancel)).is_ok());
+/// cancel.store(true, Ordering::Relaxed);
+/// assert_eq!(check_cancelled(Some(&cancel)), Err(CANCELLED.to_string()));
+/// assert!(check_cancelled(None).is_ok());
+/// ```
+pub fn check_cancelled(cancel: Option<&AtomicBool>) -> Result<(), String> {
+    match cancel {
+        Some(flag) if flag.load(Ordering::Relaxed) => Err(CANCELLED.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Что происходит при отмене операции на этом этапе
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelPolicy {
+    /// Этап прерывается сразу, частичный результат удаляется (загрузка архива)
+    Abort,
+    /// Текущий элемент дописывается, затем этап останавливается (распаковка)
+    FinishEntry,
+    /// Уже созданное откатывается (создание проекта)
+    Rollback,
+}
+
+impl fmt::Display for CancelPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelPolicy::Abort => write!(f, "stops immediately"),
+            CancelPolicy::FinishEntry => write!(f, "stops after the current file"),
+            CancelPolicy::Rollback => write!(f, "removes what was created"),
+        }
+    }
+}
+
+/// Вид этапа: определяет, какие события прогресса к нему относятся, и политику отмены
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseKind {
+    /// Загрузка архива пресетов
+    Download,
+    /// Распаковка архива
+    Extract,
+    /// Повторное сканирование директорий пресетов
+    Rediscover,
+    /// Запись файлов проекта
+    Create,
+}
+
+impl PhaseKind {
+    /// Политика отмены этапа
+    pub fn cancel_policy(self) -> CancelPolicy {
+        match self {
+            PhaseKind::Download | PhaseKind::Rediscover => CancelPolicy::Abort,
+            PhaseKind::Extract => CancelPolicy::FinishEntry,
+            PhaseKind::Create => CancelPolicy::Rollback,
+        }
+    }
+}
+
+/// Этап операции
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+    /// Вид этапа
+    pub kind: PhaseKind,
+    /// Подпись для индикатора (например, `Downloading presets`)
+    pub label: String,
+    /// Доля этапа в общем прогрессе относительно других этапов
+    pub weight: f32,
+}
+
+impl Phase {
+    /// Создать этап
+    pub fn new(kind: PhaseKind, label: impl Into<String>, weight: f32) -> Self {
+        Self { kind, label: label.into(), weight: weight.max(0.0) }
+    }
+}
+
+/// Вес загрузки архива относительно его распаковки и сканирования
+const DOWNLOAD_WEIGHT: f32 = 6.0;
+const EXTRACT_WEIGHT: f32 = 3.0;
+const REDISCOVER_WEIGHT: f32 = 1.0;
+
+/// План операции из нескольких этапов
+///
+/// Этапы проходятся только вперед: событие прогресса этапа другого вида
+/// завершает текущий этап и переходит к следующему этапу этого вида.
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::operation::{CancelPolicy, OperationPlan, PhaseKind};
+///
+/// let mut plan = OperationPlan::refresh_presets(&[]);
+/// plan.report(PhaseKind::Download, 0.5);
+/// assert_eq!(plan.progress(), 0.3);
+/// assert_eq!(plan.label(), "Downloading presets (1 of 3)");
+///
+/// plan.report(PhaseKind::Extract, 1.0);
+/// assert_eq!(plan.progress(), 0.9);
+/// assert_eq!(plan.cancel_policy(), Some(CancelPolicy::FinishEntry));
+///
+/// // Более ранний этап не возвращается
+/// plan.report(PhaseKind::Download, 0.0);
+/// assert_eq!(plan.current().unwrap().kind, PhaseKind::Extract);
+///
+/// plan.finish();
+/// assert_eq!(plan.progress(), 1.0);
+/// assert_eq!(plan.cancel_policy(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationPlan {
+    phases: Vec<Phase>,
+    /// Индекс текущего этапа (`phases.len()` - операция завершена)
+    current: usize,
+    /// Прогресс текущего этапа, 0.0..=1.0
+    fraction: f32,
+}
+
+impl OperationPlan {
+    /// План из этапов `phases` (операция начинается с первого этапа)
+    pub fn new(phases: Vec<Phase>) -> Self {
+        Self { phases, current: 0, fraction: 0.0 }
+    }
+
+    /// Обновление пресетов: загрузка и распаковка основного архива и каждого
+    /// дополнительного источника `sources`, затем повторное сканирование
+    pub fn refresh_presets(sources: &[&str]) -> Self {
+        let mut phases = vec![
+            Phase::new(PhaseKind::Download, "Downloading presets", DOWNLOAD_WEIGHT),
+            Phase::new(PhaseKind::Extract, "Extracting presets", EXTRACT_WEIGHT),
+        ];
+        for source in sources {
+            phases.push(Phase::new(PhaseKind::Download, format!("Downloading {}", source), DOWNLOAD_WEIGHT));
+            phases.push(Phase::new(PhaseKind::Extract, format!("Extracting {}", source), EXTRACT_WEIGHT));
+        }
+        phases.push(Phase::new(PhaseKind::Rediscover, "Scanning presets", REDISCOVER_WEIGHT));
+        Self::new(phases)
+    }
+
+    /// Создание проекта по пресету `config`
+    ///
+    /// Этапы совпадают с шагами прогресса `create_project` (директории, шаблоны,
+    /// пустые файлы, README), вес этапа - число его шагов, поэтому прогресс
+    /// передается через `report_step`. Этапы без шагов не показываются.
+    pub fn create_project(config: &PresetConfig) -> Self {
+        let phases = [
+            ("Creating directories", config.directories.len()),
+            ("Copying templates", config.templates.len()),
+            ("Creating empty files", config.empty_files.len()),
+            ("Writing README", 1),
+        ];
+        Self::new(phases.into_iter()
+            .filter(|(_, steps)| *steps > 0)
+            .map(|(label, steps)| Phase::new(PhaseKind::Create, label, steps as f32))
+            .collect())
+    }
+
+    /// Этапы плана
+    pub fn phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    /// Текущий этап (`None` - операция завершена)
+    pub fn current(&self) -> Option<&Phase> {
+        self.phases.get(self.current)
+    }
+
+    /// Сообщить прогресс этапа вида `kind`
+    ///
+    /// Если текущий этап другого вида, план переходит к следующему этапу вида
+    /// `kind`; если такого этапа впереди нет, событие игнорируется.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - вид этапа, к которому относится событие
+    /// * `fraction` - выполненная доля этапа, 0.0..=1.0
+    pub fn report(&mut self, kind: PhaseKind, fraction: f32) {
+        if self.current().map(|phase| phase.kind) != Some(kind) {
+            let Some(offset) = self.phases.iter().skip(self.current + 1).position(|phase| phase.kind == kind) else {
+                return;
+            };
+            self.current += offset + 1;
+        }
+        self.fraction = fraction.clamp(0.0, 1.0);
+    }
+
+    /// Сообщить номер выполненного шага для плана, у которого вес этапа - число
+    /// его шагов (`create_project`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::operation::{OperationPlan, Phase, PhaseKind};
+    ///
+    /// let mut plan = OperationPlan::new(vec![
+    ///     Phase::new(PhaseKind::Create, "Creating directories", 2.0),
+    ///     Phase::new(PhaseKind::Create, "Writing README", 1.0),
+    /// ]);
+    /// plan.report_step(1);
+    /// assert_eq!(plan.label(), "Creating directories (1 of 2)");
+    /// plan.report_step(3);
+    /// assert_eq!(plan.label(), "Writing README (2 of 2)");
+    /// assert_eq!(plan.progress(), 1.0);
+    /// ```
+    pub fn report_step(&mut self, step: usize) {
+        let mut before = 0.0;
+        for (index, phase) in self.phases.iter().enumerate() {
+            let done = step as f32 - before;
+            if done <= phase.weight || index + 1 == self.phases.len() {
+                if index >= self.current {
+                    self.current = index;
+                    self.fraction = (done / phase.weight.max(1.0)).clamp(0.0, 1.0);
+                }
+                return;
+            }
+            before += phase.weight;
+        }
+    }
+
+    /// Завершить операцию (общий прогресс - 100%)
+    pub fn finish(&mut self) {
+        self.current = self.phases.len();
+        self.fraction = 0.0;
+    }
+
+    /// Общий прогресс операции, 0.0..=1.0: завершенные этапы плюс доля текущего
+    /// этапа, взвешенные весами этапов
+    pub fn progress(&self) -> f32 {
+        let total: f32 = self.phases.iter().map(|phase| phase.weight).sum();
+        if total <= 0.0 {
+            return if self.current >= self.phases.len() { 1.0 } else { 0.0 };
+        }
+        let done: f32 = self.phases.iter().take(self.current).map(|phase| phase.weight).sum();
+        let current = self.current().map_or(0.0, |phase| phase.weight * self.fraction);
+        ((done + current) / total).clamp(0.0, 1.0)
+    }
+
+    /// Подпись текущего этапа с его номером: `Extracting presets (2 of 3)`
+    pub fn label(&self) -> String {
+        match self.current() {
+            Some(phase) => format!("{} ({} of {})", phase.label, self.current + 1, self.phases.len()),
+            None => "Finished".to_string(),
+        }
+    }
+
+    /// Политика отмены текущего этапа (`None` - операция завершена)
+    pub fn cancel_policy(&self) -> Option<CancelPolicy> {
+        self.current().map(|phase| phase.kind.cancel_policy())
+    }
+}
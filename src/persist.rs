@@ -0,0 +1,148 @@
+//! # Модуль надежной записи файлов состояния
+//!
+//! Файлы состояния приложения (настройки, происхождение пресетов) перезаписываются
+//! часто, и сбой посреди `fs::write` оставляет обрезанный файл. `atomic_write`
+//! пишет данные во временный файл рядом с целевым, сбрасывает их на диск и
+//! переименовывает поверх целевого: читатель видит либо старое, либо новое
+//! содержимое целиком.
+//!
+//! `write_with_backup` дополнительно сохраняет копию `<имя>.bak` последней успешной
+//! записи, а `read_with_backup` возвращается к ней, если основной файл не читается
+//! или не разбирается (например, поврежден при сбое диска или неудачной ручной правке).
+
+use crate::retry::{retry_io, RetryPolicy};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Расширение, добавляемое к имени файла резервной копии
+pub const BACKUP_EXTENSION: &str = "bak";
+
+/// Путь резервной копии файла: `settings.toml` → `settings.toml.bak`
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, &format!(".{}", BACKUP_EXTENSION))
+}
+
+/// Путь с суффиксом, добавленным к имени файла
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Атомарно записать файл
+///
+/// Данные записываются во временный файл в той же директории (переименование
+/// атомарно только в пределах одной файловой системы), сбрасываются на диск
+/// (`sync_all`) и переименовываются поверх `path`. На Windows переименование
+/// заменяет существующий файл, но завершается ошибкой, пока файл открыт другим
+/// процессом (антивирус, индексатор): такие ошибки повторяются
+/// (`RetryPolicy::transient`). При ошибке временный файл удаляется, а `path`
+/// остается прежним.
+///
+/// # Returns
+///
+/// `Ok(())` или ошибка записи, сброса на диск или переименования
+pub fn atomic_write(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    atomic_write_from(path, &mut &bytes[..])
+}
+
+/// Атомарно записать в файл данные из `reader` (см. `atomic_write`)
+///
+/// Данные копируются потоком, без чтения в память целиком. Поскольку файл
+/// заменяется переименованием, а не перезаписывается на месте, жесткие ссылки
+/// на прежний файл сохраняют старое содержимое.
+pub fn atomic_write_from(path: &Path, reader: &mut impl io::Read) -> io::Result<()> {
+    let temp = with_suffix(path, &format!(".tmp-{}", std::process::id()));
+    let result = write_synced(&temp, reader).and_then(|()| {
+        retry_io(&RetryPolicy::transient(), "replace file", true, &mut Vec::new(), || fs::rename(&temp, path))
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+        return result;
+    }
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// Записать данные в новый файл и дождаться их записи на диск
+fn write_synced(path: &Path, reader: &mut impl io::Read) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    io::copy(reader, &mut file)?;
+    file.flush()?;
+    file.sync_all()
+}
+
+/// Сбросить на диск запись директории о переименованном файле (только Unix;
+/// на Windows директорию нельзя открыть как файл)
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            if let Ok(dir) = fs::File::open(parent) {
+                let _ = dir.sync_all();
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// Атомарно записать файл и сохранить его копию `<имя>.bak`
+///
+/// Копия обновляется только после успешной записи основного файла, поэтому она
+/// всегда содержит последнее записанное целиком содержимое. Ошибка записи копии
+/// не считается ошибкой: основной файл уже сохранен.
+pub fn write_with_backup(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    atomic_write(path, bytes)?;
+    let _ = atomic_write(&backup_path(path), bytes);
+    Ok(())
+}
+
+/// Прочитать и разобрать файл, при повреждении - его резервную копию
+///
+/// Если основной файл не читается или `parse` возвращает ошибку, читается
+/// `<имя>.bak` (см. `write_with_backup`); причина записывается в stderr.
+///
+/// # Arguments
+///
+/// * `path` - основной файл
+/// * `parse` - разбор содержимого файла
+///
+/// # Returns
+///
+/// Разобранное содержимое основного файла или копии; `None`, если не удалось ни то, ни другое
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::persist::{read_with_backup, write_with_backup};
+///
+/// let dir = std::env::temp_dir().join(format!("persist_doc_{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let path = dir.join("state.json");
+/// write_with_backup(&path, br#"{"count": 2}"#).unwrap();
+///
+/// // Сбой посреди записи другой программой: файл обрезан
+/// std::fs::write(&path, br#"{"cou"#).unwrap();
+///
+/// let parse = |content: &str| serde_json::from_str::<serde_json::Value>(content);
+/// let recovered = read_with_backup(&path, parse).unwrap();
+/// assert_eq!(recovered["count"], 2);
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn read_with_backup<T, E: fmt::Display>(path: &Path, parse: impl Fn(&str) -> Result<T, E>) -> Option<T> {
+    let error = match fs::read_to_string(path) {
+        Ok(content) => match parse(&content) {
+            Ok(value) => return Some(value),
+            Err(e) => e.to_string(),
+        },
+        Err(e) => e.to_string(),
+    };
+    let backup = backup_path(path);
+    let value = fs::read_to_string(&backup).ok().and_then(|content| parse(&content).ok())?;
+    eprintln!("Failed to read {:?} ({}), restored from {:?}", path, error, backup);
+    Some(value)
+}
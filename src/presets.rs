@@ -11,11 +11,25 @@
 //! Каждый пресет должен находиться в отдельной директории и содержать файл `files_config.json`
 //! с конфигурацией структуры проекта, шаблонов и полей.
 
+use crate::expression::eval_expression;
+use crate::integrity::IntegrityManifest;
+use crate::manifest::{collect_files, hash_bytes, hash_file, hash_reader, manifest_key};
+use crate::operation::check_cancelled;
+use crate::persist::{atomic_write, atomic_write_from};
+use crate::settings::{AppSettings, NetworkSettings, PresetSource, StorageRoot};
+use crate::update::parse_tag;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::env;
+use std::fmt;
 use std::fs;
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use unicode_normalization::UnicodeNormalization;
 
 /// URL для загрузки архива пресетов из GitHub
 pub const PRESETS_ZIP_URL: &str = "https://github.com/vladcraftcom/ai_prompt_presets/archive/refs/heads/main.zip";
@@ -23,11 +37,22 @@ pub const PRESETS_ZIP_URL: &str = "https://github.com/vladcraftcom/ai_prompt_pre
 /// Имя переменной окружения для хранения пути к директории пресетов
 pub const PRESETS_PATH_ENV_VAR: &str = "AI_PROJECT_TEMPLATE_PRESETS_PATH";
 
+/// Разделитель выбранных значений поля "multi_select" по умолчанию
+pub const MULTI_SELECT_SEPARATOR: &str = ", ";
+
+/// Поддерживаемые типы полей пресета (`FieldConfig::field_type`)
+pub const FIELD_TYPES: &[&str] = &["text", "textarea", "select", "multi_select"];
+
+/// Число видимых строк поля "textarea" по умолчанию
+pub const DEFAULT_TEXTAREA_ROWS: u16 = 4;
+
 /// Конфигурация пресета проекта
 ///
 /// Описывает структуру проекта, который будет создан на основе этого пресета.
 /// Загружается из файла `files_config.json` в директории пресета.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Обязательны только `preset_id`, `preset_name`, `description` и `directories`:
+/// остальные поля по умолчанию пустые и не записываются при сериализации.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct PresetConfig {
     #[serde(rename = "preset_id")]
     pub id: String,
@@ -35,32 +60,378 @@ pub struct PresetConfig {
     pub name: String,
     pub description: String,
     pub directories: Vec<String>,
+    /// Записывать `.gitkeep` в директории из `directories`, оставшиеся пустыми
+    /// после создания проекта (по умолчанию `false`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keep_empty_dirs: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub templates: Vec<TemplateConfig>,
-    #[serde(rename = "empty_files")]
+    #[serde(rename = "empty_files", default, skip_serializing_if = "Vec::is_empty")]
     pub empty_files: Vec<String>,
-    #[serde(rename = "readme_template")]
+    /// Шаблон README.md; пустой шаблон - README не генерируется
+    #[serde(rename = "readme_template", default, skip_serializing_if = "String::is_empty")]
     pub readme_template: String,
+    /// Генерировать README.md (по умолчанию `true`). `false` - для пресетов, шаблоны
+    /// которых уже содержат собственный README (например, наборы промптов)
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub generate_readme: bool,
+    /// Содержимое `.gitignore` проекта; пустая строка - файл не записывается
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub gitignore_template: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub fields: Vec<FieldConfig>,
+    /// Вычисляемые поля: значение выводится выражением из других полей и
+    /// подставляется как у обычного поля, но в форме не показывается
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub computed_fields: Vec<ComputedFieldConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub options: Vec<OptionConfig>,
+    /// Команды, выполняемые после создания проекта (`git init`, `npm install`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_commands: Vec<PostCommandConfig>,
+    /// Автор, версия и теги пресета
+    #[serde(default, skip_serializing_if = "PresetMetadata::is_empty")]
+    pub metadata: PresetMetadata,
+    /// Директория, из которой загружена конфигурация (заполняет `load_preset_config`).
+    /// Ее имя может отличаться от `preset_id` нормализацией Unicode или регистром
+    #[serde(skip)]
+    pub source_dir: Option<PathBuf>,
+}
+
+impl PresetConfig {
+    /// Копирует ли пресет шаблон в `README.md` проекта
+    pub fn has_readme_template_file(&self) -> bool {
+        self.templates.iter().any(|t| t.destination.trim_start_matches("./") == "README.md")
+    }
+
+    /// Генерирует ли `create_project` README.md из `readme_template`
+    ///
+    /// `false`, если генерация выключена (`generate_readme`), README копируется
+    /// из шаблона или `readme_template` пустой.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::presets::PresetConfig;
+    ///
+    /// let json = r#"{"preset_id": "bare", "preset_name": "Bare", "description": "", "directories": ["src"]}"#;
+    /// let config: PresetConfig = serde_json::from_str(json).unwrap();
+    /// assert!(config.templates.is_empty() && config.fields.is_empty());
+    /// assert!(!config.generates_readme());
+    /// // Пропущенные поля не появляются при сериализации
+    /// assert_eq!(
+    ///     serde_json::to_string(&config).unwrap(),
+    ///     r#"{"preset_id":"bare","preset_name":"Bare","description":"","directories":["src"]}"#
+    /// );
+    /// ```
+    pub fn generates_readme(&self) -> bool {
+        self.generate_readme && !self.readme_template.trim().is_empty() && !self.has_readme_template_file()
+    }
+
+    /// Директория пресета с файлами шаблонов
+    ///
+    /// Директория, найденная при загрузке (`source_dir`), а для конфигурации,
+    /// созданной не через `load_preset_config`, - `presets_dir/preset_id`.
+    pub fn source_dir(&self, presets_dir: &Path) -> PathBuf {
+        self.source_dir.clone().unwrap_or_else(|| presets_dir.join(&self.id))
+    }
+}
+
+/// Конфигурация пресета после нормализации (см. `normalize_preset_config`)
+#[derive(Debug, Clone)]
+pub struct NormalizedConfig {
+    /// Конфигурация без повторов, с отсортированными путями
+    pub config: PresetConfig,
+    /// Предупреждения (в формате строк лога `Warning: ...`)
+    pub warnings: Vec<String>,
+}
+
+/// Нормализовать конфигурацию пресета перед созданием проекта
+///
+/// Чистая функция: файловая система не читается.
+///
+/// - повторяющиеся `directories` и `empty_files` удаляются;
+/// - одинаковые шаблоны (тот же источник и назначение) оставляются в одном экземпляре,
+///   разные шаблоны с одним назначением - ошибка;
+/// - пустой файл, совпадающий с назначением шаблона, удаляется с предупреждением
+///   (используется шаблон);
+/// - директории, шаблоны (по назначению) и пустые файлы сортируются, поэтому
+///   лог и план не зависят от порядка объявления.
+///
+/// Пути сравниваются после замены `\` на `/`, удаления пробелов по краям компонентов
+/// и удаления `.` и пустых компонентов; сами пути в конфигурации не переписываются
+/// (остается первый объявленный вариант). Регистр учитывается: пути, различающиеся
+/// только регистром, находит `validation::case_collisions`.
+///
+/// # Returns
+///
+/// `Ok(NormalizedConfig)` или `Err`, если два разных шаблона пишут в один файл
+pub fn normalize_preset_config(config: &PresetConfig) -> Result<NormalizedConfig, String> {
+    let mut normalized = config.clone();
+    let mut warnings = Vec::new();
+
+    let mut seen = BTreeSet::new();
+    normalized.directories.retain(|dir| seen.insert(path_key(dir)));
+    normalized.directories.sort_by_key(|dir| path_key(dir));
+
+    let mut templates: Vec<TemplateConfig> = Vec::new();
+    for template in &config.templates {
+        let key = path_key(&template.destination);
+        match templates.iter().find(|t| path_key(&t.destination) == key) {
+            Some(existing) if path_key(&existing.source) == path_key(&template.source)
+                && existing.link == template.link => {}
+            Some(existing) => {
+                return Err(format!(
+                    "Templates '{}' and '{}' both write to '{}'",
+                    existing.source, template.source, template.destination
+                ));
+            }
+            None => templates.push(template.clone()),
+        }
+    }
+    templates.sort_by_key(|t| path_key(&t.destination));
+    let template_keys: BTreeSet<String> = templates.iter()
+        .map(|t| path_key(&t.destination))
+        .collect();
+    normalized.templates = templates;
+
+    let mut seen = BTreeSet::new();
+    normalized.empty_files.retain(|file| {
+        let key = path_key(file);
+        if !seen.insert(key.clone()) {
+            return false;
+        }
+        if template_keys.contains(&key) {
+            warnings.push(format!(
+                "Warning: Empty file '{}' is also a template destination; the template is used",
+                file
+            ));
+            return false;
+        }
+        true
+    });
+    normalized.empty_files.sort_by_key(|file| path_key(file));
+
+    Ok(NormalizedConfig { config: normalized, warnings })
+}
+
+/// Ключ пути проекта для сравнения (`docs\a.md`, `./docs/ a.md ` -> `docs/a.md`)
+fn path_key(path: &str) -> String {
+    path.replace('\\', "/")
+        .split('/')
+        .map(str::trim)
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Значение по умолчанию для флагов, включенных по умолчанию
+fn default_true() -> bool {
+    true
+}
+
+/// Пропустить при сериализации флаг со значением по умолчанию `true`
+fn is_true(value: &bool) -> bool {
+    *value
 }
 
 /// Конфигурация шаблона файла
 ///
 /// Описывает файл-шаблон, который будет скопирован из директории пресета
 /// в создаваемый проект.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct TemplateConfig {
     /// Имя файла-источника в директории пресета
     pub source: String,
     /// Имя файла-назначения в создаваемом проекте
     pub destination: String,
+    /// Создать жесткую ссылку вместо копии (для больших файлов).
+    /// Если ссылку создать нельзя (другая файловая система), файл копируется.
+    /// Связанный файл разделяет содержимое с шаблоном установленного пресета, поэтому
+    /// его нужно считать доступным только для чтения: правка в проекте изменит пресет
+    /// (и `integrity::verify_presets` сочтет его поврежденным). Обновление пресетов
+    /// заменяет файлы, а не перезаписывает их, поэтому ссылка просто разрывается.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub link: bool,
+    /// Подставить значения полей в содержимое файла (как в `readme_template`).
+    /// Файл должен быть текстом в UTF-8, иначе он копируется без изменений.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub substitute: bool,
+    /// Переводы строк в записанном файле (по умолчанию `Preserve` - файл копируется
+    /// как есть). С `Lf` и `CrLf` файл записывается как текст без BOM; файл, не
+    /// являющийся текстом в UTF-8, копируется без изменений.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_endings: Option<LineEnding>,
+}
+
+impl TemplateConfig {
+    /// Переводы строк, к которым приводится файл (`None` - сохранить как есть)
+    pub fn text_line_ending(&self) -> Option<LineEnding> {
+        self.line_endings.filter(|ending| *ending != LineEnding::Preserve)
+    }
+
+    /// Записывается ли файл как текст (подстановка или приведение переводов строк),
+    /// а не копируется побайтно
+    pub fn rewrites_content(&self) -> bool {
+        self.substitute || self.text_line_ending().is_some()
+    }
+}
+
+/// Переводы строк в файлах проекта
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::presets::LineEnding;
+///
+/// // Файл из Windows-редактора: BOM и CRLF
+/// let fixture = "\u{feff}# Notes\r\n\r\n- one\r\n- two\n";
+/// assert_eq!(LineEnding::Lf.normalize(fixture), "# Notes\n\n- one\n- two\n");
+/// assert_eq!(LineEnding::CrLf.normalize(fixture), "# Notes\r\n\r\n- one\r\n- two\r\n");
+/// assert_eq!(LineEnding::Preserve.normalize(fixture), "# Notes\r\n\r\n- one\r\n- two\n");
+///
+/// let ending: LineEnding = serde_json::from_str(r#""crlf""#).unwrap();
+/// assert_eq!(ending, LineEnding::CrLf);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    CrLf,
+    /// Оставить переводы строк источника
+    #[default]
+    Preserve,
+}
+
+impl LineEnding {
+    /// Удалить BOM в начале текста и привести переводы строк
+    /// (`Preserve` только удаляет BOM)
+    pub fn normalize(self, text: &str) -> String {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        match self {
+            LineEnding::Preserve => text.to_string(),
+            LineEnding::Lf => text.replace("\r\n", "\n"),
+            LineEnding::CrLf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Сведения об авторе и версии пресета (раздел `metadata` в `files_config.json`)
+///
+/// Все поля необязательны. `min_app_version` задает минимальную версию приложения,
+/// с которой пресет совместим: в более старой версии проект из него не создается.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct PresetMetadata {
+    /// Автор пресета
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    /// Версия пресета (semver, например `1.2.0`)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub version: String,
+    /// Страница пресета
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    /// Теги для фильтрации списка пресетов
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Минимальная версия приложения (semver)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_app_version: Option<String>,
+}
+
+impl PresetMetadata {
+    /// Не заполнено ни одно поле
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Краткая подпись для интерфейса: `by {author} v{version}`
+    ///
+    /// # Returns
+    ///
+    /// `None`, если не заданы ни автор, ни версия
+    pub fn summary(&self) -> Option<String> {
+        match (self.author.trim(), self.version.trim()) {
+            ("", "") => None,
+            (author, "") => Some(format!("by {}", author)),
+            ("", version) => Some(format!("v{}", version.trim_start_matches('v'))),
+            (author, version) => Some(format!("by {} v{}", author, version.trim_start_matches('v'))),
+        }
+    }
+
+    /// Есть ли у пресета тег (без учета регистра)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own.trim().eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// Минимальная версия приложения, если `app_version` ниже нее
+    ///
+    /// Версии, которые не разбираются как semver, не блокируют создание проекта.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_version` - версия запущенного приложения (`CARGO_PKG_VERSION`)
+    ///
+    /// # Returns
+    ///
+    /// `Some(min_app_version)`, если приложение слишком старое для пресета
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::presets::PresetMetadata;
+    ///
+    /// let metadata = PresetMetadata { min_app_version: Some("0.3.0".into()), ..Default::default() };
+    /// assert_eq!(metadata.unsupported_app_version("0.2.5"), Some("0.3.0"));
+    /// assert_eq!(metadata.unsupported_app_version("0.3.0"), None);
+    /// assert_eq!(PresetMetadata::default().unsupported_app_version("0.2.5"), None);
+    /// ```
+    pub fn unsupported_app_version(&self, app_version: &str) -> Option<&str> {
+        let required = self.min_app_version.as_deref()?;
+        let (Some(min), Some(current)) = (parse_tag(required), parse_tag(app_version)) else {
+            return None;
+        };
+        (current < min).then_some(required)
+    }
+}
+
+/// Команда, выполняемая после создания проекта
+///
+/// Запускается напрямую, без оболочки: `program` ищется в `PATH`, аргументы
+/// передаются как есть. Ошибка команды не прерывает создание проекта.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct PostCommandConfig {
+    /// Имя или путь программы
+    pub program: String,
+    /// Аргументы командной строки
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Рабочая директория относительно корня проекта (`None` - корень проекта)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Идентификатор опции пресета, которая должна быть включена для запуска команды
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub option_guard: Option<String>,
+}
+
+impl fmt::Display for PostCommandConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
 }
 
 /// Конфигурация динамического поля пресета
 ///
 /// Описывает поле ввода в UI, которое будет отображено при выборе пресета.
 /// Значение поля подставляется в шаблон README при создании проекта.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct FieldConfig {
     /// Уникальный идентификатор поля
     pub id: String,
@@ -68,22 +439,296 @@ pub struct FieldConfig {
     pub label: String,
     /// Обязательно ли заполнение поля
     pub required: bool,
-    /// Тип поля: "text" или "select"
+    /// Тип поля: "text", "textarea" (многострочный текст), "select" или "multi_select"
     #[serde(rename = "type")]
     pub field_type: String,
-    /// Опции для выпадающего списка (только для типа "select")
+    /// Опции для выпадающего списка или группы флажков ("select" и "multi_select")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<Vec<String>>,
+    /// Разделитель выбранных значений поля "multi_select" в подставляемом тексте
+    /// (`None` - `MULTI_SELECT_SEPARATOR`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub separator: Option<String>,
     /// Описание поля (опционально)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Начальное значение поля (опционально); пользователь может его изменить или очистить
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Идентификатор опции, при выключении которой поле скрывается (см. `field_visible`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depends_on_option: Option<String>,
+    /// Преобразование введенного значения перед подстановкой (`"slug"`, `"snake_case"` и т.д.)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<FieldTransform>,
+    /// Число видимых строк поля "textarea" (`None` - `DEFAULT_TEXTAREA_ROWS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u16>,
+}
+
+impl FieldConfig {
+    /// Заполнено ли поле значением `value`
+    ///
+    /// Значение из одних пробелов считается пустым. Значение select-поля со списком
+    /// опций должно совпадать с одной из опций, а у "multi_select" должна быть выбрана
+    /// хотя бы одна опция и только из списка. Единое правило для кнопки Create в GUI
+    /// и проверок `ProjectBuilder::build`.
+    pub fn is_filled(&self, value: Option<&String>) -> bool {
+        let Some(value) = value.filter(|v| !v.trim().is_empty()) else { return false };
+        match self.options {
+            Some(ref options) if self.field_type == "select" => options.contains(value),
+            Some(ref options) if self.is_multi_select() => {
+                self.selected_values(value).iter().all(|v| options.iter().any(|o| o == v))
+            }
+            _ => true,
+        }
+    }
+
+    /// Является ли поле группой флажков ("multi_select")
+    pub fn is_multi_select(&self) -> bool {
+        self.field_type == "multi_select"
+    }
+
+    /// Является ли поле многострочным ("textarea")
+    ///
+    /// Значение хранится в `dynamic_fields` одной строкой с переводами строк `\n`
+    /// и подставляется в шаблоны и README как есть.
+    pub fn is_textarea(&self) -> bool {
+        self.field_type == "textarea"
+    }
+
+    /// Число видимых строк поля "textarea" (не меньше одной)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::presets::{FieldConfig, DEFAULT_TEXTAREA_ROWS};
+    ///
+    /// let field: FieldConfig = serde_json::from_str(r#"{
+    ///     "id": "synopsis", "label": "Synopsis", "required": false, "type": "textarea", "rows": 8
+    /// }"#).unwrap();
+    /// assert!(field.is_textarea());
+    /// assert_eq!(field.textarea_rows(), 8);
+    ///
+    /// let field = FieldConfig { rows: None, ..field };
+    /// assert_eq!(field.textarea_rows(), DEFAULT_TEXTAREA_ROWS);
+    /// ```
+    pub fn textarea_rows(&self) -> u16 {
+        self.rows.unwrap_or(DEFAULT_TEXTAREA_ROWS).max(1)
+    }
+
+    /// Предупреждение о неизвестном типе поля (в формате строки лога)
+    ///
+    /// Поле неизвестного типа показывается как однострочное текстовое поле.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::presets::FieldConfig;
+    ///
+    /// let field: FieldConfig = serde_json::from_str(r#"{
+    ///     "id": "notes", "label": "Notes", "required": false, "type": "richtext"
+    /// }"#).unwrap();
+    /// assert_eq!(
+    ///     field.unknown_type_warning().unwrap(),
+    ///     "Warning: Field 'notes' has unknown type 'richtext', shown as a text input"
+    /// );
+    /// ```
+    pub fn unknown_type_warning(&self) -> Option<String> {
+        (!FIELD_TYPES.contains(&self.field_type.as_str())).then(|| format!(
+            "Warning: Field '{}' has unknown type '{}', shown as a text input",
+            self.id, self.field_type
+        ))
+    }
+
+    /// Разделитель выбранных значений поля "multi_select"
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or(MULTI_SELECT_SEPARATOR)
+    }
+
+    /// Выбранные значения поля "multi_select", записанного одной строкой
+    ///
+    /// Значения поля хранятся в `dynamic_fields` уже соединенными через `separator()`,
+    /// поэтому подставляются в шаблоны без дополнительной обработки.
+    pub fn selected_values<'v>(&self, value: &'v str) -> Vec<&'v str> {
+        value.split(self.separator())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .collect()
+    }
+
+    /// Включить или выключить опцию поля "multi_select"
+    ///
+    /// # Returns
+    ///
+    /// Новое значение поля: выбранные опции в порядке объявления через `separator()`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::presets::FieldConfig;
+    ///
+    /// let field: FieldConfig = serde_json::from_str(r#"{
+    ///     "id": "ci", "label": "CI", "required": false, "type": "multi_select",
+    ///     "options": ["github_actions", "gitlab_ci", "circleci"]
+    /// }"#).unwrap();
+    ///
+    /// let value = field.toggle_option("", "circleci", true);
+    /// let value = field.toggle_option(&value, "github_actions", true);
+    /// assert_eq!(value, "github_actions, circleci");
+    /// assert_eq!(field.toggle_option(&value, "circleci", false), "github_actions");
+    /// ```
+    pub fn toggle_option(&self, value: &str, option: &str, enabled: bool) -> String {
+        let selected = self.selected_values(value);
+        self.options.iter().flatten()
+            .filter(|o| if o.as_str() == option { enabled } else { selected.contains(&o.as_str()) })
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(self.separator())
+    }
+
+    /// Значение, которое подставляется в шаблоны вместо введенного `value`
+    /// (с примененным `transform`)
+    pub fn substituted_value(&self, value: &str) -> String {
+        match self.transform {
+            Some(transform) => transform.apply(value),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// Преобразование значения поля перед подстановкой в шаблоны
+///
+/// В форме хранится введенное значение, а в шаблоны, пути и README попадает
+/// преобразованное. Значение поля "multi_select" преобразуется целиком.
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::presets::FieldTransform;
+///
+/// let value = "My Cool Project";
+/// assert_eq!(FieldTransform::Slug.apply(value), "my-cool-project");
+/// assert_eq!(FieldTransform::Lowercase.apply(value), "my cool project");
+/// assert_eq!(FieldTransform::Uppercase.apply(value), "MY COOL PROJECT");
+/// assert_eq!(FieldTransform::SnakeCase.apply(value), "my_cool_project");
+/// assert_eq!(FieldTransform::CamelCase.apply(value), "myCoolProject");
+///
+/// // Границы слов - также переходы регистра
+/// assert_eq!(FieldTransform::SnakeCase.apply("parseHTTPServer v2"), "parse_http_server_v2");
+/// assert_eq!(FieldTransform::CamelCase.apply("user-id"), "userId");
+/// assert_eq!(FieldTransform::Slug.apply("  Ärger & Co.  "), "ärger-co");
+///
+/// let transform: FieldTransform = serde_json::from_str(r#""snake_case""#).unwrap();
+/// assert_eq!(transform, FieldTransform::SnakeCase);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldTransform {
+    /// Слова в нижнем регистре через `-`; все символы, кроме букв и цифр, - разделители
+    Slug,
+    /// Нижний регистр
+    Lowercase,
+    /// Верхний регистр
+    Uppercase,
+    /// `camelCase`: первое слово в нижнем регистре, остальные с заглавной буквы
+    CamelCase,
+    /// `snake_case`: слова в нижнем регистре через `_`
+    SnakeCase,
+}
+
+impl FieldTransform {
+    /// Преобразовать значение
+    pub fn apply(self, value: &str) -> String {
+        match self {
+            FieldTransform::Slug => value.split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join("-"),
+            FieldTransform::Lowercase => value.to_lowercase(),
+            FieldTransform::Uppercase => value.to_uppercase(),
+            FieldTransform::CamelCase => split_words(value).iter().enumerate()
+                .map(|(i, word)| if i == 0 { word.to_lowercase() } else { capitalize(word) })
+                .collect(),
+            FieldTransform::SnakeCase => split_words(value).iter()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+        }
+    }
+}
+
+/// Разбить значение на слова для `CamelCase` и `SnakeCase`
+///
+/// Разделители - символы, не являющиеся буквами или цифрами, а также переходы к
+/// заглавной букве после строчной или цифры (`myCool`) и последняя заглавная
+/// аббревиатуры перед строчной (`HTTPServer` → `HTTP`, `Server`).
+fn split_words(value: &str) -> Vec<String> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Слово с заглавной первой буквой и строчными остальными
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+/// Конфигурация вычисляемого поля пресета
+///
+/// Поле без виджета: значение вычисляется при создании проекта после значений
+/// обычных полей (`computed_field_values`), а плейсхолдеры `{ID}`/`{id}` заменяются
+/// так же, как у обычных полей. Вычисляемые поля вычисляются по порядку, поэтому
+/// выражение может ссылаться на объявленные выше вычисляемые поля.
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::presets::ComputedFieldConfig;
+///
+/// let json = r#"{"id": "package_name", "expression": "slug(@{project_name})"}"#;
+/// let computed: ComputedFieldConfig = serde_json::from_str(json).unwrap();
+/// assert_eq!(computed.expression, "slug(@{project_name})");
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ComputedFieldConfig {
+    /// Идентификатор поля (имя плейсхолдера)
+    pub id: String,
+    /// Выражение: `@{field_id}`, строки, `slug()`, `upper()`, `lower()`, `year()`,
+    /// `date()` и конкатенация `+` (см. модуль `expression`)
+    pub expression: String,
 }
 
 /// Конфигурация опции пресета
 ///
 /// Описывает флаг/чекбокс, который будет отображен в UI при выборе пресета.
 /// Опции используются для настройки поведения при создании проекта.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct OptionConfig {
     /// Уникальный идентификатор опции
     pub id: String,
@@ -91,14 +736,405 @@ pub struct OptionConfig {
     pub label: String,
     /// Значение по умолчанию
     pub default: bool,
+    /// Рекомендуемое значение, если оно отличается от `default` (например, опция
+    /// выключена по умолчанию, но рекомендуется новичкам)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended: Option<bool>,
     /// Описание опции (опционально)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Идентификатор управляющей опции: эта опция показывается, только если
+    /// управляющая включена (см. `option_visible`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<String>,
+}
+
+impl OptionConfig {
+    /// Рекомендуемое значение опции (`recommended`, иначе `default`)
+    pub fn recommended_value(&self) -> bool {
+        self.recommended.unwrap_or(self.default)
+    }
+}
+
+/// Заполнить значениями по умолчанию поля, для которых еще нет значения
+///
+/// Уже введенные значения, в том числе пустые (пользователь очистил поле),
+/// не перезаписываются: `default` задает только начальное значение.
+///
+/// # Arguments
+///
+/// * `config` - конфигурация пресета
+/// * `values` - значения полей (field_id -> value), изменяются на месте
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::presets::{apply_field_defaults, PresetConfig};
+///
+/// let config: PresetConfig = serde_json::from_str(r#"{
+///     "preset_id": "demo", "preset_name": "Demo", "description": "",
+///     "directories": [], "templates": [], "empty_files": [], "options": [],
+///     "fields": [
+///         {"id": "license", "label": "License", "required": false, "type": "text", "default": "MIT"},
+///         {"id": "author", "label": "Author", "required": false, "type": "text"},
+///         {"id": "team", "label": "Team", "required": false, "type": "text", "default": "core"}
+///     ]
+/// }"#).unwrap();
+///
+/// let mut values = HashMap::from([("team".to_string(), String::new())]);
+/// apply_field_defaults(&config, &mut values);
+/// assert_eq!(values.get("license").map(String::as_str), Some("MIT"));
+/// assert_eq!(values.get("author"), None);
+/// assert_eq!(values.get("team").map(String::as_str), Some("")); // очищенное поле остается пустым
+/// ```
+pub fn apply_field_defaults(
+    config: &PresetConfig,
+    values: &mut std::collections::HashMap<String, String>,
+) {
+    for field in &config.fields {
+        if let Some(ref default) = field.default {
+            values.entry(field.id.clone()).or_insert_with(|| default.clone());
+        }
+    }
+}
+
+/// Применить рекомендуемые настройки пресета
+///
+/// Опции получают `recommended_value()`, поля с `default` - значение по умолчанию
+/// (в отличие от `apply_field_defaults`, введенные значения перезаписываются).
+/// Поля без `default` не меняются.
+///
+/// # Returns
+///
+/// `true`, если хотя бы одно значение изменилось
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::presets::{apply_recommended_settings, PresetConfig};
+///
+/// let config: PresetConfig = serde_json::from_str(r#"{
+///     "preset_id": "demo", "preset_name": "Demo", "description": "", "directories": [],
+///     "fields": [{"id": "license", "label": "License", "required": false, "type": "text", "default": "MIT"}],
+///     "options": [
+///         {"id": "tests", "label": "Tests", "default": false, "recommended": true},
+///         {"id": "docs", "label": "Docs", "default": true}
+///     ]
+/// }"#).unwrap();
+///
+/// let mut fields = HashMap::from([("license".to_string(), "GPL".to_string())]);
+/// let mut options = HashMap::from([("docs".to_string(), false)]);
+/// assert!(apply_recommended_settings(&config, &mut fields, &mut options));
+/// assert_eq!(fields["license"], "MIT");
+/// assert_eq!(options, HashMap::from([("tests".to_string(), true), ("docs".to_string(), true)]));
+/// assert!(!apply_recommended_settings(&config, &mut fields, &mut options));
+/// ```
+pub fn apply_recommended_settings(
+    config: &PresetConfig,
+    fields: &mut std::collections::HashMap<String, String>,
+    options: &mut std::collections::HashMap<String, bool>,
+) -> bool {
+    let mut changed = false;
+    for option in &config.options {
+        let value = option.recommended_value();
+        changed |= options.insert(option.id.clone(), value) != Some(value);
+    }
+    for field in &config.fields {
+        if let Some(ref default) = field.default {
+            changed |= fields.insert(field.id.clone(), default.clone()).as_ref() != Some(default);
+        }
+    }
+    changed
+}
+
+/// Показывается ли опция `option_id` при текущих значениях опций
+///
+/// Опция с `depends_on` показывается, только если управляющая опция сама показана
+/// и включена (значение из `options`, иначе ее `default`). Опция, зависящая от
+/// несуществующей опции или от самой себя по кругу, не показывается.
+pub fn option_visible(
+    config: &PresetConfig,
+    option_id: &str,
+    options: &std::collections::HashMap<String, bool>,
+) -> bool {
+    let mut current = option_id;
+    // Цепочка без циклов не длиннее числа опций
+    for _ in 0..=config.options.len() {
+        let Some(option) = config.options.iter().find(|o| o.id == current) else { return false };
+        let Some(ref parent_id) = option.depends_on else { return true };
+        let Some(parent) = config.options.iter().find(|o| &o.id == parent_id) else { return false };
+        if !options.get(&parent.id).copied().unwrap_or(parent.default) {
+            return false;
+        }
+        current = &parent.id;
+    }
+    false
+}
+
+/// Вернуть скрытые опции (`option_visible`) к значениям по умолчанию
+///
+/// Вызывается после изменения опций: значение зависимой опции, скрытой вместе с
+/// выключенной управляющей опцией, не должно попасть в создание проекта. Скрытость
+/// проверяется по всей цепочке, поэтому сброс распространяется на опции, зависящие
+/// от зависимых.
+///
+/// # Returns
+///
+/// Идентификаторы опций, значение которых изменилось, в порядке объявления
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::presets::{option_visible, reset_hidden_options, PresetConfig};
+///
+/// let config: PresetConfig = serde_json::from_str(r#"{
+///     "preset_id": "demo", "preset_name": "Demo", "description": "", "directories": [],
+///     "options": [
+///         {"id": "docker", "label": "Add Docker support", "default": false},
+///         {"id": "multi_arch", "label": "Build multi-arch image", "default": false, "depends_on": "docker"},
+///         {"id": "push", "label": "Push image", "default": false, "depends_on": "multi_arch"}
+///     ]
+/// }"#).unwrap();
+///
+/// let mut options = HashMap::from([
+///     ("docker".to_string(), true),
+///     ("multi_arch".to_string(), true),
+///     ("push".to_string(), true),
+/// ]);
+/// assert!(option_visible(&config, "push", &options));
+/// assert!(reset_hidden_options(&config, &mut options).is_empty());
+///
+/// // Выключение управляющей опции сбрасывает всю цепочку
+/// options.insert("docker".to_string(), false);
+/// assert!(!option_visible(&config, "multi_arch", &options));
+/// assert_eq!(reset_hidden_options(&config, &mut options), ["multi_arch", "push"]);
+/// assert_eq!(options["multi_arch"], false);
+/// assert_eq!(options["push"], false);
+/// ```
+pub fn reset_hidden_options(
+    config: &PresetConfig,
+    options: &mut std::collections::HashMap<String, bool>,
+) -> Vec<String> {
+    let hidden: Vec<&OptionConfig> = config.options.iter()
+        .filter(|option| !option_visible(config, &option.id, options))
+        .collect();
+    let mut reset = Vec::new();
+    for option in hidden {
+        if options.insert(option.id.clone(), option.default) != Some(option.default) {
+            reset.push(option.id.clone());
+        }
+    }
+    reset
+}
+
+/// Показывается ли поле `field` при текущих значениях опций
+///
+/// Поле с `depends_on_option` показывается, только если управляющая опция сама
+/// показана (`option_visible`) и включена (значение из `options`, иначе ее `default`).
+/// Поле, зависящее от несуществующей опции, не показывается. Скрытое поле не
+/// обязательно для заполнения, а его плейсхолдеры заменяются пустой строкой
+/// (`visible_field_values`).
+pub fn field_visible(
+    config: &PresetConfig,
+    field: &FieldConfig,
+    options: &std::collections::HashMap<String, bool>,
+) -> bool {
+    let Some(ref guard) = field.depends_on_option else { return true };
+    let Some(option) = config.options.iter().find(|o| &o.id == guard) else { return false };
+    option_visible(config, guard, options) && options.get(guard).copied().unwrap_or(option.default)
+}
+
+/// Значения полей с примененными преобразованиями (`FieldConfig::transform`)
+///
+/// Значения, для которых нет поля в конфигурации, возвращаются без изменений.
+pub fn transformed_field_values(
+    config: &PresetConfig,
+    fields: &std::collections::HashMap<String, String>,
+) -> std::collections::HashMap<String, String> {
+    fields.iter()
+        .map(|(id, value)| {
+            let value = match config.fields.iter().find(|field| &field.id == id) {
+                Some(field) => field.substituted_value(value),
+                None => value.clone(),
+            };
+            (id.clone(), value)
+        })
+        .collect()
+}
+
+/// Значения полей для подстановки в шаблоны, README и манифест
+///
+/// Значения скрытых полей (`field_visible`) заменяются пустой строкой: введенное
+/// раньше значение остается в форме на случай повторного включения опции, но в
+/// проект не попадает.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::presets::{field_visible, visible_field_values, PresetConfig};
+///
+/// let config: PresetConfig = serde_json::from_str(r#"{
+///     "preset_id": "demo", "preset_name": "Demo", "description": "", "directories": [],
+///     "fields": [
+///         {"id": "author", "label": "Author", "required": true, "type": "text"},
+///         {"id": "image_tag", "label": "Docker image tag", "required": true, "type": "text",
+///          "depends_on_option": "docker"}
+///     ],
+///     "options": [{"id": "docker", "label": "Add Docker support", "default": false}]
+/// }"#).unwrap();
+/// let fields = HashMap::from([
+///     ("author".to_string(), "Ann".to_string()),
+///     ("image_tag".to_string(), "demo:1.0".to_string()),
+/// ]);
+///
+/// let options = HashMap::new();
+/// assert!(!field_visible(&config, &config.fields[1], &options));
+/// let values = visible_field_values(&config, &fields, &options);
+/// assert_eq!(values["author"], "Ann");
+/// assert_eq!(values["image_tag"], "");
+///
+/// let options = HashMap::from([("docker".to_string(), true)]);
+/// assert_eq!(visible_field_values(&config, &fields, &options)["image_tag"], "demo:1.0");
+/// ```
+pub fn visible_field_values(
+    config: &PresetConfig,
+    fields: &std::collections::HashMap<String, String>,
+    options: &std::collections::HashMap<String, bool>,
+) -> std::collections::HashMap<String, String> {
+    let mut values = fields.clone();
+    for field in &config.fields {
+        if !field_visible(config, field, options) {
+            values.insert(field.id.clone(), String::new());
+        }
+    }
+    values
+}
+
+/// Добавить к значениям полей значения вычисляемых полей (`computed_fields`)
+///
+/// Выражения вычисляются по порядку объявления; в них доступны поля пресета (без
+/// значения - пустая строка), `project_name` и объявленные выше вычисляемые поля.
+///
+/// # Returns
+///
+/// Значения полей вместе с вычисляемыми или `Err` с идентификатором поля, выражение
+/// которого не вычисляется
+pub fn computed_field_values(
+    config: &PresetConfig,
+    project_name: &str,
+    fields: &std::collections::HashMap<String, String>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let mut values = fields.clone();
+    if config.computed_fields.is_empty() {
+        return Ok(values);
+    }
+    let mut context = values.clone();
+    for field in &config.fields {
+        context.entry(field.id.clone()).or_default();
+    }
+    context.insert("project_name".to_string(), project_name.to_string());
+    for computed in &config.computed_fields {
+        let value = eval_expression(&computed.expression, &context)
+            .map_err(|e| format!("Computed field '{}': {}", computed.id, e))?;
+        context.insert(computed.id.clone(), value.clone());
+        values.insert(computed.id.clone(), value);
+    }
+    Ok(values)
+}
+
+/// Итоговые значения полей для подстановки: скрытые поля очищены
+/// (`visible_field_values`), применены `transform` (`transformed_field_values`)
+/// и добавлены вычисляемые поля (`computed_field_values`)
+///
+/// Используется `create_project`, проверкой путей перед созданием и предпросмотром
+/// README в GUI.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::presets::{resolved_field_values, PresetConfig};
+///
+/// let config: PresetConfig = serde_json::from_str(r#"{
+///     "preset_id": "demo", "preset_name": "Demo", "description": "", "directories": [],
+///     "fields": [{"id": "org", "label": "Organization", "required": true, "type": "text", "transform": "lowercase"}],
+///     "computed_fields": [
+///         {"id": "package_name", "expression": "slug(@{project_name})"},
+///         {"id": "module", "expression": "@{org} + '.' + @{package_name}"}
+///     ]
+/// }"#).unwrap();
+/// let fields = HashMap::from([("org".to_string(), "Acme".to_string())]);
+///
+/// let values = resolved_field_values(&config, "My App", &fields, &HashMap::new()).unwrap();
+/// assert_eq!(values["package_name"], "my-app");
+/// assert_eq!(values["module"], "acme.my-app");
+/// ```
+pub fn resolved_field_values(
+    config: &PresetConfig,
+    project_name: &str,
+    fields: &std::collections::HashMap<String, String>,
+    options: &std::collections::HashMap<String, bool>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let values = transformed_field_values(config, &visible_field_values(config, fields, options));
+    computed_field_values(config, project_name, &values)
+}
+
+/// Удалить значения select-полей, которых больше нет среди опций поля
+///
+/// После обновления пресета список опций может измениться, а сохраненное значение -
+/// остаться прежним и попасть в шаблоны. Значения, совпадающие с одной из опций,
+/// сохраняются. У полей "multi_select" удаляются только исчезнувшие опции.
+///
+/// # Arguments
+///
+/// * `config` - конфигурация пресета
+/// * `values` - значения полей (field_id -> value), изменяются на месте
+///
+/// # Returns
+///
+/// Список удаленных значений (field_id, value) в порядке объявления полей
+pub fn reconcile_select_values(
+    config: &PresetConfig,
+    values: &mut std::collections::HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut dropped = Vec::new();
+    for field in &config.fields {
+        let Some(ref options) = field.options else { continue };
+        if field.is_multi_select() {
+            // Исчезнувшие опции убираются, остальные выбранные значения сохраняются
+            let Some(value) = values.get(&field.id) else { continue };
+            let (kept, removed): (Vec<&str>, Vec<&str>) = field.selected_values(value).into_iter()
+                .partition(|v| options.iter().any(|o| o == v));
+            if !removed.is_empty() {
+                let removed = removed.join(field.separator());
+                let kept = kept.join(field.separator());
+                values.insert(field.id.clone(), kept);
+                dropped.push((field.id.clone(), removed));
+            }
+            continue;
+        }
+        if field.field_type != "select" {
+            continue;
+        }
+        let stale = values.get(&field.id)
+            .is_some_and(|value| !value.is_empty() && !options.contains(value));
+        if stale {
+            if let Some(value) = values.remove(&field.id) {
+                dropped.push((field.id.clone(), value));
+            }
+        }
+    }
+    dropped
 }
 
 /// Получить путь по умолчанию для директории пресетов
 ///
-/// Возвращает путь `{HOME}/Documents/ai_prompt_presets` на всех платформах.
+/// Возвращает путь `{HOME}/Documents/ai_prompt_presets` на всех платформах,
+/// а в портативном режиме - `ai_prompt_presets` рядом с исполняемым файлом.
 ///
 /// # Returns
 ///
@@ -109,6 +1145,9 @@ pub struct OptionConfig {
 /// - На Unix системах использует переменную `HOME`
 /// - На Windows использует `USERPROFILE` как fallback, если `HOME` не задана
 pub fn get_default_presets_path() -> PathBuf {
+    if let Some(StorageRoot::Portable { executable_dir, .. }) = StorageRoot::current() {
+        return executable_dir.join("ai_prompt_presets");
+    }
     let home = env::var("HOME")
         .or_else(|_| env::var("USERPROFILE")) // Windows fallback
         .unwrap_or_else(|_| ".".to_string());
@@ -116,6 +1155,9 @@ pub fn get_default_presets_path() -> PathBuf {
     PathBuf::from(home).join("Documents").join("ai_prompt_presets")
 }
 
+/// Имя файла с путем к пресетам прежних версий (путь теперь хранится в `settings.toml`)
+const PRESETS_PATH_FILE_NAME: &str = "presets_path.txt";
+
 /// Сохранить путь к пресетам в глобальное пространство имен ОС
 ///
 /// Сохраняет путь к директории пресетов так, чтобы он был доступен при следующем запуске приложения.
@@ -123,8 +1165,12 @@ pub fn get_default_presets_path() -> PathBuf {
 /// # Platform-specific implementation
 ///
 /// - **Windows**: Использует команду `setx` для установки переменной окружения пользователя.
-///   Если `setx` недоступен, сохраняет в конфиг-файл как fallback.
-/// - **Linux/macOS**: Сохраняет путь в файл `~/.config/ai_project_template/presets_path.txt`
+///   Если `setx` завершился с ошибкой, сохраняет в конфиг-файл как fallback.
+/// - **Linux/macOS**: Сохраняет путь в `presets_dir` файла настроек
+///   `~/.config/ai_project_template/settings.toml`
+/// - **Портативный режим** (все платформы): `setx` не вызывается, путь сохраняется
+///   в `data/settings.toml` рядом с исполняемым файлом - относительно директории
+///   исполняемого файла, если пресеты лежат внутри нее
 ///
 /// # Arguments
 ///
@@ -134,192 +1180,919 @@ pub fn get_default_presets_path() -> PathBuf {
 ///
 /// `Ok(())` если путь успешно сохранен, иначе `Err` с описанием ошибки
 pub fn save_presets_path_to_global_namespace(path: &Path) -> Result<(), String> {
+    let root = StorageRoot::current()
+        .ok_or_else(|| "Could not determine config directory".to_string())?;
+
     #[cfg(target_os = "windows")]
     {
-        // Для Windows используем переменную окружения пользователя
-        // Это работает без необходимости работы с реестром
-        use std::process::Command;
-        let path_str = path.to_string_lossy().to_string();
-        
-        // Устанавливаем переменную окружения через setx (только для текущего пользователя)
-        // Это сохраняет её перманентно, но доступна только в новых процессах
-        // Альтернатива: использовать winreg crate для реестра
-        let output = Command::new("setx")
-            .args(&["AI_PROJECT_TEMPLATE_PRESETS_PATH", &path_str])
-            .output()
-            .map_err(|e| format!("Failed to run setx: {}. Note: setx may not be in PATH.", e))?;
-        
-        if !output.status.success() {
+        if !root.is_portable() {
+            // Для Windows используем переменную окружения пользователя
+            // Это работает без необходимости работы с реестром
+            use std::process::Command;
+            let path_str = path.to_string_lossy().to_string();
+
+            // Устанавливаем переменную окружения через setx (только для текущего пользователя)
+            // Это сохраняет её перманентно, но доступна только в новых процессах
+            // Альтернатива: использовать winreg crate для реестра
+            let output = Command::new("setx")
+                .args(&[PRESETS_PATH_ENV_VAR, &path_str])
+                .output()
+                .map_err(|e| format!("Failed to run setx: {}. Note: setx may not be in PATH.", e))?;
+
+            if output.status.success() {
+                return Ok(());
+            }
             // Fallback: сохранить в конфиг файл
-            return save_to_config_file(path);
         }
-        
-        Ok(())
     }
     
-    #[cfg(any(target_os = "linux", target_os = "macos"))]
-    {
-        save_to_config_file(path)
-    }
+    save_to_config_file(&root, path)
 }
 
-/// Сохранить путь в конфигурационный файл (Linux/macOS)
+/// Сохранить путь в `presets_dir` файла настроек (`AppSettings::set_presets_path`)
 ///
-/// Создает файл `~/.config/ai_project_template/presets_path.txt` с путем к пресетам.
-#[cfg(any(target_os = "linux", target_os = "macos"))]
-fn save_to_config_file(path: &Path) -> Result<(), String> {
-    let home = env::var("HOME")
-        .map_err(|_| "HOME environment variable not set")?;
-    
-    let config_path = PathBuf::from(home)
-        .join(".config")
-        .join("ai_project_template");
-    
-    fs::create_dir_all(&config_path)
-        .map_err(|e| format!("Failed to create config dir: {}", e))?;
-    
-    let config_file = config_path.join("presets_path.txt");
-    fs::write(&config_file, path.to_string_lossy().as_ref())
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
-    
+/// Файл `presets_path.txt` прежних версий после этого удаляется.
+fn save_to_config_file(root: &StorageRoot, path: &Path) -> Result<(), String> {
+    let mut settings = AppSettings::load();
+    settings.set_presets_path(path);
+    settings.save()?;
+    fs::remove_file(root.dir().join(PRESETS_PATH_FILE_NAME)).ok();
     Ok(())
 }
 
-/// Сохранить путь в конфигурационный файл (Windows fallback)
+/// Загрузить путь к пресетам из глобального пространства имен ОС
 ///
-/// Создает файл `%USERPROFILE%\.config\ai_project_template\presets_path.txt` с путем к пресетам.
-/// Используется как fallback если команда `setx` недоступна.
-#[cfg(target_os = "windows")]
-fn save_to_config_file(path: &Path) -> Result<(), String> {
-    // Для Windows также сохраняем в конфиг файл как fallback
-    let home = env::var("USERPROFILE")
-        .or_else(|_| env::var("HOME"))
-        .map_err(|_| "Could not determine home directory")?;
+/// Пытается загрузить путь к директории пресетов, сохраненный ранее.
+/// Проверяет сначала переменную окружения (для текущей сессии),
+/// затем `presets_dir` в файле настроек и `presets_path.txt` прежних версий.
+/// В портативном режиме переменная окружения игнорируется, а относительный
+/// путь разрешается от директории исполняемого файла.
+///
+/// # Returns
+///
+/// `Some(PathBuf)` если путь найден, иначе `None`
+pub fn load_presets_path_from_global_namespace() -> Option<PathBuf> {
+    let root = StorageRoot::current()?;
+
+    // Сначала проверяем переменную окружения (актуальная для текущей сессии)
+    if !root.is_portable() {
+        if let Ok(path) = env::var(PRESETS_PATH_ENV_VAR) {
+            return Some(PathBuf::from(path));
+        }
+    }
     
-    let config_path = PathBuf::from(home)
-        .join(".config")
-        .join("ai_project_template");
+    // Затем проверяем файл настроек (работает на всех платформах)
+    if let Some(path) = AppSettings::load().presets_path() {
+        return Some(path);
+    }
     
-    fs::create_dir_all(&config_path)
-        .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    // Файл прежних версий, если путь еще не перенесен в настройки
+    let content = fs::read_to_string(root.dir().join(PRESETS_PATH_FILE_NAME)).ok()?;
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let path = PathBuf::from(trimmed);
+    match root {
+        StorageRoot::Portable { executable_dir, .. } if path.is_relative() => Some(executable_dir.join(path)),
+        _ => Some(path),
+    }
+}
+
+/// Имя файла JSON Schema конфигурации пресета, предлагаемое при экспорте
+pub const PRESET_SCHEMA_FILE_NAME: &str = "files_config.schema.json";
+
+/// JSON Schema файла `files_config.json`
+///
+/// Схема строится из типов `PresetConfig` и вложенных конфигураций и учитывает их
+/// атрибуты serde (`rename`, `default`), поэтому не расходится с разбором файла.
+/// Редакторы используют ее для автодополнения и проверки, если в конфигурации есть
+/// ссылка `"$schema"` (при загрузке пресета этот ключ игнорируется).
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::presets::{preset_config_schema, PresetConfig};
+///
+/// let schema = preset_config_schema();
+/// let properties = schema["properties"].as_object().unwrap();
+/// let required: Vec<&str> = schema["required"].as_array().unwrap()
+///     .iter().map(|v| v.as_str().unwrap()).collect();
+/// assert_eq!(required, ["description", "directories", "preset_id", "preset_name"]);
+///
+/// // Все ключи, которые пишет сериализация, описаны в схеме
+/// let json = r##"{
+///     "$schema": "./files_config.schema.json",
+///     "preset_id": "full", "preset_name": "Full", "description": "", "directories": ["src"],
+///     "keep_empty_dirs": true, "generate_readme": false, "readme_template": "# {PROJECT_NAME}",
+///     "gitignore_template": "target/", "empty_files": ["TODO.md"],
+///     "templates": [{"source": "a.md", "destination": "docs/a.md", "link": true, "substitute": true}],
+///     "fields": [{"id": "genre", "label": "Genre", "required": false, "type": "select", "options": ["a"]}],
+///     "options": [{"id": "ci", "label": "CI", "default": false, "recommended": true}],
+///     "post_commands": [{"program": "git", "args": ["init"], "option_guard": "ci"}]
+/// }"##;
+/// let config: PresetConfig = serde_json::from_str(json).unwrap();
+/// let value = serde_json::to_value(&config).unwrap();
+/// for key in value.as_object().unwrap().keys() {
+///     assert!(properties.contains_key(key), "{} is missing from the schema", key);
+/// }
+/// ```
+pub fn preset_config_schema() -> serde_json::Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(PresetConfig))
+        .expect("JSON Schema is always serializable");
+    // Ссылка на схему в самом файле конфигурации
+    if let Some(properties) = schema.get_mut("properties").and_then(|p| p.as_object_mut()) {
+        properties.insert("$schema".to_string(), serde_json::json!({ "type": "string" }));
+    }
+    schema
+}
+
+/// Записать JSON Schema конфигурации пресета (`preset_config_schema`) в файл
+///
+/// # Returns
+///
+/// `Ok(())` или `Err` с описанием ошибки записи
+pub fn export_preset_config_schema(path: &Path) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&preset_config_schema())
+        .map_err(|e| format!("Failed to serialize schema: {}", e))?;
+    atomic_write(path, (json + "\n").as_bytes())
+        .map_err(|e| format!("Failed to write schema {:?}: {}", path, e))
+}
+
+/// Загрузить конфигурацию пресета из файла
+///
+/// Читает и парсит JSON файл `files_config.json` из директории пресета.
+///
+/// # Arguments
+///
+/// * `presets_dir` - корневая директория со всеми пресетами
+/// * `preset_id` - идентификатор пресета (имя директории)
+///
+/// # Returns
+///
+/// `Ok(PresetConfig)` если конфигурация успешно загружена и распарсена,
+/// иначе `Err` с описанием ошибки
+///
+/// # Errors
+///
+/// Возвращает ошибку если:
+/// - файл `files_config.json` не существует
+/// - файл не может быть прочитан
+/// - JSON не валиден или не соответствует структуре `PresetConfig`
+pub fn load_preset_config(presets_dir: &Path, preset_id: &str) -> Result<PresetConfig, String> {
+    let preset_dir = find_preset_dir(presets_dir, preset_id)
+        .unwrap_or_else(|| presets_dir.join(preset_id));
+    let config_path = preset_dir.join("files_config.json");
     
-    let config_file = config_path.join("presets_path.txt");
-    fs::write(&config_file, path.to_string_lossy().as_ref())
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read preset config from {:?}: {}", config_path, e))?;
     
-    Ok(())
+    let mut config: PresetConfig = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse preset config: {}", e))?;
+    config.source_dir = Some(preset_dir);
+    Ok(config)
 }
 
-/// Загрузить путь к пресетам из глобального пространства имен ОС
+/// Совпадают ли имена пресетов после нормализации Unicode (NFC) и без учета регистра
 ///
-/// Пытается загрузить путь к директории пресетов, сохраненный ранее.
-/// Проверяет сначала переменную окружения (для текущей сессии),
-/// затем конфигурационный файл (для постоянного хранения).
+/// macOS может хранить имя директории в NFD (`e` + комбинируемый акут), а `preset_id`
+/// в конфигурации обычно записан в NFC (`é`): байты различаются, а имя - нет.
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::presets::same_preset_name;
+///
+/// assert!(same_preset_name("Cafe\u{301}", "café"));
+/// assert!(!same_preset_name("cafe", "café"));
+/// ```
+pub fn same_preset_name(a: &str, b: &str) -> bool {
+    let key = |name: &str| name.nfc().collect::<String>().to_lowercase();
+    key(a) == key(b)
+}
+
+/// Разделитель идентификатора пресета и имени источника (`software@company`)
+pub const PRESET_SOURCE_SEPARATOR: char = '@';
+
+/// Идентификатор пресета дополнительного источника, совпавшего по идентификатору
+/// с уже найденным пресетом
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::presets::source_preset_id;
+///
+/// assert_eq!(source_preset_id("software", "company"), "software@company");
+/// ```
+pub fn source_preset_id(preset_id: &str, source: &str) -> String {
+    format!("{}{}{}", preset_id, PRESET_SOURCE_SEPARATOR, source)
+}
+
+/// Найти директорию пресета по идентификатору
+///
+/// Сначала проверяется `presets_dir/preset_id`, затем директория, имя которой
+/// совпадает с идентификатором по `same_preset_name` (например, сохраненный в
+/// манифесте `preset_id` в NFC и директория в NFD). Идентификатор с суффиксом
+/// источника (`software@company`, см. `source_preset_id`) ищется без суффикса.
 ///
 /// # Returns
 ///
-/// `Some(PathBuf)` если путь найден, иначе `None`
-pub fn load_presets_path_from_global_namespace() -> Option<PathBuf> {
-    // Сначала проверяем переменную окружения (актуальная для текущей сессии)
-    if let Ok(path) = env::var(PRESETS_PATH_ENV_VAR) {
-        return Some(PathBuf::from(path));
+/// Путь найденной директории или `None`
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::command::plan_project;
+/// use ai_project_template::presets::{find_preset_dir, load_preset_config};
+/// use std::fs;
+///
+/// let presets = std::env::temp_dir().join(format!("ai_project_template_nfd_{}", std::process::id()));
+/// // Директория в NFD, preset_id в конфигурации - в NFC
+/// let dir = presets.join("Cafe\u{301}");
+/// fs::create_dir_all(&dir).unwrap();
+/// fs::write(dir.join("prompt.md"), "# Prompt").unwrap();
+/// fs::write(dir.join("files_config.json"), r#"{
+///     "preset_id": "Café", "preset_name": "Café", "description": "", "directories": [],
+///     "templates": [{"source": "prompt.md", "destination": "prompt.md"}]
+/// }"#).unwrap();
+///
+/// assert!(find_preset_dir(&presets, "Café").is_some());
+/// let config = load_preset_config(&presets, "Café").unwrap();
+/// // Шаблоны берутся из найденной директории, а не из presets/Café
+/// let plan = plan_project(&presets, &config);
+/// assert!(plan.files[0].source.exists());
+///
+/// fs::remove_dir_all(&presets).unwrap();
+/// ```
+pub fn find_preset_dir(presets_dir: &Path, preset_id: &str) -> Option<PathBuf> {
+    let exact = presets_dir.join(preset_id);
+    if exact.is_dir() {
+        return Some(exact);
     }
+    let normalized = fs::read_dir(presets_dir).ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.is_dir() && path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| same_preset_name(name, preset_id))
+        });
+    normalized.or_else(|| {
+        let (base, _) = preset_id.rsplit_once(PRESET_SOURCE_SEPARATOR)?;
+        find_preset_dir(presets_dir, base)
+    })
+}
+
+/// Максимальное число просматриваемых директорий при обнаружении пресетов по умолчанию
+pub const DEFAULT_MAX_SCANNED_DIRS: usize = 500;
+
+/// Число посторонних элементов, начиная с которого директория пресетов
+/// считается выбранной по ошибке (например, вся папка Documents)
+pub const WRONG_FOLDER_ENTRY_THRESHOLD: usize = 50;
+
+/// Текст ошибки отмененного сканирования
+pub const SCAN_CANCELLED: &str = "Preset scan cancelled";
+
+/// Ограничения сканирования директории пресетов
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Сколько директорий просмотреть, прежде чем остановиться
+    pub max_dirs: usize,
+    /// Флаг отмены: сканирование прерывается с ошибкой `SCAN_CANCELLED`
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self { max_dirs: DEFAULT_MAX_SCANNED_DIRS, cancel: None }
+    }
+}
+
+impl ScanOptions {
+    /// Без ограничений (CLI, проверка пресетов)
+    pub fn unbounded() -> Self {
+        Self { max_dirs: usize::MAX, cancel: None }
+    }
+
+    /// Запрошена ли отмена
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+}
+
+/// Результат сканирования директории пресетов
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PresetScan {
+    /// Идентификаторы пресетов и директории, в которых они найдены
+    pub presets: Vec<(String, PathBuf)>,
+    /// Элементы основной директории, не являющиеся пресетами (файлы и директории
+    /// без `files_config.json`)
+    pub non_preset_entries: usize,
+    /// Сканирование остановлено по лимиту `max_dirs` - часть пресетов могла не попасть в список
+    pub truncated: bool,
+    /// Имя дополнительного источника по идентификатору пресета (пресеты основной и
+    /// пользовательской директорий сюда не попадают)
+    pub sources: BTreeMap<String, String>,
+}
+
+impl PresetScan {
+    /// Похоже, что директория пресетов выбрана по ошибке
+    pub fn looks_like_wrong_folder(&self) -> bool {
+        self.non_preset_entries > WRONG_FOLDER_ENTRY_THRESHOLD
+    }
+}
+
+/// Просканировать директорию пресетов с ограничениями
+///
+/// Пресетом считается поддиректория первого уровня с файлом `files_config.json`;
+/// вложенные директории не просматриваются. Тип элемента берется из записи
+/// директории, поэтому файлы отбрасываются без дополнительных обращений к диску.
+///
+/// # Arguments
+///
+/// * `presets_dir` - корневая директория со всеми пресетами
+/// * `options` - лимит директорий и флаг отмены
+///
+/// # Returns
+///
+/// `Ok(PresetScan)` с пресетами, отсортированными по идентификатору (порядок обхода
+/// директории зависит от ОС), или `Err`, если директорию не удалось прочитать или
+/// сканирование отменено (`SCAN_CANCELLED`)
+pub fn scan_presets(presets_dir: &Path, options: &ScanOptions) -> Result<PresetScan, String> {
+    let dir = fs::read_dir(presets_dir)
+        .map_err(|e| format!("Failed to read presets directory {:?}: {}", presets_dir, e))?;
+    
+    let mut scan = PresetScan::default();
+    let mut scanned_dirs = 0;
     
-    // Затем проверяем конфиг файл (работает на всех платформах)
-    if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
-        let config_file = PathBuf::from(home)
-            .join(".config")
-            .join("ai_project_template")
-            .join("presets_path.txt");
+    for entry in dir {
+        if options.is_cancelled() {
+            return Err(SCAN_CANCELLED.to_string());
+        }
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let is_dir = entry.file_type()
+            .map(|t| t.is_dir() || (t.is_symlink() && entry.path().is_dir()))
+            .unwrap_or(false);
+        if !is_dir {
+            scan.non_preset_entries += 1;
+            continue;
+        }
+        
+        if scanned_dirs >= options.max_dirs {
+            scan.truncated = true;
+            break;
+        }
+        scanned_dirs += 1;
         
-        if let Ok(content) = fs::read_to_string(&config_file) {
-            let trimmed = content.trim();
-            if !trimmed.is_empty() {
-                return Some(PathBuf::from(trimmed));
+        let path = entry.path();
+        match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) if path.join("files_config.json").exists() => {
+                scan.presets.push((name.to_string(), presets_dir.to_path_buf()));
             }
+            _ => scan.non_preset_entries += 1,
+        }
+    }
+    
+    scan.presets.sort();
+    Ok(scan)
+}
+
+/// Обнаружить все доступные пресеты в директории
+///
+/// Сканирует директорию пресетов и находит все поддиректории, содержащие файл `files_config.json`.
+/// Имя поддиректории используется как идентификатор пресета. Ограничения не применяются
+/// (см. `scan_presets`).
+///
+/// # Arguments
+///
+/// * `presets_dir` - корневая директория со всеми пресетами
+///
+/// # Returns
+///
+/// `Ok(Vec<String>)` со списком идентификаторов найденных пресетов,
+/// иначе `Err` с описанием ошибки
+///
+/// # Example
+///
+/// Если структура директории следующая:
+/// ```text
+/// presets/
+///   ├── software/
+///   │   └── files_config.json
+///   └── book/
+///       └── files_config.json
+/// ```
+///
+/// Функция вернет `vec!["book", "software"]`
+pub fn discover_presets(presets_dir: &Path) -> Result<Vec<String>, String> {
+    let scan = scan_presets(presets_dir, &ScanOptions::unbounded())?;
+    Ok(scan.presets.into_iter().map(|(id, _)| id).collect())
+}
+
+/// Обнаружить пресеты в основной и пользовательской директориях и в директориях
+/// дополнительных источников
+///
+/// Пользовательская директория хранит пресеты, импортированные, когда основная
+/// директория доступна только для чтения (например, общая папка, которую
+/// обслуживает администратор). При совпадении идентификаторов используется пресет
+/// из пользовательской директории. Отсутствующая пользовательская директория
+/// ошибкой не считается.
+///
+/// Пресеты источника ищутся в `presets_dir/subdir` (на уровень глубже основных).
+/// Пресет источника, идентификатор которого уже занят, получает идентификатор
+/// `id@source` (`source_preset_id`); `load_preset_config` находит его директорию
+/// по идентификатору без суффикса.
+///
+/// # Arguments
+///
+/// * `presets_dir` - основная директория пресетов
+/// * `user_presets_dir` - директория пользовательских пресетов
+/// * `sources` - дополнительные источники пресетов (еще не скачанные пропускаются)
+/// * `options` - лимит директорий (для каждой директории) и флаг отмены
+///
+/// # Returns
+///
+/// `Ok(PresetScan)` с идентификаторами пресетов и директориями, в которых
+/// они найдены (для `load_preset_config`); посторонние элементы считаются только
+/// в основной директории. `Err` если основную директорию не удалось прочитать
+/// или сканирование отменено
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::presets::{discover_presets_merged, load_preset_config, ScanOptions};
+/// use ai_project_template::settings::PresetSource;
+/// use std::fs;
+///
+/// let presets = std::env::temp_dir().join(format!("ai_project_template_sources_{}", std::process::id()));
+/// let config = r#"{"preset_id": "software", "preset_name": "Software", "description": "", "directories": []}"#;
+/// for dir in [presets.join("software"), presets.join("company").join("software")] {
+///     fs::create_dir_all(&dir).unwrap();
+///     fs::write(dir.join("files_config.json"), config).unwrap();
+/// }
+/// let source = PresetSource {
+///     name: "company".to_string(),
+///     zip_url: "https://git.example.com/presets.zip".to_string(),
+///     subdir: "company".to_string(),
+/// };
+///
+/// let scan = discover_presets_merged(&presets, None, &[source], &ScanOptions::default()).unwrap();
+/// let ids: Vec<&str> = scan.presets.iter().map(|(id, _)| id.as_str()).collect();
+/// assert_eq!(ids, ["software", "software@company"]);
+/// assert_eq!(scan.non_preset_entries, 0);
+/// let (_, root) = &scan.presets[1];
+/// let config = load_preset_config(root, "software@company").unwrap();
+/// assert_eq!(config.source_dir.unwrap(), presets.join("company").join("software"));
+///
+/// fs::remove_dir_all(&presets).unwrap();
+/// ```
+pub fn discover_presets_merged(
+    presets_dir: &Path,
+    user_presets_dir: Option<&Path>,
+    sources: &[PresetSource],
+    options: &ScanOptions,
+) -> Result<PresetScan, String> {
+    let mut scan = scan_presets(presets_dir, options)?;
+
+    let user_dir = user_presets_dir.filter(|dir| dir.is_dir() && *dir != presets_dir);
+    if let Some(user_dir) = user_dir {
+        let user_scan = scan_presets(user_dir, options)?;
+        scan.truncated |= user_scan.truncated;
+        for (id, root) in user_scan.presets {
+            match scan.presets.iter_mut().find(|(existing, _)| *existing == id) {
+                Some(entry) => entry.1 = root,
+                None => scan.presets.push((id, root)),
+            }
+        }
+        scan.presets.sort();
+    }
+
+    for source in sources {
+        let source_dir = presets_dir.join(&source.subdir);
+        if !source_dir.is_dir() {
+            continue;
+        }
+        // Директория источника сама не пресет, но и не посторонний элемент
+        scan.non_preset_entries = scan.non_preset_entries.saturating_sub(1);
+        let source_scan = scan_presets(&source_dir, options)?;
+        scan.truncated |= source_scan.truncated;
+        for (id, root) in source_scan.presets {
+            let id = if scan.presets.iter().any(|(existing, _)| *existing == id) {
+                source_preset_id(&id, &source.name)
+            } else {
+                id
+            };
+            scan.sources.insert(id.clone(), source.name.clone());
+            scan.presets.push((id, root));
+        }
+    }
+    scan.presets.sort();
+
+    Ok(scan)
+}
+
+/// Отпечатки пресетов директории для сравнения до и после обновления
+///
+/// Отпечаток пресета - SHA-256 по путям и содержимому всех его файлов.
+/// Пресеты, директорию которых не удалось прочитать, пропускаются.
+///
+/// # Returns
+///
+/// Отпечатки по идентификатору пресета (пустые, если директория не читается)
+pub fn preset_fingerprints(presets_dir: &Path) -> BTreeMap<String, String> {
+    let mut fingerprints = BTreeMap::new();
+    for id in discover_presets(presets_dir).unwrap_or_default() {
+        let preset_dir = presets_dir.join(&id);
+        let mut keys = Vec::new();
+        if collect_files(&preset_dir, &preset_dir, &mut keys).is_err() {
+            continue;
+        }
+        keys.sort();
+        let mut listing = String::new();
+        for key in keys {
+            let hash = hash_file(&preset_dir.join(&key)).unwrap_or_default();
+            listing.push_str(&format!("{}:{}\n", key, hash));
+        }
+        fingerprints.insert(id, hash_bytes(listing.as_bytes()));
+    }
+    fingerprints
+}
+
+/// Число добавленных, измененных и удаленных пресетов между двумя наборами отпечатков
+pub fn count_changed_presets(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> usize {
+    let changed = after.iter()
+        .filter(|(id, fingerprint)| before.get(*id) != Some(*fingerprint))
+        .count();
+    let removed = before.keys().filter(|id| !after.contains_key(*id)).count();
+    changed + removed
+}
+
+/// Имя файла с происхождением пресета внутри его директории
+pub const PRESET_ORIGIN_FILE_NAME: &str = ".preset_origin.json";
+
+/// Происхождение пресета
+///
+/// Записывается в `PRESET_ORIGIN_FILE_NAME` при распаковке архива и импорте;
+/// для пресетов без этого файла определяется по расположению.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PresetOrigin {
+    /// Распакован из архива пресетов (`PRESETS_ZIP_URL`); обновляется Refresh Presets
+    Managed,
+    /// Находится в директории пользовательских пресетов
+    User,
+    /// Импортирован из директории `path`
+    Imported {
+        /// Исходная директория импортированного пресета
+        path: PathBuf,
+    },
+    /// Происхождение неизвестно (например, пресет создан вручную)
+    Unknown,
+}
+
+impl fmt::Display for PresetOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetOrigin::Managed => write!(f, "managed"),
+            PresetOrigin::User => write!(f, "user"),
+            PresetOrigin::Imported { .. } => write!(f, "imported"),
+            PresetOrigin::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Определить происхождение пресета
+///
+/// # Arguments
+///
+/// * `preset_dir` - директория пресета
+/// * `user_presets_dir` - директория пользовательских пресетов (если известна)
+///
+/// # Returns
+///
+/// Записанное происхождение, иначе `User` для пресетов из пользовательской
+/// директории и `Unknown` для остальных
+pub fn preset_origin(preset_dir: &Path, user_presets_dir: Option<&Path>) -> PresetOrigin {
+    let recorded = fs::read_to_string(preset_dir.join(PRESET_ORIGIN_FILE_NAME))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    if let Some(origin) = recorded {
+        return origin;
+    }
+    if user_presets_dir.is_some_and(|dir| preset_dir.parent() == Some(dir)) {
+        PresetOrigin::User
+    } else {
+        PresetOrigin::Unknown
+    }
+}
+
+/// Записать происхождение пресета в его директорию
+pub fn write_preset_origin(preset_dir: &Path, origin: &PresetOrigin) -> Result<(), String> {
+    let path = preset_dir.join(PRESET_ORIGIN_FILE_NAME);
+    let content = serde_json::to_string_pretty(origin)
+        .map_err(|e| format!("Failed to serialize preset origin: {}", e))?;
+    atomic_write(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Сводка об обнаруженном пресете
+///
+/// Результат `discover_preset_summaries`: в отличие от `discover_presets`, конфигурация
+/// каждого пресета разбирается, а ошибка разбора сохраняется вместо молчаливого пропуска.
+#[derive(Debug, Clone)]
+pub struct PresetSummary {
+    /// Идентификатор пресета (имя директории; `id@источник` для пресета источника,
+    /// совпавшего с другим)
+    pub id: String,
+    /// Путь к директории пресета
+    pub path: PathBuf,
+    /// Отображаемое имя (`preset_name` или `id`, если конфиг не разобран)
+    pub name: String,
+    /// Описание пресета
+    pub description: String,
+    /// Количество динамических полей
+    pub field_count: usize,
+    /// Количество опций
+    pub option_count: usize,
+    /// Ошибка загрузки конфигурации, если она не разобралась
+    pub error: Option<String>,
+    /// Происхождение пресета
+    pub origin: PresetOrigin,
+}
+
+/// Обнаружить пресеты и разобрать их конфигурации
+///
+/// Использует `discover_presets_merged` для поиска (те же директории, что и в
+/// списке GUI) и `load_preset_config` для разбора, поэтому сломанные пресеты
+/// попадают в результат с заполненным `error`.
+///
+/// # Arguments
+///
+/// * `presets_dir` - корневая директория со всеми пресетами
+/// * `user_presets_dir` - директория пользовательских пресетов
+/// * `sources` - дополнительные источники пресетов
+/// * `options` - лимит сканирования
+///
+/// # Returns
+///
+/// `Ok(Vec<PresetSummary>)` в порядке идентификаторов, иначе `Err` с описанием ошибки
+pub fn discover_preset_summaries(
+    presets_dir: &Path,
+    user_presets_dir: Option<&Path>,
+    sources: &[PresetSource],
+    options: &ScanOptions,
+) -> Result<Vec<PresetSummary>, String> {
+    let scan = discover_presets_merged(presets_dir, user_presets_dir, sources, options)?;
+    
+    Ok(scan.presets.into_iter().map(|(id, root)| {
+        let path = find_preset_dir(&root, &id).unwrap_or_else(|| root.join(&id));
+        let origin = preset_origin(&path, user_presets_dir);
+        match load_preset_config(&root, &id) {
+            Ok(config) => PresetSummary {
+                name: config.name,
+                description: config.description,
+                field_count: config.fields.len(),
+                option_count: config.options.len(),
+                error: None,
+                origin,
+                id,
+                path,
+            },
+            Err(e) => PresetSummary {
+                name: id.clone(),
+                description: String::new(),
+                field_count: 0,
+                option_count: 0,
+                error: Some(e),
+                origin,
+                id,
+                path,
+            },
+        }
+    }).collect())
+}
+
+/// Определить директорию пресетов так же, как это делает GUI
+///
+/// Явно переданный путь (например, флаг `--presets-dir`) имеет приоритет,
+/// затем используется `load_presets_path_from_global_namespace`
+/// (переменная окружения, затем конфигурационный файл).
+///
+/// # Arguments
+///
+/// * `override_dir` - путь, явно заданный пользователем
+///
+/// # Returns
+///
+/// `Some(PathBuf)` если директория определена, иначе `None`
+pub fn resolve_presets_dir(override_dir: Option<&Path>) -> Option<PathBuf> {
+    override_dir
+        .map(Path::to_path_buf)
+        .or_else(load_presets_path_from_global_namespace)
+}
+
+/// Временная директория, удаляемая вместе со значением
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+/// Импортируемый пресет, подготовленный к копированию
+struct StagedImport {
+    /// Директория пресета (с `files_config.json`)
+    preset_dir: PathBuf,
+    /// Конфигурация пресета
+    config: PresetConfig,
+    /// Временная директория распакованного архива (удаляется после импорта)
+    _temp_dir: Option<TempDir>,
+}
+
+/// Является ли путь ZIP-архивом (по расширению)
+fn is_zip_file(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Прочитать `files_config.json` импортируемого пресета
+fn read_import_config(preset_dir: &Path) -> Result<PresetConfig, String> {
+    let config_path = preset_dir.join("files_config.json");
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to read preset config from {:?}: {}", config_path, e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse preset config: {}", e))
+}
+
+/// Подготовить директорию или ZIP-архив пресета к импорту
+///
+/// Архив распаковывается во временную директорию; `files_config.json` ищется
+/// в корне архива или в его единственной директории верхнего уровня (так
+/// упаковывает папку большинство архиваторов).
+fn stage_import(source: &Path) -> Result<StagedImport, String> {
+    if source.is_dir() {
+        let config = read_import_config(source)?;
+        return Ok(StagedImport { preset_dir: source.to_path_buf(), config, _temp_dir: None });
+    }
+    if !is_zip_file(source) {
+        return Err(format!("{:?} is neither a preset folder nor a .zip archive", source));
+    }
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let temp_dir = TempDir(env::temp_dir().join(format!("{}_{}_{}", IMPORT_TEMP_PREFIX, std::process::id(), nanos)));
+    extract_zip(source, &temp_dir.0)?;
+    let preset_dir = if temp_dir.0.join("files_config.json").is_file() {
+        temp_dir.0.clone()
+    } else {
+        let dirs: Vec<PathBuf> = fs::read_dir(&temp_dir.0)
+            .map_err(|e| format!("Failed to read extracted archive: {}", e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        match dirs.as_slice() {
+            [dir] => dir.clone(),
+            _ => return Err(format!("{:?} has no files_config.json at its top level", source)),
+        }
+    };
+    let config = read_import_config(&preset_dir)?;
+    Ok(StagedImport { preset_dir, config, _temp_dir: Some(temp_dir) })
+}
+
+/// Распаковать ZIP-архив в директорию
+fn extract_zip(archive_path: &Path, target_dir: &Path) -> Result<(), String> {
+    let file = fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open {:?}: {}", archive_path, e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Failed to open zip archive {:?}: {}", archive_path, e))?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(|e| format!("Failed to get file {} from archive: {}", i, e))?;
+        let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else { continue };
+        let path = target_dir.join(name);
+        if entry.is_dir() {
+            fs::create_dir_all(&path)
+                .map_err(|e| format!("Failed to create dir {:?}: {}", path, e))?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create dir {:?}: {}", parent, e))?;
         }
+        let mut out = fs::File::create(&path)
+            .map_err(|e| format!("Failed to create file {:?}: {}", path, e))?;
+        io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to extract file {:?}: {}", path, e))?;
     }
-    
-    None
+    Ok(())
 }
 
-/// Загрузить конфигурацию пресета из файла
-///
-/// Читает и парсит JSON файл `files_config.json` из директории пресета.
+/// Идентификатор пресета импортируемой директории или архива
 ///
-/// # Arguments
+/// `preset_id` из `files_config.json` становится именем директории пресета,
+/// поэтому должен быть одним именем папки.
+fn import_id(config: &PresetConfig) -> Result<String, String> {
+    let mut components = Path::new(&config.id).components();
+    match (components.next(), components.next()) {
+        (Some(std::path::Component::Normal(_)), None) => Ok(config.id.clone()),
+        _ => Err(format!("Preset id '{}' cannot be used as a folder name", config.id)),
+    }
+}
+
+/// Прочитать идентификатор пресета перед импортом
 ///
-/// * `presets_dir` - корневая директория со всеми пресетами
-/// * `preset_id` - идентификатор пресета (имя директории)
+/// Позволяет проверить, не занят ли идентификатор, до копирования
+/// (и спросить подтверждение перезаписи).
 ///
 /// # Returns
 ///
-/// `Ok(PresetConfig)` если конфигурация успешно загружена и распарсена,
-/// иначе `Err` с описанием ошибки
-///
-/// # Errors
-///
-/// Возвращает ошибку если:
-/// - файл `files_config.json` не существует
-/// - файл не может быть прочитан
-/// - JSON не валиден или не соответствует структуре `PresetConfig`
-pub fn load_preset_config(presets_dir: &Path, preset_id: &str) -> Result<PresetConfig, String> {
-    let config_path = presets_dir.join(preset_id).join("files_config.json");
-    
-    let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read preset config from {:?}: {}", config_path, e))?;
-    
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse preset config: {}", e))
+/// `preset_id` из `files_config.json` директории или ZIP-архива, либо `Err`,
+/// если конфигурация отсутствует или некорректна
+pub fn import_preset_id(source: &Path) -> Result<String, String> {
+    import_id(&stage_import(source)?.config)
 }
 
-/// Обнаружить все доступные пресеты в директории
+/// Импортировать директорию или ZIP-архив пресета в директорию пресетов
 ///
-/// Сканирует директорию пресетов и находит все поддиректории, содержащие файл `files_config.json`.
-/// Имя поддиректории используется как идентификатор пресета.
+/// Копирует пресет в `presets_dir/preset_id`, где `preset_id` берется из его
+/// `files_config.json`. Перед копированием проверяет, что конфигурация корректна.
+/// Архив может содержать пресет в корне или в единственной директории верхнего
+/// уровня. Исходный путь записывается как происхождение пресета (`PresetOrigin::Imported`).
 ///
 /// # Arguments
 ///
 /// * `presets_dir` - корневая директория со всеми пресетами
+/// * `source` - директория или `.zip`-архив импортируемого пресета
+/// * `overwrite` - заменить существующий пресет с тем же идентификатором
 ///
 /// # Returns
 ///
-/// `Ok(Vec<String>)` со списком идентификаторов найденных пресетов,
-/// иначе `Err` с описанием ошибки
+/// `Ok(String)` с идентификатором импортированного пресета, `Err` если конфигурация
+/// некорректна, пресет уже есть (и `overwrite == false`) или копирование не удалось
 ///
 /// # Example
 ///
-/// Если структура директории следующая:
-/// ```text
-/// presets/
-///   ├── software/
-///   │   └── files_config.json
-///   └── book/
-///       └── files_config.json
 /// ```
+/// use ai_project_template::presets::{import_preset, load_preset_config};
+/// use std::fs;
 ///
-/// Функция вернет `vec!["software", "book"]`
-pub fn discover_presets(presets_dir: &Path) -> Result<Vec<String>, String> {
-    let dir = fs::read_dir(presets_dir)
-        .map_err(|e| format!("Failed to read presets directory {:?}: {}", presets_dir, e))?;
-    
-    let mut presets = Vec::new();
-    
-    for entry in dir {
+/// let root = std::env::temp_dir().join(format!("ai_project_template_import_doc_{}", std::process::id()));
+/// let presets = root.join("presets");
+/// let source = root.join("my-folder");
+/// fs::create_dir_all(&presets).unwrap();
+/// fs::create_dir_all(&source).unwrap();
+/// let config = r#"{"preset_id": "notes", "preset_name": "Notes", "description": "", "directories": []}"#;
+/// fs::write(source.join("files_config.json"), config).unwrap();
+///
+/// // Директория импортируется под preset_id из конфигурации
+/// assert_eq!(import_preset(&presets, &source, false).unwrap(), "notes");
+/// assert!(load_preset_config(&presets, "notes").is_ok());
+/// // Повторный импорт - только с явной перезаписью
+/// assert!(import_preset(&presets, &source, false).is_err());
+/// assert!(import_preset(&presets, &source, true).is_ok());
+///
+/// fs::remove_dir_all(&root).unwrap();
+/// ```
+pub fn import_preset(presets_dir: &Path, source: &Path, overwrite: bool) -> Result<String, String> {
+    let staged = stage_import(source)?;
+    let preset_id = import_id(&staged.config)?;
+
+    let target = presets_dir.join(&preset_id);
+    if target.exists() {
+        if !overwrite {
+            return Err(format!("Preset '{}' already exists in {:?}", preset_id, presets_dir));
+        }
+        let same = fs::canonicalize(&target).ok() == fs::canonicalize(&staged.preset_dir).ok();
+        if same {
+            return Err(format!("{:?} is already the installed preset '{}'", source, preset_id));
+        }
+        fs::remove_dir_all(&target)
+            .map_err(|e| format!("Failed to replace preset {:?}: {}", target, e))?;
+    }
+    copy_dir_recursive(&staged.preset_dir, &target)?;
+    let source_path = fs::canonicalize(source).unwrap_or_else(|_| source.to_path_buf());
+    write_preset_origin(&target, &PresetOrigin::Imported { path: source_path })?;
+    Ok(preset_id)
+}
+
+/// Рекурсивно скопировать директорию
+pub(crate) fn copy_dir_recursive(source: &Path, target: &Path) -> Result<(), String> {
+    fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create directory {:?}: {}", target, e))?;
+    let entries = fs::read_dir(source)
+        .map_err(|e| format!("Failed to read directory {:?}: {}", source, e))?;
+    for entry in entries {
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
         let path = entry.path();
-        
+        let destination = target.join(entry.file_name());
         if path.is_dir() {
-            let config_path = path.join("files_config.json");
-            if config_path.exists() {
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    presets.push(name.to_string());
-                }
-            }
+            copy_dir_recursive(&path, &destination)?;
+        } else {
+            fs::copy(&path, &destination)
+                .map_err(|e| format!("Failed to copy {:?}: {}", path, e))?;
         }
     }
-    
-    Ok(presets)
+    Ok(())
 }
 
 /// Получить имя пресета для отображения
@@ -342,6 +2115,146 @@ pub fn get_preset_display_name(presets_dir: &Path, preset_id: &str) -> String {
     }
 }
 
+/// Префикс имени временного архива пресетов
+pub const TEMP_ZIP_PREFIX: &str = "presets_temp";
+
+/// Префикс имени временной директории импорта пресета (`import_preset`)
+pub const IMPORT_TEMP_PREFIX: &str = "ai_project_template_import";
+
+/// Возраст, после которого временный архив или директория импорта считаются
+/// оставшимися от аварийно завершенного запуска
+pub const STALE_TEMP_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Путь к временному архиву для загрузки в `target_dir`
+///
+/// Архив создается в родительской директории `target_dir` с уникальным именем
+/// `presets_temp_{pid}_{millis}.zip`, чтобы параллельные загрузки не мешали друг другу.
+pub fn temp_zip_path(target_dir: &Path) -> PathBuf {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    temp_zip_dir(target_dir)
+        .join(format!("{}_{}_{}.zip", TEMP_ZIP_PREFIX, std::process::id(), millis))
+}
+
+/// Директория временных архивов для загрузки в `target_dir`
+pub fn temp_zip_dir(target_dir: &Path) -> PathBuf {
+    target_dir.parent().unwrap_or(target_dir).to_path_buf()
+}
+
+/// Является ли имя файла именем временного архива приложения
+///
+/// Совпадение точное: `presets_temp.zip` (прежние версии) или
+/// `presets_temp_{цифры}_{цифры}.zip`. Похожие имена (`presets_temp_old.zip`,
+/// `presets_temp.zip.bak`) не подходят.
+pub fn is_temp_zip_name(name: &str) -> bool {
+    let Some(stem) = name.strip_prefix(TEMP_ZIP_PREFIX).and_then(|rest| rest.strip_suffix(".zip")) else {
+        return false;
+    };
+    stem.is_empty() || is_pid_stamp(stem)
+}
+
+/// Является ли имя директории именем временной директории импорта
+///
+/// Совпадение точное: `ai_project_template_import_{цифры}_{цифры}`.
+pub fn is_import_temp_dir_name(name: &str) -> bool {
+    name.strip_prefix(IMPORT_TEMP_PREFIX).is_some_and(is_pid_stamp)
+}
+
+/// Суффикс временного имени `_{pid}_{метка времени}` (только цифры)
+fn is_pid_stamp(suffix: &str) -> bool {
+    let Some(rest) = suffix.strip_prefix('_') else { return false };
+    let mut parts = rest.split('_');
+    let is_number = |part: Option<&str>| part.is_some_and(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+    is_number(parts.next()) && is_number(parts.next()) && parts.next().is_none()
+}
+
+/// Найти временные архивы и директории импорта, оставшиеся от прошлых запусков
+///
+/// Проверяются только элементы первого уровня каждой директории: файлы с именем,
+/// точно соответствующим `is_temp_zip_name`, и директории с именем по
+/// `is_import_temp_dir_name`; время изменения должно быть старше `max_age`.
+///
+/// Директории сборки проекта (`{имя}.__tmp_{pid}` рядом с проектом, см.
+/// `create_project`) не ищутся: они лежат в произвольных директориях назначения,
+/// которые при запуске неизвестны.
+///
+/// # Arguments
+///
+/// * `dirs` - директории временных архивов (см. `temp_zip_dir`) и системная
+///   временная директория
+/// * `max_age` - минимальный возраст файла или директории
+///
+/// # Returns
+///
+/// Пути найденных элементов (отсортированы, без повторов)
+pub fn find_stale_temp_files(dirs: &[PathBuf], max_age: Duration) -> Vec<PathBuf> {
+    let now = SystemTime::now();
+    let mut found = BTreeSet::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else { continue };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else { continue };
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else { continue };
+            let name_matches = (file_type.is_file() && is_temp_zip_name(&name))
+                || (file_type.is_dir() && is_import_temp_dir_name(&name));
+            if !name_matches {
+                continue;
+            }
+            let old_enough = entry.metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age >= max_age);
+            if old_enough {
+                found.insert(entry.path());
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// Общая корневая директория всех элементов архива
+///
+/// Архивы репозиториев (GitHub, GitLab, Gitea) кладут содержимое в директорию
+/// `{repo}-{branch}/`; ее имя зависит от репозитория, поэтому определяется по архиву.
+/// Архив с единственным пресетом (`software/files_config.json`) не разворачивается.
+fn archive_root_prefix<R: io::Read + io::Seek>(archive: &mut zip::ZipArchive<R>) -> Option<PathBuf> {
+    let mut prefix: Option<PathBuf> = None;
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).ok()?;
+        let path = file.enclosed_name()?.to_path_buf();
+        let mut components = path.components();
+        let top = PathBuf::from(components.next()?.as_os_str());
+        // Файл в корне архива - общей директории нет
+        if components.next().is_none() && !file.is_dir() {
+            return None;
+        }
+        if path == top.join("files_config.json") {
+            return None;
+        }
+        match prefix {
+            Some(ref existing) if *existing != top => return None,
+            Some(_) => {}
+            None => prefix = Some(top),
+        }
+    }
+    prefix
+}
+
+/// Шаг прогресса `download_and_extract_presets`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadProgress {
+    /// Скачано `received` байт из `total` (`None` - сервер не сообщил `Content-Length`)
+    Downloading { received: u64, total: Option<u64> },
+    /// Распаковано `done` элементов архива из `total`
+    Extracting { done: usize, total: usize },
+}
+
+/// Как часто сообщать о загрузке архива неизвестного размера
+const UNKNOWN_SIZE_PROGRESS_STEP: u64 = 256 * 1024;
+
 /// Скачать и распаковать пресеты из GitHub
 ///
 /// Обновляет пресеты из GitHub, не удаляя кастомные пресеты пользователя:
@@ -350,21 +2263,32 @@ pub fn get_preset_display_name(presets_dir: &Path, preset_id: &str) -> String {
 /// 3. Удаляет временный ZIP файл
 ///
 /// **Важно**: Эта функция не удаляет существующие пресеты. Она только обновляет/добавляет
-/// те пресеты, которые есть в архиве. Кастомные пресеты пользователя останутся нетронутыми.
+/// те пресеты, которые есть в архиве. Кастомные пресеты пользователя останутся нетронутыми,
+/// в том числе пресеты с тем же именем, что и в архиве, если их происхождение - `User`
+/// или `Imported`. Распакованные пресеты помечаются как `PresetOrigin::Managed`.
 ///
 /// # Arguments
 ///
 /// * `target_dir` - директория, в которую будут распакованы пресеты
 /// * `zip_url` - URL для скачивания ZIP архива пресетов
+/// * `network` - сетевые настройки (таймаут, прокси)
+/// * `cancel` - флаг отмены (см. `operation::check_cancelled`): загрузка прерывается после
+///   очередного фрагмента, недокачанный архив удаляется; распаковка останавливается
+///   после текущего элемента архива (недораспакованные пресеты находит `integrity::verify_presets`)
+/// * `on_progress` - прогресс в два этапа: `Downloading` по мере загрузки (не чаще,
+///   чем раз на процент, а без `Content-Length` - раз на 256 КБ) и после скачивания,
+///   затем `Extracting` перед каждым элементом архива и после распаковки
 ///
 /// # Returns
 ///
 /// `Ok(())` если операция завершена успешно, иначе `Err` с описанием ошибки
+/// (`Err(CANCELLED)` при отмене)
 ///
 /// # Platform-specific behavior
 ///
 /// - На Unix системах сохраняет права доступа файлов из архива
-/// - На всех платформах удаляет префикс `ai_prompt_presets-main/` из путей в архиве
+/// - На всех платформах удаляет общую корневую директорию архива (`ai_prompt_presets-main/`
+///   в архиве GitHub), если все элементы архива находятся в ней
 ///
 /// # Errors
 ///
@@ -376,9 +2300,69 @@ pub fn get_preset_display_name(presets_dir: &Path, preset_id: &str) -> String {
 pub async fn download_and_extract_presets(
     target_dir: &Path,
     zip_url: &str,
+    network: &NetworkSettings,
+    cancel: Option<&AtomicBool>,
+    on_progress: impl Fn(DownloadProgress) + Send,
 ) -> Result<(), String> {
-    // 2. Скачать ZIP архив
-    let response = reqwest::get(zip_url)
+    let temp_zip = download_presets_archive(target_dir, zip_url, network, cancel, &on_progress).await?;
+    let extracted = extract_presets_archive(&temp_zip, target_dir, None, cancel, &on_progress);
+    
+    // Удалить временный ZIP файл (и после прерванной распаковки)
+    fs::remove_file(&temp_zip)
+        .ok(); // Игнорируем ошибки удаления
+    
+    extracted.map(|_| ())
+}
+
+/// Восстановить поврежденные пресеты из архива
+///
+/// Архив скачивается заново, но распаковываются только элементы пресетов `presets`
+/// (см. `integrity::verify_presets`); остальные пресеты, в том числе
+/// пользовательские, не затрагиваются.
+///
+/// # Arguments
+///
+/// * `target_dir` - директория, в которую распакован архив пресетов
+/// * `zip_url` - URL архива пресетов
+/// * `network` - сетевые настройки (таймаут, прокси)
+/// * `presets` - идентификаторы восстанавливаемых пресетов
+/// * `cancel` - флаг отмены, как у `download_and_extract_presets`
+/// * `on_progress` - прогресс, как у `download_and_extract_presets`
+///
+/// # Returns
+///
+/// Идентификаторы пресетов, найденных в архиве и распакованных заново
+pub async fn repair_presets(
+    target_dir: &Path,
+    zip_url: &str,
+    network: &NetworkSettings,
+    presets: &BTreeSet<String>,
+    cancel: Option<&AtomicBool>,
+    on_progress: impl Fn(DownloadProgress) + Send,
+) -> Result<BTreeSet<String>, String> {
+    let temp_zip = download_presets_archive(target_dir, zip_url, network, cancel, &on_progress).await?;
+    let repaired = extract_presets_archive(&temp_zip, target_dir, Some(presets), cancel, &on_progress);
+    fs::remove_file(&temp_zip)
+        .ok(); // Игнорируем ошибки удаления
+    repaired
+}
+
+/// Скачать архив пресетов во временный файл рядом с `target_dir` (`temp_zip_path`)
+///
+/// Флаг отмены проверяется после записи каждого фрагмента; при отмене
+/// недокачанный файл удаляется.
+async fn download_presets_archive(
+    target_dir: &Path,
+    zip_url: &str,
+    network: &NetworkSettings,
+    cancel: Option<&AtomicBool>,
+    on_progress: &(impl Fn(DownloadProgress) + Send),
+) -> Result<PathBuf, String> {
+    // Создать HTTP клиент с учетом прокси и таймаута
+    let client = network.http_client()?;
+    
+    let mut response = client.get(zip_url)
+        .send()
         .await
         .map_err(|e| format!("Failed to download from {}: {}", zip_url, e))?;
     
@@ -386,51 +2370,162 @@ pub async fn download_and_extract_presets(
         return Err(format!("HTTP error: {}", response.status()));
     }
     
-    // 3. Сохранить во временный файл в целевой директории
-    let temp_zip = target_dir.parent()
-        .unwrap_or(target_dir)
-        .join("presets_temp.zip");
-    
-    let bytes = response.bytes()
-        .await
-        .map_err(|e| format!("Failed to read response bytes: {}", e))?;
+    // Сохранить во временный файл рядом с целевой директорией, сообщая о прогрессе
+    let temp_zip = temp_zip_path(target_dir);
     
     let mut file = fs::File::create(&temp_zip)
         .map_err(|e| format!("Failed to create temp file {:?}: {}", temp_zip, e))?;
     
-    file.write_all(&bytes)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    let total = response.content_length().filter(|&len| len > 0);
+    let mut received: u64 = 0;
+    let mut reported: u64 = 0;
+    on_progress(DownloadProgress::Downloading { received, total });
+    while let Some(chunk) = response.chunk()
+        .await
+        .map_err(|e| format!("Failed to read response bytes: {}", e))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        if let Err(e) = check_cancelled(cancel) {
+            drop(file);
+            fs::remove_file(&temp_zip).ok();
+            return Err(e);
+        }
+        received += chunk.len() as u64;
+        // Порог следующего сообщения: процент от размера или фиксированный шаг
+        let step = total.map_or(UNKNOWN_SIZE_PROGRESS_STEP, |total| (total / 100).max(1));
+        if received - reported >= step {
+            reported = received;
+            on_progress(DownloadProgress::Downloading { received, total });
+        }
+    }
+    if reported != received {
+        on_progress(DownloadProgress::Downloading { received, total });
+    }
     file.sync_all()
         .map_err(|e| format!("Failed to sync temp file: {}", e))?;
-    drop(file); // Закрыть файл перед распаковкой
+    Ok(temp_zip)
+}
+
+/// Куда распаковать элемент архива
+///
+/// # Returns
+///
+/// Путь относительно целевой директории и пресет, к которому относится элемент
+/// (`None` - файл или директория в корне); `None`, если элемент пропускается:
+/// небезопасный путь, корневая директория архива, пользовательский или
+/// импортированный пресет, пресет не из `only`
+fn archive_entry_target(
+    file: &zip::read::ZipFile<'_>,
+    root_prefix: Option<&Path>,
+    target_dir: &Path,
+    only: Option<&BTreeSet<String>>,
+) -> Option<(PathBuf, Option<String>)> {
+    let outpath = file.enclosed_name()?.to_owned();
+    
+    // Убрать корневую директорию архива (ai_prompt_presets-main/ и т.п.)
+    let outpath = match root_prefix {
+        Some(prefix) => match outpath.strip_prefix(prefix) {
+            Ok(rest) if rest.as_os_str().is_empty() => return None,
+            Ok(rest) => rest.to_path_buf(),
+            Err(_) => outpath,
+        },
+        None => outpath,
+    };
     
-    // 4. Распаковать ZIP
-    let zip_file = fs::File::open(&temp_zip)
-        .map_err(|e| format!("Failed to open zip file {:?}: {}", temp_zip, e))?;
+    let preset_id = match outpath.components().count() {
+        1 => None,
+        _ => outpath.components().next().map(|top| top.as_os_str().to_string_lossy().into_owned()),
+    };
+    match preset_id {
+        Some(ref id) => {
+            if only.is_some_and(|only| !only.contains(id)) {
+                return None;
+            }
+            let origin = preset_origin(&target_dir.join(id), None);
+            if matches!(origin, PresetOrigin::User | PresetOrigin::Imported { .. }) {
+                return None;
+            }
+        }
+        // При восстановлении отдельных пресетов файлы корня архива не нужны
+        None if only.is_some() => return None,
+        None => {}
+    }
+    Some((outpath, preset_id))
+}
+
+/// Распаковать скачанный архив пресетов в `target_dir`
+///
+/// До распаковки файлов в директорию каждого пресета записывается манифест
+/// целостности (`integrity::IntegrityManifest`) с хешами всех его файлов из
+/// архива, поэтому прерванная распаковка обнаруживается `integrity::verify_presets`.
+/// Пользовательские и импортированные пресеты пропускаются, распакованные
+/// пресеты с `files_config.json` помечаются как `PresetOrigin::Managed`.
+///
+/// # Arguments
+///
+/// * `archive_path` - ZIP архив пресетов
+/// * `target_dir` - директория пресетов
+/// * `only` - распаковать только элементы этих пресетов (`None` - весь архив)
+/// * `cancel` - флаг отмены: распаковка останавливается перед следующим элементом
+///   архива (текущий дописывается, см. `operation::CancelPolicy::FinishEntry`)
+/// * `on_progress` - `Extracting` перед каждым элементом архива и после распаковки
+///
+/// # Returns
+///
+/// Идентификаторы распакованных пресетов или `Err(CANCELLED)` при отмене
+pub fn extract_presets_archive(
+    archive_path: &Path,
+    target_dir: &Path,
+    only: Option<&BTreeSet<String>>,
+    cancel: Option<&AtomicBool>,
+    on_progress: impl Fn(DownloadProgress),
+) -> Result<BTreeSet<String>, String> {
+    let zip_file = fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open zip file {:?}: {}", archive_path, e))?;
     
     let mut archive = zip::ZipArchive::new(zip_file)
         .map_err(|e| format!("Failed to open zip archive: {}", e))?;
     
-    // Распаковать все файлы
-    for i in 0..archive.len() {
+    let entries = archive.len();
+    let root_prefix = archive_root_prefix(&mut archive);
+    
+    // Хеши файлов каждого пресета записываются до распаковки
+    let mut manifests: BTreeMap<String, IntegrityManifest> = BTreeMap::new();
+    for i in 0..entries {
         let mut file = archive.by_index(i)
             .map_err(|e| format!("Failed to get file {} from archive: {}", i, e))?;
-        
-        let outpath = match file.enclosed_name() {
-            Some(path) => path.to_owned(),
-            None => continue,
+        let Some((outpath, Some(preset_id))) = archive_entry_target(&file, root_prefix.as_deref(), target_dir, only) else {
+            continue;
         };
-        
-        // Убрать префикс ai_prompt_presets-main/ если есть
-        let outpath = if outpath.starts_with("ai_prompt_presets-main/") {
-            PathBuf::from(outpath.strip_prefix("ai_prompt_presets-main/").unwrap())
-        } else {
-            PathBuf::from(outpath)
+        let manifest = manifests.entry(preset_id.clone()).or_default();
+        if !file.is_dir() {
+            let hash = hash_reader(&mut file)
+                .map_err(|e| format!("Failed to read {:?} from archive: {}", outpath, e))?;
+            let relative = outpath.strip_prefix(&preset_id).unwrap_or(&outpath);
+            manifest.files.insert(manifest_key(relative), hash);
+        }
+    }
+    for (preset_id, manifest) in &manifests {
+        let preset_dir = target_dir.join(preset_id);
+        fs::create_dir_all(&preset_dir)
+            .map_err(|e| format!("Failed to create dir {:?}: {}", preset_dir, e))?;
+        manifest.save(&preset_dir)?;
+    }
+    
+    // Распаковать все файлы (кроме пресетов пользователя)
+    for i in 0..entries {
+        check_cancelled(cancel)?;
+        on_progress(DownloadProgress::Extracting { done: i, total: entries });
+        let mut file = archive.by_index(i)
+            .map_err(|e| format!("Failed to get file {} from archive: {}", i, e))?;
+        let Some((outpath, _)) = archive_entry_target(&file, root_prefix.as_deref(), target_dir, only) else {
+            continue;
         };
         
         let full_path = target_dir.join(&outpath);
         
-        if file.name().ends_with('/') {
+        if file.is_dir() {
             // Создать директорию
             fs::create_dir_all(&full_path)
                 .map_err(|e| format!("Failed to create dir {:?}: {}", full_path, e))?;
@@ -441,11 +2536,9 @@ pub async fn download_and_extract_presets(
                     .map_err(|e| format!("Failed to create parent dir {:?}: {}", parent, e))?;
             }
             
-            // Извлечь файл
-            let mut outfile = fs::File::create(&full_path)
-                .map_err(|e| format!("Failed to create file {:?}: {}", full_path, e))?;
-            
-            io::copy(&mut file, &mut outfile)
+            // Извлечь файл: замена переименованием разрывает жесткие ссылки из
+            // проектов (`TemplateConfig::link`), а не перезаписывает их содержимое
+            atomic_write_from(&full_path, &mut file)
                 .map_err(|e| format!("Failed to extract file {:?}: {}", full_path, e))?;
         }
         
@@ -459,11 +2552,434 @@ pub async fn download_and_extract_presets(
             }
         }
     }
+    on_progress(DownloadProgress::Extracting { done: entries, total: entries });
     
-    // 5. Удалить временный ZIP файл
-    fs::remove_file(&temp_zip)
-        .ok(); // Игнорируем ошибки удаления
+    // Пометить распакованные пресеты как управляемые архивом
+    for preset_id in manifests.keys() {
+        let preset_dir = target_dir.join(preset_id);
+        if preset_dir.join("files_config.json").is_file() {
+            write_preset_origin(&preset_dir, &PresetOrigin::Managed)?;
+        }
+    }
     
-    Ok(())
+    Ok(manifests.into_keys().collect())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Конфигурация пресета с заданными путями; шаблоны - пары (source, destination)
+    fn config_with(directories: &[&str], templates: &[(&str, &str)], empty_files: &[&str]) -> PresetConfig {
+        let mut config: PresetConfig = serde_json::from_str(
+            r#"{"preset_id": "demo", "preset_name": "Demo", "description": "", "directories": []}"#
+        ).unwrap();
+        config.directories = directories.iter().map(|d| d.to_string()).collect();
+        config.templates = templates.iter()
+            .map(|(source, destination)| TemplateConfig {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                link: false,
+                substitute: false,
+                line_endings: None,
+            })
+            .collect();
+        config.empty_files = empty_files.iter().map(|f| f.to_string()).collect();
+        config
+    }
+
+    /// Ожидаемый результат нормализации: пути после нормализации и число предупреждений
+    struct Normalized {
+        directories: &'static [&'static str],
+        destinations: &'static [&'static str],
+        empty_files: &'static [&'static str],
+        warnings: usize,
+    }
+
+    #[test]
+    fn normalize_preset_config_collision_matrix() {
+        type Case = (&'static str, &'static [&'static str], &'static [(&'static str, &'static str)], &'static [&'static str], Result<Normalized, &'static str>);
+        let cases: &[Case] = &[
+            ("no collisions, sorted", &["tests", "src"], &[("b.md", "docs/b.md"), ("a.md", "docs/a.md")], &["z.txt", "a.txt"],
+                Ok(Normalized { directories: &["src", "tests"], destinations: &["docs/a.md", "docs/b.md"], empty_files: &["a.txt", "z.txt"], warnings: 0 })),
+            ("duplicate directory", &["src", "src"], &[], &[],
+                Ok(Normalized { directories: &["src"], destinations: &[], empty_files: &[], warnings: 0 })),
+            ("directory spelled differently", &["src/bin", "./src//bin/", "src\\bin"], &[], &[],
+                Ok(Normalized { directories: &["src/bin"], destinations: &[], empty_files: &[], warnings: 0 })),
+            ("directory with surrounding whitespace", &["src", " src ", "docs / api"], &[], &[],
+                Ok(Normalized { directories: &["docs / api", "src"], destinations: &[], empty_files: &[], warnings: 0 })),
+            ("directories differing by case are kept", &["Docs", "docs"], &[], &[],
+                Ok(Normalized { directories: &["Docs", "docs"], destinations: &[], empty_files: &[], warnings: 0 })),
+            ("identical template declared twice", &[], &[("main.rs", "src/main.rs"), ("./main.rs", "src\\main.rs")], &[],
+                Ok(Normalized { directories: &[], destinations: &["src/main.rs"], empty_files: &[], warnings: 0 })),
+            ("two sources, one destination", &[], &[("main.rs", "src/main.rs"), ("lib.rs", "src/main.rs")], &[],
+                Err("Templates 'main.rs' and 'lib.rs' both write to 'src/main.rs'")),
+            ("two sources, destination differs by whitespace", &[], &[("main.rs", "src/main.rs"), ("lib.rs", "src/ main.rs ")], &[],
+                Err("Templates 'main.rs' and 'lib.rs' both write to 'src/ main.rs '")),
+            ("two sources, destinations differ by case", &[], &[("a.md", "README.md"), ("b.md", "readme.md")], &[],
+                Ok(Normalized { directories: &[], destinations: &["README.md", "readme.md"], empty_files: &[], warnings: 0 })),
+            ("duplicate empty file", &[], &[], &["src/.keep", "src/./.keep"],
+                Ok(Normalized { directories: &[], destinations: &[], empty_files: &["src/.keep"], warnings: 0 })),
+            ("empty file collides with template", &[], &[("main.rs", "src/main.rs")], &["src/main.rs", "src/lib.rs"],
+                Ok(Normalized { directories: &[], destinations: &["src/main.rs"], empty_files: &["src/lib.rs"], warnings: 1 })),
+            ("empty file collides with template up to whitespace", &[], &[("main.rs", "src/main.rs")], &[" src/main.rs"],
+                Ok(Normalized { directories: &[], destinations: &["src/main.rs"], empty_files: &[], warnings: 1 })),
+            ("empty file differs from template by case", &[], &[("main.rs", "src/main.rs")], &["src/Main.rs"],
+                Ok(Normalized { directories: &[], destinations: &["src/main.rs"], empty_files: &["src/Main.rs"], warnings: 0 })),
+            ("directory named like an empty file", &["notes"], &[], &["notes"],
+                Ok(Normalized { directories: &["notes"], destinations: &[], empty_files: &["notes"], warnings: 0 })),
+        ];
+
+        for (name, directories, templates, empty_files, expected) in cases {
+            let result = normalize_preset_config(&config_with(directories, templates, empty_files));
+            match (result, expected) {
+                (Ok(normalized), Ok(expected)) => {
+                    let config = &normalized.config;
+                    assert_eq!(config.directories, expected.directories, "{}", name);
+                    let destinations: Vec<&str> = config.templates.iter().map(|t| t.destination.as_str()).collect();
+                    assert_eq!(destinations, expected.destinations, "{}", name);
+                    assert_eq!(config.empty_files, expected.empty_files, "{}", name);
+                    assert_eq!(normalized.warnings.len(), expected.warnings, "{}: {:?}", name, normalized.warnings);
+                }
+                (Err(e), Err(expected)) => assert_eq!(e, *expected, "{}", name),
+                (result, _) => panic!("{}: unexpected result {:?}", name, result.map(|n| n.config.templates)),
+            }
+        }
+    }
+
+    #[test]
+    fn normalize_preset_config_is_independent_of_declaration_order() {
+        let forward = config_with(&["src", "docs", "src"], &[("a.md", "docs/a.md"), ("main.rs", "src/main.rs")], &["b.txt", "a.txt"]);
+        let backward = config_with(&["src", "docs"], &[("main.rs", "src/main.rs"), ("a.md", "docs/a.md")], &["a.txt", "b.txt", "a.txt"]);
+
+        let forward = normalize_preset_config(&forward).unwrap().config;
+        let backward = normalize_preset_config(&backward).unwrap().config;
+
+        assert_eq!(serde_json::to_string(&forward).unwrap(), serde_json::to_string(&backward).unwrap());
+    }
+
+    #[test]
+    fn normalize_preset_config_warning_names_the_file() {
+        let config = config_with(&[], &[("gitignore", ".gitignore")], &[".gitignore"]);
+
+        let normalized = normalize_preset_config(&config).unwrap();
+
+        assert_eq!(normalized.warnings, vec![
+            "Warning: Empty file '.gitignore' is also a template destination; the template is used".to_string(),
+        ]);
+    }
+
+    /// Временная директория теста (удаляется в конце теста)
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("ai_project_template_presets_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Сдвинуть время изменения файла или директории на `age` в прошлое
+    fn age(path: &Path, age: Duration) {
+        fs::File::open(path).unwrap().set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    #[test]
+    fn temp_names_match_exactly() {
+        for name in ["presets_temp.zip", "presets_temp_1234_1700000000000.zip"] {
+            assert!(is_temp_zip_name(name), "{}", name);
+        }
+        for name in [
+            "presets_temp_old.zip", "presets_temp.zip.bak", "presets_temp_1_2.zip.part", "presets_temp_1.zip",
+            "presets_temp_1_2_3.zip", "presets_temp__2.zip", "presets_temp_1_x.zip", "my_presets_temp.zip", "presets.zip",
+        ] {
+            assert!(!is_temp_zip_name(name), "{}", name);
+        }
+        assert!(is_import_temp_dir_name("ai_project_template_import_1234_1700000000000000000"));
+        for name in [
+            "ai_project_template_import", "ai_project_template_import_doc_1234", "ai_project_template_import_1234",
+            "ai_project_template_import_1_2_old", "ai_project_template_presets_1_2",
+        ] {
+            assert!(!is_import_temp_dir_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn stale_temp_cleanup_skips_lookalikes_and_fresh_files() {
+        let dir = temp_dir("stale_temp");
+        let day = STALE_TEMP_AGE + Duration::from_secs(60);
+        for name in [
+            "presets_temp.zip", "presets_temp_12_34.zip",
+            "presets_temp_old.zip", "presets_temp.zip.bak", "presets_temp_12_34.zip.tmp", "ai_project_template_import_5_6",
+        ] {
+            fs::write(dir.join(name), "zip").unwrap();
+            age(&dir.join(name), day);
+        }
+        fs::write(dir.join("presets_temp_56_78.zip"), "fresh").unwrap();
+        for name in ["ai_project_template_import_1_2", "ai_project_template_import_old", "presets_temp_90_12.zip"] {
+            fs::create_dir(dir.join(name)).unwrap();
+            fs::write(dir.join(name).join("files_config.json"), "{}").unwrap();
+            age(&dir.join(name), day);
+        }
+        fs::create_dir(dir.join("ai_project_template_import_3_4")).unwrap();
+        // Вложенные архивы не проверяются
+        fs::create_dir(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested").join("presets_temp.zip"), "zip").unwrap();
+        age(&dir.join("nested").join("presets_temp.zip"), day);
+
+        let found = find_stale_temp_files(&[dir.clone(), dir.clone(), dir.join("missing")], STALE_TEMP_AGE);
+
+        assert_eq!(found, vec![
+            dir.join("ai_project_template_import_1_2"),
+            dir.join("presets_temp.zip"),
+            dir.join("presets_temp_12_34.zip"),
+        ]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Записать ZIP архив из пар (путь в архиве, содержимое)
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    /// Записать пресет `preset_id` с файлом `notes.md` и происхождением `origin`
+    fn write_installed_preset(presets_dir: &Path, preset_id: &str, notes: &str, origin: &PresetOrigin) {
+        let preset_dir = presets_dir.join(preset_id);
+        fs::create_dir_all(&preset_dir).unwrap();
+        fs::write(preset_dir.join("files_config.json"), format!(
+            r#"{{"preset_id": "{}", "preset_name": "{}", "description": "", "directories": []}}"#,
+            preset_id, preset_id
+        )).unwrap();
+        fs::write(preset_dir.join("notes.md"), notes).unwrap();
+        write_preset_origin(&preset_dir, origin).unwrap();
+    }
+
+    #[test]
+    fn repair_rewrites_only_listed_managed_presets() {
+        let root = temp_dir("repair_only");
+        let presets_dir = root.join("presets");
+        write_installed_preset(&presets_dir, "alpha", "damaged", &PresetOrigin::Managed);
+        fs::remove_file(presets_dir.join("alpha").join("files_config.json")).unwrap();
+        write_installed_preset(&presets_dir, "beta", "edited locally", &PresetOrigin::Managed);
+        write_installed_preset(&presets_dir, "mine", "user notes", &PresetOrigin::User);
+        let imported = PresetOrigin::Imported { path: root.join("elsewhere") };
+        write_installed_preset(&presets_dir, "shared", "imported notes", &imported);
+        let archive = root.join("presets.zip");
+        let config = |id: &str| format!(r#"{{"preset_id": "{}", "preset_name": "{}", "description": "", "directories": []}}"#, id, id);
+        write_zip(&archive, &[
+            ("presets-main/README.md", "archive readme"),
+            ("presets-main/alpha/files_config.json", &config("alpha")),
+            ("presets-main/alpha/notes.md", "alpha from archive"),
+            ("presets-main/beta/files_config.json", &config("beta")),
+            ("presets-main/beta/notes.md", "beta from archive"),
+            ("presets-main/mine/files_config.json", &config("mine")),
+            ("presets-main/mine/notes.md", "mine from archive"),
+            ("presets-main/shared/notes.md", "shared from archive"),
+        ]);
+        let snapshot = |id: &str| -> BTreeMap<String, String> {
+            fs::read_dir(presets_dir.join(id)).unwrap()
+                .map(|entry| entry.unwrap())
+                .map(|entry| (entry.file_name().to_string_lossy().into_owned(), fs::read_to_string(entry.path()).unwrap()))
+                .collect()
+        };
+        let untouched: Vec<_> = ["beta", "mine", "shared"].iter().map(|id| snapshot(id)).collect();
+
+        let only: BTreeSet<String> = ["alpha", "mine", "shared", "missing"].iter().map(|id| id.to_string()).collect();
+        let repaired = extract_presets_archive(&archive, &presets_dir, Some(&only), None, |_| {}).unwrap();
+
+        assert_eq!(repaired, BTreeSet::from(["alpha".to_string()]));
+        assert_eq!(fs::read_to_string(presets_dir.join("alpha").join("notes.md")).unwrap(), "alpha from archive");
+        assert_eq!(load_preset_config(&presets_dir, "alpha").unwrap().id, "alpha");
+        assert_eq!(preset_origin(&presets_dir.join("alpha"), None), PresetOrigin::Managed);
+        let manifest = IntegrityManifest::load(&presets_dir.join("alpha")).unwrap().unwrap();
+        assert_eq!(manifest.files.keys().collect::<Vec<_>>(), ["files_config.json", "notes.md"]);
+        let after: Vec<_> = ["beta", "mine", "shared"].iter().map(|id| snapshot(id)).collect();
+        assert_eq!(after, untouched);
+        // Файлы корня архива при восстановлении не распаковываются
+        assert!(!presets_dir.join("README.md").exists());
+        assert!(!presets_dir.join("missing").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn refresh_replaces_files_instead_of_writing_through_links() {
+        let root = temp_dir("refresh_links");
+        let presets_dir = root.join("presets");
+        let archive = root.join("presets.zip");
+        let config = r#"{"preset_id": "alpha", "preset_name": "alpha", "description": "", "directories": []}"#;
+        write_zip(&archive, &[("alpha/files_config.json", config), ("alpha/notes.md", "version 1")]);
+        extract_presets_archive(&archive, &presets_dir, None, None, |_| {}).unwrap();
+        // Файл проекта, созданный с `link: true`
+        let linked = root.join("project_notes.md");
+        fs::hard_link(presets_dir.join("alpha").join("notes.md"), &linked).unwrap();
+
+        write_zip(&archive, &[("alpha/files_config.json", config), ("alpha/notes.md", "version 2")]);
+        extract_presets_archive(&archive, &presets_dir, None, None, |_| {}).unwrap();
+
+        assert_eq!(fs::read_to_string(presets_dir.join("alpha").join("notes.md")).unwrap(), "version 2");
+        assert_eq!(fs::read_to_string(&linked).unwrap(), "version 1");
+        assert!(crate::integrity::verify_presets(&presets_dir).unwrap().iter().all(|result| !result.needs_repair()));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn merged_discovery_prefers_user_presets() {
+        let root = temp_dir("merged");
+        let presets_dir = root.join("presets");
+        let user_dir = root.join("user");
+        write_installed_preset(&presets_dir, "alpha", "", &PresetOrigin::Managed);
+        write_installed_preset(&presets_dir, "shared", "", &PresetOrigin::Managed);
+        write_installed_preset(&user_dir, "shared", "", &PresetOrigin::User);
+        write_installed_preset(&user_dir, "mine", "", &PresetOrigin::User);
+
+        let scan = discover_presets_merged(&presets_dir, Some(&user_dir), &[], &ScanOptions::default()).unwrap();
+
+        // Пресет пользователя с тем же идентификатором заменяет основной
+        assert_eq!(scan.presets, [
+            ("alpha".to_string(), presets_dir.clone()),
+            ("mine".to_string(), user_dir.clone()),
+            ("shared".to_string(), user_dir.clone()),
+        ]);
+        assert!(!scan.truncated);
+        assert!(scan.sources.is_empty());
+
+        // Основная директория, указанная и как пользовательская, не сканируется дважды
+        let scan = discover_presets_merged(&presets_dir, Some(&presets_dir), &[], &ScanOptions::default()).unwrap();
+        assert_eq!(scan.presets, [("alpha".to_string(), presets_dir.clone()), ("shared".to_string(), presets_dir.clone())]);
+        // Несуществующая пользовательская директория не ошибка
+        let scan = discover_presets_merged(&presets_dir, Some(&root.join("missing")), &[], &ScanOptions::default()).unwrap();
+        assert_eq!(scan.presets.len(), 2);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Конфигурация импортируемого пресета с идентификатором `preset_id`
+    fn import_config(preset_id: &str) -> String {
+        format!(r#"{{"preset_id": "{}", "preset_name": "Imported", "description": "", "directories": []}}"#, preset_id)
+    }
+
+    #[test]
+    fn import_zip_with_preset_in_top_level_dir() {
+        let root = temp_dir("import_zip");
+        let presets_dir = root.join("presets");
+        let archive = root.join("book.zip");
+        write_zip(&archive, &[
+            ("book/files_config.json", &import_config("book")),
+            ("book/templates/plan.md", "plan"),
+        ]);
+
+        assert_eq!(import_preset_id(&archive).unwrap(), "book");
+        assert_eq!(import_preset(&presets_dir, &archive, false).unwrap(), "book");
+
+        assert_eq!(fs::read_to_string(presets_dir.join("book").join("templates").join("plan.md")).unwrap(), "plan");
+        assert_eq!(
+            preset_origin(&presets_dir.join("book"), None),
+            PresetOrigin::Imported { path: fs::canonicalize(&archive).unwrap() }
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn import_zip_with_several_top_level_dirs_is_rejected() {
+        let root = temp_dir("import_several_dirs");
+        let presets_dir = root.join("presets");
+        fs::create_dir_all(&presets_dir).unwrap();
+        let archive = root.join("bundle.zip");
+        write_zip(&archive, &[
+            ("book/files_config.json", &import_config("book")),
+            ("notes/files_config.json", &import_config("notes")),
+        ]);
+
+        let error = import_preset(&presets_dir, &archive, false).unwrap_err();
+
+        assert_eq!(error, format!("{:?} has no files_config.json at its top level", archive));
+        assert_eq!(fs::read_dir(&presets_dir).unwrap().count(), 0);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn import_rejects_preset_id_that_is_not_a_folder_name() {
+        let root = temp_dir("import_bad_id");
+        let presets_dir = root.join("presets");
+        fs::create_dir_all(&presets_dir).unwrap();
+        for preset_id in ["../x", "a/b", "/x", ".", ".."] {
+            let source = root.join("source");
+            fs::create_dir_all(&source).unwrap();
+            fs::write(source.join("files_config.json"), import_config(preset_id)).unwrap();
+
+            assert_eq!(
+                import_preset(&presets_dir, &source, true),
+                Err(format!("Preset id '{}' cannot be used as a folder name", preset_id))
+            );
+            assert_eq!(import_preset_id(&source), import_preset(&presets_dir, &source, true));
+            fs::remove_dir_all(&source).unwrap();
+        }
+
+        assert!(!root.join("x").exists());
+        assert_eq!(fs::read_dir(&presets_dir).unwrap().count(), 0);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn import_onto_itself_keeps_installed_preset() {
+        let root = temp_dir("import_itself");
+        let presets_dir = root.join("presets");
+        write_installed_preset(&presets_dir, "notes", "keep me", &PresetOrigin::Managed);
+        let installed = presets_dir.join("notes");
+
+        let error = import_preset(&presets_dir, &installed, true).unwrap_err();
+        assert_eq!(error, format!("{:?} is already the installed preset 'notes'", installed));
+        // Тот же пресет по пути с `..`
+        let indirect = presets_dir.join("notes").join("..").join("notes");
+        assert!(import_preset(&presets_dir, &indirect, true).is_err());
+
+        assert_eq!(fs::read_to_string(installed.join("notes.md")).unwrap(), "keep me");
+        assert_eq!(preset_origin(&installed, None), PresetOrigin::Managed);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn import_overwrite_replaces_previous_files() {
+        let root = temp_dir("import_overwrite");
+        let presets_dir = root.join("presets");
+        write_installed_preset(&presets_dir, "notes", "old notes", &PresetOrigin::Managed);
+        fs::write(presets_dir.join("notes").join("stale.md"), "stale").unwrap();
+        let source = root.join("my-notes");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("files_config.json"), import_config("notes")).unwrap();
+        fs::write(source.join("notes.md"), "new notes").unwrap();
+
+        assert_eq!(
+            import_preset(&presets_dir, &source, false),
+            Err(format!("Preset 'notes' already exists in {:?}", presets_dir))
+        );
+        assert_eq!(import_preset(&presets_dir, &source, true).unwrap(), "notes");
+
+        let installed = presets_dir.join("notes");
+        assert_eq!(fs::read_to_string(installed.join("notes.md")).unwrap(), "new notes");
+        assert!(!installed.join("stale.md").exists());
+        assert_eq!(preset_origin(&installed, None), PresetOrigin::Imported { path: fs::canonicalize(&source).unwrap() });
+        // Источник не изменяется
+        assert!(!source.join(PRESET_ORIGIN_FILE_NAME).exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn import_requires_config_and_supported_source() {
+        let root = temp_dir("import_invalid_source");
+        let presets_dir = root.join("presets");
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::write(root.join("notes.txt"), "text").unwrap();
+
+        let error = import_preset(&presets_dir, &root.join("empty"), false).unwrap_err();
+        assert!(error.starts_with("Failed to read preset config from "), "{}", error);
+        assert_eq!(
+            import_preset(&presets_dir, &root.join("notes.txt"), false),
+            Err(format!("{:?} is neither a preset folder nor a .zip archive", root.join("notes.txt")))
+        );
+        assert!(!presets_dir.exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+//! # Модуль повторов файловых операций
+//!
+//! Антивирусы, служба индексирования и клиенты облачной синхронизации (OneDrive,
+//! Dropbox) на Windows открывают только что записанные файлы, и следующая операция
+//! над файлом завершается ошибкой нарушения совместного доступа или отказом в доступе.
+//! `retry_io` повторяет такую операцию несколько раз с паузами, остальные ошибки
+//! возвращаются сразу.
+//!
+//! Временные ошибки распознаются только на Windows: на остальных платформах
+//! `is_transient_error` всегда возвращает `false`, и операции не повторяются.
+
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+/// Код ошибки Windows `ERROR_SHARING_VIOLATION`
+pub const ERROR_SHARING_VIOLATION: i32 = 32;
+
+/// Код ошибки Windows `ERROR_ACCESS_DENIED`
+pub const ERROR_ACCESS_DENIED: i32 = 5;
+
+/// Паузы между повторами
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Пауза перед каждым повтором (число повторов равно длине списка)
+    pub delays: Vec<Duration>,
+}
+
+impl RetryPolicy {
+    /// Без повторов
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Повторы при кратковременной блокировке файла (50, 150, 400 мс)
+    pub fn transient() -> Self {
+        Self {
+            delays: [50, 150, 400].into_iter().map(Duration::from_millis).collect(),
+        }
+    }
+
+    /// Повторы для папок облачной синхронизации (100, 300, 900 мс)
+    pub fn cloud_sync() -> Self {
+        Self {
+            delays: [100, 300, 900].into_iter().map(Duration::from_millis).collect(),
+        }
+    }
+
+    /// Включены ли повторы
+    pub fn is_enabled(&self) -> bool {
+        !self.delays.is_empty()
+    }
+}
+
+/// Является ли ошибка временной (файл занят другим процессом)
+///
+/// # Arguments
+///
+/// * `error` - ошибка операции
+/// * `fresh_file` - файл создан этой же операцией создания проекта; отказ в доступе
+///   к такому файлу означает, что его держит открытым сторонний процесс
+pub fn is_transient_error(error: &io::Error, fresh_file: bool) -> bool {
+    #[cfg(windows)]
+    {
+        match error.raw_os_error() {
+            Some(ERROR_SHARING_VIOLATION) => true,
+            Some(ERROR_ACCESS_DENIED) => fresh_file,
+            _ => false,
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (error, fresh_file);
+        false
+    }
+}
+
+/// Выполнить файловую операцию, повторяя ее при временных ошибках
+///
+/// # Arguments
+///
+/// * `policy` - паузы между повторами
+/// * `description` - описание операции для лога (`copy docs/plan.md`)
+/// * `fresh_file` - файла не было до начала создания проекта (см. `is_transient_error`)
+/// * `log_lines` - лог, в который записывается каждый повтор
+/// * `operation` - операция; должна быть безопасна для повтора
+///
+/// # Returns
+///
+/// Результат первой успешной попытки; если повторы исчерпаны или ошибка не временная -
+/// исходную ошибку
+pub fn retry_io<T>(
+    policy: &RetryPolicy,
+    description: &str,
+    fresh_file: bool,
+    log_lines: &mut Vec<String>,
+    operation: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    retry_io_when(policy, description, log_lines, |e| is_transient_error(e, fresh_file), operation)
+}
+
+/// То же, что `retry_io`, но временную ошибку распознает `is_transient`
+fn retry_io_when<T>(
+    policy: &RetryPolicy,
+    description: &str,
+    log_lines: &mut Vec<String>,
+    is_transient: impl Fn(&io::Error) -> bool,
+    mut operation: impl FnMut() -> io::Result<T>,
+) -> io::Result<T> {
+    let mut delays = policy.delays.iter();
+    loop {
+        match operation() {
+            Ok(value) => return Ok(value),
+            Err(e) if is_transient(&e) => {
+                let Some(delay) = delays.next() else { return Err(e) };
+                log_lines.push(format!(
+                    "Note: {} failed ({}), retrying in {} ms",
+                    description, e, delay.as_millis()
+                ));
+                thread::sleep(*delay);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Политика без пауз, чтобы тесты не ждали
+    fn instant_policy(retries: usize) -> RetryPolicy {
+        RetryPolicy { delays: vec![Duration::ZERO; retries] }
+    }
+
+    #[test]
+    fn policies_have_expected_delays() {
+        assert!(!RetryPolicy::none().is_enabled());
+        assert_eq!(RetryPolicy::transient().delays, [50, 150, 400].map(Duration::from_millis));
+        assert_eq!(RetryPolicy::cloud_sync().delays, [100, 300, 900].map(Duration::from_millis));
+        assert!(RetryPolicy::cloud_sync().is_enabled());
+    }
+
+    #[test]
+    fn success_is_returned_without_log_lines() {
+        let mut log_lines = Vec::new();
+        let mut calls = 0;
+
+        let result = retry_io(&instant_policy(3), "copy a.txt", true, &mut log_lines, || {
+            calls += 1;
+            Ok(42)
+        });
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls, 1);
+        assert!(log_lines.is_empty());
+    }
+
+    #[test]
+    fn permanent_error_is_not_retried() {
+        let mut log_lines = Vec::new();
+        let mut calls = 0;
+
+        let result: io::Result<()> = retry_io(&instant_policy(3), "copy a.txt", true, &mut log_lines, || {
+            calls += 1;
+            Err(io::Error::new(io::ErrorKind::NotFound, "missing"))
+        });
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(calls, 1);
+        assert!(log_lines.is_empty());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn sharing_violation_code_is_not_transient_outside_windows() {
+        let error = io::Error::from_raw_os_error(ERROR_SHARING_VIOLATION);
+        assert!(!is_transient_error(&error, true));
+    }
+
+    /// Временной в тестах считается `WouldBlock`: коды Windows на других платформах
+    /// не распознаются
+    fn is_would_block(error: &io::Error) -> bool {
+        error.kind() == io::ErrorKind::WouldBlock
+    }
+
+    #[test]
+    fn transient_error_is_retried_until_success() {
+        let mut log_lines = Vec::new();
+        let mut calls = 0;
+
+        let result = retry_io_when(&instant_policy(3), "copy a.txt", &mut log_lines, is_would_block, || {
+            calls += 1;
+            if calls < 3 {
+                Err(io::Error::new(io::ErrorKind::WouldBlock, "file is locked"))
+            } else {
+                Ok(calls)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(log_lines, [
+            "Note: copy a.txt failed (file is locked), retrying in 0 ms",
+            "Note: copy a.txt failed (file is locked), retrying in 0 ms",
+        ]);
+    }
+
+    #[test]
+    fn transient_error_is_returned_when_retries_run_out() {
+        let mut log_lines = Vec::new();
+        let mut calls = 0;
+
+        let result: io::Result<()> = retry_io_when(&instant_policy(2), "write README.md", &mut log_lines, is_would_block, || {
+            calls += 1;
+            Err(io::Error::new(io::ErrorKind::WouldBlock, "file is locked"))
+        });
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::WouldBlock);
+        // Первая попытка и два повтора
+        assert_eq!(calls, 3);
+        assert_eq!(log_lines.len(), 2);
+    }
+
+    #[test]
+    fn disabled_policy_does_not_retry() {
+        let mut log_lines = Vec::new();
+        let mut calls = 0;
+
+        let result: io::Result<()> = retry_io_when(&RetryPolicy::none(), "copy a.txt", &mut log_lines, is_would_block, || {
+            calls += 1;
+            Err(io::Error::new(io::ErrorKind::WouldBlock, "file is locked"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+        assert!(log_lines.is_empty());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn access_denied_is_transient_only_for_fresh_files() {
+        let error = io::Error::from_raw_os_error(ERROR_ACCESS_DENIED);
+        assert!(is_transient_error(&error, true));
+        assert!(!is_transient_error(&error, false));
+    }
+}
@@ -0,0 +1,125 @@
+//! # Модуль внешних сервисов приложения
+//!
+//! Загрузка пресетов из сети и системные диалоги выбора папки и файла вынесены за трейты
+//! `PresetFetcher` и `FolderPicker`. `AppState` получает их через `Services`,
+//! поэтому поток первого запуска (выбор папки → загрузка → обнаружение пресетов →
+//! выбор пресета) можно прогнать через `update()` с подставными реализациями -
+//! без сети и без окон.
+
+use ai_project_template::presets::{download_and_extract_presets, repair_presets, DownloadProgress};
+use ai_project_template::settings::NetworkSettings;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// Асинхронный результат сервиса (должен выполняться в `Command::perform`)
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Получатель прогресса загрузки и распаковки
+pub type ProgressFn = Box<dyn Fn(DownloadProgress) + Send + Sync>;
+
+/// Источник пресетов
+pub trait PresetFetcher: Send + Sync {
+    /// Скачать архив `zip_url` и распаковать пресеты в `target_dir`, сообщая прогресс в `progress`;
+    /// установленный `cancel` прерывает операцию с ошибкой `operation::CANCELLED`
+    fn fetch(&self, target_dir: PathBuf, zip_url: String, network: NetworkSettings, cancel: Arc<AtomicBool>, progress: ProgressFn) -> BoxFuture<Result<(), String>>;
+
+    /// Скачать архив `zip_url` заново и распаковать в `target_dir` только пресеты `presets`;
+    /// результат - пресеты, найденные в архиве
+    fn repair(&self, target_dir: PathBuf, zip_url: String, network: NetworkSettings, presets: BTreeSet<String>, cancel: Arc<AtomicBool>, progress: ProgressFn) -> BoxFuture<Result<BTreeSet<String>, String>>;
+}
+
+/// Диалоги выбора папки и файла
+pub trait FolderPicker: Send + Sync {
+    /// Предложить выбрать папку, начиная с `start_dir`; `None` - выбор отменен
+    fn pick_folder(&self, start_dir: PathBuf) -> BoxFuture<Option<PathBuf>>;
+
+    /// Предложить путь для сохранения файла `file_name` в `start_dir`; `None` - отменено
+    fn save_file(&self, start_dir: PathBuf, file_name: String) -> BoxFuture<Option<PathBuf>>;
+
+    /// Предложить выбрать файл с расширением из `extensions` (фильтр `filter_name`); `None` - отменено
+    fn pick_file(&self, start_dir: PathBuf, filter_name: String, extensions: Vec<String>) -> BoxFuture<Option<PathBuf>>;
+}
+
+/// Загрузка пресетов из ZIP-архива репозитория (по умолчанию `PRESETS_ZIP_URL` на GitHub)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GithubPresetFetcher;
+
+impl PresetFetcher for GithubPresetFetcher {
+    fn fetch(&self, target_dir: PathBuf, zip_url: String, network: NetworkSettings, cancel: Arc<AtomicBool>, progress: ProgressFn) -> BoxFuture<Result<(), String>> {
+        Box::pin(async move {
+            download_and_extract_presets(&target_dir, &zip_url, &network, Some(&cancel), progress).await
+        })
+    }
+
+    fn repair(&self, target_dir: PathBuf, zip_url: String, network: NetworkSettings, presets: BTreeSet<String>, cancel: Arc<AtomicBool>, progress: ProgressFn) -> BoxFuture<Result<BTreeSet<String>, String>> {
+        Box::pin(async move {
+            repair_presets(&target_dir, &zip_url, &network, &presets, Some(&cancel), progress).await
+        })
+    }
+}
+
+/// Системный диалог выбора папки (`rfd`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DialogFolderPicker;
+
+impl FolderPicker for DialogFolderPicker {
+    fn pick_folder(&self, start_dir: PathBuf) -> BoxFuture<Option<PathBuf>> {
+        Box::pin(async move {
+            rfd::AsyncFileDialog::new()
+                .set_directory(&start_dir)
+                .pick_folder()
+                .await
+                .map(|folder| folder.path().to_path_buf())
+        })
+    }
+
+    fn save_file(&self, start_dir: PathBuf, file_name: String) -> BoxFuture<Option<PathBuf>> {
+        Box::pin(async move {
+            rfd::AsyncFileDialog::new()
+                .set_directory(&start_dir)
+                .set_file_name(file_name)
+                .save_file()
+                .await
+                .map(|file| file.path().to_path_buf())
+        })
+    }
+
+    fn pick_file(&self, start_dir: PathBuf, filter_name: String, extensions: Vec<String>) -> BoxFuture<Option<PathBuf>> {
+        Box::pin(async move {
+            rfd::AsyncFileDialog::new()
+                .set_directory(&start_dir)
+                .add_filter(filter_name, &extensions)
+                .pick_file()
+                .await
+                .map(|file| file.path().to_path_buf())
+        })
+    }
+}
+
+/// Набор сервисов, используемых состоянием приложения
+#[derive(Clone)]
+pub struct Services {
+    /// Источник пресетов
+    pub fetcher: Arc<dyn PresetFetcher>,
+    /// Диалог выбора папки
+    pub picker: Arc<dyn FolderPicker>,
+}
+
+impl Default for Services {
+    fn default() -> Self {
+        Self {
+            fetcher: Arc::new(GithubPresetFetcher),
+            picker: Arc::new(DialogFolderPicker),
+        }
+    }
+}
+
+impl std::fmt::Debug for Services {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Services").finish_non_exhaustive()
+    }
+}
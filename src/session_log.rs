@@ -0,0 +1,319 @@
+//! # Модуль лога сессии
+//!
+//! `SessionLog` хранит строки лога, сгруппированные по операциям (обновление
+//! пресетов, создание проекта, импорт). Операция открывает раздел с коротким
+//! номером (`#3`), все строки до ее завершения попадают в этот раздел, а
+//! завершение записывает результат и длительность. Строки вне операций собираются
+//! в общие разделы без заголовка. Лог ограничен по числу строк: при переполнении
+//! удаляются целые старые разделы, а не их части.
+//!
+//! Лог можно сохранить в файл (`SessionLog::export_text`): перед строками лога
+//! записывается заголовок со временем, версией приложения, пресетом и проектом.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Максимальное число строк лога по умолчанию
+pub const DEFAULT_MAX_LOG_ENTRIES: usize = 5000;
+
+/// Имя файла лога, записываемого в директорию созданного проекта
+pub const PROJECT_LOG_FILE_NAME: &str = ".project_creation.log";
+
+/// Сведения, записываемые перед строками сохраненного лога
+#[derive(Debug, Clone)]
+pub struct LogExportHeader {
+    /// Время сохранения
+    pub saved_at: chrono::DateTime<chrono::Local>,
+    /// Версия приложения
+    pub app_version: String,
+    /// Выбранный пресет
+    pub preset: Option<String>,
+    /// Директория созданного проекта
+    pub project_path: Option<PathBuf>,
+}
+
+/// Результат операции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Операция еще выполняется
+    Running,
+    /// Операция завершилась успешно
+    Succeeded,
+    /// Операция завершилась ошибкой
+    Failed,
+    /// Операция прервана пользователем
+    Cancelled,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Running => write!(f, "running"),
+            Outcome::Succeeded => write!(f, "succeeded"),
+            Outcome::Failed => write!(f, "failed"),
+            Outcome::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Операция, к которой относится раздел лога
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationInfo {
+    /// Короткий номер операции в пределах сессии
+    pub id: u64,
+    /// Тип операции (`Create`, `Refresh presets`, `Import`)
+    pub kind: String,
+    /// Цель операции (имя проекта, директория пресетов); пустая - без цели
+    pub target: String,
+    /// Результат
+    pub outcome: Outcome,
+    /// Длительность (`None`, пока операция выполняется)
+    pub duration: Option<Duration>,
+    started: Instant,
+}
+
+impl OperationInfo {
+    /// Заголовок раздела: `#3 Create 'my_book' - succeeded in 1.2 s`
+    pub fn header(&self) -> String {
+        let mut header = format!("#{} {}", self.id, self.kind);
+        if !self.target.is_empty() {
+            header.push_str(&format!(" '{}'", self.target));
+        }
+        match self.duration {
+            Some(duration) => header.push_str(&format!(" - {} in {:.1} s", self.outcome, duration.as_secs_f32())),
+            None => header.push_str(&format!(" - {}", self.outcome)),
+        }
+        header
+    }
+}
+
+/// Раздел лога: строки одной операции или строки между операциями
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSection {
+    /// Операция раздела (`None` - общие сообщения вне операций)
+    pub operation: Option<OperationInfo>,
+    /// Строки раздела в порядке записи
+    pub entries: Vec<String>,
+}
+
+/// Лог сессии, сгруппированный по операциям
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::session_log::{Outcome, SessionLog};
+///
+/// let mut log = SessionLog::with_capacity(3);
+/// log.push("Found 3 preset(s)");
+/// let id = log.begin("Create", "my_book");
+/// log.push("Created directory: docs");
+/// log.finish(id, Outcome::Succeeded);
+/// assert_eq!(log.sections().len(), 2);
+/// assert!(log.to_text().contains("== #1 Create 'my_book' - succeeded in"));
+///
+/// // Переполнение удаляет первый раздел целиком
+/// let id = log.begin("Create", "notes");
+/// log.push("Created directory: a");
+/// log.push("Created directory: b");
+/// log.finish(id, Outcome::Failed);
+/// assert_eq!(log.sections().len(), 2);
+/// assert_eq!(log.sections()[0].operation.as_ref().unwrap().target, "my_book");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionLog {
+    sections: Vec<LogSection>,
+    /// Номер выполняющейся операции
+    current: Option<u64>,
+    next_id: u64,
+    max_entries: usize,
+}
+
+impl Default for SessionLog {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_MAX_LOG_ENTRIES)
+    }
+}
+
+impl SessionLog {
+    /// Пустой лог, хранящий не больше `max_entries` строк (последний раздел не
+    /// удаляется, даже если он один больше лимита)
+    pub fn with_capacity(max_entries: usize) -> Self {
+        Self { sections: Vec::new(), current: None, next_id: 1, max_entries }
+    }
+
+    /// Разделы лога от старых к новым
+    pub fn sections(&self) -> &[LogSection] {
+        &self.sections
+    }
+
+    /// Начать операцию: следующие строки попадают в ее раздел
+    ///
+    /// Незавершенная предыдущая операция остается в статусе `Running`.
+    ///
+    /// # Returns
+    ///
+    /// Номер операции для `finish`
+    pub fn begin(&mut self, kind: impl Into<String>, target: impl Into<String>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sections.push(LogSection {
+            operation: Some(OperationInfo {
+                id,
+                kind: kind.into(),
+                target: target.into(),
+                outcome: Outcome::Running,
+                duration: None,
+                started: Instant::now(),
+            }),
+            entries: Vec::new(),
+        });
+        self.current = Some(id);
+        id
+    }
+
+    /// Завершить операцию `id` с результатом `outcome`
+    ///
+    /// Строки после завершения попадают в общий раздел. Повторное завершение и
+    /// номер уже удаленной операции игнорируются.
+    pub fn finish(&mut self, id: u64, outcome: Outcome) {
+        if let Some(info) = self.operation_mut(id).filter(|info| info.outcome == Outcome::Running) {
+            info.outcome = outcome;
+            info.duration = Some(info.started.elapsed());
+        }
+        if self.current == Some(id) {
+            self.current = None;
+        }
+    }
+
+    /// Номер выполняющейся операции
+    pub fn current(&self) -> Option<u64> {
+        self.current
+    }
+
+    /// Добавить строку в раздел текущей операции или в общий раздел
+    pub fn push(&mut self, entry: impl Into<String>) {
+        let entry = entry.into();
+        let current = self.current.and_then(|id| self.sections.iter()
+            .rposition(|section| section.operation.as_ref().is_some_and(|info| info.id == id)));
+        let index = match current {
+            Some(index) => index,
+            None => {
+                // Общие строки продолжают последний раздел, только если он тоже общий
+                if self.sections.last().is_none_or(|section| section.operation.is_some()) {
+                    self.sections.push(LogSection { operation: None, entries: Vec::new() });
+                }
+                self.sections.len() - 1
+            }
+        };
+        self.sections[index].entries.push(entry);
+        self.evict();
+    }
+
+    /// Добавить несколько строк
+    pub fn extend<I: IntoIterator<Item = S>, S: Into<String>>(&mut self, entries: I) {
+        for entry in entries {
+            self.push(entry);
+        }
+    }
+
+    /// Последняя записанная строка
+    pub fn last(&self) -> Option<&String> {
+        self.sections.iter().rev().find_map(|section| section.entries.last())
+    }
+
+    /// Последняя строка ошибки (начинается с `Error`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::session_log::SessionLog;
+    ///
+    /// let mut log = SessionLog::with_capacity(100);
+    /// assert_eq!(log.last_error(), None);
+    /// log.push("Error: Failed to open folder");
+    /// log.begin("Create", "demo");
+    /// log.push("Error: Template not found: a.md");
+    /// log.push("Project creation failed!");
+    /// assert_eq!(log.last_error(), Some("Error: Template not found: a.md"));
+    /// ```
+    pub fn last_error(&self) -> Option<&str> {
+        self.sections.iter().rev()
+            .flat_map(|section| section.entries.iter().rev())
+            .find(|entry| entry.starts_with("Error"))
+            .map(String::as_str)
+    }
+
+    /// Число строк во всех разделах
+    pub fn len(&self) -> usize {
+        self.sections.iter().map(|section| section.entries.len()).sum()
+    }
+
+    /// Лог пуст
+    pub fn is_empty(&self) -> bool {
+        self.sections.iter().all(|section| section.entries.is_empty())
+    }
+
+    /// Текст лога для копирования и сохранения: у разделов операций - строка-заголовок
+    /// `== #N ... ==`, разделы отделены пустой строкой
+    pub fn to_text(&self) -> String {
+        let blocks: Vec<String> = self.sections.iter()
+            .map(|section| {
+                let mut block = String::new();
+                if let Some(ref info) = section.operation {
+                    block.push_str(&format!("== {} ==\n", info.header()));
+                }
+                block.push_str(&section.entries.join("\n"));
+                block
+            })
+            .collect();
+        blocks.join("\n\n")
+    }
+
+    /// Текст лога для сохранения в файл: заголовок (`LogExportHeader`), пустая строка
+    /// и `to_text()`; файл заканчивается переводом строки
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use chrono::TimeZone;
+    /// use ai_project_template::session_log::{LogExportHeader, SessionLog};
+    ///
+    /// let mut log = SessionLog::with_capacity(100);
+    /// log.push("Presets loaded");
+    /// let header = LogExportHeader {
+    ///     saved_at: chrono::Local.with_ymd_and_hms(2024, 3, 9, 10, 30, 0).unwrap(),
+    ///     app_version: "1.2.0".to_string(),
+    ///     preset: Some("book".to_string()),
+    ///     project_path: None,
+    /// };
+    /// assert_eq!(
+    ///     log.export_text(&header),
+    ///     "Saved: 2024-03-09 10:30:00\nApp version: 1.2.0\nPreset: book\nProject: (none)\n\nPresets loaded\n"
+    /// );
+    /// ```
+    pub fn export_text(&self, header: &LogExportHeader) -> String {
+        format!(
+            "Saved: {}\nApp version: {}\nPreset: {}\nProject: {}\n\n{}\n",
+            header.saved_at.format("%Y-%m-%d %H:%M:%S"),
+            header.app_version,
+            header.preset.as_deref().unwrap_or("(none)"),
+            header.project_path.as_ref().map(|path| path.display().to_string()).unwrap_or_else(|| "(none)".to_string()),
+            self.to_text()
+        )
+    }
+
+    fn operation_mut(&mut self, id: u64) -> Option<&mut OperationInfo> {
+        self.sections.iter_mut()
+            .filter_map(|section| section.operation.as_mut())
+            .find(|info| info.id == id)
+    }
+
+    /// Удалить старые разделы целиком, пока лог больше лимита
+    fn evict(&mut self) {
+        while self.sections.len() > 1 && self.len() > self.max_entries {
+            self.sections.remove(0);
+        }
+    }
+}
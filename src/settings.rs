@@ -0,0 +1,648 @@
+//! # Модуль настроек приложения
+//!
+//! Все пользовательские настройки, включая путь к директории пресетов, хранятся в TOML
+//! файле `~/.config/ai_project_template/settings.toml`
+//! (на Windows - `%USERPROFILE%\.config\ai_project_template\settings.toml`).
+//!
+//! Отсутствующий или поврежденный файл не является ошибкой: используются значения
+//! по умолчанию, а неизвестные и отсутствующие ключи заполняются через `#[serde(default)]`.
+//!
+//! ## Миграция
+//!
+//! Если `settings.toml` еще нет, настройки читаются из `settings.json` прежних версий,
+//! а путь к пресетам - из `presets_path.txt`. При следующем сохранении они записываются
+//! в `settings.toml`.
+//!
+//! ## Портативный режим
+//!
+//! Если рядом с исполняемым файлом лежит `portable.flag` или приложение запущено
+//! с `--portable`, все состояние (настройки, путь к пресетам, лог сессии) хранится
+//! в `data/` рядом с исполняемым файлом, а домашняя директория не используется.
+//! Место хранения определяется в одной точке - `StorageRoot::current`.
+
+use crate::command::{DEFAULT_LARGE_FILE_THRESHOLD, DEFAULT_PROJECT_SIZE_LIMIT};
+use crate::editor::DEFAULT_EDITOR_COMMAND;
+use crate::locale::{tr, Language};
+use crate::persist::{backup_path, read_with_backup, write_with_backup};
+use crate::presets::{DEFAULT_MAX_SCANNED_DIRS, PRESETS_ZIP_URL};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Имя файла настроек в директории конфигурации
+pub const SETTINGS_FILE_NAME: &str = "settings.toml";
+
+/// Имя файла настроек прежних версий (JSON), читается, пока нет `SETTINGS_FILE_NAME`
+pub const LEGACY_SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// Минимальное время показа индикатора выполнения по умолчанию, мс
+pub const DEFAULT_MIN_BUSY_MS: u64 = 2000;
+
+/// Доступные значения минимального времени показа индикатора выполнения, мс
+pub const MIN_BUSY_MS_STEPS: &[u64] = &[0, 500, 1000, 2000, 3000];
+
+/// Имя файла-флага портативного режима рядом с исполняемым файлом
+pub const PORTABLE_FLAG_FILE_NAME: &str = "portable.flag";
+
+/// Имя директории данных портативного режима рядом с исполняемым файлом
+pub const PORTABLE_DATA_DIR_NAME: &str = "data";
+
+/// Портативный режим включен флагом `--portable`
+static PORTABLE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Доступные значения масштаба интерфейса
+pub const UI_SCALE_STEPS: &[f32] = &[0.8, 0.9, 1.0, 1.1, 1.25, 1.5];
+
+/// Настройки приложения
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::settings::AppSettings;
+///
+/// let mut settings = AppSettings::default();
+/// settings.last_preset = Some("book".to_string());
+/// settings.preset_last_used.insert("book".to_string(), chrono::Utc::now());
+/// settings.window_size = Some((900.0, 700.0));
+/// let content = toml::to_string_pretty(&settings).unwrap();
+/// assert_eq!(toml::from_str::<AppSettings>(&content).unwrap(), settings);
+/// // Отсутствующие ключи заполняются значениями по умолчанию
+/// assert_eq!(toml::from_str::<AppSettings>("").unwrap(), AppSettings::default());
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Директория пресетов (в портативном режиме - относительно директории
+    /// исполняемого файла, см. `presets_path`)
+    pub presets_dir: Option<PathBuf>,
+    /// Проверять наличие новой версии приложения раз в сутки (по умолчанию выключено)
+    pub check_for_updates: bool,
+    /// Время последней проверки обновлений
+    pub last_update_check: Option<chrono::DateTime<chrono::Utc>>,
+    /// Последний выбранный пресет (восстанавливается при запуске, если он еще есть)
+    pub last_preset: Option<String>,
+    /// Последняя выбранная директория назначения проектов (проверяется при запуске)
+    pub last_destination: Option<PathBuf>,
+    /// Имя последнего созданного проекта (подсказка в поле имени)
+    pub last_project_name: String,
+    /// Минимальное время показа индикатора выполнения в миллисекундах, чтобы быстрая
+    /// операция не мелькала
+    pub min_busy_ms: u64,
+    /// Размер окна при закрытии (логические пиксели); `None` - размер по умолчанию
+    pub window_size: Option<(f32, f32)>,
+    /// Тема оформления
+    pub theme: ThemeChoice,
+    /// Язык интерфейса и заголовка README (см. модуль `locale`)
+    pub language: Language,
+    /// Масштаб интерфейса (текст и размеры виджетов), от 0.8 до 1.5
+    pub ui_scale: f32,
+    /// Порог размера файла шаблона в мегабайтах, начиная с которого перед созданием
+    /// проекта запрашивается подтверждение
+    pub large_file_threshold_mb: u64,
+    /// Мягкий лимит общего размера записываемых файлов проекта в мегабайтах, сверх
+    /// которого перед созданием запрашивается подтверждение
+    pub project_size_limit_mb: u64,
+    /// Спрашивать перед удалением временных архивов, оставшихся от аварийно
+    /// завершенных запусков (`false` - удалять автоматически)
+    pub confirm_temp_cleanup: bool,
+    /// Предупреждать, если проект создается в папке облачной синхронизации
+    pub warn_cloud_sync: bool,
+    /// Показывать системные уведомления (создание проекта, обновление пресетов)
+    pub notifications: bool,
+    /// Записывать лог сессии в `.project_creation.log` созданного проекта
+    pub save_project_log: bool,
+    /// Сворачивать окно в трей при закрытии вместо выхода (если трей доступен)
+    pub minimize_to_tray: bool,
+    /// Инициализировать git-репозиторий в новом проекте (последний выбор пользователя)
+    pub init_git: bool,
+    /// Шаблон команды редактора, в котором открывается созданный проект
+    /// (`{path}` заменяется путем к проекту, см. модуль `editor`)
+    pub open_in_editor: String,
+    /// Открывать созданный проект в редакторе (последний выбор пользователя)
+    pub open_in_editor_when_done: bool,
+    /// Порядок пресетов в списке выбора
+    pub preset_sort: SortOrder,
+    /// Время последнего создания проекта по идентификатору пресета (для `SortOrder::ByLastUsed`)
+    pub preset_last_used: BTreeMap<String, chrono::DateTime<chrono::Utc>>,
+    /// Сколько директорий просматривать при поиске пресетов (защита от выбора
+    /// папки с тысячами элементов)
+    pub max_scanned_dirs: usize,
+    /// Директория пользовательских пресетов, используемая, когда основная директория
+    /// пресетов доступна только для чтения (`None` - `{config_dir}/presets`)
+    pub user_presets_dir: Option<PathBuf>,
+    /// URL ZIP-архива репозитория пресетов (по умолчанию `PRESETS_ZIP_URL`); позволяет
+    /// команде скачивать собственный набор пресетов
+    pub presets_zip_url: String,
+    /// Дополнительные источники пресетов (например, приватный репозиторий команды);
+    /// каждый распаковывается в свою поддиректорию `presets_dir`
+    pub preset_sources: Vec<PresetSource>,
+    /// Сетевые настройки (загрузка пресетов и проверка обновлений)
+    pub network: NetworkSettings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            presets_dir: None,
+            check_for_updates: false,
+            last_update_check: None,
+            last_preset: None,
+            last_destination: None,
+            last_project_name: String::new(),
+            min_busy_ms: DEFAULT_MIN_BUSY_MS,
+            window_size: None,
+            theme: ThemeChoice::default(),
+            language: Language::default(),
+            ui_scale: 1.0,
+            large_file_threshold_mb: DEFAULT_LARGE_FILE_THRESHOLD / (1024 * 1024),
+            project_size_limit_mb: DEFAULT_PROJECT_SIZE_LIMIT / (1024 * 1024),
+            notifications: true,
+            save_project_log: true,
+            warn_cloud_sync: true,
+            confirm_temp_cleanup: true,
+            minimize_to_tray: false,
+            init_git: false,
+            open_in_editor: DEFAULT_EDITOR_COMMAND.to_string(),
+            open_in_editor_when_done: false,
+            preset_sort: SortOrder::default(),
+            preset_last_used: BTreeMap::new(),
+            max_scanned_dirs: DEFAULT_MAX_SCANNED_DIRS,
+            user_presets_dir: None,
+            presets_zip_url: PRESETS_ZIP_URL.to_string(),
+            preset_sources: Vec::new(),
+            network: NetworkSettings::default(),
+        }
+    }
+}
+
+/// Дополнительный источник пресетов
+///
+/// Основной архив (`presets_zip_url`) распаковывается в корень директории пресетов,
+/// дополнительный - в поддиректорию `subdir`. В `settings.toml`:
+///
+/// ```toml
+/// [[preset_sources]]
+/// name = "company"
+/// zip_url = "https://git.example.com/team/presets/archive/main.zip"
+/// subdir = "company"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PresetSource {
+    /// Имя источника: суффикс идентификатора (`software@company`) и отображаемого
+    /// имени пресета при совпадении с пресетом из другого источника
+    pub name: String,
+    /// URL ZIP-архива
+    pub zip_url: String,
+    /// Поддиректория директории пресетов, в которую распаковывается архив
+    pub subdir: String,
+}
+
+impl PresetSource {
+    /// Проверить источник: непустое имя без `@`, корректный URL и поддиректория
+    /// из одного компонента пути
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ai_project_template::settings::PresetSource;
+    ///
+    /// let source = PresetSource {
+    ///     name: "company".to_string(),
+    ///     zip_url: "https://git.example.com/team/presets/archive/main.zip".to_string(),
+    ///     subdir: "company".to_string(),
+    /// };
+    /// assert!(source.check().is_ok());
+    /// assert!(PresetSource { subdir: "../outside".to_string(), ..source.clone() }.check().is_err());
+    /// assert!(PresetSource { name: "a@b".to_string(), ..source }.check().is_err());
+    /// ```
+    pub fn check(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() || self.name.contains('@') {
+            return Err("Source name must be non-empty and must not contain '@'".to_string());
+        }
+        check_presets_zip_url(&self.zip_url)?;
+        let mut components = Path::new(&self.subdir).components();
+        match (components.next(), components.next()) {
+            (Some(std::path::Component::Normal(_)), None) => Ok(()),
+            _ => Err(format!("Subdirectory must be a single folder name, got '{}'", self.subdir)),
+        }
+    }
+}
+
+/// Выбор темы оформления
+///
+/// В `settings.toml` хранится в нижнем регистре (`theme = "light"`); в файлах прежних
+/// версий без ключа `theme` используется темная тема.
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::settings::{AppSettings, ThemeChoice};
+///
+/// let settings: AppSettings = toml::from_str(r#"theme = "system""#).unwrap();
+/// assert_eq!(settings.theme, ThemeChoice::System);
+/// assert_eq!(toml::from_str::<AppSettings>("").unwrap().theme, ThemeChoice::Dark);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    /// Следовать теме ОС (при ошибке определения - темная)
+    System,
+    /// Темная тема
+    #[default]
+    Dark,
+    /// Светлая тема
+    Light,
+}
+
+impl ThemeChoice {
+    /// Все варианты в порядке отображения в списке выбора
+    pub const ALL: [ThemeChoice; 3] = [ThemeChoice::System, ThemeChoice::Dark, ThemeChoice::Light];
+}
+
+impl fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeChoice::System => write!(f, "{}", tr("theme.system")),
+            ThemeChoice::Dark => write!(f, "{}", tr("theme.dark")),
+            ThemeChoice::Light => write!(f, "{}", tr("theme.light")),
+        }
+    }
+}
+
+/// Порядок пресетов в списке выбора
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// По идентификатору (имени директории)
+    ById,
+    /// По отображаемому имени без учета регистра
+    #[default]
+    ByDisplayName,
+    /// Сначала недавно использованные, затем остальные по отображаемому имени
+    ByLastUsed,
+}
+
+impl SortOrder {
+    /// Все варианты в порядке отображения в списке выбора
+    pub const ALL: [SortOrder; 3] = [SortOrder::ById, SortOrder::ByDisplayName, SortOrder::ByLastUsed];
+
+    /// Отсортировать пресеты
+    ///
+    /// При равенстве ключа порядок определяет идентификатор, поэтому результат
+    /// не зависит от порядка обхода файловой системы.
+    ///
+    /// # Arguments
+    ///
+    /// * `presets` - пары (идентификатор, отображаемое имя)
+    /// * `last_used` - время последнего использования по идентификатору пресета
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use ai_project_template::settings::SortOrder;
+    ///
+    /// let mut presets = vec![
+    ///     ("software".to_string(), "Software".to_string()),
+    ///     ("a_book".to_string(), "book".to_string()),
+    ///     ("notes".to_string(), "Notes".to_string()),
+    /// ];
+    /// let ids = |p: &[(String, String)]| p.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>();
+    ///
+    /// SortOrder::ByDisplayName.sort(&mut presets, &BTreeMap::new());
+    /// assert_eq!(ids(&presets), ["a_book", "notes", "software"]);
+    ///
+    /// let used = BTreeMap::from([("software".to_string(), chrono::Utc::now())]);
+    /// SortOrder::ByLastUsed.sort(&mut presets, &used);
+    /// assert_eq!(ids(&presets), ["software", "a_book", "notes"]);
+    /// ```
+    pub fn sort(
+        self,
+        presets: &mut [(String, String)],
+        last_used: &BTreeMap<String, chrono::DateTime<chrono::Utc>>,
+    ) {
+        let by_name = |(a_id, a_name): &(String, String), (b_id, b_name): &(String, String)| {
+            a_name.to_lowercase().cmp(&b_name.to_lowercase()).then_with(|| a_id.cmp(b_id))
+        };
+        match self {
+            SortOrder::ById => presets.sort_by(|a, b| a.0.cmp(&b.0)),
+            SortOrder::ByDisplayName => presets.sort_by(by_name),
+            // Более позднее время - раньше в списке, неиспользованные (None) - в конце
+            SortOrder::ByLastUsed => presets.sort_by(|a, b| {
+                last_used.get(&b.0).cmp(&last_used.get(&a.0)).then_with(|| by_name(a, b))
+            }),
+        }
+    }
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortOrder::ById => write!(f, "{}", tr("sort.by_id")),
+            SortOrder::ByDisplayName => write!(f, "{}", tr("sort.by_name")),
+            SortOrder::ByLastUsed => write!(f, "{}", tr("sort.by_last_used")),
+        }
+    }
+}
+
+/// Сетевые настройки, общие для всех HTTP запросов приложения
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// Таймаут запроса в секундах
+    pub timeout_secs: u64,
+    /// URL прокси-сервера (например, `http://proxy.local:3128`); `None` - без прокси
+    pub proxy: Option<String>,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 60,
+            proxy: None,
+        }
+    }
+}
+
+impl NetworkSettings {
+    /// Создать HTTP клиент с учетом таймаута и прокси
+    ///
+    /// # Returns
+    ///
+    /// `Ok(reqwest::Client)` или `Err` если URL прокси некорректен
+    pub fn http_client(&self) -> Result<reqwest::Client, String> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs))
+            .user_agent(concat!("ai_project_template/", env!("CARGO_PKG_VERSION")));
+
+        if let Some(ref proxy) = self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| format!("Invalid proxy URL {}: {}", proxy, e))?;
+            builder = builder.proxy(proxy);
+        }
+
+        builder.build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))
+    }
+}
+
+impl AppSettings {
+    /// Порог большого файла в байтах
+    pub fn large_file_threshold(&self) -> u64 {
+        self.large_file_threshold_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// Мягкий лимит общего размера проекта в байтах
+    pub fn project_size_limit(&self) -> u64 {
+        self.project_size_limit_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// Директория пользовательских пресетов с учетом значения по умолчанию
+    pub fn user_presets_path(&self) -> Option<PathBuf> {
+        self.user_presets_dir.clone()
+            .or_else(|| config_dir().map(|dir| dir.join("presets")))
+    }
+
+    /// Сохраненная директория пресетов
+    ///
+    /// В портативном режиме относительный путь разрешается от директории
+    /// исполняемого файла.
+    pub fn presets_path(&self) -> Option<PathBuf> {
+        let path = self.presets_dir.clone().filter(|p| !p.as_os_str().is_empty())?;
+        match StorageRoot::current() {
+            Some(StorageRoot::Portable { executable_dir, .. }) if path.is_relative() => Some(executable_dir.join(path)),
+            _ => Some(path),
+        }
+    }
+
+    /// Запомнить директорию пресетов
+    ///
+    /// В портативном режиме путь внутри директории исполняемого файла записывается
+    /// относительным, чтобы папка приложения оставалась рабочей после переноса.
+    pub fn set_presets_path(&mut self, path: &Path) {
+        let stored = match StorageRoot::current() {
+            Some(StorageRoot::Portable { executable_dir, .. }) => {
+                path.strip_prefix(&executable_dir).unwrap_or(path).to_path_buf()
+            }
+            _ => path.to_path_buf(),
+        };
+        self.presets_dir = Some(stored);
+    }
+
+    /// Загрузить настройки из файла
+    ///
+    /// # Returns
+    ///
+    /// Сохраненные настройки или настройки по умолчанию, если файла нет или он поврежден.
+    /// Поврежденный `settings.toml` заменяется копией `settings.toml.bak` (см. `persist`).
+    /// Пока `settings.toml` нет, читается `settings.json` прежних версий
+    pub fn load() -> Self {
+        let dir = config_dir();
+        // Поврежденный settings.toml восстанавливается из копии последней успешной записи
+        let toml_path = dir.as_ref()
+            .map(|dir| dir.join(SETTINGS_FILE_NAME))
+            .filter(|path| path.exists() || backup_path(path).exists());
+        let mut settings: Self = match toml_path {
+            Some(path) => read_with_backup(&path, toml::from_str::<Self>),
+            None => dir
+                .and_then(|dir| fs::read_to_string(dir.join(LEGACY_SETTINGS_FILE_NAME)).ok())
+                .and_then(|content| serde_json::from_str(&content).ok()),
+        }
+        .unwrap_or_default();
+        settings.ui_scale = settings.ui_scale.clamp(UI_SCALE_STEPS[0], UI_SCALE_STEPS[UI_SCALE_STEPS.len() - 1]);
+        // Отредактированный вручную файл с некорректным URL не должен ломать загрузку
+        if check_presets_zip_url(&settings.presets_zip_url).is_err() {
+            settings.presets_zip_url = PRESETS_ZIP_URL.to_string();
+        }
+        settings.preset_sources.retain(|source| match source.check() {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Ignoring preset source '{}': {}", source.name, e);
+                false
+            }
+        });
+        settings
+    }
+
+    /// Сохранить настройки в файл
+    ///
+    /// Файл записывается атомарно, рядом сохраняется копия `settings.toml.bak`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` если настройки сохранены, иначе `Err` с описанием ошибки
+    pub fn save(&self) -> Result<(), String> {
+        let dir = config_dir()
+            .ok_or_else(|| "Could not determine config directory".to_string())?;
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create config dir: {}", e))?;
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        write_with_backup(&dir.join(SETTINGS_FILE_NAME), content.as_bytes())
+            .map_err(|e| format!("Failed to write settings file: {}", e))
+    }
+}
+
+/// Проверить URL архива пресетов перед сохранением в настройки
+///
+/// # Returns
+///
+/// `Ok(())` или `Err` с причиной: URL не разбирается или его схема не `http`/`https`
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::settings::check_presets_zip_url;
+///
+/// assert!(check_presets_zip_url("https://git.example.com/team/presets/archive/main.zip").is_ok());
+/// assert!(check_presets_zip_url("ftp://example.com/presets.zip").is_err());
+/// assert!(check_presets_zip_url("not a url").is_err());
+/// ```
+pub fn check_presets_zip_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        scheme => Err(format!("Unsupported URL scheme '{}': use http or https", scheme)),
+    }
+}
+
+/// Место хранения состояния приложения
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageRoot {
+    /// Домашняя директория пользователя (`{HOME}/.config/ai_project_template`)
+    User(PathBuf),
+    /// Портативный режим (`data/` рядом с исполняемым файлом)
+    Portable {
+        /// Директория исполняемого файла
+        executable_dir: PathBuf,
+        /// Директория данных
+        data_dir: PathBuf,
+    },
+}
+
+impl StorageRoot {
+    /// Определить место хранения
+    ///
+    /// Портативный режим включается флагом `--portable` (см. `enable_portable_mode`)
+    /// или файлом `portable.flag` рядом с исполняемым файлом.
+    ///
+    /// # Returns
+    ///
+    /// `Some(StorageRoot)` или `None`, если не заданы ни `HOME`, ни `USERPROFILE`
+    /// (и портативный режим не включен)
+    pub fn current() -> Option<Self> {
+        if let Some(executable_dir) = executable_dir() {
+            let portable = PORTABLE_REQUESTED.load(Ordering::Relaxed)
+                || executable_dir.join(PORTABLE_FLAG_FILE_NAME).is_file();
+            if portable {
+                let data_dir = executable_dir.join(PORTABLE_DATA_DIR_NAME);
+                return Some(StorageRoot::Portable { executable_dir, data_dir });
+            }
+        }
+        env::var("HOME")
+            .or_else(|_| env::var("USERPROFILE"))
+            .ok()
+            .map(|home| StorageRoot::User(PathBuf::from(home).join(".config").join("ai_project_template")))
+    }
+
+    /// Директория для файлов состояния
+    pub fn dir(&self) -> &Path {
+        match self {
+            StorageRoot::User(dir) => dir,
+            StorageRoot::Portable { data_dir, .. } => data_dir,
+        }
+    }
+
+    /// Включен ли портативный режим
+    pub fn is_portable(&self) -> bool {
+        matches!(self, StorageRoot::Portable { .. })
+    }
+}
+
+/// Включить портативный режим для текущего процесса (флаг `--portable`)
+///
+/// Должна вызываться до первого обращения к настройкам.
+pub fn enable_portable_mode() {
+    PORTABLE_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Включен ли портативный режим
+pub fn is_portable_mode() -> bool {
+    StorageRoot::current().is_some_and(|root| root.is_portable())
+}
+
+/// Директория исполняемого файла
+pub fn executable_dir() -> Option<PathBuf> {
+    env::current_exe().ok()?.parent().map(Path::to_path_buf)
+}
+
+/// Директория конфигурации приложения (см. `StorageRoot::current`)
+///
+/// # Returns
+///
+/// `Some(PathBuf)` или `None`, если место хранения определить не удалось
+pub fn config_dir() -> Option<PathBuf> {
+    StorageRoot::current().map(|root| root.dir().to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::{SavedAnswers, SAVED_ANSWERS_FILE_NAME};
+    use crate::history::{RecentProjects, RECENT_PROJECTS_FILE_NAME};
+    use crate::presets::{load_presets_path_from_global_namespace, save_presets_path_to_global_namespace};
+    use std::sync::{Mutex, PoisonError};
+
+    /// Тесты меняют `HOME` и режим хранения процесса и поэтому выполняются по одному
+    static HOME_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn portable_mode_never_touches_home() {
+        let _lock = HOME_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let home = env::temp_dir().join(format!("ai_project_template_settings_portable_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).unwrap();
+        let previous_home = env::var_os("HOME");
+        env::set_var("HOME", &home);
+        // Исполняемый файл теста лежит в директории сборки, `data/` создается рядом с ним
+        let executable_dir = executable_dir().unwrap();
+        let data_dir = executable_dir.join(PORTABLE_DATA_DIR_NAME);
+        let _ = fs::remove_dir_all(&data_dir);
+        enable_portable_mode();
+
+        let root = StorageRoot::current();
+        let settings = AppSettings { check_for_updates: true, ..AppSettings::default() };
+        settings.save().unwrap();
+        let presets_dir = executable_dir.join("presets");
+        save_presets_path_to_global_namespace(&presets_dir).unwrap();
+        SavedAnswers::default().save().unwrap();
+        RecentProjects::default().save().unwrap();
+        let loaded = AppSettings::load();
+        let loaded_presets_dir = load_presets_path_from_global_namespace();
+
+        PORTABLE_REQUESTED.store(false, Ordering::Relaxed);
+        match previous_home {
+            Some(previous) => env::set_var("HOME", previous),
+            None => env::remove_var("HOME"),
+        }
+
+        assert_eq!(root, Some(StorageRoot::Portable { executable_dir, data_dir: data_dir.clone() }));
+        assert!(loaded.check_for_updates);
+        assert_eq!(loaded_presets_dir, Some(presets_dir));
+        // Путь внутри директории исполняемого файла хранится относительным
+        assert_eq!(loaded.presets_dir, Some(PathBuf::from("presets")));
+        for file in [SETTINGS_FILE_NAME, SAVED_ANSWERS_FILE_NAME, RECENT_PROJECTS_FILE_NAME] {
+            assert!(data_dir.join(file).is_file(), "{}", file);
+        }
+        assert_eq!(fs::read_dir(&home).unwrap().count(), 0);
+        fs::remove_dir_all(&data_dir).unwrap();
+        fs::remove_dir_all(&home).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+//! # Модуль подстановки значений полей
+//!
+//! Правила подстановки значений динамических полей в шаблоны собраны в одном месте,
+//! чтобы README, его предпросмотр и проверка пресетов вели себя одинаково.
+//!
+//! ## Правила
+//!
+//! - Плейсхолдеры: `{name}`, `{{name}}` и `{{{name}}}`; имя состоит из латинских букв,
+//!   цифр и `_`. На одной позиции приоритет у более длинной формы, тройные скобки
+//!   сохраняют одну пару скобок вокруг значения.
+//! - Шаблон обрабатывается за один проход: значения вставляются как литералы и
+//!   повторно не разбираются, поэтому `{other}` внутри значения остается текстом
+//!   независимо от порядка полей.
+//! - Содержимое файлов (`substitute_placeholders`): значения вставляются без изменений,
+//!   любой длины. Так обрабатываются `readme_template` и шаблоны с `"substitute": true`.
+//! - Пути (`substitute_path_placeholders`): значение должно быть одним компонентом
+//!   пути - непустое, без `/`, `\`, NUL, не `.` и не `..`, не длиннее `MAX_PATH_VALUE_LEN` байт.
+//!   Итоговый путь должен быть относительным и не выходить за директорию проекта.
+//!   Неподходящее значение отклоняется с ошибкой, а не исправляется молча.
+//!
+//! ## Пример
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use ai_project_template::substitution::{substitute_path_placeholders, substitute_placeholders};
+//!
+//! let long = "x".repeat(10_000);
+//! for value in ["{other}", "{{other}}", "a/b", "a\\b", "..", long.as_str()] {
+//!     let values = HashMap::from([("name".to_string(), value), ("other".to_string(), "BAD")]);
+//!     // В содержимое файла значение попадает как есть и не раскрывается повторно
+//!     assert_eq!(substitute_placeholders("[{name}]", &values), format!("[{}]", value));
+//!     // В путь - только безопасный компонент; скобки в имени файла допустимы
+//!     let path = substitute_path_placeholders("docs/{name}.md", &values);
+//!     if value.starts_with('{') {
+//!         assert_eq!(path.unwrap(), format!("docs/{}.md", value));
+//!     } else {
+//!         assert!(path.is_err());
+//!     }
+//! }
+//! ```
+
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::sync::OnceLock;
+
+/// Максимальная длина значения, подставляемого в путь (ограничение имени файла
+/// в большинстве файловых систем)
+pub const MAX_PATH_VALUE_LEN: usize = 255;
+
+/// Регулярное выражение плейсхолдеров: группы 1, 2 и 3 - имя в тройных, двойных
+/// и одинарных скобках (компилируется один раз на процесс)
+fn placeholder_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"\{\{\{([A-Za-z0-9_]+)\}\}\}|\{\{([A-Za-z0-9_]+)\}\}|\{([A-Za-z0-9_]+)\}").unwrap()
+    })
+}
+
+/// Имя плейсхолдера и признак тройных скобок
+fn placeholder<'t>(caps: &Captures<'t>) -> (&'t str, bool) {
+    match (caps.get(1), caps.get(2), caps.get(3)) {
+        (Some(name), _, _) => (name.as_str(), true),
+        (_, Some(name), _) | (_, _, Some(name)) => (name.as_str(), false),
+        _ => unreachable!(),
+    }
+}
+
+/// Имена плейсхолдеров шаблона в порядке появления (с повторами)
+///
+/// # Example
+///
+/// ```
+/// use ai_project_template::substitution::placeholder_names;
+///
+/// assert_eq!(placeholder_names("{a} {{b}} {{{c}}} {\"json\": 1}"), vec!["a", "b", "c"]);
+/// ```
+pub fn placeholder_names(template: &str) -> Vec<&str> {
+    placeholder_regex().captures_iter(template)
+        .map(|caps| placeholder(&caps).0)
+        .collect()
+}
+
+/// Подставить значения в плейсхолдеры `{name}`, `{{name}}` и `{{{name}}}`
+///
+/// Шаблон обрабатывается за один проход, значения вставляются как литералы.
+/// Неизвестные имена (и фрагменты вроде JSON, не похожие на плейсхолдер)
+/// остаются без изменений.
+///
+/// # Arguments
+///
+/// * `template` - текст шаблона
+/// * `values` - значения по точному имени плейсхолдера (регистр учитывается)
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::substitution::substitute_placeholders;
+///
+/// let values = HashMap::from([("NAME".to_string(), "demo"), ("name".to_string(), "demo")]);
+/// assert_eq!(substitute_placeholders("{NAME} {name}", &values), "demo demo");
+/// assert_eq!(substitute_placeholders("{{NAME}} {{name}}", &values), "demo demo");
+/// assert_eq!(substitute_placeholders("{{{name}}}", &values), "{demo}");
+/// assert_eq!(substitute_placeholders(r#"{"name": 1} {other}"#, &values), r#"{"name": 1} {other}"#);
+/// ```
+pub fn substitute_placeholders(template: &str, values: &HashMap<String, &str>) -> String {
+    placeholder_regex().replace_all(template, |caps: &Captures| {
+        let (name, escaped) = placeholder(caps);
+        match values.get(name) {
+            Some(value) if escaped => format!("{{{}}}", value),
+            Some(value) => value.to_string(),
+            None => caps[0].to_string(),
+        }
+    }).into_owned()
+}
+
+/// Значения плейсхолдеров для имени проекта, даты и полей пресета
+///
+/// Поля доступны в верхнем и нижнем регистре (`{AUTHOR}` и `{author}`), встроенные
+/// `PROJECT_NAME` и `DATE` имеют приоритет над одноименными полями.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::substitution::{placeholder_values, substitute_placeholders};
+///
+/// let fields = HashMap::from([("Author".to_string(), "Ann".to_string())]);
+/// let values = placeholder_values("demo", "2024-01-01 10:00", &fields);
+/// assert_eq!(
+///     substitute_placeholders("{PROJECT_NAME} {{project_name}} {AUTHOR} {{author}} {DATE}", &values),
+///     "demo demo Ann Ann 2024-01-01 10:00"
+/// );
+/// ```
+pub fn placeholder_values<'a>(
+    project_name: &'a str,
+    datetime: &'a str,
+    fields: &'a HashMap<String, String>,
+) -> HashMap<String, &'a str> {
+    let mut values: HashMap<String, &str> = HashMap::new();
+    for (field_id, value) in fields {
+        values.insert(field_id.to_uppercase(), value);
+        values.insert(field_id.to_lowercase(), value);
+    }
+    for (name, value) in [("PROJECT_NAME", project_name), ("project_name", project_name), ("DATE", datetime), ("date", datetime)] {
+        values.insert(name.to_string(), value);
+    }
+    values
+}
+
+/// Проверить, что значение можно подставить в путь как один компонент
+///
+/// # Returns
+///
+/// `Ok(())` или `Err` с причиной: пустое значение, разделители путей `/` и `\`, NUL,
+/// значение `.` или `..`, длина больше `MAX_PATH_VALUE_LEN` байт
+pub fn check_path_value(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err("Value is empty".to_string());
+    }
+    if value.contains(['/', '\\']) {
+        return Err(format!("Value '{}' contains a path separator", value));
+    }
+    if value.contains('\0') {
+        return Err("Value contains a NUL character".to_string());
+    }
+    if value == "." || value == ".." {
+        return Err(format!("Value '{}' is not allowed in a path", value));
+    }
+    if value.len() > MAX_PATH_VALUE_LEN {
+        return Err(format!(
+            "Value is too long for a path ({} bytes, at most {})",
+            value.len(), MAX_PATH_VALUE_LEN
+        ));
+    }
+    Ok(())
+}
+
+/// Подставить значения в относительный путь (например, путь назначения шаблона)
+///
+/// Каждое подставляемое значение проверяется `check_path_value`, а итоговый путь -
+/// на то, что он относительный и не содержит `..` (значения `.` в соседних
+/// плейсхолдерах не должны сложиться в выход из директории проекта).
+///
+/// # Returns
+///
+/// `Ok(String)` с путем или `Err` с описанием первого неподходящего значения
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use ai_project_template::substitution::substitute_path_placeholders;
+///
+/// let values = HashMap::from([("module".to_string(), "core"), ("bad".to_string(), "../etc")]);
+/// assert_eq!(substitute_path_placeholders("src/{module}/mod.rs", &values).unwrap(), "src/core/mod.rs");
+/// assert!(substitute_path_placeholders("src/{bad}/mod.rs", &values).is_err());
+/// ```
+pub fn substitute_path_placeholders(template: &str, values: &HashMap<String, &str>) -> Result<String, String> {
+    for name in placeholder_names(template) {
+        if let Some(value) = values.get(name) {
+            check_path_value(value).map_err(|e| format!("Field '{}': {}", name, e))?;
+        }
+    }
+    let path = substitute_placeholders(template, values);
+    let escapes = Path::new(&path).components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return Err(format!("Path '{}' leaves the project directory", path));
+    }
+    Ok(path)
+}
@@ -0,0 +1,185 @@
+//! # Модуль замера времени
+//!
+//! `PhaseTimer` замеряет длительность этапов создания проекта (проверки,
+//! директории, шаблоны, пустые файлы, README) и, в подробном режиме, отдельных
+//! файловых операций дольше порога. Результат (`TimingReport`) попадает в
+//! `CreationReport` и помогает понять, где тратится время при медленном создании.
+
+use std::time::{Duration, Instant};
+
+/// Порог медленной файловой операции в подробном режиме
+pub const SLOW_OPERATION_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Длительность этапа
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseTiming {
+    /// Название этапа
+    pub name: String,
+    /// Длительность этапа
+    pub duration: Duration,
+}
+
+/// Файловая операция, превысившая порог
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowOperation {
+    /// Описание операции (например, `copy docs/plan.md`)
+    pub description: String,
+    /// Длительность операции
+    pub duration: Duration,
+}
+
+/// Результат замеров
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimingReport {
+    /// Этапы в порядке выполнения
+    pub phases: Vec<PhaseTiming>,
+    /// Медленные операции (только в подробном режиме)
+    pub slow_operations: Vec<SlowOperation>,
+}
+
+impl TimingReport {
+    /// Самый долгий этап
+    pub fn slowest_phase(&self) -> Option<&PhaseTiming> {
+        self.phases.iter().max_by_key(|phase| phase.duration)
+    }
+
+    /// Строки для лога: этапы, затем медленные операции
+    pub fn log_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.phases.iter()
+            .map(|phase| format!("Timing: {} {:.3} s", phase.name, phase.duration.as_secs_f64()))
+            .collect();
+        lines.extend(self.slow_operations.iter().map(|op| {
+            format!("Timing: slow {} {:.3} s", op.description, op.duration.as_secs_f64())
+        }));
+        lines
+    }
+}
+
+/// Таймер этапов операции
+///
+/// Этап длится от `start` до следующего `start` или `finish`.
+#[derive(Debug)]
+pub struct PhaseTimer {
+    current: Option<(String, Instant)>,
+    slow_threshold: Option<Duration>,
+    report: TimingReport,
+}
+
+impl Default for PhaseTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhaseTimer {
+    /// Таймер только этапов
+    pub fn new() -> Self {
+        Self { current: None, slow_threshold: None, report: TimingReport::default() }
+    }
+
+    /// Подробный таймер: дополнительно записывает операции дольше `threshold`
+    pub fn verbose(threshold: Duration) -> Self {
+        Self { slow_threshold: Some(threshold), ..Self::new() }
+    }
+
+    /// Начать этап (предыдущий этап завершается)
+    pub fn start(&mut self, name: impl Into<String>) {
+        self.finish();
+        self.current = Some((name.into(), Instant::now()));
+    }
+
+    /// Идет ли сейчас какой-либо этап
+    pub fn is_running(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Завершить текущий этап
+    pub fn finish(&mut self) {
+        if let Some((name, started)) = self.current.take() {
+            self.report.phases.push(PhaseTiming { name, duration: started.elapsed() });
+        }
+    }
+
+    /// Выполнить операцию и записать ее, если она дольше порога (в подробном режиме)
+    ///
+    /// Описание вычисляется только для медленных операций.
+    pub fn time<T>(&mut self, description: impl FnOnce() -> String, operation: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = operation();
+        let duration = started.elapsed();
+        if self.slow_threshold.is_some_and(|threshold| duration >= threshold) {
+            self.report.slow_operations.push(SlowOperation { description: description(), duration });
+        }
+        result
+    }
+
+    /// Завершить замеры и получить результат
+    pub fn into_report(mut self) -> TimingReport {
+        self.finish();
+        self.report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_are_recorded_in_start_order() {
+        let mut timer = PhaseTimer::new();
+        assert!(!timer.is_running());
+
+        timer.start("pre-flight");
+        assert!(timer.is_running());
+        timer.start("directories");
+        std::thread::sleep(Duration::from_millis(5));
+        timer.start("templates");
+        timer.finish();
+        timer.finish();
+        assert!(!timer.is_running());
+        timer.start("README");
+
+        let report = timer.into_report();
+        let names: Vec<&str> = report.phases.iter().map(|phase| phase.name.as_str()).collect();
+        assert_eq!(names, ["pre-flight", "directories", "templates", "README"]);
+        assert!(report.phases[1].duration >= Duration::from_millis(5));
+        assert_eq!(report.slowest_phase().map(|phase| phase.name.as_str()), Some("directories"));
+    }
+
+    #[test]
+    fn slow_operations_are_recorded_only_in_verbose_mode() {
+        let mut timer = PhaseTimer::new();
+        let value = timer.time(|| panic!("description of a fast operation is not needed"), || 42);
+        assert_eq!(value, 42);
+        assert!(timer.into_report().slow_operations.is_empty());
+
+        let mut timer = PhaseTimer::verbose(Duration::from_millis(5));
+        timer.time(|| "copy fast.md".to_string(), || ());
+        timer.time(|| "copy slow.md".to_string(), || std::thread::sleep(Duration::from_millis(10)));
+        let report = timer.into_report();
+        assert_eq!(report.slow_operations.len(), 1);
+        assert_eq!(report.slow_operations[0].description, "copy slow.md");
+        assert!(report.slow_operations[0].duration >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn log_lines_list_phases_then_slow_operations() {
+        let report = TimingReport {
+            phases: vec![
+                PhaseTiming { name: "directories".to_string(), duration: Duration::from_millis(12) },
+                PhaseTiming { name: "templates".to_string(), duration: Duration::from_micros(2_500_400) },
+            ],
+            slow_operations: vec![
+                SlowOperation { description: "copy docs/plan.md".to_string(), duration: Duration::from_millis(1_500) },
+            ],
+        };
+
+        assert_eq!(report.log_lines(), vec![
+            "Timing: directories 0.012 s",
+            "Timing: templates 2.500 s",
+            "Timing: slow copy docs/plan.md 1.500 s",
+        ]);
+        assert!(TimingReport::default().log_lines().is_empty());
+        assert_eq!(TimingReport::default().slowest_phase(), None);
+    }
+}
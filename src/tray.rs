@@ -0,0 +1,208 @@
+//! # Модуль иконки в системном трее
+//!
+//! Иконка с меню быстрых действий ("Show window", "Create project…",
+//! "Refresh presets", "Quit") и всплывающей подсказкой о ходе операции.
+//!
+//! Поддержка трея включается cargo-фичей `tray` (крейт `tray-icon`). Без фичи,
+//! а также там, где трей недоступен (нет сервера StatusNotifier/AppIndicator в
+//! некоторых Wayland-окружениях, нет дисплея), `Tray::start` возвращает `None`
+//! и приложение работает как обычно.
+//!
+//! ## Платформенные особенности
+//!
+//! - **Linux**: иконка создается в отдельном потоке с собственным циклом GTK,
+//!   подсказка передается в этот поток через канал
+//! - **Windows/macOS**: иконка создается в главном потоке (из `update`, после
+//!   запуска цикла событий)
+
+/// Действие, выбранное в меню трея
+///
+/// Без фичи `tray` меню нет и действия не создаются.
+#[cfg_attr(not(feature = "tray"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    /// Показать и активировать окно
+    ShowWindow,
+    /// Показать окно и перейти к вводу имени проекта
+    CreateProject,
+    /// Обновить пресеты
+    RefreshPresets,
+    /// Выйти из приложения
+    Quit,
+}
+
+/// Подсказка иконки, когда операций нет
+pub const IDLE_TOOLTIP: &str = "Project Creator";
+
+#[cfg(feature = "tray")]
+pub use imp::Tray;
+
+#[cfg(not(feature = "tray"))]
+pub use stub::Tray;
+
+#[cfg(feature = "tray")]
+mod imp {
+    use super::{TrayAction, IDLE_TOOLTIP};
+    use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+    use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+    const MENU_SHOW: &str = "show";
+    const MENU_CREATE: &str = "create";
+    const MENU_REFRESH: &str = "refresh";
+    const MENU_QUIT: &str = "quit";
+
+    /// Размер сгенерированной иконки в пикселях
+    const ICON_SIZE: u32 = 32;
+
+    /// Иконка в системном трее
+    pub struct Tray {
+        #[cfg(target_os = "linux")]
+        tooltip_tx: std::sync::mpsc::Sender<String>,
+        #[cfg(not(target_os = "linux"))]
+        icon: TrayIcon,
+    }
+
+    impl std::fmt::Debug for Tray {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Tray").finish_non_exhaustive()
+        }
+    }
+
+    impl Tray {
+        /// Создать иконку в трее
+        ///
+        /// # Returns
+        ///
+        /// `Some(Tray)` если иконка создана, `None` если трей на этой платформе недоступен
+        pub fn start() -> Option<Self> {
+            Self::start_platform()
+        }
+
+        #[cfg(target_os = "linux")]
+        fn start_platform() -> Option<Self> {
+            use gtk::glib;
+            use std::sync::mpsc;
+            use std::time::Duration;
+
+            let (ready_tx, ready_rx) = mpsc::channel::<bool>();
+            let (tooltip_tx, tooltip_rx) = mpsc::channel::<String>();
+
+            // Если libappindicator не найдена, tray-icon паникует - паника остается
+            // в этом потоке, а главный поток получает закрытый канал
+            std::thread::spawn(move || {
+                if gtk::init().is_err() {
+                    let _ = ready_tx.send(false);
+                    return;
+                }
+                let icon = match build_icon() {
+                    Ok(icon) => icon,
+                    Err(_) => {
+                        let _ = ready_tx.send(false);
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(true);
+
+                glib::timeout_add_local(Duration::from_millis(200), move || {
+                    while let Ok(tooltip) = tooltip_rx.try_recv() {
+                        let _ = icon.set_tooltip(Some(tooltip));
+                    }
+                    glib::ControlFlow::Continue
+                });
+                gtk::main();
+            });
+
+            match ready_rx.recv_timeout(Duration::from_secs(2)) {
+                Ok(true) => Some(Self { tooltip_tx }),
+                _ => None,
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        fn start_platform() -> Option<Self> {
+            build_icon().ok().map(|icon| Self { icon })
+        }
+
+        /// Обновить всплывающую подсказку иконки
+        pub fn set_tooltip(&self, tooltip: &str) {
+            #[cfg(target_os = "linux")]
+            let _ = self.tooltip_tx.send(tooltip.to_string());
+            #[cfg(not(target_os = "linux"))]
+            let _ = self.icon.set_tooltip(Some(tooltip));
+        }
+
+        /// Забрать действия, выбранные в меню с момента предыдущего вызова
+        pub fn poll(&self) -> Vec<TrayAction> {
+            let mut actions = Vec::new();
+            while let Ok(event) = MenuEvent::receiver().try_recv() {
+                let action = match event.id.0.as_str() {
+                    MENU_SHOW => TrayAction::ShowWindow,
+                    MENU_CREATE => TrayAction::CreateProject,
+                    MENU_REFRESH => TrayAction::RefreshPresets,
+                    MENU_QUIT => TrayAction::Quit,
+                    _ => continue,
+                };
+                actions.push(action);
+            }
+            actions
+        }
+    }
+
+    /// Построить иконку с меню
+    fn build_icon() -> Result<TrayIcon, String> {
+        let menu = Menu::new();
+        menu.append_items(&[
+            &MenuItem::with_id(MENU_SHOW, "Show window", true, None),
+            &MenuItem::with_id(MENU_CREATE, "Create project…", true, None),
+            &MenuItem::with_id(MENU_REFRESH, "Refresh presets", true, None),
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(MENU_QUIT, "Quit", true, None),
+        ])
+        .map_err(|e| format!("Failed to build tray menu: {}", e))?;
+
+        TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip(IDLE_TOOLTIP)
+            .with_icon(app_icon()?)
+            .build()
+            .map_err(|e| format!("Failed to create tray icon: {}", e))
+    }
+
+    /// Сгенерировать иконку приложения (скругленный квадрат акцентного цвета)
+    fn app_icon() -> Result<Icon, String> {
+        let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+        for y in 0..ICON_SIZE {
+            for x in 0..ICON_SIZE {
+                let corner = (x < 3 || x >= ICON_SIZE - 3) && (y < 3 || y >= ICON_SIZE - 3);
+                let alpha = if corner { 0 } else { 255 };
+                rgba.extend_from_slice(&[0x3b, 0x82, 0xf6, alpha]);
+            }
+        }
+        Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE)
+            .map_err(|e| format!("Failed to create tray icon image: {}", e))
+    }
+}
+
+#[cfg(not(feature = "tray"))]
+mod stub {
+    use super::TrayAction;
+
+    /// Заглушка для сборок без фичи `tray`: иконка никогда не создается
+    #[derive(Debug)]
+    pub struct Tray;
+
+    impl Tray {
+        /// Трей недоступен без фичи `tray`
+        pub fn start() -> Option<Self> {
+            None
+        }
+
+        /// Ничего не делает
+        pub fn set_tooltip(&self, _tooltip: &str) {}
+
+        /// Действий нет
+        pub fn poll(&self) -> Vec<TrayAction> {
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! # Модуль проверки обновлений
+//!
+//! Запрашивает последний релиз приложения через GitHub Releases API и сравнивает
+//! его тег с версией текущей сборки (`CARGO_PKG_VERSION`) по правилам semver.
+//! Проверка включается настройкой `AppSettings::check_for_updates`.
+
+use crate::settings::NetworkSettings;
+use semver::Version;
+use serde::Deserialize;
+
+/// URL GitHub API для получения последнего релиза приложения
+pub const RELEASES_API_URL: &str = "https://api.github.com/repos/vladcraftcom/ai_project_template/releases/latest";
+
+/// Минимальный интервал между автоматическими проверками обновлений (сутки)
+pub const UPDATE_CHECK_INTERVAL_HOURS: i64 = 24;
+
+/// Информация о доступном релизе
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseInfo {
+    /// Версия релиза (из тега)
+    pub version: Version,
+    /// Название релиза (или тег, если название не задано)
+    pub name: String,
+    /// Страница релиза на GitHub
+    pub url: String,
+}
+
+/// Ответ GitHub API (только используемые поля)
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    name: Option<String>,
+    html_url: String,
+}
+
+/// Разобрать тег релиза (`v1.2.3` или `1.2.3`) в версию semver
+pub fn parse_tag(tag: &str) -> Option<Version> {
+    Version::parse(tag.trim().trim_start_matches('v')).ok()
+}
+
+/// Проверить наличие более новой версии приложения
+///
+/// # Arguments
+///
+/// * `network` - сетевые настройки (таймаут, прокси)
+///
+/// # Returns
+///
+/// `Ok(Some(ReleaseInfo))` если последний релиз новее текущей версии,
+/// `Ok(None)` если обновлений нет, `Err` при сетевой ошибке или неожиданном ответе
+pub async fn check_for_update(network: &NetworkSettings) -> Result<Option<ReleaseInfo>, String> {
+    let client = network.http_client()?;
+    let response = client.get(RELEASES_API_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query {}: {}", RELEASES_API_URL, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP error: {}", response.status()));
+    }
+
+    let bytes = response.bytes()
+        .await
+        .map_err(|e| format!("Failed to read response bytes: {}", e))?;
+    let release: GithubRelease = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+
+    let latest = parse_tag(&release.tag_name)
+        .ok_or_else(|| format!("Release tag is not a semver version: {}", release.tag_name))?;
+    let current = Version::parse(env!("CARGO_PKG_VERSION"))
+        .map_err(|e| format!("Invalid package version: {}", e))?;
+
+    if latest <= current {
+        return Ok(None);
+    }
+
+    Ok(Some(ReleaseInfo {
+        version: latest,
+        name: release.name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(release.tag_name),
+        url: release.html_url,
+    }))
+}
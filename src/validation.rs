@@ -0,0 +1,648 @@
+//! # Модуль валидации пресетов
+//!
+//! Проверяет конфигурации пресетов и возвращает список найденных проблем
+//! с уровнем серьезности и путем внутри конфигурации. Используется подкомандой
+//! `validate-presets` и GUI.
+//!
+//! ## Проверки
+//!
+//! - JSON разбирается в `PresetConfig`
+//! - `preset_id` совпадает с именем директории
+//! - файлы-источники шаблонов существуют
+//! - идентификаторы полей и опций уникальны, `preset_id` уникален среди пресетов
+//! - поля типа `"select"` и `"multi_select"` имеют непустой список опций, а `default`
+//!   входит в этот список; тип поля известен, `rows` задан только у `"textarea"`
+//! - плейсхолдеры `readme_template` и путей назначения шаблонов ссылаются на известные поля
+//! - пути проекта не различаются только регистром (`Docs/` и `docs/notes.md`)
+//! - разные шаблоны не пишут в один файл, пустые файлы не совпадают с назначениями шаблонов
+//! - команды `post_commands` не пустые, рабочая директория внутри проекта, `option_guard`
+//!   ссылается на опцию пресета
+//! - `depends_on` опций ссылается на другую опцию пресета без циклов
+//! - `depends_on_option` полей ссылается на опцию пресета, цепочка которой без циклов
+//! - выражения `computed_fields` разбираются и ссылаются на поля пресета, `project_name`
+//!   или объявленные выше вычисляемые поля
+
+use crate::expression::expression_refs;
+use crate::presets::{discover_presets, load_preset_config, normalize_preset_config, same_preset_name, PresetConfig};
+use crate::substitution::placeholder_names;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Component, Path};
+
+/// Встроенные плейсхолдеры, подставляемые для любого пресета (без учета регистра)
+pub const BUILTIN_PLACEHOLDERS: &[&str] = &["project_name", "date"];
+
+/// Уровень серьезности проблемы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Пресет работает, но, вероятно, не так, как задумал автор
+    Warning,
+    /// Пресет не может быть корректно использован
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Проблема, найденная при валидации пресета
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Уровень серьезности
+    pub severity: Severity,
+    /// Путь внутри конфигурации, например `templates[2].source`
+    pub path: String,
+    /// Человекочитаемое описание проблемы
+    pub message: String,
+}
+
+impl ValidationIssue {
+    /// Создать проблему уровня `Error`
+    pub fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, path: path.into(), message: message.into() }
+    }
+
+    /// Создать проблему уровня `Warning`
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, path: path.into(), message: message.into() }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.severity, self.path, self.message)
+    }
+}
+
+/// Проверить один пресет
+///
+/// # Arguments
+///
+/// * `presets_dir` - корневая директория со всеми пресетами
+/// * `preset_id` - идентификатор пресета (имя директории)
+///
+/// # Returns
+///
+/// Список найденных проблем; пустой список означает, что пресет валиден
+pub fn validate_preset(presets_dir: &Path, preset_id: &str) -> Vec<ValidationIssue> {
+    match load_preset_config(presets_dir, preset_id) {
+        Ok(config) => {
+            // Директория может отличаться от идентификатора (нормализация, суффикс источника)
+            let preset_dir = config.source_dir.clone().unwrap_or_else(|| presets_dir.join(preset_id));
+            let dir_name = preset_dir.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| preset_id.to_string());
+            validate_config(&preset_dir, &dir_name, &config)
+        }
+        Err(e) => vec![ValidationIssue::error("files_config.json", e)],
+    }
+}
+
+/// Проверить уже загруженную конфигурацию пресета
+///
+/// # Arguments
+///
+/// * `preset_dir` - директория пресета (для проверки файлов-источников)
+/// * `dir_name` - имя директории пресета
+/// * `config` - конфигурация пресета
+pub fn validate_config(preset_dir: &Path, dir_name: &str, config: &PresetConfig) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if config.id != dir_name {
+        let message = if same_preset_name(&config.id, dir_name) {
+            // Например, директория в NFD (macOS), а preset_id в NFC
+            format!(
+                "preset_id '{}' differs from directory name '{}' only by Unicode normalization or case; rename one so they match exactly",
+                config.id, dir_name
+            )
+        } else {
+            format!("preset_id '{}' does not match directory name '{}'", config.id, dir_name)
+        };
+        issues.push(ValidationIssue::warning("preset_id", message));
+    }
+
+    for (i, template) in config.templates.iter().enumerate() {
+        if !preset_dir.join(&template.source).exists() {
+            issues.push(ValidationIssue::error(
+                format!("templates[{}].source", i),
+                format!("Template source not found: {}", template.source),
+            ));
+        }
+    }
+
+    let mut field_ids = HashSet::new();
+    for (i, field) in config.fields.iter().enumerate() {
+        if !field_ids.insert(field.id.as_str()) {
+            issues.push(ValidationIssue::error(
+                format!("fields[{}].id", i),
+                format!("Duplicate field id '{}'", field.id),
+            ));
+        }
+        if field.unknown_type_warning().is_some() {
+            issues.push(ValidationIssue::warning(
+                format!("fields[{}].type", i),
+                format!("Field '{}' has unknown type '{}', shown as a text input", field.id, field.field_type),
+            ));
+        } else if field.rows.is_some() && !field.is_textarea() {
+            issues.push(ValidationIssue::warning(
+                format!("fields[{}].rows", i),
+                format!("rows is ignored because field '{}' is not a textarea", field.id),
+            ));
+        }
+        let has_options = field.field_type == "select" || field.is_multi_select();
+        if has_options && field.options.as_ref().is_none_or(|o| o.is_empty()) {
+            issues.push(ValidationIssue::error(
+                format!("fields[{}].options", i),
+                format!("Select field '{}' has no options", field.id),
+            ));
+        }
+        if let (Some(default), Some(options)) = (&field.default, &field.options) {
+            let defaults = if field.is_multi_select() { field.selected_values(default) } else { vec![default.as_str()] };
+            if let Some(unknown) = defaults.iter().find(|v| has_options && !options.iter().any(|o| o == *v)) {
+                issues.push(ValidationIssue::error(
+                    format!("fields[{}].default", i),
+                    format!("Default '{}' of select field '{}' is not one of its options", unknown, field.id),
+                ));
+            }
+        }
+    }
+
+    for (i, computed) in config.computed_fields.iter().enumerate() {
+        if computed.id.trim().is_empty() {
+            issues.push(ValidationIssue::error(format!("computed_fields[{}].id", i), "Computed field id is empty"));
+        } else if !field_ids.insert(computed.id.as_str()) {
+            issues.push(ValidationIssue::error(
+                format!("computed_fields[{}].id", i),
+                format!("Duplicate field id '{}'", computed.id),
+            ));
+        } else if BUILTIN_PLACEHOLDERS.contains(&computed.id.to_lowercase().as_str()) {
+            issues.push(ValidationIssue::warning(
+                format!("computed_fields[{}].id", i),
+                format!("Computed field '{}' is shadowed by the built-in placeholder of the same name", computed.id),
+            ));
+        }
+        let refs = match expression_refs(&computed.expression) {
+            Ok(refs) => refs,
+            Err(e) => {
+                issues.push(ValidationIssue::error(
+                    format!("computed_fields[{}].expression", i),
+                    format!("Computed field '{}': {}", computed.id, e),
+                ));
+                continue;
+            }
+        };
+        // Доступны поля пресета, имя проекта и вычисляемые поля, объявленные выше
+        let available = |id: &str| {
+            id.eq_ignore_ascii_case("project_name")
+                || config.fields.iter().any(|f| f.id.eq_ignore_ascii_case(id))
+                || config.computed_fields[..i].iter().any(|c| c.id.eq_ignore_ascii_case(id))
+        };
+        for id in refs.iter().filter(|id| !available(id)) {
+            let message = if config.computed_fields[i..].iter().any(|c| c.id.eq_ignore_ascii_case(id)) {
+                format!("Computed field '{}' refers to '{}', which is not computed before it", computed.id, id)
+            } else {
+                format!("Computed field '{}' refers to unknown field '{}'", computed.id, id)
+            };
+            issues.push(ValidationIssue::error(format!("computed_fields[{}].expression", i), message));
+        }
+    }
+
+    let mut option_ids = HashSet::new();
+    for (i, option) in config.options.iter().enumerate() {
+        if !option_ids.insert(option.id.as_str()) {
+            issues.push(ValidationIssue::error(
+                format!("options[{}].id", i),
+                format!("Duplicate option id '{}'", option.id),
+            ));
+        }
+    }
+    for (i, option) in config.options.iter().enumerate() {
+        let Some(ref parent) = option.depends_on else { continue };
+        if !config.options.iter().any(|o| &o.id == parent) {
+            issues.push(ValidationIssue::warning(
+                format!("options[{}].depends_on", i),
+                format!("Option '{}' depends on unknown option '{}' and will never be shown", option.id, parent),
+            ));
+        } else if depends_on_itself(config, &option.id) {
+            issues.push(ValidationIssue::error(
+                format!("options[{}].depends_on", i),
+                format!("Option '{}' depends on itself through 'depends_on'", option.id),
+            ));
+        }
+    }
+    for (i, field) in config.fields.iter().enumerate() {
+        let Some(ref guard) = field.depends_on_option else { continue };
+        if !option_ids.contains(guard.as_str()) {
+            issues.push(ValidationIssue::warning(
+                format!("fields[{}].depends_on_option", i),
+                format!("Field '{}' depends on unknown option '{}' and will never be shown", field.id, guard),
+            ));
+        } else if depends_on_chain_is_circular(config, guard) {
+            issues.push(ValidationIssue::warning(
+                format!("fields[{}].depends_on_option", i),
+                format!("Field '{}' depends on option '{}' with a circular 'depends_on' chain and will never be shown", field.id, guard),
+            ));
+        }
+    }
+
+    for (i, command) in config.post_commands.iter().enumerate() {
+        if command.program.trim().is_empty() {
+            issues.push(ValidationIssue::error(
+                format!("post_commands[{}].program", i),
+                "Command program is empty",
+            ));
+        }
+        if let Some(ref dir) = command.working_dir {
+            if Path::new(dir).components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+                issues.push(ValidationIssue::error(
+                    format!("post_commands[{}].working_dir", i),
+                    format!("Working directory '{}' is outside the project", dir),
+                ));
+            }
+        }
+        if let Some(ref guard) = command.option_guard {
+            if !option_ids.contains(guard.as_str()) {
+                issues.push(ValidationIssue::warning(
+                    format!("post_commands[{}].option_guard", i),
+                    format!("Option '{}' does not exist, command will never run", guard),
+                ));
+            }
+        }
+    }
+
+    match normalize_preset_config(config) {
+        Ok(normalized) => {
+            for warning in normalized.warnings {
+                issues.push(ValidationIssue::warning(
+                    "empty_files",
+                    warning.trim_start_matches("Warning: "),
+                ));
+            }
+        }
+        Err(e) => issues.push(ValidationIssue::error("templates", e)),
+    }
+
+    for (first, second) in case_collisions(config) {
+        issues.push(ValidationIssue::warning(
+            "paths",
+            format!(
+                "Paths '{}' and '{}' differ only by case and collide on case-insensitive filesystems",
+                first, second
+            ),
+        ));
+    }
+
+    if !config.generate_readme && !config.readme_template.is_empty() {
+        issues.push(ValidationIssue::warning(
+            "readme_template",
+            "readme_template is ignored because generate_readme is false",
+        ));
+    }
+
+    let gitignore_from_template = config.templates.iter()
+        .any(|t| t.destination.trim_start_matches("./") == ".gitignore");
+    if gitignore_from_template && !config.gitignore_template.is_empty() {
+        issues.push(ValidationIssue::warning(
+            "gitignore_template",
+            "gitignore_template is ignored because a template is copied to .gitignore",
+        ));
+    }
+
+    for (i, template) in config.templates.iter().enumerate() {
+        for placeholder in unresolved_placeholders(config, &template.destination) {
+            issues.push(ValidationIssue::warning(
+                format!("templates[{}].destination", i),
+                format!("Placeholder {{{}}} does not match any field", placeholder),
+            ));
+        }
+    }
+
+    for placeholder in unresolved_placeholders(config, &config.readme_template) {
+        issues.push(ValidationIssue::warning(
+            "readme_template",
+            format!("Placeholder {{{}}} does not match any field", placeholder),
+        ));
+    }
+
+    issues
+}
+
+/// Найти пути проекта, которые различаются только регистром
+///
+/// Учитываются поддиректории, назначения шаблонов, пустые файлы и `README.md`,
+/// а также их родительские директории: `Docs/` конфликтует с `docs/notes.md`.
+/// На Windows и macOS (по умолчанию) такие пути указывают на один и тот же файл.
+///
+/// # Returns
+///
+/// Пары конфликтующих путей в порядке объявления (первым - объявленный раньше)
+pub fn case_collisions(config: &PresetConfig) -> Vec<(String, String)> {
+    let declared = config.directories.iter()
+        .chain(config.templates.iter().map(|t| &t.destination))
+        .chain(config.empty_files.iter())
+        .map(|path| path.as_str())
+        .chain(config.generates_readme().then_some("README.md"));
+
+    let mut seen: HashMap<String, String> = HashMap::new();
+    let mut collisions = Vec::new();
+    for path in declared {
+        let normalized = path.replace('\\', "/");
+        let components: Vec<&str> = normalized.split('/')
+            .filter(|c| !c.is_empty() && *c != ".")
+            .collect();
+        for depth in 1..=components.len() {
+            let prefix = components[..depth].join("/");
+            match seen.get(&prefix.to_lowercase()) {
+                Some(existing) if *existing != prefix => {
+                    let pair = (existing.clone(), prefix);
+                    if !collisions.contains(&pair) {
+                        collisions.push(pair);
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(prefix.to_lowercase(), prefix);
+                }
+            }
+        }
+    }
+    collisions
+}
+
+/// Найти плейсхолдеры шаблона (`readme_template` или путь назначения), которые не будут подставлены
+fn unresolved_placeholders(config: &PresetConfig, template: &str) -> Vec<String> {
+    let known: HashSet<String> = config.fields.iter()
+        .map(|f| f.id.to_lowercase())
+        .chain(config.computed_fields.iter().map(|c| c.id.to_lowercase()))
+        .chain(BUILTIN_PLACEHOLDERS.iter().map(|p| p.to_string()))
+        .collect();
+
+    let mut unresolved = Vec::new();
+    for name in placeholder_names(template) {
+        if !known.contains(&name.to_lowercase()) && !unresolved.iter().any(|n| n == name) {
+            unresolved.push(name.to_string());
+        }
+    }
+    unresolved
+}
+
+/// Возвращается ли цепочка `depends_on` опции `option_id` к ней самой
+fn depends_on_itself(config: &PresetConfig, option_id: &str) -> bool {
+    let mut current = option_id;
+    for _ in 0..config.options.len() {
+        let parent = config.options.iter()
+            .find(|o| o.id == current)
+            .and_then(|o| o.depends_on.as_deref());
+        match parent {
+            Some(parent) if parent == option_id => return true,
+            Some(parent) => current = parent,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Приводит ли цепочка `depends_on` от опции `option_id` к циклу
+///
+/// В отличие от `depends_on_itself`, цикл может не проходить через саму опцию.
+fn depends_on_chain_is_circular(config: &PresetConfig, option_id: &str) -> bool {
+    let mut current = option_id;
+    for _ in 0..=config.options.len() {
+        match config.options.iter().find(|o| o.id == current).and_then(|o| o.depends_on.as_deref()) {
+            Some(parent) => current = parent,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Проверить все пресеты в директории
+///
+/// Дополнительно к проверкам `validate_preset` ищет пресеты с одинаковым `preset_id`.
+///
+/// # Returns
+///
+/// `Ok(BTreeMap)` с проблемами по каждому пресету (отсортировано по идентификатору),
+/// `Err` если директорию пресетов не удалось прочитать
+pub fn validate_presets(presets_dir: &Path) -> Result<BTreeMap<String, Vec<ValidationIssue>>, String> {
+    let mut report = BTreeMap::new();
+    let mut declared_ids: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for dir_name in discover_presets(presets_dir)? {
+        if let Ok(config) = load_preset_config(presets_dir, &dir_name) {
+            declared_ids.entry(config.id).or_default().push(dir_name.clone());
+        }
+        let issues = validate_preset(presets_dir, &dir_name);
+        report.insert(dir_name, issues);
+    }
+
+    for (id, dirs) in declared_ids.iter().filter(|(_, dirs)| dirs.len() > 1) {
+        for dir_name in dirs {
+            if let Some(issues) = report.get_mut(dir_name) {
+                issues.push(ValidationIssue::error(
+                    "preset_id",
+                    format!("Duplicate preset_id '{}' (declared in: {})", id, dirs.join(", ")),
+                ));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// Временная директория пресетов теста (удаляется в конце теста)
+    fn presets_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir()
+            .join(format!("ai_project_template_validation_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    /// Записать пресет `dir_name` с конфигурацией `config` и файлами-источниками `sources`
+    fn write_preset(root: &Path, dir_name: &str, config: &str, sources: &[&str]) {
+        let preset_dir = root.join(dir_name);
+        fs::create_dir_all(&preset_dir).unwrap();
+        fs::write(preset_dir.join("files_config.json"), config).unwrap();
+        for source in sources {
+            fs::write(preset_dir.join(source), "template").unwrap();
+        }
+    }
+
+    /// Проверить фикстуру: пресет `demo` с заданными `templates`, `fields`, `options`
+    /// и `readme_template` (значения - фрагменты JSON)
+    fn validate_fixture(name: &str, templates: &str, fields: &str, options: &str, readme: &str) -> Vec<ValidationIssue> {
+        let root = presets_root(name);
+        write_preset(&root, "demo", &format!(r#"{{
+            "preset_id": "demo", "preset_name": "Demo", "description": "",
+            "directories": ["src"],
+            "templates": {}, "fields": {}, "options": {},
+            "readme_template": {}
+        }}"#, templates, fields, options, readme), &["main.rs", "lib.rs"]);
+        let issues = validate_preset(&root, "demo");
+        fs::remove_dir_all(&root).unwrap();
+        issues
+    }
+
+    #[test]
+    fn valid_preset_has_no_issues() {
+        let issues = validate_fixture(
+            "valid",
+            r#"[{"source": "main.rs", "destination": "src/{language}.rs"}]"#,
+            r#"[{"id": "language", "label": "Language", "required": true, "type": "select", "options": ["rust", "go"], "default": "rust"}]"#,
+            r#"[{"id": "ci", "label": "CI", "default": false}]"#,
+            r##""# {project_name} in {Language}, {date}""##,
+        );
+
+        assert_eq!(issues, vec![]);
+    }
+
+    #[test]
+    fn malformed_json_is_reported_with_position() {
+        let root = presets_root("malformed");
+        write_preset(&root, "demo", r#"{"preset_id": "demo", "preset_name": "Demo",}"#, &[]);
+
+        let issues = validate_preset(&root, "demo");
+
+        assert_eq!(issues, vec![ValidationIssue::error(
+            "files_config.json",
+            "Failed to parse preset config: trailing comma at line 1 column 45",
+        )]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn preset_id_must_match_directory() {
+        let root = presets_root("preset_id");
+        write_preset(&root, "demo", r#"{"preset_id": "other", "preset_name": "Demo", "description": "", "directories": []}"#, &[]);
+
+        let issues = validate_preset(&root, "demo");
+
+        assert_eq!(issues, vec![ValidationIssue::warning(
+            "preset_id",
+            "preset_id 'other' does not match directory name 'demo'",
+        )]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn template_rules() {
+        let issues = validate_fixture(
+            "templates",
+            r#"[
+                {"source": "main.rs", "destination": "src/main.rs"},
+                {"source": "missing.rs", "destination": "src/missing.rs"},
+                {"source": "lib.rs", "destination": "src/main.rs"},
+                {"source": "main.rs", "destination": "src/{module}.rs"}
+            ]"#,
+            "[]",
+            "[]",
+            r#""""#,
+        );
+
+        assert_eq!(issues, vec![
+            ValidationIssue::error("templates[1].source", "Template source not found: missing.rs"),
+            ValidationIssue::error("templates", "Templates 'main.rs' and 'lib.rs' both write to 'src/main.rs'"),
+            ValidationIssue::warning("templates[3].destination", "Placeholder {module} does not match any field"),
+        ]);
+    }
+
+    #[test]
+    fn field_and_option_rules() {
+        let issues = validate_fixture(
+            "fields",
+            "[]",
+            r#"[
+                {"id": "kind", "label": "Kind", "required": true, "type": "select"},
+                {"id": "kind", "label": "Kind again", "required": false, "type": "text"},
+                {"id": "tags", "label": "Tags", "required": false, "type": "multi_select", "options": []},
+                {"id": "edition", "label": "Edition", "required": false, "type": "select", "options": ["2021"], "default": "2018"},
+                {"id": "notes", "label": "Notes", "required": false, "type": "text", "rows": 4},
+                {"id": "color", "label": "Color", "required": false, "type": "colour"}
+            ]"#,
+            r#"[{"id": "ci", "label": "CI", "default": false}, {"id": "ci", "label": "CI again", "default": true}]"#,
+            r#""""#,
+        );
+
+        assert_eq!(issues, vec![
+            ValidationIssue::error("fields[0].options", "Select field 'kind' has no options"),
+            ValidationIssue::error("fields[1].id", "Duplicate field id 'kind'"),
+            ValidationIssue::error("fields[2].options", "Select field 'tags' has no options"),
+            ValidationIssue::error("fields[3].default", "Default '2018' of select field 'edition' is not one of its options"),
+            ValidationIssue::warning("fields[4].rows", "rows is ignored because field 'notes' is not a textarea"),
+            ValidationIssue::warning("fields[5].type", "Field 'color' has unknown type 'colour', shown as a text input"),
+            ValidationIssue::error("options[1].id", "Duplicate option id 'ci'"),
+        ]);
+    }
+
+    #[test]
+    fn readme_placeholders_must_reference_known_fields() {
+        let issues = validate_fixture(
+            "readme",
+            "[]",
+            r#"[{"id": "language", "label": "Language", "required": false, "type": "text"}]"#,
+            "[]",
+            r#""{PROJECT_NAME}: {language} {licence} {licence}""#,
+        );
+
+        assert_eq!(issues, vec![
+            ValidationIssue::warning("readme_template", "Placeholder {licence} does not match any field"),
+        ]);
+    }
+
+    #[test]
+    fn case_only_path_differences_are_reported() {
+        let issues = validate_fixture(
+            "case",
+            r#"[{"source": "main.rs", "destination": "Src/main.rs"}]"#,
+            "[]",
+            "[]",
+            r#""""#,
+        );
+
+        assert_eq!(issues, vec![ValidationIssue::warning(
+            "paths",
+            "Paths 'src' and 'Src' differ only by case and collide on case-insensitive filesystems",
+        )]);
+    }
+
+    #[test]
+    fn issue_display_is_stable() {
+        let issue = ValidationIssue::error("fields[1].id", "Duplicate field id 'kind'");
+
+        assert_eq!(issue.to_string(), "[error] fields[1].id: Duplicate field id 'kind'");
+        assert!(Severity::Error > Severity::Warning);
+    }
+
+    #[test]
+    fn validate_presets_reports_duplicate_preset_ids() {
+        let root = presets_root("duplicate_ids");
+        for dir_name in ["alpha", "beta"] {
+            write_preset(&root, dir_name, r#"{"preset_id": "alpha", "preset_name": "Alpha", "description": "", "directories": []}"#, &[]);
+        }
+        write_preset(&root, "gamma", r#"{"preset_id": "gamma", "preset_name": "Gamma", "description": "", "directories": []}"#, &[]);
+
+        let report = validate_presets(&root).unwrap();
+
+        assert_eq!(report.keys().collect::<Vec<_>>(), ["alpha", "beta", "gamma"]);
+        let duplicate = ValidationIssue::error("preset_id", "Duplicate preset_id 'alpha' (declared in: alpha, beta)");
+        assert_eq!(report["alpha"], vec![duplicate.clone()]);
+        assert_eq!(report["beta"], vec![
+            ValidationIssue::warning("preset_id", "preset_id 'alpha' does not match directory name 'beta'"),
+            duplicate,
+        ]);
+        assert_eq!(report["gamma"], vec![]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}